@@ -4,10 +4,14 @@
 //! Console naming uses lowercase snake_case; dev-only commands are prefixed with `dev_`.
 #![forbid(unsafe_code)]
 
-use physics_rapier::PhysicsWorld;
-use rapier3d::control::{CharacterAutostep, CharacterLength, KinematicCharacterController};
+use physics_rapier::{layer_mask_groups, PhysicsWorld, SurfaceMaterial};
+use rapier3d::control::{
+    CharacterAutostep, CharacterCollision as KccCollision, CharacterLength,
+    KinematicCharacterController,
+};
 use rapier3d::math::{Isometry, Point, Translation, UnitVector, Vector};
-use rapier3d::prelude::{Capsule, QueryFilter, Ray, Real};
+use rapier3d::parry::query::{ShapeCastHit, ShapeCastOptions};
+use rapier3d::prelude::{Capsule, ColliderHandle, QueryFilter, Ray, Real};
 
 #[derive(Clone, Copy, Debug)]
 pub struct CollisionProfile {
@@ -15,6 +19,11 @@ pub struct CollisionProfile {
     pub capsule_radius: Real,
     /// Capsule cylinder height in meters (distance between sphere centers).
     pub capsule_height: Real,
+    /// Cylinder height while crouched; clamped to `capsule_height`.
+    pub crouch_capsule_height: Real,
+    /// Rate the cylinder height changes when crouching or standing, in m/s
+    /// (0 = instant).
+    pub crouch_transition_speed: Real,
     /// Maximum step height for auto-stepping in meters.
     pub step_height: Real,
     /// Minimum width of free space required after stepping.
@@ -33,6 +42,14 @@ pub struct CollisionProfile {
     pub wall_slide_damping: Real,
     /// Minimum horizontal progress required to keep a step-up while hitting a wall.
     pub wall_step_min_forward: Real,
+    /// Character mass in kilograms, used when pushing dynamic bodies.
+    pub mass: Real,
+    /// Heaviest dynamic body the character can push; heavier ones block it.
+    pub max_push_mass: Real,
+    /// Whether autostep may climb onto dynamic bodies.
+    pub step_on_dynamic_bodies: bool,
+    /// Collision layers that block the character (see `collision_world::layers`).
+    pub layer_mask: u32,
 }
 
 impl CollisionProfile {
@@ -40,6 +57,8 @@ impl CollisionProfile {
         Self {
             capsule_radius: 0.4,
             capsule_height: 1.8,
+            crouch_capsule_height: 0.8,
+            crouch_transition_speed: 6.0,
             step_height: 0.45,
             step_min_width: 0.2,
             max_slope_angle: 45.0_f32.to_radians(),
@@ -49,6 +68,10 @@ impl CollisionProfile {
             normal_nudge_factor: 1.0e-4,
             wall_slide_damping: 0.2,
             wall_step_min_forward: 0.005,
+            mass: 80.0,
+            max_push_mass: 120.0,
+            step_on_dynamic_bodies: false,
+            layer_mask: u32::MAX,
         }
     }
 
@@ -56,6 +79,8 @@ impl CollisionProfile {
         Self {
             capsule_radius: 0.45,
             capsule_height: 1.7,
+            crouch_capsule_height: 0.7,
+            crouch_transition_speed: 5.0,
             step_height: 0.32,
             step_min_width: 0.15,
             max_slope_angle: 45.0_f32.to_radians(),
@@ -65,11 +90,30 @@ impl CollisionProfile {
             normal_nudge_factor: 1.0e-4,
             wall_slide_damping: 0.35,
             wall_step_min_forward: 0.005,
+            mass: 80.0,
+            max_push_mass: 120.0,
+            step_on_dynamic_bodies: false,
+            layer_mask: u32::MAX,
         }
     }
 
     fn capsule(&self) -> Capsule {
-        Capsule::new_y(self.capsule_height * 0.5, self.capsule_radius)
+        self.capsule_with_height(self.capsule_height)
+    }
+
+    fn capsule_with_height(&self, height: Real) -> Capsule {
+        Capsule::new_y(height * 0.5, self.capsule_radius)
+    }
+
+    fn crouch_height(&self) -> Real {
+        self.crouch_capsule_height.clamp(0.0, self.capsule_height)
+    }
+
+    /// Solid colliders on the layers in `layer_mask`.
+    fn query_filter(&self) -> QueryFilter<'static> {
+        QueryFilter::default()
+            .exclude_sensors()
+            .groups(layer_mask_groups(self.layer_mask))
     }
 
     fn apply_to(&self, controller: &mut KinematicCharacterController) {
@@ -77,7 +121,7 @@ impl CollisionProfile {
             Some(CharacterAutostep {
                 max_height: CharacterLength::Absolute(self.step_height),
                 min_width: CharacterLength::Absolute(self.step_min_width),
-                include_dynamic_bodies: false,
+                include_dynamic_bodies: self.step_on_dynamic_bodies,
             })
         } else {
             None
@@ -94,7 +138,7 @@ impl CollisionProfile {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct CollisionMoveResult {
     pub position: Isometry<Real>,
     pub translation: Vector<Real>,
@@ -104,20 +148,77 @@ pub struct CollisionMoveResult {
     pub wall_normal: Option<Vector<Real>>,
     pub hit_ceiling: bool,
     pub sliding: bool,
+    /// Collider the character ended up standing on.
+    pub ground_collider: Option<ColliderHandle>,
+    /// Surface material of `ground_collider`, when it has one.
+    pub ground_surface: Option<SurfaceMaterial>,
+    /// Translation applied by kinematic movers (riding or pushing) before the
+    /// requested move.
+    pub carried: Vector<Real>,
+    /// A mover pushed the character into geometry it could not escape.
+    pub crushed: bool,
+}
+
+/// Outcome of [`CharacterCollision::update_crouch`].
+#[derive(Clone, Copy, Debug)]
+pub struct CrouchUpdate {
+    /// Capsule center after the resize.
+    pub position: Isometry<Real>,
+    /// The capsule is shorter than standing height.
+    pub crouched: bool,
+    /// Standing up was requested but the taller capsule would overlap solids.
+    pub stand_blocked: bool,
+}
+
+/// Limits for [`CharacterCollision::detect_ledge`].
+#[derive(Clone, Copy, Debug)]
+pub struct LedgeQuery {
+    /// How far ahead of the capsule the wall below a ledge may be.
+    pub reach: Real,
+    /// Ledge heights above the feet that count, in meters.
+    pub min_height: Real,
+    pub max_height: Real,
+    /// Largest angle between the facing direction and the wall, in radians.
+    pub max_facing_angle: Real,
+}
+
+/// A ledge top the character fits on, with a clear path up and across.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ledge {
+    pub collider: ColliderHandle,
+    /// Capsule center standing on the ledge.
+    pub target: Isometry<Real>,
+    /// Ledge top above the character's feet.
+    pub height: Real,
+    /// Horizontal normal of the wall below the ledge, facing the character.
+    pub wall_normal: Vector<Real>,
 }
 
 pub struct CharacterCollision {
     profile: CollisionProfile,
     controller: KinematicCharacterController,
     capsule: Capsule,
+    /// Current cylinder height, between the crouch and standing heights.
+    height: Real,
+    /// Support from the previous move, used to ride kinematic platforms.
+    ground_collider: Option<ColliderHandle>,
+    /// Hits from the last move, replayed as impulses on dynamic bodies.
+    collisions: Vec<KccCollision>,
 }
 
 #[derive(Clone, Copy, Debug)]
 struct GroundProbeHit {
+    collider: ColliderHandle,
     normal: Vector<Real>,
     sliding: bool,
 }
 
+/// Fraction of a mover push that must be achieved before the character
+/// counts as crushed.
+const CRUSH_PUSH_FRACTION: Real = 0.5;
+/// How far past the ledge edge the capsule stands after mantling.
+const LEDGE_STAND_INSET: Real = 0.05;
+
 impl CharacterCollision {
     fn world_up(world: &PhysicsWorld) -> Vector<Real> {
         if world.gravity.norm_squared() > 1.0e-6 {
@@ -135,6 +236,9 @@ impl CharacterCollision {
             profile,
             controller,
             capsule,
+            height: profile.capsule_height,
+            ground_collider: None,
+            collisions: Vec::new(),
         }
     }
 
@@ -142,9 +246,17 @@ impl CharacterCollision {
         self.profile
     }
 
+    /// Replaces the profile; a crouched character stays crouched at the new
+    /// profile's crouch height.
     pub fn set_profile(&mut self, profile: CollisionProfile) {
+        let crouched = self.is_crouched();
         self.profile = profile;
-        self.capsule = profile.capsule();
+        self.height = if crouched {
+            profile.crouch_height()
+        } else {
+            profile.capsule_height
+        };
+        self.capsule = profile.capsule_with_height(self.height);
         profile.apply_to(&mut self.controller);
     }
 
@@ -152,6 +264,223 @@ impl CharacterCollision {
         &self.capsule
     }
 
+    /// Current cylinder height (see `CollisionProfile::capsule_height`).
+    pub fn capsule_height(&self) -> Real {
+        self.height
+    }
+
+    /// Distance from the capsule center down to its lowest point.
+    pub fn foot_offset(&self) -> Real {
+        self.height * 0.5 + self.profile.capsule_radius
+    }
+
+    pub fn is_crouched(&self) -> bool {
+        self.height < self.profile.capsule_height - 1.0e-4
+    }
+
+    /// Snaps back to standing height without an obstruction check, e.g. on
+    /// respawn.
+    pub fn stand(&mut self) {
+        self.height = self.profile.capsule_height;
+        self.capsule = self.profile.capsule();
+    }
+
+    /// Snaps to crouch height without a transition, keeping the capsule
+    /// center, e.g. when restoring a save made while crouched.
+    pub fn crouch(&mut self) {
+        self.height = self.profile.crouch_height();
+        self.capsule = self.profile.capsule_with_height(self.height);
+    }
+
+    /// Whether the standing capsule fits with its feet where they are now.
+    pub fn can_stand(&self, world: &PhysicsWorld, position: Isometry<Real>) -> bool {
+        let rise = self.profile.capsule_height - self.height;
+        let standing = Translation::from(Self::world_up(world) * (rise * 0.5)) * position;
+        !self.overlaps_solid(world, &standing, &self.profile.capsule())
+    }
+
+    /// Moves the capsule height toward crouching or standing at
+    /// `crouch_transition_speed`. Grounded characters keep their feet in place;
+    /// airborne ones keep their head, tucking the feet up. Growing is refused
+    /// while the taller capsule would overlap solids.
+    pub fn update_crouch(
+        &mut self,
+        world: &PhysicsWorld,
+        position: Isometry<Real>,
+        crouch: bool,
+        grounded: bool,
+        dt: Real,
+    ) -> CrouchUpdate {
+        let target = if crouch {
+            self.profile.crouch_height()
+        } else {
+            self.profile.capsule_height
+        };
+        let speed = self.profile.crouch_transition_speed;
+        let max_delta = if speed > 0.0 {
+            speed * dt.max(0.0)
+        } else {
+            Real::INFINITY
+        };
+        let next = if target > self.height {
+            (self.height + max_delta).min(target)
+        } else {
+            (self.height - max_delta).max(target)
+        };
+        let delta = next - self.height;
+        if delta.abs() <= 1.0e-6 {
+            return CrouchUpdate {
+                position,
+                crouched: self.is_crouched(),
+                stand_blocked: false,
+            };
+        }
+        let anchor = if grounded { 0.5 } else { -0.5 };
+        let next_position = Translation::from(Self::world_up(world) * (delta * anchor)) * position;
+        let capsule = self.profile.capsule_with_height(next);
+        if delta > 0.0 && self.overlaps_solid(world, &next_position, &capsule) {
+            return CrouchUpdate {
+                position,
+                crouched: self.is_crouched(),
+                stand_blocked: true,
+            };
+        }
+        self.height = next;
+        self.capsule = capsule;
+        CrouchUpdate {
+            position: next_position,
+            crouched: self.is_crouched(),
+            stand_blocked: false,
+        }
+    }
+
+    /// Looks for a ledge along `forward`: a wall within `query.reach` that
+    /// the character faces, a walkable top in the height window with room to
+    /// stand on it, and a clear path rising in place then moving across.
+    pub fn detect_ledge(
+        &self,
+        world: &PhysicsWorld,
+        position: Isometry<Real>,
+        forward: Vector<Real>,
+        query: LedgeQuery,
+    ) -> Option<Ledge> {
+        let up = Self::world_up(world);
+        let forward = forward - up * forward.dot(&up);
+        if forward.norm_squared() <= 1.0e-6 || query.max_height <= query.min_height {
+            return None;
+        }
+        let forward = forward.normalize();
+        let (_, wall) = self.cast_capsule(world, &position, forward, query.reach)?;
+        let normal = *wall.normal1;
+        let wall_normal = normal - up * normal.dot(&up);
+        if wall_normal.norm() < 0.7 {
+            return None;
+        }
+        let wall_normal = wall_normal.normalize();
+        if forward.dot(&-wall_normal) < query.max_facing_angle.cos() {
+            return None;
+        }
+
+        // Drop the capsule onto the top from just above the tallest ledge.
+        let advance = wall.time_of_impact + self.profile.capsule_radius * 2.0 + LEDGE_STAND_INSET;
+        let lift = query.max_height + self.profile.offset;
+        let probe = Translation::from(forward * advance + up * lift) * position;
+        if self.overlaps_solid(world, &probe, &self.capsule) {
+            return None;
+        }
+        let drop = query.max_height - query.min_height;
+        let (collider, top) = self.cast_capsule(world, &probe, -up, drop)?;
+        if top.time_of_impact <= 0.0 || top.normal1.dot(&up) < self.profile.max_slope_angle.cos() {
+            return None;
+        }
+        let settle = (top.time_of_impact - self.profile.offset).max(0.0);
+        let target = Translation::from(-up * settle) * probe;
+
+        let rise = (target.translation.vector - position.translation.vector).dot(&up);
+        if self.cast_capsule(world, &position, up, rise).is_some() {
+            return None;
+        }
+        let lifted = Translation::from(up * rise) * position;
+        let across = target.translation.vector - lifted.translation.vector;
+        let across_len = across.norm();
+        if across_len > 1.0e-4
+            && self
+                .cast_capsule(world, &lifted, across / across_len, across_len)
+                .is_some()
+        {
+            return None;
+        }
+        Some(Ledge {
+            collider,
+            target,
+            height: lift - top.time_of_impact,
+            wall_normal,
+        })
+    }
+
+    fn cast_capsule(
+        &self,
+        world: &PhysicsWorld,
+        position: &Isometry<Real>,
+        direction: Vector<Real>,
+        distance: Real,
+    ) -> Option<(ColliderHandle, ShapeCastHit)> {
+        world.query_pipeline().cast_shape(
+            world.bodies(),
+            world.colliders(),
+            position,
+            &direction,
+            &self.capsule,
+            ShapeCastOptions {
+                max_time_of_impact: distance,
+                target_distance: 0.0,
+                stop_at_penetration: true,
+                compute_impact_geometry_on_penetration: true,
+            },
+            self.profile.query_filter(),
+        )
+    }
+
+    fn overlaps_solid(
+        &self,
+        world: &PhysicsWorld,
+        position: &Isometry<Real>,
+        capsule: &Capsule,
+    ) -> bool {
+        world
+            .query_pipeline()
+            .intersection_with_shape(
+                world.bodies(),
+                world.colliders(),
+                position,
+                capsule,
+                self.profile.query_filter(),
+            )
+            .is_some()
+    }
+
+    /// Forgets the platform the character was standing on, e.g. after a
+    /// teleport.
+    pub fn clear_ground(&mut self) {
+        self.ground_collider = None;
+    }
+
+    /// Pushes the dynamic bodies hit by the last `move_character`; call once
+    /// per move, before the next physics step.
+    pub fn push_dynamic_bodies(&self, world: &mut PhysicsWorld, dt: Real) {
+        if self.collisions.is_empty() {
+            return;
+        }
+        world.apply_character_impulses(
+            &self.controller,
+            dt,
+            &self.capsule,
+            self.profile.mass,
+            self.profile.max_push_mass,
+            &self.collisions,
+        );
+    }
+
     pub fn move_character(
         &mut self,
         world: &PhysicsWorld,
@@ -160,6 +489,7 @@ impl CharacterCollision {
         allow_step: bool,
         dt: Real,
     ) -> CollisionMoveResult {
+        let (position, carried, crushed) = self.apply_movers(world, position, dt);
         let mut hit_wall = false;
         let mut hit_ceiling = false;
         let mut contact_ground_normal = None;
@@ -190,6 +520,8 @@ impl CharacterCollision {
             self.controller.snap_to_ground = None;
         }
 
+        let mut collisions = std::mem::take(&mut self.collisions);
+        collisions.clear();
         let output = self.controller.move_shape(
             dt,
             world.bodies(),
@@ -198,8 +530,9 @@ impl CharacterCollision {
             &self.capsule,
             &position,
             desired_translation,
-            QueryFilter::default(),
+            self.profile.query_filter(),
             |collision| {
+                collisions.push(collision);
                 let normal = collision.hit.normal1;
                 let up_dot = normal.dot(&up);
                 if moving_up && up_dot < -0.1 {
@@ -233,6 +566,7 @@ impl CharacterCollision {
         );
         self.controller.autostep = original_autostep;
         self.controller.snap_to_ground = original_snap;
+        self.collisions = collisions;
         let mut adjusted_translation = output.translation;
         let horiz = Vector::new(adjusted_translation.x, 0.0, adjusted_translation.z);
         let min_forward = self
@@ -277,11 +611,13 @@ impl CharacterCollision {
         let next_position = Translation::from(adjusted_translation) * position;
         let mut grounded = false;
         let mut ground_normal = None;
+        let mut ground_collider = None;
         let mut sliding = false;
         if !moving_up {
             if let Some(hit) = self.probe_ground(world, next_position) {
                 grounded = true;
                 ground_normal = Some(hit.normal);
+                ground_collider = Some(hit.collider);
                 sliding = hit.sliding;
             }
         }
@@ -298,8 +634,10 @@ impl CharacterCollision {
         if !allow_step && desired_translation.y > 0.0 {
             grounded = false;
             ground_normal = None;
+            ground_collider = None;
             sliding = false;
         }
+        self.ground_collider = ground_collider;
         CollisionMoveResult {
             position: next_position,
             translation: adjusted_translation,
@@ -309,9 +647,103 @@ impl CharacterCollision {
             wall_normal,
             hit_ceiling,
             sliding,
+            ground_collider,
+            ground_surface: ground_collider.and_then(|collider| world.surface(collider)),
+            carried,
+            crushed,
         }
     }
 
+    /// Carries the character along with the kinematic body it stood on last
+    /// move, then resolves pushes from kinematic bodies that moved into it.
+    fn apply_movers(
+        &mut self,
+        world: &PhysicsWorld,
+        position: Isometry<Real>,
+        dt: Real,
+    ) -> (Isometry<Real>, Vector<Real>, bool) {
+        let mut position = position;
+        let mut carried = Vector::zeros();
+        let mut crushed = false;
+        let up = Self::world_up(world);
+        let foot = Point::from(position.translation.vector - up * self.foot_offset());
+        if let Some(ground) = self.ground_collider {
+            if let Some(motion) = world.kinematic_displacement(ground) {
+                let carry = motion.transform_point(&foot) - foot;
+                if carry.norm_squared() > 1.0e-10 {
+                    let moved = self.sweep(world, position, carry, ground, dt);
+                    position = Translation::from(moved) * position;
+                    carried += moved;
+                }
+            }
+        }
+
+        let mut pushers = Vec::new();
+        world.query_pipeline().intersections_with_shape(
+            world.bodies(),
+            world.colliders(),
+            &position,
+            &self.capsule,
+            QueryFilter::only_kinematic()
+                .exclude_sensors()
+                .groups(layer_mask_groups(self.profile.layer_mask)),
+            |handle| {
+                pushers.push(handle);
+                true
+            },
+        );
+        for pusher in pushers {
+            if Some(pusher) == self.ground_collider {
+                continue;
+            }
+            let Some(motion) = world.kinematic_displacement(pusher) else {
+                continue;
+            };
+            let center = Point::from(position.translation.vector);
+            let push = motion.transform_point(&center) - center;
+            let push_len = push.norm();
+            if push_len <= 1.0e-5 {
+                continue;
+            }
+            let push = push + push / push_len * self.profile.offset;
+            let moved = self.sweep(world, position, push, pusher, dt);
+            if moved.norm() < push_len * CRUSH_PUSH_FRACTION {
+                crushed = true;
+            }
+            position = Translation::from(moved) * position;
+            carried += moved;
+        }
+        (position, carried, crushed)
+    }
+
+    /// Moves the capsule by `translation` without stepping or snapping,
+    /// ignoring `exclude`; returns the translation achieved.
+    fn sweep(
+        &mut self,
+        world: &PhysicsWorld,
+        position: Isometry<Real>,
+        translation: Vector<Real>,
+        exclude: ColliderHandle,
+        dt: Real,
+    ) -> Vector<Real> {
+        let original_autostep = self.controller.autostep.take();
+        let original_snap = self.controller.snap_to_ground.take();
+        let output = self.controller.move_shape(
+            dt,
+            world.bodies(),
+            world.colliders(),
+            world.query_pipeline(),
+            &self.capsule,
+            &position,
+            translation,
+            self.profile.query_filter().exclude_collider(exclude),
+            |_| {},
+        );
+        self.controller.autostep = original_autostep;
+        self.controller.snap_to_ground = original_snap;
+        output.translation
+    }
+
     fn probe_ground(
         &self,
         world: &PhysicsWorld,
@@ -334,8 +766,7 @@ impl CharacterCollision {
         let direction = -up;
         // Use a smaller foot probe to stabilize grounding without wall bias.
         let foot_radius = self.profile.capsule_radius * 0.75;
-        let foot_offset =
-            -(self.profile.capsule_height * 0.5 + self.profile.capsule_radius) + foot_radius;
+        let foot_offset = -self.foot_offset() + foot_radius;
         let foot_center = position.translation.vector + up * foot_offset;
         let ray_origin = Point::from(foot_center);
         let ray = Ray::new(ray_origin, direction);
@@ -348,7 +779,7 @@ impl CharacterCollision {
                 &ray,
                 max_toi,
                 true,
-                QueryFilter::default(),
+                self.profile.query_filter(),
             )
             .or_else(|| {
                 world.query_pipeline().cast_ray_and_get_normal(
@@ -357,10 +788,11 @@ impl CharacterCollision {
                     &ray,
                     max_toi,
                     false,
-                    QueryFilter::default(),
+                    self.profile.query_filter(),
                 )
             })?;
-        let normal = hit.1.normal;
+        let (collider, intersection) = hit;
+        let normal = intersection.normal;
         let up_dot = normal.dot(&up);
         if up_dot <= 0.0 {
             return None;
@@ -369,13 +801,18 @@ impl CharacterCollision {
             return None;
         }
         let sliding = up_dot <= self.controller.min_slope_slide_angle.cos();
-        Some(GroundProbeHit { normal, sliding })
+        Some(GroundProbeHit {
+            collider,
+            normal,
+            sliding,
+        })
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use physics_rapier::{DynamicBodyDesc, DynamicBodyShape};
     use rapier3d::prelude::*;
 
     fn build_floor(world: &mut PhysicsWorld) {
@@ -400,4 +837,287 @@ mod tests {
         assert!(result.grounded);
         assert!(result.position.translation.y > 1.0);
     }
+
+    #[test]
+    fn detect_ledge_needs_reachable_top_facing_and_headroom() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        let block = |x: Real, height: Real| {
+            ColliderBuilder::cuboid(0.5, height * 0.5, 1.0)
+                .translation(vector![x, height * 0.5, 0.0])
+                .build()
+        };
+        let ledge = world.insert_static_collider(block(1.2, 1.2));
+        world.insert_static_collider(block(-1.2, 3.0));
+        world.step(1.0 / 60.0);
+
+        let profile = CollisionProfile::rpg_default();
+        let collision = CharacterCollision::new(profile);
+        let position = Isometry::translation(0.0, collision.foot_offset() + 0.02, 0.0);
+        let query = LedgeQuery {
+            reach: 0.5,
+            min_height: 0.5,
+            max_height: 1.6,
+            max_facing_angle: 35.0_f32.to_radians(),
+        };
+        let found = collision
+            .detect_ledge(&world, position, vector![1.0, 0.0, 0.0], query)
+            .expect("ledge");
+        assert_eq!(found.collider, ledge);
+        assert!((found.height - 1.18).abs() < 1.0e-3, "{}", found.height);
+        assert!((found.wall_normal - vector![-1.0, 0.0, 0.0]).norm() < 1.0e-3);
+        let feet = found.target.translation.y - collision.foot_offset();
+        assert!((feet - 1.2).abs() < 0.1);
+        assert!(found.target.translation.x > 0.7 + profile.capsule_radius);
+
+        let oblique = vector![1.0, 0.0, 1.0].normalize();
+        assert!(collision
+            .detect_ledge(&world, position, oblique, query)
+            .is_none());
+        assert!(collision
+            .detect_ledge(&world, position, vector![-1.0, 0.0, 0.0], query)
+            .is_none());
+        world.insert_static_collider(
+            ColliderBuilder::cuboid(2.0, 0.1, 2.0)
+                .translation(vector![0.0, 3.0, 0.0])
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+        assert!(collision
+            .detect_ledge(&world, position, vector![1.0, 0.0, 0.0], query)
+            .is_none());
+    }
+
+    #[test]
+    fn sensors_do_not_block_movement() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        world.insert_sensor_collider(
+            ColliderBuilder::cuboid(0.5, 2.0, 2.0)
+                .translation(vector![1.0, 2.0, 0.0])
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+
+        let mut collision = CharacterCollision::new(CollisionProfile::arena_default());
+        let position = Isometry::translation(0.0, 1.0, 0.0);
+        let result =
+            collision.move_character(&world, position, vector![2.0, 0.0, 0.0], true, 1.0 / 60.0);
+
+        assert!(result.position.translation.x > 1.9);
+    }
+
+    #[test]
+    fn rides_an_ascending_platform() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        let (platform, _) = world.insert_kinematic_collider(
+            ColliderBuilder::cuboid(2.0, 0.1, 2.0)
+                .translation(vector![0.0, -0.1, 0.0])
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+
+        let mut collision = CharacterCollision::new(CollisionProfile::arena_default());
+        let mut position = Isometry::translation(0.0, 1.32, 0.0);
+        let mut platform_y = -0.1;
+        for _ in 0..60 {
+            platform_y += 0.02;
+            world.set_kinematic_position(platform, Isometry::translation(0.0, platform_y, 0.0));
+            world.step(1.0 / 60.0);
+            let result = collision.move_character(
+                &world,
+                position,
+                vector![0.0, -0.01, 0.0],
+                true,
+                1.0 / 60.0,
+            );
+            position = result.position;
+            assert!(!result.crushed);
+        }
+
+        let feet = position.translation.y - 1.3;
+        assert!((feet - (platform_y + 0.1)).abs() < 0.1, "feet {}", feet);
+    }
+
+    #[test]
+    fn closing_door_pushes_then_crushes() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        world.insert_static_collider(
+            ColliderBuilder::cuboid(0.1, 2.0, 2.0)
+                .translation(vector![-2.0, 2.0, 0.0])
+                .build(),
+        );
+        let (door, _) = world.insert_kinematic_collider(
+            ColliderBuilder::cuboid(0.1, 1.5, 2.0)
+                .translation(vector![1.0, 1.5, 0.0])
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+
+        let mut collision = CharacterCollision::new(CollisionProfile::arena_default());
+        let mut position = Isometry::translation(0.0, 1.32, 0.0);
+        let mut door_x = 1.0;
+        let mut pushed = false;
+        let mut crushed = false;
+        for _ in 0..60 {
+            door_x -= 0.05;
+            world.set_kinematic_position(door, Isometry::translation(door_x, 1.5, 0.0));
+            world.step(1.0 / 60.0);
+            let result =
+                collision.move_character(&world, position, Vector::zeros(), true, 1.0 / 60.0);
+            position = result.position;
+            pushed |= result.carried.x < 0.0;
+            crushed |= result.crushed;
+            if !crushed {
+                assert!(position.translation.x < door_x - 0.1 - 0.39);
+            }
+        }
+        assert!(pushed);
+        assert!(crushed);
+        assert!(position.translation.x > -1.9 + 0.39);
+    }
+
+    #[test]
+    fn pushes_light_props_and_is_blocked_by_heavy_ones() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        let prop = |z: Real, mass: Real| {
+            DynamicBodyDesc::new(
+                DynamicBodyShape::Box {
+                    half_extents: [0.3, 0.3, 0.3],
+                },
+                Isometry::translation(1.0, 0.3, z),
+                mass,
+            )
+        };
+        let (light, _) = world.insert_dynamic_body(&prop(-2.0, 5.0)).expect("light");
+        let (heavy, _) = world.insert_dynamic_body(&prop(2.0, 400.0)).expect("heavy");
+        world.step(1.0 / 60.0);
+
+        let dt = 1.0 / 60.0;
+        let mut lanes = [
+            (
+                CharacterCollision::new(CollisionProfile::arena_default()),
+                -2.0,
+            ),
+            (
+                CharacterCollision::new(CollisionProfile::arena_default()),
+                2.0,
+            ),
+        ]
+        .map(|(collision, z)| (collision, Isometry::translation(0.0, 1.32, z)));
+        for _ in 0..90 {
+            for (collision, position) in &mut lanes {
+                let result = collision.move_character(
+                    &world,
+                    *position,
+                    vector![0.05, -0.01, 0.0],
+                    true,
+                    dt,
+                );
+                *position = result.position;
+                collision.push_dynamic_bodies(&mut world, dt);
+            }
+            world.step(dt);
+        }
+
+        let light_x = world.bodies().get(light).unwrap().translation().x;
+        let heavy_x = world.bodies().get(heavy).unwrap().translation().x;
+        assert!(light_x > 2.5, "light prop at {}", light_x);
+        assert!(lanes[0].1.translation.x > 2.0);
+        assert!((heavy_x - 1.0).abs() < 0.05, "heavy prop at {}", heavy_x);
+        assert!(lanes[1].1.translation.x < heavy_x - 0.3);
+    }
+
+    #[test]
+    fn stays_crouched_under_a_low_ceiling() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        world.insert_static_collider(
+            ColliderBuilder::cuboid(1.0, 0.1, 2.0)
+                .translation(vector![2.5, 1.9, 0.0])
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+
+        let dt = 1.0 / 60.0;
+        let mut collision = CharacterCollision::new(CollisionProfile::arena_default());
+        let mut position = Isometry::translation(0.0, 1.32, 0.0);
+        let mut grounded = true;
+        let mut tick = |collision: &mut CharacterCollision,
+                        position: &mut Isometry<Real>,
+                        crouch: bool,
+                        dx: Real| {
+            let update = collision.update_crouch(&world, *position, crouch, grounded, dt);
+            let result = collision.move_character(
+                &world,
+                update.position,
+                vector![dx, -0.01, 0.0],
+                true,
+                dt,
+            );
+            *position = result.position;
+            grounded = result.grounded;
+            update
+        };
+
+        for _ in 0..20 {
+            tick(&mut collision, &mut position, true, 0.0);
+        }
+        assert!(collision.is_crouched());
+        assert!((collision.capsule_height() - 0.8).abs() < 1.0e-5);
+        for _ in 0..50 {
+            tick(&mut collision, &mut position, true, 0.05);
+        }
+        // Rises until the head meets the ceiling, then stays there.
+        let mut update = tick(&mut collision, &mut position, false, 0.0);
+        for _ in 0..10 {
+            update = tick(&mut collision, &mut position, false, 0.0);
+        }
+        assert!(update.stand_blocked);
+        assert!(update.crouched);
+        assert!(position.translation.y + collision.foot_offset() < 1.8);
+        assert!(!collision.can_stand(&world, position));
+        assert!(position.translation.y - collision.foot_offset() < 0.05);
+
+        for _ in 0..40 {
+            tick(&mut collision, &mut position, false, 0.05);
+        }
+        assert!(!collision.is_crouched());
+        assert!((position.translation.y - 1.32).abs() < 0.05);
+    }
+
+    #[test]
+    fn layer_mask_picks_which_clips_block() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        let clip = |x: Real, layer: u32| {
+            ColliderBuilder::cuboid(0.1, 2.0, 2.0)
+                .translation(vector![x, 2.0, 0.0])
+                .collision_groups(physics_rapier::layer_groups(layer, u32::MAX))
+                .build()
+        };
+        // Bits: world, player_clip, monster_clip.
+        world.insert_static_collider(clip(1.0, 0b100));
+        world.insert_static_collider(clip(3.0, 0b010));
+        world.step(1.0 / 60.0);
+
+        let mut profile = CollisionProfile::arena_default();
+        profile.layer_mask = 0b011;
+        let mut collision = CharacterCollision::new(profile);
+        let mut position = Isometry::translation(0.0, 1.32, 0.0);
+        for _ in 0..30 {
+            let result = collision.move_character(
+                &world,
+                position,
+                vector![0.2, 0.0, 0.0],
+                true,
+                1.0 / 60.0,
+            );
+            position = result.position;
+        }
+        let x = position.translation.x;
+        assert!(x > 1.1 && x < 2.9 - 0.39 + 0.01, "x {}", x);
+    }
 }
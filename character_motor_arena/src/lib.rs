@@ -1,9 +1,16 @@
 //! Arena movement motor (velocity intent only).
 #![forbid(unsafe_code)]
 
+use character_motor_common::{ladder_step, LadderTuning};
+use player_controller::{
+    find_param, InputIntent, Motor, MotorContext, MotorOutput, MotorParam, ParamUnit,
+    PlayerKinematics,
+};
 use rapier3d::math::Vector;
 use rapier3d::prelude::Real;
 
+pub use character_motor_common::{LadderInput, SurfaceInput};
+
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum FrictionlessJumpMode {
     None,
@@ -16,6 +23,8 @@ pub enum FrictionlessJumpMode {
 pub struct ArenaMotorConfig {
     pub max_speed_ground: Real,
     pub max_speed_air: Real,
+    /// Ground speed cap while crouched.
+    pub max_speed_crouch: Real,
     pub ground_accel: Real,
     pub air_accel: Real,
     pub friction: Real,
@@ -40,6 +49,23 @@ pub struct ArenaMotorConfig {
     pub frictionless_jump_grace: Real,
     pub frictionless_jump_friction_scale: Real,
     pub frictionless_jump_friction_scale_best_angle: Real,
+    /// Immersion level (0 dry, 1 submerged) at which the motor swims.
+    pub swim_level: Real,
+    pub max_speed_swim: Real,
+    pub swim_accel: Real,
+    /// Fraction of velocity lost per second while swimming.
+    pub swim_drag: Real,
+    /// Lift at full immersion as a multiple of gravity; above 1 floats.
+    pub swim_buoyancy: Real,
+    /// Jumping while swimming at or below this level leaps out of the liquid.
+    pub water_exit_level: Real,
+    pub water_exit_jump_speed: Real,
+    /// Climb speed on ladders in m/s.
+    pub ladder_speed: Real,
+    /// Speed away from the ladder when jumping off.
+    pub ladder_jump_impulse: Real,
+    /// Upward speed when jumping off a ladder.
+    pub ladder_jump_lift: Real,
 }
 
 impl Default for ArenaMotorConfig {
@@ -50,6 +76,7 @@ impl Default for ArenaMotorConfig {
         Self {
             max_speed_ground,
             max_speed_air,
+            max_speed_crouch: max_speed_ground * 0.5,
             ground_accel: 18.0,
             air_accel: 18.0,
             friction: 8.0,
@@ -74,6 +101,26 @@ impl Default for ArenaMotorConfig {
             frictionless_jump_grace: 0.1,
             frictionless_jump_friction_scale: 0.25,
             frictionless_jump_friction_scale_best_angle: 0.25,
+            swim_level: 0.5,
+            max_speed_swim: max_speed_ground * 0.7,
+            swim_accel: 10.0,
+            swim_drag: 2.5,
+            swim_buoyancy: 1.2,
+            water_exit_level: 0.7,
+            water_exit_jump_speed: 5.0,
+            ladder_speed: 3.0,
+            ladder_jump_impulse: 4.0,
+            ladder_jump_lift: 2.5,
+        }
+    }
+}
+
+impl ArenaMotorConfig {
+    pub fn ladder_tuning(&self) -> LadderTuning {
+        LadderTuning {
+            speed: self.ladder_speed,
+            jump_impulse: self.ladder_jump_impulse,
+            jump_lift: self.ladder_jump_lift,
         }
     }
 }
@@ -82,6 +129,15 @@ impl Default for ArenaMotorConfig {
 pub struct ArenaMotorInput {
     pub move_axis: [Real; 2],
     pub jump: bool,
+    pub crouched: bool,
+    /// How submerged the body is, 0 dry to 1 fully under.
+    pub immersion: Real,
+    /// Vertical swim input in -1..=1.
+    pub swim_up: Real,
+    /// Climbable surface the character is holding on to.
+    pub ladder: Option<LadderInput>,
+    /// Material of the ground underfoot.
+    pub surface: SurfaceInput,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -112,6 +168,14 @@ pub struct ArenaMotorOutput {
     pub jumped: bool,
 }
 
+/// Internal timers carried between steps (jump buffering and bhop grace).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ArenaMotorTimers {
+    pub jump_buffer_time: Real,
+    pub bhop_grace_time: Real,
+    pub was_grounded: bool,
+}
+
 pub struct ArenaMotor {
     config: ArenaMotorConfig,
     jump_buffer_time: Real,
@@ -143,6 +207,20 @@ impl ArenaMotor {
         self.was_grounded = false;
     }
 
+    pub fn timers(&self) -> ArenaMotorTimers {
+        ArenaMotorTimers {
+            jump_buffer_time: self.jump_buffer_time,
+            bhop_grace_time: self.bhop_grace_time,
+            was_grounded: self.was_grounded,
+        }
+    }
+
+    pub fn set_timers(&mut self, timers: ArenaMotorTimers) {
+        self.jump_buffer_time = timers.jump_buffer_time;
+        self.bhop_grace_time = timers.bhop_grace_time;
+        self.was_grounded = timers.was_grounded;
+    }
+
     pub fn step(
         &mut self,
         input: ArenaMotorInput,
@@ -150,13 +228,23 @@ impl ArenaMotor {
         dt: Real,
     ) -> ArenaMotorOutput {
         let dt = dt.max(0.0);
+        if let Some(ladder) = input.ladder {
+            return self.climb(ladder, input, state, dt);
+        }
+        if input.immersion >= self.config.swim_level {
+            return self.swim(input, state, dt);
+        }
         let move_intent = build_move_intent(
             state.yaw,
             input.move_axis,
             state.grounded,
             state.ground_normal,
         );
-        let max_speed = if state.grounded {
+        let max_speed = if state.grounded && input.crouched {
+            self.config
+                .max_speed_ground
+                .min(self.config.max_speed_crouch)
+        } else if state.grounded {
             self.config.max_speed_ground
         } else {
             self.config.max_speed_air
@@ -193,13 +281,16 @@ impl ArenaMotor {
         let mut planar = Vector::new(velocity.x, 0.0, velocity.z);
         if state.grounded {
             let friction_scale = self.friction_scale(input, golden_quality);
-            let friction = self.config.friction * friction_scale;
-            planar = apply_friction(planar, friction, self.config.stop_speed, dt);
+            let (friction, stop_speed) = input.surface.friction_tuning(
+                self.config.friction * friction_scale,
+                self.config.stop_speed,
+            );
+            planar = apply_friction(planar, friction, stop_speed, dt);
         }
 
         if move_intent_speed > 0.0 {
             let accel = if state.grounded {
-                self.config.ground_accel
+                self.config.ground_accel * input.surface.acceleration
             } else {
                 self.config.air_accel * golden_gain
             };
@@ -252,6 +343,70 @@ impl ArenaMotor {
         }
     }
 
+    /// Free 3D movement with drag and buoyancy; jumping near the surface
+    /// leaps out.
+    fn swim(
+        &mut self,
+        input: ArenaMotorInput,
+        state: ArenaMotorState,
+        dt: Real,
+    ) -> ArenaMotorOutput {
+        let config = self.config;
+        let forward = Vector::new(state.yaw.sin(), 0.0, -state.yaw.cos());
+        let right = Vector::new(state.yaw.cos(), 0.0, state.yaw.sin());
+        let wish = right * input.move_axis[0]
+            + forward * input.move_axis[1]
+            + Vector::y() * input.swim_up.clamp(-1.0, 1.0);
+        let mut velocity = state.velocity * (1.0 - config.swim_drag * dt).max(0.0);
+        velocity.y += (config.swim_buoyancy * input.immersion - 1.0) * config.gravity * dt;
+        let mag = wish.norm();
+        if mag > 0.0 {
+            let wish_speed = mag.min(1.0) * config.max_speed_swim;
+            velocity = accelerate(velocity, wish / mag, wish_speed, config.swim_accel, dt);
+        }
+        let mut jumped = false;
+        if input.jump
+            && input.immersion <= config.water_exit_level
+            && velocity.y < config.water_exit_jump_speed
+        {
+            velocity.y = config.water_exit_jump_speed;
+            jumped = true;
+        }
+        self.jump_buffer_time = 0.0;
+        self.bhop_grace_time = 0.0;
+        self.was_grounded = state.grounded;
+        ArenaMotorOutput {
+            desired_translation: velocity * dt,
+            next_velocity: velocity,
+            jumped,
+        }
+    }
+
+    /// Holds on to a ladder; gravity and jump buffering are suspended.
+    fn climb(
+        &mut self,
+        ladder: LadderInput,
+        input: ArenaMotorInput,
+        state: ArenaMotorState,
+        dt: Real,
+    ) -> ArenaMotorOutput {
+        let step = ladder_step(
+            self.config.ladder_tuning(),
+            ladder,
+            state.yaw,
+            input.move_axis,
+            input.jump,
+        );
+        self.jump_buffer_time = 0.0;
+        self.bhop_grace_time = 0.0;
+        self.was_grounded = state.grounded;
+        ArenaMotorOutput {
+            desired_translation: step.velocity * dt,
+            next_velocity: step.velocity,
+            jumped: step.jumped,
+        }
+    }
+
     fn friction_scale(&self, input: ArenaMotorInput, golden_quality: Real) -> Real {
         match self.config.frictionless_jump_mode {
             FrictionlessJumpMode::None => 1.0,
@@ -282,6 +437,241 @@ impl ArenaMotor {
     }
 }
 
+/// Tuning exposed through [`Motor::params`]; angles are in degrees.
+pub const ARENA_MOTOR_PARAMS: &[MotorParam] = &[
+    MotorParam::new(
+        "ground_max_speed",
+        ParamUnit::MetersPerSecond,
+        "Arena ground max speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "air_max_speed",
+        ParamUnit::MetersPerSecond,
+        "Arena air max speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "crouch_max_speed",
+        ParamUnit::MetersPerSecond,
+        "Arena crouched ground max speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "ground_accel",
+        ParamUnit::PerSecond,
+        "Arena ground acceleration.",
+    )
+    .at_least(0.0),
+    MotorParam::new("air_accel", ParamUnit::PerSecond, "Arena air acceleration.").at_least(0.0),
+    MotorParam::new("friction", ParamUnit::PerSecond, "Arena ground friction.").at_least(0.0),
+    MotorParam::new(
+        "stop_speed",
+        ParamUnit::MetersPerSecond,
+        "Arena speed below which friction stops the player.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "gravity",
+        ParamUnit::MetersPerSecondSquared,
+        "Arena gravity.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "jump_speed",
+        ParamUnit::MetersPerSecond,
+        "Arena jump speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "air_resistance",
+        ParamUnit::PerSecond,
+        "Arena air resistance (speed-scaled).",
+    )
+    .range(0.0, 10.0),
+    MotorParam::new(
+        "air_resistance_speed_scale",
+        ParamUnit::MetersPerSecond,
+        "Arena air resistance reaches full strength at this speed.",
+    )
+    .at_least(0.01),
+    MotorParam::new(
+        "golden_target_deg",
+        ParamUnit::Degrees,
+        "Golden angle target (degrees, view-forward relative).",
+    )
+    .range(0.0, 180.0),
+    MotorParam::new(
+        "golden_gain_min",
+        ParamUnit::Scale,
+        "Golden angle minimum gain.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "golden_gain_peak",
+        ParamUnit::Scale,
+        "Golden angle peak gain.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "golden_bonus_scale",
+        ParamUnit::Scale,
+        "Golden angle bonus scale for uncapped speed growth.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "golden_blend_start",
+        ParamUnit::MetersPerSecond,
+        "Golden angle blend start speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "golden_blend_end",
+        ParamUnit::MetersPerSecond,
+        "Golden angle blend end speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "cs_strength_deg",
+        ParamUnit::DegreesPerSecond,
+        "Corridor shaping strength (degrees/sec).",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "cs_min_speed",
+        ParamUnit::MetersPerSecond,
+        "Corridor shaping minimum speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "cs_max_angle_deg",
+        ParamUnit::Degrees,
+        "Corridor shaping max angle per tick (degrees).",
+    )
+    .range(0.0, 180.0),
+    MotorParam::new(
+        "cs_min_alignment",
+        ParamUnit::Scale,
+        "Corridor shaping minimum alignment (dot).",
+    )
+    .range(-1.0, 1.0),
+    MotorParam::new(
+        "jump_buffer_window",
+        ParamUnit::Seconds,
+        "Arena jump buffer window.",
+    )
+    .range(0.0, 1.0),
+    MotorParam::new(
+        "bhop_grace",
+        ParamUnit::Seconds,
+        "Arena reduced-friction window after a buffered landing jump.",
+    )
+    .range(0.0, 1.0),
+];
+
+impl Motor for ArenaMotor {
+    fn step(
+        &mut self,
+        input: &InputIntent,
+        state: &PlayerKinematics,
+        ctx: MotorContext,
+    ) -> MotorOutput {
+        let output = ArenaMotor::step(
+            self,
+            ArenaMotorInput {
+                move_axis: input.move_axis,
+                jump: input.jump,
+                crouched: state.crouched,
+                immersion: state.immersion_level(),
+                swim_up: input.move_up,
+                ladder: LadderInput::from_kinematics(state, ctx.pitch),
+                surface: SurfaceInput::from_kinematics(state),
+            },
+            ArenaMotorState {
+                velocity: state.velocity,
+                grounded: state.grounded,
+                ground_normal: state.ground_normal,
+                yaw: ctx.yaw,
+            },
+            ctx.dt,
+        );
+        MotorOutput {
+            desired_translation: output.desired_translation,
+            next_velocity: output.next_velocity,
+        }
+    }
+
+    fn params(&self) -> &'static [MotorParam] {
+        ARENA_MOTOR_PARAMS
+    }
+
+    fn param(&self, name: &str) -> Option<Real> {
+        let config = &self.config;
+        Some(match name {
+            "ground_max_speed" => config.max_speed_ground,
+            "air_max_speed" => config.max_speed_air,
+            "crouch_max_speed" => config.max_speed_crouch,
+            "ground_accel" => config.ground_accel,
+            "air_accel" => config.air_accel,
+            "friction" => config.friction,
+            "stop_speed" => config.stop_speed,
+            "gravity" => config.gravity,
+            "jump_speed" => config.jump_speed,
+            "air_resistance" => config.air_resistance,
+            "air_resistance_speed_scale" => config.air_resistance_speed_scale,
+            "golden_target_deg" => config.golden_angle_target.to_degrees(),
+            "golden_gain_min" => config.golden_angle_gain_min,
+            "golden_gain_peak" => config.golden_angle_gain_peak,
+            "golden_bonus_scale" => config.golden_angle_bonus_scale,
+            "golden_blend_start" => config.golden_angle_blend_speed_start,
+            "golden_blend_end" => config.golden_angle_blend_speed_end,
+            "cs_strength_deg" => config.corridor_shaping_strength.to_degrees(),
+            "cs_min_speed" => config.corridor_shaping_min_speed,
+            "cs_max_angle_deg" => config.corridor_shaping_max_angle_per_tick.to_degrees(),
+            "cs_min_alignment" => config.corridor_shaping_min_alignment,
+            "jump_buffer_window" => config.jump_buffer_window,
+            "bhop_grace" => config.frictionless_jump_grace,
+            _ => return None,
+        })
+    }
+
+    fn set_param(&mut self, name: &str, value: Real) -> Result<(), String> {
+        let value = find_param(ARENA_MOTOR_PARAMS, name)?.clamp(value);
+        let config = &mut self.config;
+        match name {
+            "ground_max_speed" => config.max_speed_ground = value,
+            "air_max_speed" => config.max_speed_air = value,
+            "crouch_max_speed" => config.max_speed_crouch = value,
+            "ground_accel" => config.ground_accel = value,
+            "air_accel" => config.air_accel = value,
+            "friction" => config.friction = value,
+            "stop_speed" => config.stop_speed = value,
+            "gravity" => config.gravity = value,
+            "jump_speed" => config.jump_speed = value,
+            "air_resistance" => config.air_resistance = value,
+            "air_resistance_speed_scale" => config.air_resistance_speed_scale = value,
+            "golden_target_deg" => config.golden_angle_target = value.to_radians(),
+            "golden_gain_min" => config.golden_angle_gain_min = value,
+            "golden_gain_peak" => config.golden_angle_gain_peak = value,
+            "golden_bonus_scale" => config.golden_angle_bonus_scale = value,
+            "golden_blend_start" => config.golden_angle_blend_speed_start = value,
+            "golden_blend_end" => config.golden_angle_blend_speed_end = value,
+            "cs_strength_deg" => config.corridor_shaping_strength = value.to_radians(),
+            "cs_min_speed" => config.corridor_shaping_min_speed = value,
+            "cs_max_angle_deg" => config.corridor_shaping_max_angle_per_tick = value.to_radians(),
+            "cs_min_alignment" => config.corridor_shaping_min_alignment = value,
+            "jump_buffer_window" => config.jump_buffer_window = value,
+            "bhop_grace" => config.frictionless_jump_grace = value,
+            _ => unreachable!("param {} is listed but not handled", name),
+        }
+        Ok(())
+    }
+
+    fn reset_state(&mut self) {
+        ArenaMotor::reset_state(self);
+    }
+}
+
 pub fn build_move_intent(
     yaw: Real,
     axis: [Real; 2],
@@ -453,6 +843,8 @@ mod tests {
         let input = ArenaMotorInput {
             move_axis: [0.0, 0.0],
             jump: true,
+            crouched: false,
+            ..Default::default()
         };
         let output = motor.step(input, state, 0.05);
         assert!(!output.jumped);
@@ -462,6 +854,8 @@ mod tests {
             ArenaMotorInput {
                 move_axis: [0.0, 0.0],
                 jump: false,
+                crouched: false,
+                ..Default::default()
             },
             state,
             0.05,
@@ -489,6 +883,8 @@ mod tests {
             ArenaMotorInput {
                 move_axis: [0.0, 0.0],
                 jump: false,
+                crouched: false,
+                ..Default::default()
             },
             state,
             0.1,
@@ -497,6 +893,8 @@ mod tests {
             ArenaMotorInput {
                 move_axis: [0.0, 0.0],
                 jump: true,
+                crouched: false,
+                ..Default::default()
             },
             state,
             0.1,
@@ -504,4 +902,117 @@ mod tests {
 
         assert!(output_jump.next_velocity.x.abs() > output_no_jump.next_velocity.x.abs());
     }
+
+    #[test]
+    fn ice_surface_keeps_sliding_and_accelerates_slowly() {
+        let mut motor = ArenaMotor::new(ArenaMotorConfig::default());
+        let ice = SurfaceInput {
+            friction: 0.1,
+            acceleration: 0.25,
+            slide: true,
+        };
+        let coast = |motor: &mut ArenaMotor, surface| {
+            let mut state = ArenaMotorState {
+                velocity: Vector::new(2.0, 0.0, 0.0),
+                grounded: true,
+                ground_normal: None,
+                yaw: 0.0,
+            };
+            for _ in 0..60 {
+                let input = ArenaMotorInput {
+                    surface,
+                    ..Default::default()
+                };
+                state.velocity = motor.step(input, state, 1.0 / 60.0).next_velocity;
+            }
+            state.velocity.x
+        };
+        assert_eq!(coast(&mut motor, SurfaceInput::default()), 0.0);
+        assert!(coast(&mut motor, ice) > 0.8);
+
+        let state = ArenaMotorState {
+            velocity: Vector::zeros(),
+            grounded: true,
+            ground_normal: None,
+            yaw: 0.0,
+        };
+        let push = |surface| ArenaMotorInput {
+            move_axis: [0.0, 1.0],
+            surface,
+            ..Default::default()
+        };
+        let stone = motor.step(push(SurfaceInput::default()), state, 0.05);
+        let slick = motor.step(push(ice), state, 0.05);
+        assert!(slick.next_velocity.norm() < stone.next_velocity.norm() * 0.5);
+    }
+
+    #[test]
+    fn crouching_caps_ground_speed() {
+        let mut motor = ArenaMotor::new(ArenaMotorConfig::default());
+        let mut state = ArenaMotorState {
+            velocity: Vector::zeros(),
+            grounded: true,
+            ground_normal: None,
+            yaw: 0.0,
+        };
+        let input = ArenaMotorInput {
+            move_axis: [0.0, 1.0],
+            jump: false,
+            crouched: true,
+            ..Default::default()
+        };
+        for _ in 0..120 {
+            state.velocity = motor.step(input, state, 1.0 / 60.0).next_velocity;
+        }
+        let config = motor.config();
+        assert!((state.velocity.norm() - config.max_speed_crouch).abs() < 0.05);
+
+        state.grounded = false;
+        let air = motor.step(input, state, 1.0 / 60.0).next_velocity;
+        assert!(Vector::new(air.x, 0.0, air.z).norm() > config.max_speed_crouch);
+    }
+
+    #[test]
+    fn swimming_floats_then_jumps_out() {
+        let mut motor = ArenaMotor::new(ArenaMotorConfig::default());
+        let config = motor.config();
+        let height = 1.8;
+        let mut feet = -3.0;
+        let mut state = ArenaMotorState {
+            velocity: Vector::zeros(),
+            grounded: false,
+            ground_normal: None,
+            yaw: 0.0,
+        };
+        let level = |feet: Real| (-feet / height).clamp(0.0, 1.0);
+        for _ in 0..600 {
+            let input = ArenaMotorInput {
+                immersion: level(feet),
+                ..Default::default()
+            };
+            let output = motor.step(input, state, 1.0 / 60.0);
+            feet += output.desired_translation.y;
+            state.velocity = output.next_velocity;
+        }
+        assert!((level(feet) - 1.0 / config.swim_buoyancy).abs() < 0.05);
+
+        let mut jumped = false;
+        for _ in 0..120 {
+            let input = ArenaMotorInput {
+                jump: true,
+                swim_up: 1.0,
+                immersion: level(feet),
+                ..Default::default()
+            };
+            let output = motor.step(input, state, 1.0 / 60.0);
+            feet += output.desired_translation.y;
+            state.velocity = output.next_velocity;
+            if output.jumped {
+                jumped = true;
+                break;
+            }
+        }
+        assert!(jumped);
+        assert!(state.velocity.y >= config.water_exit_jump_speed);
+    }
 }
@@ -0,0 +1,162 @@
+//! Movement modes shared by the arena and RPG motors.
+#![forbid(unsafe_code)]
+
+use player_controller::PlayerKinematics;
+use rapier3d::math::Vector;
+use rapier3d::prelude::Real;
+
+/// Looking this far below the horizon climbs down at full speed; level or
+/// higher climbs up.
+const LADDER_DESCEND_PITCH: Real = 30.0 * std::f32::consts::PI / 180.0;
+/// Speed pressing the character into the ladder so contact is kept.
+const LADDER_GRIP_SPEED: Real = 0.5;
+
+/// A climbable surface the character is holding on to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LadderInput {
+    /// Horizontal unit normal pointing from the surface toward the character.
+    pub normal: Vector<Real>,
+    /// Look pitch in radians, positive up.
+    pub pitch: Real,
+}
+
+impl LadderInput {
+    /// The ladder the controller reports the player holding, if any.
+    pub fn from_kinematics(state: &PlayerKinematics, pitch: Real) -> Option<Self> {
+        state.ladder.map(|contact| Self {
+            normal: contact.normal,
+            pitch,
+        })
+    }
+}
+
+/// Ground tuning of the surface the character stands on, as multipliers on
+/// the motor's own friction and acceleration.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfaceInput {
+    pub friction: Real,
+    pub acceleration: Real,
+    /// Friction only scales speed down, without the stop-speed floor that
+    /// brings slow movement to a halt.
+    pub slide: bool,
+}
+
+impl Default for SurfaceInput {
+    fn default() -> Self {
+        Self {
+            friction: 1.0,
+            acceleration: 1.0,
+            slide: false,
+        }
+    }
+}
+
+impl SurfaceInput {
+    /// Tuning of the ground the controller reports under the player.
+    pub fn from_kinematics(state: &PlayerKinematics) -> Self {
+        state
+            .ground_surface
+            .as_ref()
+            .map_or_else(Self::default, |surface| Self {
+                friction: surface.friction,
+                acceleration: surface.acceleration,
+                slide: surface.slide,
+            })
+    }
+
+    /// Friction and stop speed to apply on this surface.
+    pub fn friction_tuning(self, friction: Real, stop_speed: Real) -> (Real, Real) {
+        let stop_speed = if self.slide { 0.0 } else { stop_speed };
+        (friction * self.friction.max(0.0), stop_speed)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct LadderTuning {
+    /// Climb and strafe speed in m/s.
+    pub speed: Real,
+    /// Speed away from the surface when jumping off.
+    pub jump_impulse: Real,
+    /// Upward speed when jumping off.
+    pub jump_lift: Real,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct LadderStep {
+    pub velocity: Vector<Real>,
+    pub jumped: bool,
+}
+
+/// Gravity-free ladder movement: forward input climbs along the look pitch,
+/// strafing slides across the surface, and jumping pushes off it.
+pub fn ladder_step(
+    tuning: LadderTuning,
+    ladder: LadderInput,
+    yaw: Real,
+    move_axis: [Real; 2],
+    jump: bool,
+) -> LadderStep {
+    let normal = ladder.normal;
+    if jump {
+        return LadderStep {
+            velocity: normal * tuning.jump_impulse + Vector::y() * tuning.jump_lift,
+            jumped: true,
+        };
+    }
+    let right = Vector::new(yaw.cos(), 0.0, yaw.sin());
+    let across = Vector::y().cross(&normal);
+    let climb = move_axis[1] * climb_direction(ladder.pitch);
+    let strafe = move_axis[0] * right.dot(&across);
+    let wish = Vector::y() * climb + across * strafe;
+    let wish = if wish.norm_squared() > 1.0 {
+        wish.normalize()
+    } else {
+        wish
+    };
+    LadderStep {
+        velocity: wish * tuning.speed - normal * LADDER_GRIP_SPEED,
+        jumped: false,
+    }
+}
+
+/// Maps look pitch to a climb direction: 1 up, -1 down.
+fn climb_direction(pitch: Real) -> Real {
+    (1.0 + 2.0 * pitch / LADDER_DESCEND_PITCH).clamp(-1.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TUNING: LadderTuning = LadderTuning {
+        speed: 3.0,
+        jump_impulse: 4.0,
+        jump_lift: 2.0,
+    };
+
+    #[test]
+    fn ladder_climbs_with_pitch_and_jumps_off() {
+        // Facing -Z at a ladder whose surface faces +Z.
+        let ladder = |pitch| LadderInput {
+            normal: Vector::z(),
+            pitch,
+        };
+        let up = ladder_step(TUNING, ladder(0.2), 0.0, [0.0, 1.0], false);
+        assert!((up.velocity.y - 3.0).abs() < 1.0e-5);
+        assert!(up.velocity.z < 0.0);
+        assert!(!up.jumped);
+
+        let down = ladder_step(TUNING, ladder(-0.8), 0.0, [0.0, 1.0], false);
+        assert!((down.velocity.y + 3.0).abs() < 1.0e-5);
+
+        let idle = ladder_step(TUNING, ladder(0.0), 0.0, [0.0, 0.0], false);
+        assert_eq!(idle.velocity.y, 0.0);
+
+        let strafe = ladder_step(TUNING, ladder(0.0), 0.0, [1.0, 0.0], false);
+        assert!((strafe.velocity.x - 3.0).abs() < 1.0e-5);
+
+        let off = ladder_step(TUNING, ladder(0.0), 0.0, [0.0, 1.0], true);
+        assert!(off.jumped);
+        assert_eq!(off.velocity, Vector::new(0.0, 2.0, 4.0));
+    }
+}
@@ -1,13 +1,24 @@
 //! RPG movement motor (stability-first velocity intent).
 #![forbid(unsafe_code)]
 
+use character_collision::LedgeQuery;
+use character_motor_common::{ladder_step, LadderTuning};
+use player_camera::CameraMode;
+use player_controller::{
+    find_param, InputIntent, Motor, MotorContext, MotorOutput, MotorParam, ParamUnit,
+    PlayerKinematics,
+};
 use rapier3d::math::Vector;
 use rapier3d::prelude::Real;
 
+pub use character_motor_common::{LadderInput, SurfaceInput};
+
 #[derive(Clone, Copy, Debug)]
 pub struct RpgMotorConfig {
     pub max_speed_ground: Real,
     pub max_speed_air: Real,
+    /// Ground speed cap while crouched.
+    pub max_speed_crouch: Real,
     pub ground_accel: Real,
     pub air_accel: Real,
     pub friction: Real,
@@ -17,8 +28,38 @@ pub struct RpgMotorConfig {
     pub air_control_scale: Real,
     /// Smoothing time constant in seconds (0 disables).
     pub input_smoothing: Real,
-    /// Max turn rate in radians/sec (0 disables).
+    /// Max turn rate in radians/sec (0 disables). Only applied with
+    /// `RpgMotorInput::limit_turn`, where the body is seen turning.
     pub turn_rate: Real,
+    /// Immersion level (0 dry, 1 submerged) at which the motor swims.
+    pub swim_level: Real,
+    pub max_speed_swim: Real,
+    pub swim_accel: Real,
+    /// Fraction of velocity lost per second while swimming.
+    pub swim_drag: Real,
+    /// Lift at full immersion as a multiple of gravity; above 1 floats.
+    pub swim_buoyancy: Real,
+    /// Jumping while swimming at or below this level climbs out.
+    pub water_exit_level: Real,
+    pub water_exit_jump_speed: Real,
+    /// Climb speed on ladders in m/s.
+    pub ladder_speed: Real,
+    /// Speed away from the ladder when jumping off.
+    pub ladder_jump_impulse: Real,
+    /// Upward speed when jumping off a ladder.
+    pub ladder_jump_lift: Real,
+    /// Climb onto ledges above `step_height` (jump at the wall, or hold
+    /// forward while airborne).
+    pub mantle_enabled: bool,
+    /// Ledge heights above the feet that can be mantled, in meters.
+    pub mantle_min_height: Real,
+    pub mantle_max_height: Real,
+    /// How far ahead the wall below a ledge may be, in meters.
+    pub mantle_reach: Real,
+    /// Largest angle between the view and the wall, in radians.
+    pub mantle_facing_angle: Real,
+    /// Seconds from grabbing the ledge to standing on it.
+    pub mantle_duration: Real,
 }
 
 impl Default for RpgMotorConfig {
@@ -26,6 +67,7 @@ impl Default for RpgMotorConfig {
         Self {
             max_speed_ground: 3.5,
             max_speed_air: 2.5,
+            max_speed_crouch: 1.5,
             ground_accel: 10.0,
             air_accel: 3.0,
             friction: 8.0,
@@ -35,6 +77,32 @@ impl Default for RpgMotorConfig {
             air_control_scale: 0.25,
             input_smoothing: 0.08,
             turn_rate: 0.0,
+            swim_level: 0.5,
+            max_speed_swim: 2.0,
+            swim_accel: 6.0,
+            swim_drag: 3.0,
+            swim_buoyancy: 1.15,
+            water_exit_level: 0.7,
+            water_exit_jump_speed: 4.5,
+            ladder_speed: 2.0,
+            ladder_jump_impulse: 3.0,
+            ladder_jump_lift: 2.0,
+            mantle_enabled: true,
+            mantle_min_height: 0.5,
+            mantle_max_height: 1.6,
+            mantle_reach: 0.5,
+            mantle_facing_angle: 35.0_f32.to_radians(),
+            mantle_duration: 0.6,
+        }
+    }
+}
+
+impl RpgMotorConfig {
+    pub fn ladder_tuning(&self) -> LadderTuning {
+        LadderTuning {
+            speed: self.ladder_speed,
+            jump_impulse: self.ladder_jump_impulse,
+            jump_lift: self.ladder_jump_lift,
         }
     }
 }
@@ -43,6 +111,51 @@ impl Default for RpgMotorConfig {
 pub struct RpgMotorInput {
     pub move_axis: [Real; 2],
     pub jump: bool,
+    pub crouched: bool,
+    /// How submerged the body is, 0 dry to 1 fully under.
+    pub immersion: Real,
+    /// Vertical swim input in -1..=1.
+    pub swim_up: Real,
+    /// Climbable surface the character is holding on to.
+    pub ladder: Option<LadderInput>,
+    /// Mantleable ledge ahead.
+    pub ledge: Option<LedgeInput>,
+    /// Material of the ground underfoot.
+    pub surface: SurfaceInput,
+    /// Limit direction changes to `turn_rate`; hosts set this for
+    /// third-person views.
+    pub limit_turn: bool,
+}
+
+/// A ledge found by the host's ledge detection.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LedgeInput {
+    /// Displacement from the character to where it stands on the ledge.
+    pub offset: Vector<Real>,
+}
+
+/// A mantle in progress: a timed path that rises in place, then moves
+/// across onto the ledge. The host has checked the path is clear.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Mantle {
+    pub elapsed: Real,
+    pub offset: Vector<Real>,
+}
+
+impl Mantle {
+    /// Displacement from the mantle start at progress `t` in 0..=1.
+    pub fn path_point(&self, t: Real) -> Vector<Real> {
+        let rise = self.offset.y.max(0.0);
+        let across = Vector::new(self.offset.x, self.offset.y - rise, self.offset.z);
+        let rise_share = rise / (rise + across.norm()).max(1.0e-6);
+        let t = t.clamp(0.0, 1.0);
+        if t < rise_share {
+            Vector::y() * rise * (t / rise_share)
+        } else {
+            let across_t = (t - rise_share) / (1.0 - rise_share).max(1.0e-6);
+            Vector::y() * rise + across * across_t.min(1.0)
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -69,6 +182,7 @@ pub struct RpgMotorOutput {
 pub struct RpgMotor {
     config: RpgMotorConfig,
     smoothed_axis: [Real; 2],
+    mantle: Option<Mantle>,
 }
 
 impl RpgMotor {
@@ -76,6 +190,7 @@ impl RpgMotor {
         Self {
             config,
             smoothed_axis: [0.0, 0.0],
+            mantle: None,
         }
     }
 
@@ -89,15 +204,66 @@ impl RpgMotor {
 
     pub fn reset_state(&mut self) {
         self.smoothed_axis = [0.0, 0.0];
+        self.mantle = None;
+    }
+
+    pub fn mantle(&self) -> Option<Mantle> {
+        self.mantle
+    }
+
+    pub fn set_mantle(&mut self, mantle: Option<Mantle>) {
+        self.mantle = mantle;
+    }
+
+    pub fn smoothed_axis(&self) -> [Real; 2] {
+        self.smoothed_axis
+    }
+
+    pub fn set_smoothed_axis(&mut self, axis: [Real; 2]) {
+        self.smoothed_axis = axis;
     }
 
     pub fn step(&mut self, input: RpgMotorInput, state: RpgMotorState, dt: Real) -> RpgMotorOutput {
         let dt = dt.max(0.0);
+        if let Some(mantle) = self.mantle {
+            return self.advance_mantle(mantle, dt);
+        }
         let axis = normalize_axis(input.move_axis);
         let axis = self.apply_input_smoothing(axis, dt);
+        if let Some(ladder) = input.ladder {
+            let step = ladder_step(
+                self.config.ladder_tuning(),
+                ladder,
+                state.yaw,
+                axis,
+                input.jump,
+            );
+            return RpgMotorOutput {
+                desired_translation: step.velocity * dt,
+                next_velocity: step.velocity,
+                jumped: step.jumped,
+            };
+        }
+        if let Some(ledge) = input.ledge {
+            let grab = input.jump || (!state.grounded && axis[1] > 0.0);
+            if self.config.mantle_enabled && grab {
+                let mantle = Mantle {
+                    elapsed: 0.0,
+                    offset: ledge.offset,
+                };
+                return self.advance_mantle(mantle, dt);
+            }
+        }
+        if input.immersion >= self.config.swim_level {
+            return self.swim(axis, input, state, dt);
+        }
         let mut intent = build_move_intent(state.yaw, axis, state.grounded, state.ground_normal);
 
-        let max_speed = if state.grounded {
+        let max_speed = if state.grounded && input.crouched {
+            self.config
+                .max_speed_ground
+                .min(self.config.max_speed_crouch)
+        } else if state.grounded {
             self.config.max_speed_ground
         } else {
             self.config.max_speed_air
@@ -114,24 +280,24 @@ impl RpgMotor {
             Vector::new(velocity.x, 0.0, velocity.z)
         };
 
-        if self.config.turn_rate > 0.0 && intent.mag > 0.0 {
+        if input.limit_turn && self.config.turn_rate > 0.0 && intent.mag > 0.0 {
             let max_turn = self.config.turn_rate.max(0.0) * dt;
             intent.dir = apply_turn_limit(planar, intent.dir, max_turn);
         }
 
         if state.grounded {
-            let (friction, stop_speed) = slope_friction_tuning(
-                self.config.friction,
-                self.config.stop_speed,
-                state.ground_normal,
-            );
+            let (friction, stop_speed) = input
+                .surface
+                .friction_tuning(self.config.friction, self.config.stop_speed);
+            let (friction, stop_speed) =
+                slope_friction_tuning(friction, stop_speed, state.ground_normal);
             planar = apply_friction(planar, friction, stop_speed, dt);
         }
 
         if intent.mag > 0.0 && control_scale > 0.0 {
             let wish_speed = intent.mag * max_speed * control_scale;
             let accel = if state.grounded {
-                self.config.ground_accel
+                self.config.ground_accel * input.surface.acceleration
             } else {
                 self.config.air_accel * control_scale
             };
@@ -171,6 +337,64 @@ impl RpgMotor {
         }
     }
 
+    /// Smoothed 3D swimming with drag and buoyancy; jumping near the surface
+    /// climbs out.
+    fn swim(
+        &self,
+        axis: [Real; 2],
+        input: RpgMotorInput,
+        state: RpgMotorState,
+        dt: Real,
+    ) -> RpgMotorOutput {
+        let config = self.config;
+        let forward = Vector::new(state.yaw.sin(), 0.0, -state.yaw.cos());
+        let right = Vector::new(state.yaw.cos(), 0.0, state.yaw.sin());
+        let wish =
+            right * axis[0] + forward * axis[1] + Vector::y() * input.swim_up.clamp(-1.0, 1.0);
+        let mut velocity = state.velocity * (1.0 - config.swim_drag * dt).max(0.0);
+        velocity.y += (config.swim_buoyancy * input.immersion - 1.0) * config.gravity * dt;
+        let mag = wish.norm();
+        if mag > 0.0 {
+            let wish_speed = mag.min(1.0) * config.max_speed_swim;
+            velocity = accelerate(velocity, wish / mag, wish_speed, config.swim_accel, dt);
+        }
+        let mut jumped = false;
+        if input.jump
+            && input.immersion <= config.water_exit_level
+            && velocity.y < config.water_exit_jump_speed
+        {
+            velocity.y = config.water_exit_jump_speed;
+            jumped = true;
+        }
+        RpgMotorOutput {
+            desired_translation: velocity * dt,
+            next_velocity: velocity,
+            jumped,
+        }
+    }
+
+    /// Moves along the mantle path; the character comes to rest on the ledge
+    /// when the duration runs out.
+    fn advance_mantle(&mut self, mut mantle: Mantle, dt: Real) -> RpgMotorOutput {
+        let duration = self.config.mantle_duration.max(1.0e-3);
+        let from = mantle.path_point(mantle.elapsed / duration);
+        mantle.elapsed = (mantle.elapsed + dt).min(duration);
+        let to = mantle.path_point(mantle.elapsed / duration);
+        let finished = mantle.elapsed >= duration;
+        self.mantle = (!finished).then_some(mantle);
+        let translation = to - from;
+        let next_velocity = if finished || dt <= 0.0 {
+            Vector::zeros()
+        } else {
+            translation / dt
+        };
+        RpgMotorOutput {
+            desired_translation: translation,
+            next_velocity,
+            jumped: false,
+        }
+    }
+
     fn apply_input_smoothing(&mut self, axis: [Real; 2], dt: Real) -> [Real; 2] {
         if self.config.input_smoothing <= 0.0 {
             self.smoothed_axis = axis;
@@ -184,6 +408,209 @@ impl RpgMotor {
     }
 }
 
+/// Tuning exposed through [`Motor::params`]; angles are in degrees.
+pub const RPG_MOTOR_PARAMS: &[MotorParam] = &[
+    MotorParam::new(
+        "ground_max_speed",
+        ParamUnit::MetersPerSecond,
+        "RPG ground max speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "air_max_speed",
+        ParamUnit::MetersPerSecond,
+        "RPG air max speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "crouch_max_speed",
+        ParamUnit::MetersPerSecond,
+        "RPG crouched ground max speed.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "ground_accel",
+        ParamUnit::PerSecond,
+        "RPG ground acceleration.",
+    )
+    .at_least(0.0),
+    MotorParam::new("air_accel", ParamUnit::PerSecond, "RPG air acceleration.").at_least(0.0),
+    MotorParam::new("friction", ParamUnit::PerSecond, "RPG ground friction.").at_least(0.0),
+    MotorParam::new(
+        "stop_speed",
+        ParamUnit::MetersPerSecond,
+        "RPG speed below which friction stops the player.",
+    )
+    .at_least(0.0),
+    MotorParam::new("gravity", ParamUnit::MetersPerSecondSquared, "RPG gravity.").at_least(0.0),
+    MotorParam::new("jump_speed", ParamUnit::MetersPerSecond, "RPG jump speed.").at_least(0.0),
+    MotorParam::new(
+        "air_control_scale",
+        ParamUnit::Scale,
+        "RPG share of ground steering kept in the air.",
+    )
+    .range(0.0, 1.0),
+    MotorParam::new(
+        "input_smoothing",
+        ParamUnit::Seconds,
+        "RPG input smoothing time constant (0 disables).",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "turn_rate_deg",
+        ParamUnit::DegreesPerSecond,
+        "RPG third-person turn rate (0 disables).",
+    )
+    .at_least(0.0),
+    MotorParam::flag(
+        "mantle_enabled",
+        "RPG motor climbs onto ledges above step height.",
+    ),
+    MotorParam::new(
+        "mantle_min_height",
+        ParamUnit::Meters,
+        "RPG mantle minimum ledge height above the feet.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "mantle_max_height",
+        ParamUnit::Meters,
+        "RPG mantle maximum ledge height above the feet.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "mantle_reach",
+        ParamUnit::Meters,
+        "RPG mantle distance to the wall below a ledge.",
+    )
+    .at_least(0.0),
+    MotorParam::new(
+        "mantle_facing_deg",
+        ParamUnit::Degrees,
+        "RPG mantle facing tolerance (degrees).",
+    )
+    .range(0.0, 90.0),
+    MotorParam::new(
+        "mantle_duration",
+        ParamUnit::Seconds,
+        "RPG mantle duration (seconds).",
+    )
+    .range(0.05, 5.0),
+];
+
+impl Motor for RpgMotor {
+    fn step(
+        &mut self,
+        input: &InputIntent,
+        state: &PlayerKinematics,
+        ctx: MotorContext,
+    ) -> MotorOutput {
+        let output = RpgMotor::step(
+            self,
+            RpgMotorInput {
+                move_axis: input.move_axis,
+                jump: input.jump,
+                crouched: state.crouched,
+                immersion: state.immersion_level(),
+                swim_up: input.move_up,
+                ladder: LadderInput::from_kinematics(state, ctx.pitch),
+                ledge: state.ledge.map(|ledge| LedgeInput {
+                    offset: ledge.target.translation.vector - state.position.translation.vector,
+                }),
+                surface: SurfaceInput::from_kinematics(state),
+                limit_turn: ctx.camera_mode == CameraMode::ThirdPerson,
+            },
+            RpgMotorState {
+                velocity: state.velocity,
+                grounded: state.grounded,
+                ground_normal: state.ground_normal,
+                yaw: ctx.yaw,
+            },
+            ctx.dt,
+        );
+        MotorOutput {
+            desired_translation: output.desired_translation,
+            next_velocity: output.next_velocity,
+        }
+    }
+
+    fn ledge_query(&self) -> Option<LedgeQuery> {
+        let config = &self.config;
+        config.mantle_enabled.then_some(LedgeQuery {
+            reach: config.mantle_reach,
+            min_height: config.mantle_min_height,
+            max_height: config.mantle_max_height,
+            max_facing_angle: config.mantle_facing_angle,
+        })
+    }
+
+    fn params(&self) -> &'static [MotorParam] {
+        RPG_MOTOR_PARAMS
+    }
+
+    fn param(&self, name: &str) -> Option<Real> {
+        let config = &self.config;
+        Some(match name {
+            "ground_max_speed" => config.max_speed_ground,
+            "air_max_speed" => config.max_speed_air,
+            "crouch_max_speed" => config.max_speed_crouch,
+            "ground_accel" => config.ground_accel,
+            "air_accel" => config.air_accel,
+            "friction" => config.friction,
+            "stop_speed" => config.stop_speed,
+            "gravity" => config.gravity,
+            "jump_speed" => config.jump_speed,
+            "air_control_scale" => config.air_control_scale,
+            "input_smoothing" => config.input_smoothing,
+            "turn_rate_deg" => config.turn_rate.to_degrees(),
+            "mantle_enabled" => {
+                if config.mantle_enabled {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            "mantle_min_height" => config.mantle_min_height,
+            "mantle_max_height" => config.mantle_max_height,
+            "mantle_reach" => config.mantle_reach,
+            "mantle_facing_deg" => config.mantle_facing_angle.to_degrees(),
+            "mantle_duration" => config.mantle_duration,
+            _ => return None,
+        })
+    }
+
+    fn set_param(&mut self, name: &str, value: Real) -> Result<(), String> {
+        let value = find_param(RPG_MOTOR_PARAMS, name)?.clamp(value);
+        let config = &mut self.config;
+        match name {
+            "ground_max_speed" => config.max_speed_ground = value,
+            "air_max_speed" => config.max_speed_air = value,
+            "crouch_max_speed" => config.max_speed_crouch = value,
+            "ground_accel" => config.ground_accel = value,
+            "air_accel" => config.air_accel = value,
+            "friction" => config.friction = value,
+            "stop_speed" => config.stop_speed = value,
+            "gravity" => config.gravity = value,
+            "jump_speed" => config.jump_speed = value,
+            "air_control_scale" => config.air_control_scale = value,
+            "input_smoothing" => config.input_smoothing = value,
+            "turn_rate_deg" => config.turn_rate = value.to_radians(),
+            "mantle_enabled" => config.mantle_enabled = value > 0.5,
+            "mantle_min_height" => config.mantle_min_height = value,
+            "mantle_max_height" => config.mantle_max_height = value,
+            "mantle_reach" => config.mantle_reach = value,
+            "mantle_facing_deg" => config.mantle_facing_angle = value.to_radians(),
+            "mantle_duration" => config.mantle_duration = value,
+            _ => unreachable!("param {} is listed but not handled", name),
+        }
+        Ok(())
+    }
+
+    fn reset_state(&mut self) {
+        RpgMotor::reset_state(self);
+    }
+}
+
 fn normalize_axis(axis: [Real; 2]) -> [Real; 2] {
     let len = (axis[0] * axis[0] + axis[1] * axis[1]).sqrt();
     if len > 1.0 {
@@ -0,0 +1,269 @@
+//! Named collision layers and the content tags that select them.
+//!
+//! Each layer owns one bit, in table order. A collider's memberships are its
+//! layer bit; its filter is the layers it collides with. Query masks name the
+//! layers a mover or trace should hit.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::CollisionChunk;
+
+const COLLISION_LAYERS_VERSION: u32 = 1;
+const MAX_LAYERS: usize = 32;
+
+/// Layers every engine table is expected to define.
+pub const BUILTIN_LAYERS: [&str; 6] = [
+    "world",
+    "player_clip",
+    "monster_clip",
+    "trigger",
+    "projectile",
+    "water",
+];
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CollisionLayerTable {
+    pub version: u32,
+    /// Layer for untagged solids and chunks without a `layer`.
+    pub default_layer: String,
+    pub layers: Vec<CollisionLayerSpec>,
+    /// Content tag to layer name; the first mapped tag on a solid wins.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// Named query masks, e.g. `player = ["world", "player_clip"]`.
+    #[serde(default)]
+    pub masks: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct CollisionLayerSpec {
+    pub name: String,
+    /// Layers this one collides with; omitted means every layer.
+    #[serde(default)]
+    pub collides_with: Option<Vec<String>>,
+}
+
+/// Raw interaction bits, in the layout Rapier's `InteractionGroups` expects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CollisionGroups {
+    pub memberships: u32,
+    pub filter: u32,
+}
+
+impl CollisionGroups {
+    pub const ALL: CollisionGroups = CollisionGroups {
+        memberships: u32::MAX,
+        filter: u32::MAX,
+    };
+}
+
+impl Default for CollisionLayerTable {
+    fn default() -> Self {
+        let layers = BUILTIN_LAYERS
+            .iter()
+            .map(|name| CollisionLayerSpec {
+                name: name.to_string(),
+                collides_with: None,
+            })
+            .collect();
+        let tags = ["player_clip", "monster_clip", "trigger", "water"]
+            .iter()
+            .map(|name| (name.to_string(), name.to_string()))
+            .chain(
+                ["slime", "lava"]
+                    .iter()
+                    .map(|name| (name.to_string(), "water".to_string())),
+            )
+            .collect();
+        let mask = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        let masks = [
+            ("player".to_string(), mask(&["world", "player_clip"])),
+            ("monster".to_string(), mask(&["world", "monster_clip"])),
+            ("projectile".to_string(), mask(&["world"])),
+        ]
+        .into_iter()
+        .collect();
+        Self {
+            version: COLLISION_LAYERS_VERSION,
+            default_layer: "world".to_string(),
+            layers,
+            tags,
+            masks,
+        }
+    }
+}
+
+impl CollisionLayerTable {
+    pub fn parse_toml(text: &str) -> Result<Self, String> {
+        let table: Self = toml::from_str(text).map_err(|err| err.to_string())?;
+        let errors = table.validate();
+        if errors.is_empty() {
+            Ok(table)
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.version != COLLISION_LAYERS_VERSION {
+            errors.push(format!("unsupported version {}", self.version));
+        }
+        if self.layers.len() > MAX_LAYERS {
+            errors.push(format!("at most {} layers are supported", MAX_LAYERS));
+        }
+        for (index, layer) in self.layers.iter().enumerate() {
+            if self.layers[..index]
+                .iter()
+                .any(|other| other.name == layer.name)
+            {
+                errors.push(format!("duplicate layer '{}'", layer.name));
+            }
+            for other in layer.collides_with.iter().flatten() {
+                if self.bit(other).is_none() {
+                    errors.push(format!(
+                        "layer '{}' collides with unknown layer '{}'",
+                        layer.name, other
+                    ));
+                }
+            }
+        }
+        for name in BUILTIN_LAYERS {
+            if self.bit(name).is_none() {
+                errors.push(format!("missing builtin layer '{}'", name));
+            }
+        }
+        if self.bit(&self.default_layer).is_none() {
+            errors.push(format!("unknown default layer '{}'", self.default_layer));
+        }
+        for (tag, layer) in &self.tags {
+            if self.bit(layer).is_none() {
+                errors.push(format!("tag '{}' maps to unknown layer '{}'", tag, layer));
+            }
+        }
+        for (mask, layers) in &self.masks {
+            for layer in layers {
+                if self.bit(layer).is_none() {
+                    errors.push(format!("mask '{}' names unknown layer '{}'", mask, layer));
+                }
+            }
+        }
+        errors
+    }
+
+    pub fn bit(&self, layer: &str) -> Option<u32> {
+        self.layers
+            .iter()
+            .take(MAX_LAYERS)
+            .position(|spec| spec.name == layer)
+            .map(|index| 1 << index)
+    }
+
+    /// Bits for `layers`; unknown names are ignored.
+    pub fn mask<S: AsRef<str>>(&self, layers: &[S]) -> u32 {
+        layers
+            .iter()
+            .filter_map(|layer| self.bit(layer.as_ref()))
+            .fold(0, |mask, bit| mask | bit)
+    }
+
+    /// Bits for the named query mask; every layer if it is not defined.
+    pub fn named_mask(&self, name: &str) -> u32 {
+        self.masks
+            .get(name)
+            .map(|layers| self.mask(layers))
+            .unwrap_or(u32::MAX)
+    }
+
+    /// Layer a solid with `tags` belongs to.
+    pub fn layer_for_tags<S: AsRef<str>>(&self, tags: &[S]) -> &str {
+        tags.iter()
+            .find_map(|tag| self.tags.get(tag.as_ref()))
+            .unwrap_or(&self.default_layer)
+    }
+
+    /// Groups for a collider on `layer`; unknown layers fall back to the
+    /// default layer.
+    pub fn groups(&self, layer: &str) -> CollisionGroups {
+        let layer = if self.bit(layer).is_some() {
+            layer
+        } else {
+            self.default_layer.as_str()
+        };
+        let Some(spec) = self.layers.iter().find(|spec| spec.name == layer) else {
+            return CollisionGroups::ALL;
+        };
+        let filter = match &spec.collides_with {
+            Some(layers) => self.mask(layers),
+            None => u32::MAX,
+        };
+        CollisionGroups {
+            memberships: self.bit(layer).unwrap_or(u32::MAX),
+            filter,
+        }
+    }
+
+    pub fn groups_for_tags<S: AsRef<str>>(&self, tags: &[S]) -> CollisionGroups {
+        self.groups(self.layer_for_tags(tags))
+    }
+
+    pub fn chunk_groups(&self, chunk: &CollisionChunk) -> CollisionGroups {
+        self.groups(chunk.layer.as_deref().unwrap_or(&self.default_layer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_pick_layers_and_masks_select_them() {
+        let table = CollisionLayerTable::default();
+        assert!(table.validate().is_empty());
+        let clip = table.groups_for_tags(&["wall", "player_clip"]);
+        assert_eq!(clip.memberships, table.bit("player_clip").unwrap());
+        assert_eq!(table.layer_for_tags(&["ground"]), "world");
+        let player = table.named_mask("player");
+        assert_ne!(player & clip.memberships, 0);
+        assert_eq!(player & table.bit("monster_clip").unwrap(), 0);
+        assert_eq!(player & table.bit("water").unwrap(), 0);
+    }
+
+    #[test]
+    fn shipped_table_matches_builtin() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../content/config/physics/collision_layers.toml");
+        let text = std::fs::read_to_string(path).expect("read layers");
+        let table = CollisionLayerTable::parse_toml(&text).expect("parse layers");
+        assert_eq!(table, CollisionLayerTable::default());
+    }
+
+    #[test]
+    fn parse_rejects_unknown_layer_references() {
+        let text = r#"
+version = 1
+default_layer = "world"
+layers = [
+    { name = "world" },
+    { name = "player_clip", collides_with = ["world"] },
+    { name = "monster_clip" },
+    { name = "trigger", collides_with = ["world"] },
+    { name = "projectile" },
+    { name = "water" },
+]
+
+[tags]
+clip = "player_clip"
+
+[masks]
+player = ["world", "player_clip"]
+"#;
+        let table = CollisionLayerTable::parse_toml(text).expect("parse");
+        assert_eq!(table.groups("player_clip").filter, 1);
+        let err = CollisionLayerTable::parse_toml(&text.replace("\"player_clip\"\n", "\"lava\"\n"))
+            .unwrap_err();
+        assert!(err.contains("unknown layer 'lava'"), "{}", err);
+    }
+}
@@ -3,6 +3,12 @@
 
 use serde::{Deserialize, Serialize};
 
+pub mod layers;
+pub mod surfaces;
+
+pub use layers::{CollisionGroups, CollisionLayerSpec, CollisionLayerTable};
+pub use surfaces::{SurfaceSpec, SurfaceTable};
+
 const COLLISION_WORLD_VERSION: u32 = 1;
 const DEFAULT_MAX_TRIANGLES_PER_CHUNK: u32 = 250_000;
 const DEFAULT_MAX_CHUNKS: usize = 10_000;
@@ -42,6 +48,12 @@ pub struct CollisionChunk {
     pub triangle_count: u32,
     #[serde(default)]
     pub partition_hint: Option<String>,
+    /// Collision layer name; the layer table's default when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<String>,
+    /// Surface material name; resolved from the source when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub surface: Option<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -557,6 +569,8 @@ mod tests {
             payload_ref: format!("inline:{}", id),
             triangle_count: 12,
             partition_hint: None,
+            layer: None,
+            surface: None,
         }
     }
 
@@ -0,0 +1,271 @@
+//! Surface materials: how ground feels to walk on and what it sounds like.
+//!
+//! Solids pick a surface through their content tags, collision chunks
+//! through their `surface` hint, and Quake brushes through their texture
+//! names. Anything unmatched uses the table's default surface.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::CollisionChunk;
+
+const SURFACE_TABLE_VERSION: u32 = 1;
+const MAX_SURFACES: usize = u16::MAX as usize;
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct SurfaceTable {
+    pub version: u32,
+    pub default_surface: String,
+    pub surfaces: Vec<SurfaceSpec>,
+    /// Content tag to surface name; the first mapped tag on a solid wins.
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// Texture name to surface name. A trailing `*` matches any texture
+    /// starting with the rest; the longest match wins.
+    #[serde(default)]
+    pub textures: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct SurfaceSpec {
+    pub name: String,
+    /// Multiplier on the motor's ground friction.
+    #[serde(default = "unit_scale")]
+    pub friction: f32,
+    /// Multiplier on the motor's ground acceleration.
+    #[serde(default = "unit_scale")]
+    pub acceleration: f32,
+    /// Ice: friction only scales speed down, so slow slides keep drifting
+    /// instead of snapping to a stop.
+    #[serde(default)]
+    pub slide: bool,
+    /// Sound assets played in turn for footsteps on this surface.
+    #[serde(default)]
+    pub footsteps: Vec<String>,
+}
+
+fn unit_scale() -> f32 {
+    1.0
+}
+
+impl SurfaceSpec {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            friction: 1.0,
+            acceleration: 1.0,
+            slide: false,
+            footsteps: Vec::new(),
+        }
+    }
+}
+
+impl Default for SurfaceTable {
+    fn default() -> Self {
+        let surfaces = vec![
+            SurfaceSpec::new("stone"),
+            SurfaceSpec::new("metal"),
+            SurfaceSpec {
+                friction: 0.1,
+                acceleration: 0.25,
+                slide: true,
+                ..SurfaceSpec::new("ice")
+            },
+            SurfaceSpec {
+                friction: 2.0,
+                acceleration: 0.6,
+                ..SurfaceSpec::new("mud")
+            },
+        ];
+        let pairs = |entries: &[(&str, &str)]| {
+            entries
+                .iter()
+                .map(|(key, surface)| (key.to_string(), surface.to_string()))
+                .collect()
+        };
+        Self {
+            version: SURFACE_TABLE_VERSION,
+            default_surface: "stone".to_string(),
+            surfaces,
+            tags: pairs(&[("ice", "ice"), ("metal", "metal"), ("mud", "mud")]),
+            textures: pairs(&[("metal*", "metal"), ("mmetal*", "metal"), ("ice*", "ice")]),
+        }
+    }
+}
+
+impl SurfaceTable {
+    pub fn parse_toml(text: &str) -> Result<Self, String> {
+        let table: Self = toml::from_str(text).map_err(|err| err.to_string())?;
+        let errors = table.validate();
+        if errors.is_empty() {
+            Ok(table)
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.version != SURFACE_TABLE_VERSION {
+            errors.push(format!("unsupported version {}", self.version));
+        }
+        if self.surfaces.len() > MAX_SURFACES {
+            errors.push(format!("at most {} surfaces are supported", MAX_SURFACES));
+        }
+        for (index, surface) in self.surfaces.iter().enumerate() {
+            if self.surfaces[..index]
+                .iter()
+                .any(|other| other.name == surface.name)
+            {
+                errors.push(format!("duplicate surface '{}'", surface.name));
+            }
+            for (field, value) in [
+                ("friction", surface.friction),
+                ("acceleration", surface.acceleration),
+            ] {
+                if !value.is_finite() || value < 0.0 {
+                    errors.push(format!(
+                        "surface '{}' {} must be a non-negative number",
+                        surface.name, field
+                    ));
+                }
+            }
+        }
+        if self.index(&self.default_surface).is_none() {
+            errors.push(format!(
+                "unknown default surface '{}'",
+                self.default_surface
+            ));
+        }
+        for (kind, map) in [("tag", &self.tags), ("texture", &self.textures)] {
+            for (key, surface) in map {
+                if self.index(surface).is_none() {
+                    errors.push(format!(
+                        "{} '{}' maps to unknown surface '{}'",
+                        kind, key, surface
+                    ));
+                }
+            }
+        }
+        errors
+    }
+
+    /// Table position of `surface`, the id colliders are marked with.
+    pub fn index(&self, surface: &str) -> Option<u16> {
+        self.surfaces
+            .iter()
+            .take(MAX_SURFACES)
+            .position(|spec| spec.name == surface)
+            .map(|index| index as u16)
+    }
+
+    /// The surface at `index`; the default surface when out of range.
+    pub fn get(&self, index: u16) -> Option<&SurfaceSpec> {
+        self.surfaces
+            .get(index as usize)
+            .or_else(|| self.spec(&self.default_surface))
+    }
+
+    pub fn spec(&self, surface: &str) -> Option<&SurfaceSpec> {
+        self.surfaces.iter().find(|spec| spec.name == surface)
+    }
+
+    /// Surface a solid with `tags` is made of.
+    pub fn surface_for_tags<S: AsRef<str>>(&self, tags: &[S]) -> &str {
+        tags.iter()
+            .find_map(|tag| self.tags.get(tag.as_ref()))
+            .unwrap_or(&self.default_surface)
+    }
+
+    /// Surface for a Quake texture name, matched case-insensitively.
+    pub fn surface_for_texture(&self, texture: &str) -> &str {
+        let texture = texture.to_ascii_lowercase();
+        let mut best: Option<(usize, &String)> = None;
+        for (pattern, surface) in &self.textures {
+            let pattern_lower = pattern.to_ascii_lowercase();
+            let matched = match pattern_lower.strip_suffix('*') {
+                Some(prefix) => texture.starts_with(prefix).then_some(prefix.len()),
+                None => (texture == pattern_lower).then_some(usize::MAX),
+            };
+            if let Some(len) = matched {
+                if best.is_none_or(|(best_len, _)| len > best_len) {
+                    best = Some((len, surface));
+                }
+            }
+        }
+        best.map_or(&self.default_surface, |(_, surface)| surface)
+    }
+
+    /// Surface for a cooked chunk: its hint when the table knows it, else
+    /// `fallback`.
+    pub fn chunk_surface<'a>(&'a self, chunk: &'a CollisionChunk, fallback: &'a str) -> &'a str {
+        chunk
+            .surface
+            .as_deref()
+            .filter(|surface| self.index(surface).is_some())
+            .unwrap_or(fallback)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_and_textures_pick_surfaces() {
+        let table = SurfaceTable::default();
+        assert!(table.validate().is_empty());
+        assert_eq!(table.surface_for_tags(&["ground", "ice"]), "ice");
+        assert_eq!(table.surface_for_tags(&["ground"]), "stone");
+        assert_eq!(table.surface_for_texture("METAL1_2"), "metal");
+        assert_eq!(table.surface_for_texture("mmetal1_1"), "metal");
+        assert_eq!(table.surface_for_texture("wbrick1_5"), "stone");
+        let ice = table.get(table.index("ice").unwrap()).unwrap();
+        assert!(ice.slide && ice.friction < 1.0);
+        assert_eq!(table.get(u16::MAX).unwrap().name, "stone");
+    }
+
+    #[test]
+    fn shipped_table_matches_builtin() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../content/config/physics/surfaces.toml");
+        let text = std::fs::read_to_string(path).expect("read surfaces");
+        let table = SurfaceTable::parse_toml(&text).expect("parse surfaces");
+        assert_eq!(table, SurfaceTable::default());
+    }
+
+    #[test]
+    fn footstep_sets_parse_per_surface() {
+        let text = r#"
+version = 1
+default_surface = "stone"
+surfaces = [
+    { name = "stone", footsteps = ["sound/step/stone1.wav", "sound/step/stone2.wav"] },
+    { name = "ice" },
+]
+"#;
+        let table = SurfaceTable::parse_toml(text).expect("parse surfaces");
+        let stone = table.spec("stone").expect("stone");
+        assert_eq!(stone.footsteps.len(), 2);
+        assert!(table.spec("ice").expect("ice").footsteps.is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_unknown_surfaces_and_bad_scales() {
+        let text = r#"
+version = 1
+default_surface = "stone"
+surfaces = [
+    { name = "stone" },
+    { name = "glue", friction = -1.0 },
+]
+
+[tags]
+ice = "ice"
+"#;
+        let err = SurfaceTable::parse_toml(text).expect_err("invalid table");
+        assert!(err.contains("tag 'ice' maps to unknown surface 'ice'"));
+        assert!(err.contains("surface 'glue' friction"));
+    }
+}
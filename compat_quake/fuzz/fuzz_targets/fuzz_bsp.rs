@@ -4,5 +4,12 @@ use compat_quake::bsp;
 use libfuzzer_sys::fuzz_target;
 
 fuzz_target!(|data: &[u8]| {
-    let _ = bsp::parse_bsp(data);
+    let Ok(bsp) = bsp::parse_bsp(data) else {
+        return;
+    };
+    for leaf in 0..bsp.leaves.len() {
+        if let Ok(pvs) = bsp.leaf_pvs(leaf) {
+            let _ = bsp.visible_faces(&pvs);
+        }
+    }
 });
@@ -0,0 +1,50 @@
+#![no_main]
+
+use compat_quake::bsp;
+use libfuzzer_sys::fuzz_target;
+
+const LUMP_COUNT: usize = 15;
+/// Planes, nodes, leaves and models: everything a contents query walks.
+const TREE_LUMPS: [usize; 4] = [1, 5, 10, 14];
+
+// Wraps the input in a valid header so every run reaches a lump parser: the
+// first byte picks the lump, the rest is its payload. Selectors with the high
+// bit set instead split the payload across the tree lumps and query the
+// node/leaf tree.
+fuzz_target!(|data: &[u8]| {
+    let Some((&selector, payload)) = data.split_first() else {
+        return;
+    };
+    let single = [usize::from(selector) % LUMP_COUNT];
+    let lumps: &[usize] = if selector & 0x80 != 0 {
+        &TREE_LUMPS
+    } else {
+        &single
+    };
+    let header_len = 8 + LUMP_COUNT * 8;
+    let mut bytes = vec![0u8; header_len];
+    bytes[0..4].copy_from_slice(b"IBSP");
+    bytes[4..8].copy_from_slice(&29u32.to_le_bytes());
+    let part = payload.len() / lumps.len();
+    for (index, lump) in lumps.iter().enumerate() {
+        let start = index * part;
+        let end = if index + 1 == lumps.len() {
+            payload.len()
+        } else {
+            start + part
+        };
+        let entry = 8 + lump * 8;
+        bytes[entry..entry + 4].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+        bytes[entry + 4..entry + 8].copy_from_slice(&((end - start) as u32).to_le_bytes());
+        bytes.extend_from_slice(&payload[start..end]);
+    }
+    let Ok(bsp) = bsp::parse_bsp(&bytes) else {
+        return;
+    };
+    for point in [[0.0, 0.0, 0.0], [64.0, -64.0, 16.0]] {
+        let _ = bsp.point_contents(point);
+    }
+    for leaf in &bsp.leaves {
+        let _ = bsp.leaf_faces(leaf);
+    }
+});
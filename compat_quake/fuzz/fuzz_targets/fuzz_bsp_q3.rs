@@ -0,0 +1,15 @@
+#![no_main]
+
+use compat_quake::bsp_q3::{parse_bsp, Face};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(bsp) = parse_bsp(data) {
+        for face in bsp.faces.iter().filter(|face| face.face_type == Face::PATCH) {
+            let _ = bsp.tessellate_patch(face, 4);
+        }
+        for brush in &bsp.brushes {
+            let _ = bsp.sides(brush);
+        }
+    }
+});
@@ -0,0 +1,13 @@
+#![no_main]
+
+use compat_quake::mdl::parse_mdl;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(mdl) = parse_mdl(data) {
+        for triangle in &mdl.triangles {
+            let _ = mdl.triangle_uvs(triangle);
+        }
+        let _ = mdl.animations();
+    }
+});
@@ -0,0 +1,15 @@
+#![no_main]
+
+use compat_quake::wad::{parse_lump_image, parse_wad};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = parse_lump_image("lump", data);
+    if let Ok(wad) = parse_wad(data.to_vec()) {
+        let names: Vec<String> = wad.lumps().iter().map(|lump| lump.name.clone()).collect();
+        for name in names {
+            let _ = wad.image(&name);
+            let _ = wad.mip_texture(&name);
+        }
+    }
+});
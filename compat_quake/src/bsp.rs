@@ -153,9 +153,103 @@ pub struct ClipNode {
     pub children: [i32; 2],
 }
 
+/// Leaf contents; Quake stores these as small negative numbers.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Contents {
+    Empty,
+    Solid,
+    Water,
+    Slime,
+    Lava,
+    Sky,
+    Other(i32),
+}
+
+impl Contents {
+    pub fn from_raw(value: i32) -> Self {
+        match value {
+            -1 => Contents::Empty,
+            -2 => Contents::Solid,
+            -3 => Contents::Water,
+            -4 => Contents::Slime,
+            -5 => Contents::Lava,
+            -6 => Contents::Sky,
+            other => Contents::Other(other),
+        }
+    }
+
+    pub fn is_liquid(self) -> bool {
+        matches!(self, Contents::Water | Contents::Slime | Contents::Lava)
+    }
+}
+
+/// A draw-hull node. Child values >= 0 index `nodes`; negative values are
+/// leaves, with `!child` giving the leaf index.
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub plane_id: i32,
+    pub children: [i32; 2],
+    pub mins: [i16; 3],
+    pub maxs: [i16; 3],
+    pub first_face: u16,
+    pub num_faces: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct Leaf {
+    pub contents: Contents,
+    /// Offset into the visibility lump, or -1 when the leaf has no PVS.
+    pub vis_offset: i32,
+    pub mins: [i16; 3],
+    pub maxs: [i16; 3],
+    pub first_mark_surface: u16,
+    pub num_mark_surfaces: u16,
+    /// Ambient sound levels: water, sky, slime, lava.
+    pub ambient_levels: [u8; 4],
+}
+
+/// Texture projection for faces: texel `s = dot(point, s[..3]) + s[3]`,
+/// likewise `t`.
+#[derive(Debug, Clone)]
+pub struct TexInfo {
+    pub s: [f32; 4],
+    pub t: [f32; 4],
+    /// Index into `textures`.
+    pub miptex: u32,
+    pub flags: u32,
+}
+
+impl TexInfo {
+    /// Sky and liquid surfaces: no lightmap, warped or scrolled when drawn.
+    pub const SPECIAL: u32 = 1;
+
+    pub fn is_special(&self) -> bool {
+        self.flags & Self::SPECIAL != 0
+    }
+}
+
+/// An embedded texture with its four mip levels of 8-bit palette indices.
+#[derive(Debug, Clone)]
+pub struct MipTexture {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    /// Level `n` is `(width >> n) * (height >> n)` bytes. Empty when the
+    /// texture is a name only (pixels live in a WAD).
+    pub mips: [Vec<u8>; 4],
+}
+
+impl MipTexture {
+    pub fn has_pixels(&self) -> bool {
+        !self.mips[0].is_empty()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Model {
     pub headnode: [i32; 4],
+    /// Leaves with a PVS row, not counting the solid leaf 0.
+    pub visleafs: i32,
     pub first_face: i32,
     pub num_faces: i32,
 }
@@ -169,7 +263,64 @@ pub struct Bsp {
     pub surfedges: Vec<i32>,
     pub faces: Vec<Face>,
     pub clipnodes: Vec<ClipNode>,
+    pub nodes: Vec<Node>,
+    pub leaves: Vec<Leaf>,
     pub models: Vec<Model>,
+    pub texinfo: Vec<TexInfo>,
+    /// One slot per miptex directory entry; `None` where the offset is -1.
+    pub textures: Vec<Option<MipTexture>>,
+    /// 8-bit lightmap samples; faces index it with `light_offset`.
+    pub lighting: Vec<u8>,
+    /// Face indices; leaves index it with `first_mark_surface`.
+    pub mark_surfaces: Vec<u16>,
+    /// Run-length compressed PVS rows; leaves index it with `vis_offset`.
+    pub visibility: Vec<u8>,
+}
+
+/// The leaves one leaf can see, decompressed from the visibility lump.
+/// Bit `n` covers leaf `n + 1`; the solid leaf 0 is never visible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pvs {
+    bits: Vec<u8>,
+    leaf_count: usize,
+}
+
+impl Pvs {
+    /// Takes a row of `leaf_count.div_ceil(8)` bytes; bits past the last
+    /// leaf are padding and get cleared.
+    fn from_row(mut bits: Vec<u8>, leaf_count: usize) -> Self {
+        let used = leaf_count % 8;
+        if let Some(last) = bits.last_mut().filter(|_| used != 0) {
+            *last &= (1u8 << used) - 1;
+        }
+        Self { bits, leaf_count }
+    }
+
+    /// Every leaf visible, used when a map has no vis data.
+    fn all(leaf_count: usize) -> Self {
+        Self::from_row(vec![0xff; leaf_count.div_ceil(8)], leaf_count)
+    }
+
+    pub fn contains(&self, leaf: usize) -> bool {
+        let Some(bit) = leaf.checked_sub(1).filter(|bit| *bit < self.leaf_count) else {
+            return false;
+        };
+        self.bits[bit / 8] & (1 << (bit % 8)) != 0
+    }
+
+    /// Visible leaf indices in ascending order.
+    pub fn leaves(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.leaf_count)
+            .filter(|bit| self.bits[bit / 8] & (1 << (bit % 8)) != 0)
+            .map(|bit| bit + 1)
+    }
+
+    pub fn visible_count(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -178,13 +329,178 @@ pub struct SpawnPoint {
     pub angle: Option<f32>,
 }
 
+/// One entity from the entity lump. Common fields are pulled out; `keys`
+/// keeps every key/value pair in lump order, including the extracted ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BspEntity {
+    pub classname: String,
+    pub origin: Option<[f32; 3]>,
+    /// Pitch, yaw, roll in degrees. A bare `angle` key fills yaw only; Quake
+    /// uses `angle` -1/-2 to mean up/down for movers.
+    pub angles: Option<[f32; 3]>,
+    /// Inline brush model index from `model "*N"`.
+    pub brush_model: Option<u32>,
+    pub targetname: Option<String>,
+    pub target: Option<String>,
+    pub keys: Vec<(String, String)>,
+}
+
+impl BspEntity {
+    pub fn value(&self, key: &str) -> Option<&str> {
+        entity_value(&self.keys, key)
+    }
+
+    pub fn yaw(&self) -> Option<f32> {
+        self.angles.map(|angles| angles[1])
+    }
+
+    fn from_keys(keys: Vec<(String, String)>) -> Self {
+        let value = |key: &str| entity_value(&keys, key).map(str::to_string);
+        let angles = entity_value(&keys, "angles")
+            .and_then(parse_origin)
+            .or_else(|| {
+                entity_value(&keys, "angle")
+                    .and_then(|value| value.trim().parse::<f32>().ok())
+                    .map(|yaw| [0.0, yaw, 0.0])
+            });
+        let brush_model = entity_value(&keys, "model")
+            .and_then(|model| model.strip_prefix('*'))
+            .and_then(|index| index.parse().ok());
+        Self {
+            classname: value("classname").unwrap_or_default(),
+            origin: entity_value(&keys, "origin").and_then(parse_origin),
+            angles,
+            brush_model,
+            targetname: value("targetname"),
+            target: value("target"),
+            keys,
+        }
+    }
+}
+
 impl Bsp {
     pub fn world_face_range(&self) -> Option<std::ops::Range<usize>> {
-        let model = self.models.first()?;
+        self.model_face_range(0)
+    }
+
+    /// Faces of inline brush model `index`; 0 is the world.
+    pub fn model_face_range(&self, index: usize) -> Option<std::ops::Range<usize>> {
+        let model = self.models.get(index)?;
         let first = usize::try_from(model.first_face).ok()?;
         let count = usize::try_from(model.num_faces).ok()?;
         Some(first..first.saturating_add(count))
     }
+
+    /// Index of the world leaf containing `point` (Quake units), walking the
+    /// draw hull from the world model's head node.
+    pub fn leaf_at(&self, point: [f32; 3]) -> Option<usize> {
+        let mut child = *self.models.first()?.headnode.first()?;
+        // A well-formed tree is at most `nodes.len()` deep; the bound also
+        // stops malformed cycles.
+        for _ in 0..=self.nodes.len() {
+            if child < 0 {
+                let leaf = usize::try_from(!child).ok()?;
+                return (leaf < self.leaves.len()).then_some(leaf);
+            }
+            let node = self.nodes.get(usize::try_from(child).ok()?)?;
+            let plane = self.planes.get(usize::try_from(node.plane_id).ok()?)?;
+            let dist = plane.normal[0] * point[0]
+                + plane.normal[1] * point[1]
+                + plane.normal[2] * point[2]
+                - plane.dist;
+            child = node.children[if dist >= 0.0 { 0 } else { 1 }];
+        }
+        None
+    }
+
+    /// Texture drawn on `face`, if its texinfo and miptex entries exist.
+    pub fn face_texture(&self, face: &Face) -> Option<&MipTexture> {
+        let texinfo = self.texinfo.get(usize::from(face.texinfo))?;
+        self.textures
+            .get(usize::try_from(texinfo.miptex).ok()?)?
+            .as_ref()
+    }
+
+    /// Faces marked in `leaf`, or `None` when the range leaves the lump.
+    pub fn leaf_faces(&self, leaf: &Leaf) -> Option<&[u16]> {
+        let first = usize::from(leaf.first_mark_surface);
+        self.mark_surfaces
+            .get(first..first + usize::from(leaf.num_mark_surfaces))
+    }
+
+    /// Leaves covered by PVS rows: the world model's `visleafs`, or every
+    /// leaf but the solid one when there is no world model.
+    pub fn visleaf_count(&self) -> usize {
+        self.models
+            .first()
+            .and_then(|model| usize::try_from(model.visleafs).ok())
+            .unwrap_or(self.leaves.len().saturating_sub(1))
+    }
+
+    /// Decompresses the PVS row of `leaf`. Leaves without vis data (leaf 0,
+    /// a -1 offset or an unvised map) see everything, as in Quake. Zero
+    /// bytes are followed by a count of zero bytes; any other byte is
+    /// eight literal bits.
+    pub fn leaf_pvs(&self, leaf: usize) -> Result<Pvs, BspError> {
+        let leaf_count = self.visleaf_count();
+        let offset = self
+            .leaves
+            .get(leaf)
+            .filter(|_| leaf != 0 && !self.visibility.is_empty())
+            .and_then(|leaf| usize::try_from(leaf.vis_offset).ok());
+        let Some(offset) = offset else {
+            return Ok(Pvs::all(leaf_count));
+        };
+        let mut input = self
+            .visibility
+            .get(offset..)
+            .ok_or(BspError::LumpOutOfBounds {
+                lump: LumpType::Visibility,
+            })?
+            .iter();
+        let row_len = leaf_count.div_ceil(8);
+        let mut bits = Vec::with_capacity(row_len);
+        while bits.len() < row_len {
+            let Some(&byte) = input.next() else {
+                break;
+            };
+            if byte != 0 {
+                bits.push(byte);
+                continue;
+            }
+            let run = input.next().copied().unwrap_or(0) as usize;
+            bits.resize((bits.len() + run).min(row_len), 0);
+        }
+        bits.resize(row_len, 0);
+        Ok(Pvs::from_row(bits, leaf_count))
+    }
+
+    /// Whether leaf `b` is in leaf `a`'s PVS; unreadable rows see nothing.
+    pub fn leaf_can_see(&self, a: usize, b: usize) -> bool {
+        (a == b && a != 0) || self.leaf_pvs(a).is_ok_and(|pvs| pvs.contains(b))
+    }
+
+    /// Face indices marked in the leaves of `pvs`, sorted and deduplicated.
+    pub fn visible_faces(&self, pvs: &Pvs) -> Vec<usize> {
+        let mut faces: Vec<usize> = pvs
+            .leaves()
+            .filter_map(|leaf| self.leaves.get(leaf))
+            .filter_map(|leaf| self.leaf_faces(leaf))
+            .flatten()
+            .map(|&face| usize::from(face))
+            .filter(|&face| face < self.faces.len())
+            .collect();
+        faces.sort_unstable();
+        faces.dedup();
+        faces
+    }
+
+    /// Contents at `point`; points outside the tree count as solid.
+    pub fn point_contents(&self, point: [f32; 3]) -> Contents {
+        self.leaf_at(point)
+            .map(|leaf| self.leaves[leaf].contents)
+            .unwrap_or(Contents::Solid)
+    }
 }
 
 pub fn parse_bsp(data: &[u8]) -> Result<Bsp, BspError> {
@@ -196,7 +512,14 @@ pub fn parse_bsp(data: &[u8]) -> Result<Bsp, BspError> {
     let surfedges = parse_surfedges(data, &header.lumps)?;
     let faces = parse_faces(data, &header.lumps)?;
     let clipnodes = parse_clipnodes(data, &header.lumps)?;
+    let nodes = parse_nodes(data, &header.lumps)?;
+    let leaves = parse_leaves(data, &header.lumps)?;
     let models = parse_models(data, &header.lumps)?;
+    let texinfo = parse_texinfo(data, &header.lumps)?;
+    let textures = parse_textures(data, &header.lumps)?;
+    let lighting = lump_slice(data, header.lumps[LumpType::Lighting as usize]).to_vec();
+    let mark_surfaces = parse_mark_surfaces(data, &header.lumps)?;
+    let visibility = lump_slice(data, header.lumps[LumpType::Visibility as usize]).to_vec();
 
     Ok(Bsp {
         header,
@@ -206,43 +529,52 @@ pub fn parse_bsp(data: &[u8]) -> Result<Bsp, BspError> {
         surfedges,
         faces,
         clipnodes,
+        nodes,
+        leaves,
         models,
+        texinfo,
+        textures,
+        lighting,
+        mark_surfaces,
+        visibility,
     })
 }
 
-pub fn parse_spawn(data: &[u8], header: &BspHeader) -> Result<Option<SpawnPoint>, BspError> {
+/// Parses every entity in the entity lump, in lump order (worldspawn first).
+pub fn parse_entities(data: &[u8], header: &BspHeader) -> Result<Vec<BspEntity>, BspError> {
     let lump = header.lumps[LumpType::Entities as usize];
     if lump.length == 0 {
-        return Ok(None);
+        return Ok(Vec::new());
     }
     let slice = lump_slice(data, lump);
+    // The lump is NUL-terminated in shipped maps.
+    let slice = slice.strip_suffix(&[0]).unwrap_or(slice);
     let text = std::str::from_utf8(slice).map_err(|_| BspError::InvalidEntities)?;
-    let entities = parse_entities(text);
+    Ok(entities_from_text(text))
+}
 
-    let mut fallback = None;
-    for entity in &entities {
-        let classname = entity_value(entity, "classname");
-        let origin = entity_value(entity, "origin").and_then(parse_origin);
-        if origin.is_none() {
-            continue;
-        }
-        let angle = entity_value(entity, "angle").and_then(|value| value.parse().ok());
-        let spawn = SpawnPoint {
-            origin: origin.unwrap(),
-            angle,
-        };
-        match classname {
-            Some("info_player_start") => return Ok(Some(spawn)),
-            Some("info_player_deathmatch") => {
-                if fallback.is_none() {
-                    fallback = Some(spawn);
-                }
-            }
-            _ => {}
-        }
-    }
+/// Entities from entity lump text; the syntax is shared with Quake 3 maps.
+pub(crate) fn entities_from_text(text: &str) -> Vec<BspEntity> {
+    parse_entity_text(text)
+        .into_iter()
+        .map(BspEntity::from_keys)
+        .collect()
+}
 
-    Ok(fallback)
+pub fn parse_spawn(data: &[u8], header: &BspHeader) -> Result<Option<SpawnPoint>, BspError> {
+    let entities = parse_entities(data, header)?;
+    let spawn_for = |classname: &str| {
+        entities
+            .iter()
+            .filter(|entity| entity.classname == classname)
+            .find_map(|entity| {
+                entity.origin.map(|origin| SpawnPoint {
+                    origin,
+                    angle: entity.yaw(),
+                })
+            })
+    };
+    Ok(spawn_for("info_player_start").or_else(|| spawn_for("info_player_deathmatch")))
 }
 
 fn parse_header(data: &[u8]) -> Result<BspHeader, BspError> {
@@ -502,6 +834,84 @@ fn parse_clipnodes(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<ClipNo
     Ok(nodes)
 }
 
+fn parse_nodes(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<Node>, BspError> {
+    const MAX_LUMP_ELEMENTS: usize = 1_000_000;
+
+    let lump = lumps[LumpType::Nodes as usize];
+    if lump.length == 0 {
+        return Ok(Vec::new());
+    }
+    if !lump.length.is_multiple_of(24) {
+        return Err(BspError::InvalidLumpSize {
+            lump: LumpType::Nodes,
+            size: lump.length,
+            stride: 24,
+        });
+    }
+
+    let slice = lump_slice(data, lump);
+    let count = slice.len() / 24;
+    if count > MAX_LUMP_ELEMENTS {
+        return Err(BspError::LumpTooLarge {
+            lump: LumpType::Nodes,
+            count,
+        });
+    }
+    let mut nodes = Vec::with_capacity(count);
+    for chunk in slice.chunks_exact(24) {
+        nodes.push(Node {
+            plane_id: read_i32_le(&chunk[0..4]),
+            children: [
+                read_i16_le(&chunk[4..6]) as i32,
+                read_i16_le(&chunk[6..8]) as i32,
+            ],
+            mins: read_i16x3_le(&chunk[8..14]),
+            maxs: read_i16x3_le(&chunk[14..20]),
+            first_face: read_u16_le(&chunk[20..22]),
+            num_faces: read_u16_le(&chunk[22..24]),
+        });
+    }
+    Ok(nodes)
+}
+
+fn parse_leaves(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<Leaf>, BspError> {
+    const MAX_LUMP_ELEMENTS: usize = 1_000_000;
+
+    let lump = lumps[LumpType::Leaves as usize];
+    if lump.length == 0 {
+        return Ok(Vec::new());
+    }
+    if !lump.length.is_multiple_of(28) {
+        return Err(BspError::InvalidLumpSize {
+            lump: LumpType::Leaves,
+            size: lump.length,
+            stride: 28,
+        });
+    }
+
+    let slice = lump_slice(data, lump);
+    let count = slice.len() / 28;
+    if count > MAX_LUMP_ELEMENTS {
+        return Err(BspError::LumpTooLarge {
+            lump: LumpType::Leaves,
+            count,
+        });
+    }
+    let mut leaves = Vec::with_capacity(count);
+    for chunk in slice.chunks_exact(28) {
+        leaves.push(Leaf {
+            contents: Contents::from_raw(read_i32_le(&chunk[0..4])),
+            vis_offset: read_i32_le(&chunk[4..8]),
+            mins: read_i16x3_le(&chunk[8..14]),
+            maxs: read_i16x3_le(&chunk[14..20]),
+            first_mark_surface: read_u16_le(&chunk[20..22]),
+            num_mark_surfaces: read_u16_le(&chunk[22..24]),
+            ambient_levels: [chunk[24], chunk[25], chunk[26], chunk[27]],
+        });
+    }
+    Ok(leaves)
+}
+
 fn parse_models(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<Model>, BspError> {
     const MAX_LUMP_ELEMENTS: usize = 1_000_000;
 
@@ -533,10 +943,12 @@ fn parse_models(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<Model>, B
             read_i32_le(&chunk[44..48]),
             read_i32_le(&chunk[48..52]),
         ];
+        let visleafs = read_i32_le(&chunk[52..56]);
         let first_face = read_i32_le(&chunk[56..60]);
         let num_faces = read_i32_le(&chunk[60..64]);
         models.push(Model {
             headnode,
+            visleafs,
             first_face,
             num_faces,
         });
@@ -544,13 +956,147 @@ fn parse_models(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<Model>, B
     Ok(models)
 }
 
+fn parse_texinfo(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<TexInfo>, BspError> {
+    const MAX_LUMP_ELEMENTS: usize = 1_000_000;
+
+    let lump = lumps[LumpType::TexInfo as usize];
+    if lump.length == 0 {
+        return Ok(Vec::new());
+    }
+    if !lump.length.is_multiple_of(40) {
+        return Err(BspError::InvalidLumpSize {
+            lump: LumpType::TexInfo,
+            size: lump.length,
+            stride: 40,
+        });
+    }
+
+    let slice = lump_slice(data, lump);
+    let count = slice.len() / 40;
+    if count > MAX_LUMP_ELEMENTS {
+        return Err(BspError::LumpTooLarge {
+            lump: LumpType::TexInfo,
+            count,
+        });
+    }
+    let mut texinfo = Vec::with_capacity(count);
+    for chunk in slice.chunks_exact(40) {
+        let vec4 = |bytes: &[u8]| {
+            [
+                read_f32_le(&bytes[0..4]),
+                read_f32_le(&bytes[4..8]),
+                read_f32_le(&bytes[8..12]),
+                read_f32_le(&bytes[12..16]),
+            ]
+        };
+        texinfo.push(TexInfo {
+            s: vec4(&chunk[0..16]),
+            t: vec4(&chunk[16..32]),
+            miptex: read_u32_le(&chunk[32..36]),
+            flags: read_u32_le(&chunk[36..40]),
+        });
+    }
+    Ok(texinfo)
+}
+
+fn parse_textures(
+    data: &[u8],
+    lumps: &[Lump; LUMP_COUNT],
+) -> Result<Vec<Option<MipTexture>>, BspError> {
+    const MAX_LUMP_ELEMENTS: usize = 65_536;
+    const MIPTEX_HEADER: usize = 40;
+
+    let lump = lumps[LumpType::Textures as usize];
+    if lump.length == 0 {
+        return Ok(Vec::new());
+    }
+    let out_of_bounds = || BspError::LumpOutOfBounds {
+        lump: LumpType::Textures,
+    };
+    let slice = lump_slice(data, lump);
+    if slice.len() < 4 {
+        return Err(out_of_bounds());
+    }
+    let count = usize::try_from(read_i32_le(&slice[0..4])).map_err(|_| out_of_bounds())?;
+    if count > MAX_LUMP_ELEMENTS {
+        return Err(BspError::LumpTooLarge {
+            lump: LumpType::Textures,
+            count,
+        });
+    }
+    let directory = slice.get(4..4 + count * 4).ok_or_else(out_of_bounds)?;
+    let mut textures = Vec::with_capacity(count);
+    for entry in directory.chunks_exact(4) {
+        let Ok(offset) = usize::try_from(read_i32_le(entry)) else {
+            textures.push(None);
+            continue;
+        };
+        let header = offset
+            .checked_add(MIPTEX_HEADER)
+            .and_then(|end| slice.get(offset..end))
+            .ok_or_else(out_of_bounds)?;
+        let name_len = header[..16].iter().position(|&b| b == 0).unwrap_or(16);
+        let width = read_u32_le(&header[16..20]);
+        let height = read_u32_le(&header[20..24]);
+        let mut mips: [Vec<u8>; 4] = Default::default();
+        for (level, mip) in mips.iter_mut().enumerate() {
+            let mip_offset = read_u32_le(&header[24 + level * 4..28 + level * 4]) as usize;
+            if mip_offset == 0 {
+                continue;
+            }
+            let size = ((width >> level) as usize)
+                .checked_mul((height >> level) as usize)
+                .ok_or_else(out_of_bounds)?;
+            let pixels = offset
+                .checked_add(mip_offset)
+                .and_then(|start| Some(start..start.checked_add(size)?))
+                .and_then(|range| slice.get(range))
+                .ok_or_else(out_of_bounds)?;
+            *mip = pixels.to_vec();
+        }
+        textures.push(Some(MipTexture {
+            name: String::from_utf8_lossy(&header[..name_len]).into_owned(),
+            width,
+            height,
+            mips,
+        }));
+    }
+    Ok(textures)
+}
+
+fn parse_mark_surfaces(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<u16>, BspError> {
+    const MAX_LUMP_ELEMENTS: usize = 1_000_000;
+
+    let lump = lumps[LumpType::MarkSurfaces as usize];
+    if lump.length == 0 {
+        return Ok(Vec::new());
+    }
+    if !lump.length.is_multiple_of(2) {
+        return Err(BspError::InvalidLumpSize {
+            lump: LumpType::MarkSurfaces,
+            size: lump.length,
+            stride: 2,
+        });
+    }
+
+    let slice = lump_slice(data, lump);
+    let count = slice.len() / 2;
+    if count > MAX_LUMP_ELEMENTS {
+        return Err(BspError::LumpTooLarge {
+            lump: LumpType::MarkSurfaces,
+            count,
+        });
+    }
+    Ok(slice.chunks_exact(2).map(read_u16_le).collect())
+}
+
 fn lump_slice(data: &[u8], lump: Lump) -> &[u8] {
     let start = lump.offset as usize;
     let end = start + lump.length as usize;
     &data[start..end]
 }
 
-fn parse_entities(text: &str) -> Vec<Vec<(String, String)>> {
+fn parse_entity_text(text: &str) -> Vec<Vec<(String, String)>> {
     let mut entities = Vec::new();
     let mut current: Vec<(String, String)> = Vec::new();
     let mut key: Option<String> = None;
@@ -626,6 +1172,14 @@ fn read_i16_le(bytes: &[u8]) -> i16 {
     i16::from_le_bytes([bytes[0], bytes[1]])
 }
 
+fn read_i16x3_le(bytes: &[u8]) -> [i16; 3] {
+    [
+        read_i16_le(&bytes[0..2]),
+        read_i16_le(&bytes[2..4]),
+        read_i16_le(&bytes[4..6]),
+    ]
+}
+
 fn read_f32_le(bytes: &[u8]) -> f32 {
     f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
 }
@@ -665,4 +1219,273 @@ mod tests {
         let err = parse_bsp(&data).expect_err("should fail");
         assert!(matches!(err, BspError::UnsupportedVersion(28)));
     }
+
+    #[test]
+    fn parse_entities_extracts_common_fields() {
+        let lump = concat!(
+            "{\n\"classname\" \"worldspawn\"\n\"wad\" \"gfx/base.wad\"\n}\n",
+            "{\n\"classname\" \"func_door\"\n\"model\" \"*3\"\n\"angle\" \"-1\"\n",
+            "\"targetname\" \"t1\"\n}\n",
+            "{\n\"classname\" \"info_player_start\"\n\"origin\" \"480 -352 88\"\n",
+            "\"angle\" \"90\"\n}\n\0"
+        );
+        let header_len = 8 + LUMP_COUNT * 8;
+        let mut data = vec![0u8; header_len];
+        data[0..4].copy_from_slice(b"IBSP");
+        data[4..8].copy_from_slice(&29u32.to_le_bytes());
+        let entry = 8 + LumpType::Entities as usize * 8;
+        data[entry..entry + 4].copy_from_slice(&(header_len as u32).to_le_bytes());
+        data[entry + 4..entry + 8].copy_from_slice(&(lump.len() as u32).to_le_bytes());
+        data.extend_from_slice(lump.as_bytes());
+        let header = parse_bsp(&data).expect("parse ok").header;
+
+        let entities = parse_entities(&data, &header).expect("entities");
+        assert_eq!(entities.len(), 3);
+        assert_eq!(entities[0].value("wad"), Some("gfx/base.wad"));
+        assert_eq!(entities[1].brush_model, Some(3));
+        assert_eq!(entities[1].yaw(), Some(-1.0));
+        assert_eq!(entities[1].targetname.as_deref(), Some("t1"));
+        assert_eq!(entities[2].origin, Some([480.0, -352.0, 88.0]));
+
+        let spawn = parse_spawn(&data, &header).expect("spawn").expect("start");
+        assert_eq!(spawn.angle, Some(90.0));
+    }
+
+    fn bsp_with_lumps(lumps: &[(LumpType, Vec<u8>)]) -> Vec<u8> {
+        let header_len = 8 + LUMP_COUNT * 8;
+        let mut data = vec![0u8; header_len];
+        data[0..4].copy_from_slice(b"IBSP");
+        data[4..8].copy_from_slice(&29u32.to_le_bytes());
+        for (lump, bytes) in lumps {
+            let entry = 8 + *lump as usize * 8;
+            let offset = data.len() as u32;
+            data[entry..entry + 4].copy_from_slice(&offset.to_le_bytes());
+            data[entry + 4..entry + 8].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+            data.extend_from_slice(bytes);
+        }
+        data
+    }
+
+    #[test]
+    fn point_contents_walks_nodes_to_liquid_leaf() {
+        let mut plane = Vec::new();
+        for value in [0.0f32, 0.0, 1.0, 16.0] {
+            plane.extend_from_slice(&value.to_le_bytes());
+        }
+        plane.extend_from_slice(&2i32.to_le_bytes());
+        let mut node = Vec::new();
+        node.extend_from_slice(&0i32.to_le_bytes());
+        node.extend_from_slice(&(-1i16).to_le_bytes());
+        node.extend_from_slice(&(-2i16).to_le_bytes());
+        node.extend_from_slice(&[0u8; 16]);
+        let mut leaves = Vec::new();
+        for (contents, ambient) in [(-1i32, 0u8), (-3, 96)] {
+            leaves.extend_from_slice(&contents.to_le_bytes());
+            leaves.extend_from_slice(&(-1i32).to_le_bytes());
+            leaves.extend_from_slice(&[0u8; 16]);
+            leaves.extend_from_slice(&[ambient, 0, 0, 0]);
+        }
+        let model = vec![0u8; 64];
+        let data = bsp_with_lumps(&[
+            (LumpType::Planes, plane),
+            (LumpType::Nodes, node),
+            (LumpType::Leaves, leaves),
+            (LumpType::Models, model),
+        ]);
+
+        let bsp = parse_bsp(&data).expect("parse ok");
+        assert_eq!(bsp.nodes[0].children, [-1, -2]);
+        assert_eq!(bsp.leaves[1].contents, Contents::Water);
+        assert_eq!(bsp.leaves[1].ambient_levels[0], 96);
+        assert_eq!(bsp.point_contents([0.0, 0.0, 32.0]), Contents::Empty);
+        assert_eq!(bsp.leaf_at([0.0, 0.0, 0.0]), Some(1));
+        assert!(bsp.point_contents([0.0, 0.0, 0.0]).is_liquid());
+
+        let mut truncated = data.clone();
+        let entry = 8 + LumpType::Leaves as usize * 8 + 4;
+        truncated[entry..entry + 4].copy_from_slice(&27u32.to_le_bytes());
+        assert!(matches!(
+            parse_bsp(&truncated),
+            Err(BspError::InvalidLumpSize {
+                lump: LumpType::Leaves,
+                ..
+            })
+        ));
+    }
+
+    fn miptex_lump(name: &str, width: u32, height: u32) -> Vec<u8> {
+        let mut lump = Vec::new();
+        lump.extend_from_slice(&2i32.to_le_bytes());
+        lump.extend_from_slice(&12i32.to_le_bytes());
+        lump.extend_from_slice(&(-1i32).to_le_bytes());
+        let mut header = [0u8; 40];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        header[16..20].copy_from_slice(&width.to_le_bytes());
+        header[20..24].copy_from_slice(&height.to_le_bytes());
+        let mut offset = 40u32;
+        for level in 0..4 {
+            let base = 24 + level * 4;
+            header[base..base + 4].copy_from_slice(&offset.to_le_bytes());
+            offset += (width >> level) * (height >> level);
+        }
+        lump.extend_from_slice(&header);
+        lump.extend((0..offset - 40).map(|index| index as u8));
+        lump
+    }
+
+    #[test]
+    fn parse_surface_lumps() {
+        let mut texinfo = Vec::new();
+        for value in [1.0f32, 0.0, 0.0, 8.0, 0.0, 1.0, 0.0, -4.0] {
+            texinfo.extend_from_slice(&value.to_le_bytes());
+        }
+        texinfo.extend_from_slice(&0u32.to_le_bytes());
+        texinfo.extend_from_slice(&TexInfo::SPECIAL.to_le_bytes());
+        let mut face = vec![0u8; 20];
+        face[16..20].copy_from_slice(&2i32.to_le_bytes());
+        let mut leaf = vec![0u8; 28];
+        leaf[0..4].copy_from_slice(&(-1i32).to_le_bytes());
+        leaf[22..24].copy_from_slice(&2u16.to_le_bytes());
+        let marks = [0u16, 0, 7]
+            .iter()
+            .flat_map(|mark| mark.to_le_bytes())
+            .collect();
+        let data = bsp_with_lumps(&[
+            (LumpType::Textures, miptex_lump("*water1", 16, 16)),
+            (LumpType::TexInfo, texinfo),
+            (LumpType::Faces, face),
+            (LumpType::Lighting, vec![10, 20, 30, 40]),
+            (LumpType::Leaves, leaf),
+            (LumpType::MarkSurfaces, marks),
+            (LumpType::Visibility, vec![0xff, 0x00, 0x03]),
+        ]);
+
+        let bsp = parse_bsp(&data).expect("parse ok");
+        assert_eq!(bsp.texinfo[0].s, [1.0, 0.0, 0.0, 8.0]);
+        assert!(bsp.texinfo[0].is_special());
+        assert_eq!(bsp.textures.len(), 2);
+        assert!(bsp.textures[1].is_none());
+        let texture = bsp.face_texture(&bsp.faces[0]).expect("texture");
+        assert_eq!(texture.name, "*water1");
+        assert_eq!((texture.width, texture.height), (16, 16));
+        let sizes: Vec<usize> = texture.mips.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [256, 64, 16, 4]);
+        assert_eq!(texture.mips[1][0], 0);
+        assert_eq!(
+            &bsp.lighting[bsp.faces[0].light_offset as usize..],
+            &[30, 40]
+        );
+        assert_eq!(bsp.leaf_faces(&bsp.leaves[0]), Some(&[0u16, 0][..]));
+        assert_eq!(bsp.mark_surfaces, [0, 0, 7]);
+        assert_eq!(bsp.visibility, [0xff, 0x00, 0x03]);
+    }
+
+    #[test]
+    fn parse_textures_rejects_out_of_bounds_mips() {
+        let mut lump = miptex_lump("wall", 16, 16);
+        lump.truncate(lump.len() - 1);
+        let data = bsp_with_lumps(&[(LumpType::Textures, lump)]);
+        assert!(matches!(
+            parse_bsp(&data),
+            Err(BspError::LumpOutOfBounds {
+                lump: LumpType::Textures
+            })
+        ));
+
+        let mut lump = miptex_lump("wall", 16, 16);
+        lump[0..4].copy_from_slice(&1000i32.to_le_bytes());
+        let data = bsp_with_lumps(&[(LumpType::Textures, lump)]);
+        assert!(matches!(
+            parse_bsp(&data),
+            Err(BspError::LumpOutOfBounds {
+                lump: LumpType::Textures
+            })
+        ));
+
+        let data = bsp_with_lumps(&[(LumpType::MarkSurfaces, vec![0, 0, 0])]);
+        assert!(matches!(
+            parse_bsp(&data),
+            Err(BspError::InvalidLumpSize {
+                lump: LumpType::MarkSurfaces,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn pvs_decompresses_rows_and_lists_visible_faces() {
+        let mut plane = Vec::new();
+        for value in [0.0f32, 0.0, 1.0, 16.0] {
+            plane.extend_from_slice(&value.to_le_bytes());
+        }
+        plane.extend_from_slice(&2i32.to_le_bytes());
+        let mut node = Vec::new();
+        node.extend_from_slice(&0i32.to_le_bytes());
+        node.extend_from_slice(&(-2i16).to_le_bytes());
+        node.extend_from_slice(&(-3i16).to_le_bytes());
+        node.extend_from_slice(&[0u8; 16]);
+        // Leaf 0 is solid; leaves 1 and 2 hold faces, 3..=20 are empty.
+        let mut leaves = Vec::new();
+        for leaf in 0..21i32 {
+            let (vis_offset, first_mark) = match leaf {
+                1 => (0i32, 0u16),
+                2 => (4, 2),
+                _ => (-1, 0),
+            };
+            let num_marks: u16 = if (1..=2).contains(&leaf) { 2 } else { 0 };
+            leaves.extend_from_slice(&(if leaf == 0 { -2i32 } else { -1 }).to_le_bytes());
+            leaves.extend_from_slice(&vis_offset.to_le_bytes());
+            leaves.extend_from_slice(&[0u8; 12]);
+            leaves.extend_from_slice(&first_mark.to_le_bytes());
+            leaves.extend_from_slice(&num_marks.to_le_bytes());
+            leaves.extend_from_slice(&[0u8; 4]);
+        }
+        let marks = [0u16, 1, 1, 2]
+            .iter()
+            .flat_map(|mark| mark.to_le_bytes())
+            .collect();
+        let mut model = vec![0u8; 64];
+        model[52..56].copy_from_slice(&20i32.to_le_bytes());
+        let data = bsp_with_lumps(&[
+            (LumpType::Planes, plane),
+            (LumpType::Nodes, node),
+            (LumpType::Leaves, leaves),
+            (LumpType::Faces, vec![0u8; 60]),
+            (LumpType::MarkSurfaces, marks),
+            (LumpType::Models, model),
+            // Leaf 1 sees 1, 2 and (after a one-byte zero run) 17; leaf 2
+            // sees only itself, with its trailing zeros run-length coded.
+            (
+                LumpType::Visibility,
+                vec![0x03, 0x00, 0x01, 0x01, 0x02, 0x00, 0x02],
+            ),
+        ]);
+
+        let bsp = parse_bsp(&data).expect("parse ok");
+        assert_eq!(bsp.visleaf_count(), 20);
+        assert_eq!(bsp.leaf_at([0.0, 0.0, 32.0]), Some(1));
+        assert_eq!(bsp.leaf_at([0.0, 0.0, 0.0]), Some(2));
+        let pvs = bsp.leaf_pvs(1).expect("pvs");
+        assert_eq!(pvs.leaves().collect::<Vec<_>>(), [1, 2, 17]);
+        assert_eq!(bsp.visible_faces(&pvs), [0, 1, 2]);
+        let pvs = bsp.leaf_pvs(2).expect("pvs");
+        assert_eq!(pvs.visible_count(), 1);
+        assert_eq!(bsp.visible_faces(&pvs), [1, 2]);
+        assert!(bsp.leaf_can_see(1, 2));
+        assert!(!bsp.leaf_can_see(2, 1));
+        assert!(!bsp.leaf_can_see(1, 3));
+        assert!(!bsp.leaf_can_see(1, 0));
+        // Leaves without a row see every leaf.
+        assert!(bsp.leaf_can_see(3, 20));
+        assert!(!bsp.leaf_can_see(3, 21));
+
+        let mut broken = bsp.clone();
+        broken.leaves[1].vis_offset = 64;
+        assert!(matches!(
+            broken.leaf_pvs(1),
+            Err(BspError::LumpOutOfBounds {
+                lump: LumpType::Visibility
+            })
+        ));
+    }
 }
@@ -1,10 +1,17 @@
 use std::fmt;
 
-// Minimal id Tech 3 BSP (IBSP v46) parsing for collision cooking.
+use crate::bsp::{entities_from_text, BspEntity};
+
+// id Tech 3 BSP (IBSP v46/v47) parsing. Visibility data is not read.
 
 const LUMP_COUNT: usize = 17;
 const Q3_BSP_SUPPORTED_VERSIONS: [u32; 2] = [46, 47];
 
+/// Texels along each side of a lightmap.
+pub const LIGHTMAP_SIZE: usize = 128;
+/// Highest patch subdivision level accepted by [`tessellate_patch`].
+pub const MAX_PATCH_LEVEL: u32 = 32;
+
 #[derive(Debug)]
 pub enum BspError {
     InvalidHeader,
@@ -22,6 +29,11 @@ pub enum BspError {
         lump: LumpType,
         count: usize,
     },
+    InvalidEntities,
+    InvalidPatch {
+        width: i32,
+        height: i32,
+    },
 }
 
 impl fmt::Display for BspError {
@@ -50,6 +62,10 @@ impl fmt::Display for BspError {
                     count
                 )
             }
+            BspError::InvalidEntities => write!(f, "invalid q3 bsp entity data"),
+            BspError::InvalidPatch { width, height } => {
+                write!(f, "invalid q3 bsp patch: {}x{}", width, height)
+            }
         }
     }
 }
@@ -136,35 +152,437 @@ impl LumpType {
     }
 }
 
+/// A shader (texture) reference with the compiler's surface and content flags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shader {
+    pub name: String,
+    pub surface_flags: u32,
+    pub content_flags: u32,
+}
+
+impl Shader {
+    pub const CONTENTS_SOLID: u32 = 0x1;
+    pub const CONTENTS_LAVA: u32 = 0x8;
+    pub const CONTENTS_SLIME: u32 = 0x10;
+    pub const CONTENTS_WATER: u32 = 0x20;
+    pub const CONTENTS_FOG: u32 = 0x40;
+    pub const CONTENTS_PLAYERCLIP: u32 = 0x1_0000;
+    pub const CONTENTS_MONSTERCLIP: u32 = 0x2_0000;
+    pub const CONTENTS_DETAIL: u32 = 0x800_0000;
+    pub const CONTENTS_TRIGGER: u32 = 0x4000_0000;
+
+    pub const SURF_SLICK: u32 = 0x2;
+    pub const SURF_SKY: u32 = 0x4;
+    pub const SURF_LADDER: u32 = 0x8;
+    pub const SURF_NODRAW: u32 = 0x80;
+    pub const SURF_NONSOLID: u32 = 0x4000;
+
+    /// Solid or player clip: brushes the player collides with.
+    pub fn blocks_player(&self) -> bool {
+        self.content_flags & (Self::CONTENTS_SOLID | Self::CONTENTS_PLAYERCLIP) != 0
+    }
+
+    pub fn is_nonsolid(&self) -> bool {
+        self.surface_flags & Self::SURF_NONSOLID != 0
+    }
+
+    pub fn is_liquid(&self) -> bool {
+        self.content_flags & (Self::CONTENTS_WATER | Self::CONTENTS_SLIME | Self::CONTENTS_LAVA)
+            != 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub normal: [f32; 3],
+    pub dist: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Node {
+    pub plane: i32,
+    /// Negative children are leaves: `-(leaf + 1)`.
+    pub children: [i32; 2],
+    pub mins: [i32; 3],
+    pub maxs: [i32; 3],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leaf {
+    /// Visibility cluster; -1 for leaves outside the map.
+    pub cluster: i32,
+    pub area: i32,
+    pub mins: [i32; 3],
+    pub maxs: [i32; 3],
+    pub first_leaf_face: i32,
+    pub num_leaf_faces: i32,
+    pub first_leaf_brush: i32,
+    pub num_leaf_brushes: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Model {
+    pub mins: [f32; 3],
+    pub maxs: [f32; 3],
+    pub first_face: i32,
+    pub num_faces: i32,
+    pub first_brush: i32,
+    pub num_brushes: i32,
+}
+
+/// A convex volume bounded by its sides' planes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Brush {
+    pub first_side: i32,
+    pub num_sides: i32,
+    pub shader: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrushSide {
+    pub plane: i32,
+    pub shader: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vertex {
+    pub position: [f32; 3],
+    pub texcoord: [f32; 2],
+    pub lightmap_coord: [f32; 2],
+    pub normal: [f32; 3],
+    pub color: [u8; 4],
+}
+
+/// A fog volume: `brush` is the fog brush, `visible_side` the side the
+/// fog is viewed through (-1 for none).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Effect {
+    pub name: String,
+    pub brush: i32,
+    pub visible_side: i32,
+}
+
 #[derive(Debug, Clone)]
 pub struct Face {
+    pub shader: i32,
+    pub effect: i32,
     pub face_type: i32,
     pub vertex_start: i32,
     pub vertex_count: i32,
     pub meshvert_start: i32,
     pub meshvert_count: i32,
+    pub lightmap: i32,
+    pub lightmap_start: [i32; 2],
+    pub lightmap_size: [i32; 2],
+    pub lightmap_origin: [f32; 3],
+    pub lightmap_vecs: [[f32; 3]; 2],
+    pub normal: [f32; 3],
+    /// Control point grid of a patch face, in points.
+    pub patch_size: [i32; 2],
+}
+
+impl Face {
+    pub const POLYGON: i32 = 1;
+    pub const PATCH: i32 = 2;
+    pub const MESH: i32 = 3;
+    pub const BILLBOARD: i32 = 4;
+}
+
+/// Ambient and directional light sampled on the light grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LightVol {
+    pub ambient: [u8; 3],
+    pub directional: [u8; 3],
+    /// Light direction as latitude and longitude in 256ths of a turn.
+    pub direction: [u8; 2],
+}
+
+/// Triangles tessellated from a patch face.
+#[derive(Debug, Clone, Default)]
+pub struct PatchMesh {
+    pub vertices: Vec<Vertex>,
+    pub indices: Vec<u32>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Bsp {
     pub header: BspHeader,
-    pub vertices: Vec<[f32; 3]>,
+    pub entities: Vec<BspEntity>,
+    pub shaders: Vec<Shader>,
+    pub planes: Vec<Plane>,
+    pub nodes: Vec<Node>,
+    pub leafs: Vec<Leaf>,
+    pub leaf_faces: Vec<i32>,
+    pub leaf_brushes: Vec<i32>,
+    pub models: Vec<Model>,
+    pub brushes: Vec<Brush>,
+    pub brush_sides: Vec<BrushSide>,
+    pub vertices: Vec<Vertex>,
     pub meshverts: Vec<i32>,
+    pub effects: Vec<Effect>,
     pub faces: Vec<Face>,
+    /// RGB lightmaps of [`LIGHTMAP_SIZE`] squared texels each.
+    pub lightmaps: Vec<Vec<u8>>,
+    pub light_vols: Vec<LightVol>,
+}
+
+impl Bsp {
+    pub fn shader(&self, index: i32) -> Option<&Shader> {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| self.shaders.get(index))
+    }
+
+    pub fn sides(&self, brush: &Brush) -> Option<&[BrushSide]> {
+        let start = usize::try_from(brush.first_side).ok()?;
+        let count = usize::try_from(brush.num_sides).ok()?;
+        self.brush_sides.get(start..start.checked_add(count)?)
+    }
+
+    /// Brushes of model `index`; model 0 is the world.
+    pub fn model_brushes(&self, index: usize) -> Option<&[Brush]> {
+        let model = self.models.get(index)?;
+        let start = usize::try_from(model.first_brush).ok()?;
+        let count = usize::try_from(model.num_brushes).ok()?;
+        self.brushes.get(start..start.checked_add(count)?)
+    }
+
+    /// Tessellates a patch face, splitting each quadratic Bezier segment of
+    /// the control grid into `level` steps (clamped to 1..=[`MAX_PATCH_LEVEL`]).
+    pub fn tessellate_patch(&self, face: &Face, level: u32) -> Result<PatchMesh, BspError> {
+        let invalid = || BspError::InvalidPatch {
+            width: face.patch_size[0],
+            height: face.patch_size[1],
+        };
+        if face.face_type != Face::PATCH {
+            return Err(invalid());
+        }
+        let width = usize::try_from(face.patch_size[0]).map_err(|_| invalid())?;
+        let height = usize::try_from(face.patch_size[1]).map_err(|_| invalid())?;
+        let start = usize::try_from(face.vertex_start).map_err(|_| invalid())?;
+        let count = usize::try_from(face.vertex_count).map_err(|_| invalid())?;
+        if width.checked_mul(height) != Some(count) {
+            return Err(invalid());
+        }
+        let control = start
+            .checked_add(count)
+            .and_then(|end| self.vertices.get(start..end))
+            .ok_or_else(invalid)?;
+        tessellate_patch(control, width, height, level).ok_or_else(invalid)
+    }
+}
+
+/// Tessellates a `width` x `height` grid of control points as biquadratic
+/// Bezier patches sharing edge points. Both sides must be odd and at least
+/// three; returns `None` otherwise.
+pub fn tessellate_patch(
+    control: &[Vertex],
+    width: usize,
+    height: usize,
+    level: u32,
+) -> Option<PatchMesh> {
+    if width < 3 || height < 3 || width.is_multiple_of(2) || height.is_multiple_of(2) {
+        return None;
+    }
+    if control.len() != width.checked_mul(height)? {
+        return None;
+    }
+    let level = level.clamp(1, MAX_PATCH_LEVEL) as usize;
+    let row = level + 1;
+    let mut mesh = PatchMesh::default();
+    for patch_y in 0..(height - 1) / 2 {
+        for patch_x in 0..(width - 1) / 2 {
+            let points: [&Vertex; 9] = std::array::from_fn(|index| {
+                let (y, x) = (index / 3, index % 3);
+                &control[(patch_y * 2 + y) * width + patch_x * 2 + x]
+            });
+            let base = mesh.vertices.len() as u32;
+            for step_y in 0..row {
+                let v = quadratic_basis(step_y as f32 / level as f32);
+                for step_x in 0..row {
+                    let u = quadratic_basis(step_x as f32 / level as f32);
+                    let weights = std::array::from_fn(|index| v[index / 3] * u[index % 3]);
+                    mesh.vertices.push(blend_vertices(&points, &weights));
+                }
+            }
+            for step_y in 0..level {
+                for step_x in 0..level {
+                    let a = base + (step_y * row + step_x) as u32;
+                    let b = a + 1;
+                    let c = a + row as u32;
+                    let d = c + 1;
+                    mesh.indices.extend_from_slice(&[a, c, b, b, c, d]);
+                }
+            }
+        }
+    }
+    Some(mesh)
+}
+
+fn quadratic_basis(t: f32) -> [f32; 3] {
+    let s = 1.0 - t;
+    [s * s, 2.0 * s * t, t * t]
+}
+
+fn blend_vertices(points: &[&Vertex; 9], weights: &[f32; 9]) -> Vertex {
+    let mut position = [0.0f32; 3];
+    let mut texcoord = [0.0f32; 2];
+    let mut lightmap_coord = [0.0f32; 2];
+    let mut normal = [0.0f32; 3];
+    let mut color = [0.0f32; 4];
+    for (point, &weight) in points.iter().zip(weights) {
+        for axis in 0..3 {
+            position[axis] += point.position[axis] * weight;
+            normal[axis] += point.normal[axis] * weight;
+        }
+        for axis in 0..2 {
+            texcoord[axis] += point.texcoord[axis] * weight;
+            lightmap_coord[axis] += point.lightmap_coord[axis] * weight;
+        }
+        for (sum, &channel) in color.iter_mut().zip(&point.color) {
+            *sum += f32::from(channel) * weight;
+        }
+    }
+    let length = normal.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if length > f32::EPSILON {
+        normal = normal.map(|value| value / length);
+    }
+    Vertex {
+        position,
+        texcoord,
+        lightmap_coord,
+        normal,
+        color: color.map(|value| value.round().clamp(0.0, 255.0) as u8),
+    }
 }
 
 pub fn parse_bsp(data: &[u8]) -> Result<Bsp, BspError> {
     let header = parse_header(data)?;
-
-    let vertices = parse_vertices(data, &header.lumps)?;
-    let meshverts = parse_meshverts(data, &header.lumps)?;
-    let faces = parse_faces(data, &header.lumps)?;
+    let lumps = &header.lumps;
+
+    let entities = parse_entities(data, lumps)?;
+    let shaders = parse_lump(data, lumps, LumpType::Shaders, 72, 65_536, |chunk| Shader {
+        name: read_name(&chunk[0..64]),
+        surface_flags: read_u32_le(&chunk[64..68]),
+        content_flags: read_u32_le(&chunk[68..72]),
+    })?;
+    let planes = parse_lump(data, lumps, LumpType::Planes, 16, 1_000_000, |chunk| {
+        Plane {
+            normal: read_vec3(&chunk[0..12]),
+            dist: read_f32_le(&chunk[12..16]),
+        }
+    })?;
+    let nodes = parse_lump(data, lumps, LumpType::Nodes, 36, 1_000_000, |chunk| Node {
+        plane: read_i32_le(&chunk[0..4]),
+        children: [read_i32_le(&chunk[4..8]), read_i32_le(&chunk[8..12])],
+        mins: read_ivec3(&chunk[12..24]),
+        maxs: read_ivec3(&chunk[24..36]),
+    })?;
+    let leafs = parse_lump(data, lumps, LumpType::Leafs, 48, 1_000_000, |chunk| Leaf {
+        cluster: read_i32_le(&chunk[0..4]),
+        area: read_i32_le(&chunk[4..8]),
+        mins: read_ivec3(&chunk[8..20]),
+        maxs: read_ivec3(&chunk[20..32]),
+        first_leaf_face: read_i32_le(&chunk[32..36]),
+        num_leaf_faces: read_i32_le(&chunk[36..40]),
+        first_leaf_brush: read_i32_le(&chunk[40..44]),
+        num_leaf_brushes: read_i32_le(&chunk[44..48]),
+    })?;
+    let leaf_faces = parse_lump(data, lumps, LumpType::LeafFaces, 4, 4_000_000, read_i32_le)?;
+    let leaf_brushes = parse_lump(
+        data,
+        lumps,
+        LumpType::LeafBrushes,
+        4,
+        4_000_000,
+        read_i32_le,
+    )?;
+    let models = parse_lump(data, lumps, LumpType::Models, 40, 65_536, |chunk| Model {
+        mins: read_vec3(&chunk[0..12]),
+        maxs: read_vec3(&chunk[12..24]),
+        first_face: read_i32_le(&chunk[24..28]),
+        num_faces: read_i32_le(&chunk[28..32]),
+        first_brush: read_i32_le(&chunk[32..36]),
+        num_brushes: read_i32_le(&chunk[36..40]),
+    })?;
+    let brushes = parse_lump(data, lumps, LumpType::Brushes, 12, 1_000_000, |chunk| {
+        Brush {
+            first_side: read_i32_le(&chunk[0..4]),
+            num_sides: read_i32_le(&chunk[4..8]),
+            shader: read_i32_le(&chunk[8..12]),
+        }
+    })?;
+    let brush_sides = parse_lump(data, lumps, LumpType::BrushSides, 8, 4_000_000, |chunk| {
+        BrushSide {
+            plane: read_i32_le(&chunk[0..4]),
+            shader: read_i32_le(&chunk[4..8]),
+        }
+    })?;
+    let vertices = parse_lump(data, lumps, LumpType::Vertices, 44, 2_000_000, |chunk| {
+        Vertex {
+            position: read_vec3(&chunk[0..12]),
+            texcoord: [read_f32_le(&chunk[12..16]), read_f32_le(&chunk[16..20])],
+            lightmap_coord: [read_f32_le(&chunk[20..24]), read_f32_le(&chunk[24..28])],
+            normal: read_vec3(&chunk[28..40]),
+            color: [chunk[40], chunk[41], chunk[42], chunk[43]],
+        }
+    })?;
+    let meshverts = parse_lump(data, lumps, LumpType::MeshVerts, 4, 4_000_000, read_i32_le)?;
+    let effects = parse_lump(data, lumps, LumpType::Effects, 72, 65_536, |chunk| Effect {
+        name: read_name(&chunk[0..64]),
+        brush: read_i32_le(&chunk[64..68]),
+        visible_side: read_i32_le(&chunk[68..72]),
+    })?;
+    let faces = parse_lump(data, lumps, LumpType::Faces, 104, 1_000_000, |chunk| Face {
+        shader: read_i32_le(&chunk[0..4]),
+        effect: read_i32_le(&chunk[4..8]),
+        face_type: read_i32_le(&chunk[8..12]),
+        vertex_start: read_i32_le(&chunk[12..16]),
+        vertex_count: read_i32_le(&chunk[16..20]),
+        meshvert_start: read_i32_le(&chunk[20..24]),
+        meshvert_count: read_i32_le(&chunk[24..28]),
+        lightmap: read_i32_le(&chunk[28..32]),
+        lightmap_start: [read_i32_le(&chunk[32..36]), read_i32_le(&chunk[36..40])],
+        lightmap_size: [read_i32_le(&chunk[40..44]), read_i32_le(&chunk[44..48])],
+        lightmap_origin: read_vec3(&chunk[48..60]),
+        lightmap_vecs: [read_vec3(&chunk[60..72]), read_vec3(&chunk[72..84])],
+        normal: read_vec3(&chunk[84..96]),
+        patch_size: [read_i32_le(&chunk[96..100]), read_i32_le(&chunk[100..104])],
+    })?;
+    let lightmaps = parse_lump(
+        data,
+        lumps,
+        LumpType::Lightmaps,
+        LIGHTMAP_SIZE * LIGHTMAP_SIZE * 3,
+        4096,
+        <[u8]>::to_vec,
+    )?;
+    let light_vols = parse_lump(data, lumps, LumpType::LightVols, 8, 4_000_000, |chunk| {
+        LightVol {
+            ambient: [chunk[0], chunk[1], chunk[2]],
+            directional: [chunk[3], chunk[4], chunk[5]],
+            direction: [chunk[6], chunk[7]],
+        }
+    })?;
 
     Ok(Bsp {
         header,
+        entities,
+        shaders,
+        planes,
+        nodes,
+        leafs,
+        leaf_faces,
+        leaf_brushes,
+        models,
+        brushes,
+        brush_sides,
         vertices,
         meshverts,
+        effects,
         faces,
+        lightmaps,
+        light_vols,
     })
 }
 
@@ -209,108 +627,43 @@ fn parse_header(data: &[u8]) -> Result<BspHeader, BspError> {
     Ok(BspHeader { version, lumps })
 }
 
-fn parse_vertices(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<[f32; 3]>, BspError> {
-    const MAX_LUMP_ELEMENTS: usize = 2_000_000;
-    const VERTEX_STRIDE: usize = 44;
-
-    let lump = lumps[LumpType::Vertices as usize];
-    if lump.length == 0 {
-        return Ok(Vec::new());
-    }
-    if !lump.length.is_multiple_of(VERTEX_STRIDE as u32) {
-        return Err(BspError::InvalidLumpSize {
-            lump: LumpType::Vertices,
-            size: lump.length,
-            stride: VERTEX_STRIDE as u32,
-        });
-    }
-    let slice = lump_slice(data, lump);
-    let count = slice.len() / VERTEX_STRIDE;
-    if count > MAX_LUMP_ELEMENTS {
-        return Err(BspError::LumpTooLarge {
-            lump: LumpType::Vertices,
-            count,
-        });
-    }
-    let mut vertices = Vec::with_capacity(count);
-    for chunk in slice.chunks_exact(VERTEX_STRIDE) {
-        vertices.push([
-            read_f32_le(&chunk[0..4]),
-            read_f32_le(&chunk[4..8]),
-            read_f32_le(&chunk[8..12]),
-        ]);
-    }
-    Ok(vertices)
-}
-
-fn parse_meshverts(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<i32>, BspError> {
-    const MAX_LUMP_ELEMENTS: usize = 4_000_000;
-
-    let lump = lumps[LumpType::MeshVerts as usize];
-    if lump.length == 0 {
-        return Ok(Vec::new());
-    }
-    if !lump.length.is_multiple_of(4) {
-        return Err(BspError::InvalidLumpSize {
-            lump: LumpType::MeshVerts,
-            size: lump.length,
-            stride: 4,
-        });
-    }
-    let slice = lump_slice(data, lump);
-    let count = slice.len() / 4;
-    if count > MAX_LUMP_ELEMENTS {
-        return Err(BspError::LumpTooLarge {
-            lump: LumpType::MeshVerts,
-            count,
-        });
-    }
-    let mut meshverts = Vec::with_capacity(count);
-    for chunk in slice.chunks_exact(4) {
-        meshverts.push(read_i32_le(&chunk[0..4]));
-    }
-    Ok(meshverts)
+fn parse_entities(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<BspEntity>, BspError> {
+    let slice = lump_slice(data, lumps[LumpType::Entities as usize]);
+    let slice = slice.strip_suffix(&[0]).unwrap_or(slice);
+    let text = std::str::from_utf8(slice).map_err(|_| BspError::InvalidEntities)?;
+    Ok(entities_from_text(text))
 }
 
-fn parse_faces(data: &[u8], lumps: &[Lump; LUMP_COUNT]) -> Result<Vec<Face>, BspError> {
-    const MAX_LUMP_ELEMENTS: usize = 1_000_000;
-    const FACE_STRIDE: usize = 104;
-
-    let lump = lumps[LumpType::Faces as usize];
+/// Splits a lump into `stride`-byte records, rejecting partial records and
+/// lumps with more than `max_count` of them.
+fn parse_lump<T>(
+    data: &[u8],
+    lumps: &[Lump; LUMP_COUNT],
+    lump_type: LumpType,
+    stride: usize,
+    max_count: usize,
+    parse: impl Fn(&[u8]) -> T,
+) -> Result<Vec<T>, BspError> {
+    let lump = lumps[lump_type as usize];
     if lump.length == 0 {
         return Ok(Vec::new());
     }
-    if !lump.length.is_multiple_of(FACE_STRIDE as u32) {
+    if !(lump.length as usize).is_multiple_of(stride) {
         return Err(BspError::InvalidLumpSize {
-            lump: LumpType::Faces,
+            lump: lump_type,
             size: lump.length,
-            stride: FACE_STRIDE as u32,
+            stride: stride as u32,
         });
     }
     let slice = lump_slice(data, lump);
-    let count = slice.len() / FACE_STRIDE;
-    if count > MAX_LUMP_ELEMENTS {
+    let count = slice.len() / stride;
+    if count > max_count {
         return Err(BspError::LumpTooLarge {
-            lump: LumpType::Faces,
+            lump: lump_type,
             count,
         });
     }
-    let mut faces = Vec::with_capacity(count);
-    for chunk in slice.chunks_exact(FACE_STRIDE) {
-        let face_type = read_i32_le(&chunk[8..12]);
-        let vertex_start = read_i32_le(&chunk[12..16]);
-        let vertex_count = read_i32_le(&chunk[16..20]);
-        let meshvert_start = read_i32_le(&chunk[20..24]);
-        let meshvert_count = read_i32_le(&chunk[24..28]);
-        faces.push(Face {
-            face_type,
-            vertex_start,
-            vertex_count,
-            meshvert_start,
-            meshvert_count,
-        });
-    }
-    Ok(faces)
+    Ok(slice.chunks_exact(stride).map(parse).collect())
 }
 
 fn lump_slice(data: &[u8], lump: Lump) -> &[u8] {
@@ -319,6 +672,11 @@ fn lump_slice(data: &[u8], lump: Lump) -> &[u8] {
     &data[start..end]
 }
 
+fn read_name(bytes: &[u8]) -> String {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
 fn read_u32_le(bytes: &[u8]) -> u32 {
     u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
 }
@@ -331,6 +689,22 @@ fn read_f32_le(bytes: &[u8]) -> f32 {
     f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
 }
 
+fn read_vec3(bytes: &[u8]) -> [f32; 3] {
+    [
+        read_f32_le(&bytes[0..4]),
+        read_f32_le(&bytes[4..8]),
+        read_f32_le(&bytes[8..12]),
+    ]
+}
+
+fn read_ivec3(bytes: &[u8]) -> [i32; 3] {
+    [
+        read_i32_le(&bytes[0..4]),
+        read_i32_le(&bytes[4..8]),
+        read_i32_le(&bytes[8..12]),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -347,6 +721,136 @@ mod tests {
         assert!(bsp.meshverts.is_empty());
     }
 
+    fn bsp_with_lumps(lumps: &[(LumpType, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0u8; 8 + LUMP_COUNT * 8];
+        data[0..4].copy_from_slice(b"IBSP");
+        data[4..8].copy_from_slice(&46u32.to_le_bytes());
+        for (lump, bytes) in lumps {
+            let entry = 8 + *lump as usize * 8;
+            let offset = data.len() as u32;
+            data[entry..entry + 4].copy_from_slice(&offset.to_le_bytes());
+            data[entry + 4..entry + 8].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+            data.extend_from_slice(bytes);
+        }
+        data
+    }
+
+    fn i32s(values: &[i32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    fn vertex(position: [f32; 3]) -> Vertex {
+        Vertex {
+            position,
+            texcoord: [position[0], position[1]],
+            lightmap_coord: [0.0; 2],
+            normal: [0.0, 0.0, 1.0],
+            color: [255; 4],
+        }
+    }
+
+    #[test]
+    fn parse_reads_shaders_brushes_and_patch_faces() {
+        let mut shaders = Vec::new();
+        for (name, surface, contents) in [
+            ("textures/base/floor", 0u32, Shader::CONTENTS_SOLID),
+            (
+                "textures/common/clip",
+                Shader::SURF_NODRAW,
+                Shader::CONTENTS_PLAYERCLIP,
+            ),
+            (
+                "textures/liquids/water",
+                Shader::SURF_NONSOLID,
+                Shader::CONTENTS_WATER,
+            ),
+        ] {
+            let mut raw = [0u8; 64];
+            raw[..name.len()].copy_from_slice(name.as_bytes());
+            shaders.extend_from_slice(&raw);
+            shaders.extend_from_slice(&surface.to_le_bytes());
+            shaders.extend_from_slice(&contents.to_le_bytes());
+        }
+        let mut planes = Vec::new();
+        for value in [0.0f32, 0.0, 1.0, 16.0] {
+            planes.extend_from_slice(&value.to_le_bytes());
+        }
+        let mut vertices = Vec::new();
+        for index in 0..9 {
+            for value in [(index % 3) as f32 * 8.0, (index / 3) as f32 * 8.0, 0.0] {
+                vertices.extend_from_slice(&value.to_le_bytes());
+            }
+            vertices.extend_from_slice(&[0u8; 28]);
+            vertices.extend_from_slice(&[255; 4]);
+        }
+        let mut face = i32s(&[0, -1, Face::PATCH, 0, 9, 0, 0, -1]);
+        face.resize(96, 0);
+        face.extend_from_slice(&i32s(&[3, 3]));
+        let entities = b"{\n\"classname\" \"worldspawn\"\n}\n{\n\"classname\" \"info_player_deathmatch\"\n\"origin\" \"0 0 24\"\n}\0";
+        let data = bsp_with_lumps(&[
+            (LumpType::Entities, entities.to_vec()),
+            (LumpType::Shaders, shaders),
+            (LumpType::Planes, planes),
+            (LumpType::Models, {
+                let mut model = vec![0u8; 24];
+                model.extend_from_slice(&i32s(&[0, 1, 0, 1]));
+                model
+            }),
+            (LumpType::Brushes, i32s(&[0, 1, 1])),
+            (LumpType::BrushSides, i32s(&[0, 0])),
+            (LumpType::Vertices, vertices),
+            (LumpType::Faces, face),
+            (LumpType::LightVols, vec![1, 2, 3, 4, 5, 6, 7, 8]),
+        ]);
+
+        let bsp = parse_bsp(&data).expect("parse ok");
+        assert_eq!(bsp.entities.len(), 2);
+        assert_eq!(bsp.entities[1].origin, Some([0.0, 0.0, 24.0]));
+        assert_eq!(bsp.shaders[0].name, "textures/base/floor");
+        assert!(bsp.shaders[0].blocks_player());
+        assert!(bsp.shaders[1].blocks_player());
+        assert!(!bsp.shaders[2].blocks_player());
+        assert!(bsp.shaders[2].is_liquid() && bsp.shaders[2].is_nonsolid());
+        assert_eq!(bsp.planes[0].dist, 16.0);
+
+        let brushes = bsp.model_brushes(0).expect("world brushes");
+        assert_eq!(
+            bsp.shader(brushes[0].shader).unwrap().name,
+            "textures/common/clip"
+        );
+        assert_eq!(bsp.sides(&brushes[0]).unwrap()[0].plane, 0);
+        assert_eq!(bsp.light_vols[0].direction, [7, 8]);
+
+        let face = &bsp.faces[0];
+        assert_eq!(face.patch_size, [3, 3]);
+        let mesh = bsp.tessellate_patch(face, 4).expect("patch");
+        assert_eq!(mesh.vertices.len(), 25);
+        assert_eq!(mesh.indices.len(), 4 * 4 * 6);
+        assert_eq!(mesh.vertices[24].position, [16.0, 16.0, 0.0]);
+        assert_eq!(mesh.vertices[12].position, [8.0, 8.0, 0.0]);
+    }
+
+    #[test]
+    fn tessellate_patch_follows_bezier_curve() {
+        let mut control: Vec<Vertex> = (0..15)
+            .map(|index| vertex([(index % 5) as f32, (index / 5) as f32, 0.0]))
+            .collect();
+        control[6].position[2] = 4.0;
+        let mesh = tessellate_patch(&control, 5, 3, 2).expect("patch");
+        // Two 3x3 patches of 3x3 samples each.
+        assert_eq!(mesh.vertices.len(), 18);
+        assert_eq!(mesh.indices.len(), 2 * 2 * 2 * 6);
+        assert_eq!(mesh.vertices[4].position, [1.0, 1.0, 1.0]);
+        assert_eq!(mesh.vertices[8].position, [2.0, 2.0, 0.0]);
+        assert!(mesh.indices.iter().all(|&index| index < 18));
+
+        assert!(tessellate_patch(&control[..12], 4, 3, 2).is_none());
+        assert!(tessellate_patch(&control, 5, 3, 0).is_some());
+    }
+
     #[test]
     fn parse_unsupported_version() {
         let mut data = vec![0u8; 8 + LUMP_COUNT * 8];
@@ -3,4 +3,6 @@
 pub mod bsp;
 pub mod bsp_q3;
 pub mod lmp;
+pub mod mdl;
 pub mod pak;
+pub mod wad;
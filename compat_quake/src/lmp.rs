@@ -72,6 +72,19 @@ impl IndexedImage {
         }
         out
     }
+
+    /// Like [`Self::to_rgba8`], but pixels using `transparent` get zero alpha.
+    pub fn to_rgba8_transparent(&self, palette: &Palette, transparent: u8) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.indices.len() * 4);
+        for &index in &self.indices {
+            if index == transparent {
+                out.extend_from_slice(&[0, 0, 0, 0]);
+            } else {
+                out.extend_from_slice(&palette.rgba(index));
+            }
+        }
+        out
+    }
 }
 
 pub fn parse_palette(data: &[u8]) -> Result<Palette, LmpError> {
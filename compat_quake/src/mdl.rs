@@ -0,0 +1,568 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::lmp::{IndexedImage, Palette};
+
+const MDL_IDENT: &[u8; 4] = b"IDPO";
+const MDL_VERSION: i32 = 6;
+const HEADER_LEN: usize = 84;
+const MAX_ELEMENTS: i32 = 65_536;
+const MAX_SKIN_SIZE: i32 = 4096;
+
+#[derive(Debug)]
+pub enum MdlError {
+    InvalidHeader,
+    UnsupportedVersion(i32),
+    Truncated { section: &'static str },
+    InvalidCount { field: &'static str, value: i32 },
+    InvalidSkinSize { width: i32, height: i32 },
+    VertexOutOfRange { triangle: usize, vertex: i32 },
+}
+
+impl fmt::Display for MdlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MdlError::InvalidHeader => write!(f, "invalid mdl header"),
+            MdlError::UnsupportedVersion(version) => {
+                write!(f, "unsupported mdl version {}", version)
+            }
+            MdlError::Truncated { section } => write!(f, "mdl data is truncated in {}", section),
+            MdlError::InvalidCount { field, value } => {
+                write!(f, "invalid mdl {} count: {}", field, value)
+            }
+            MdlError::InvalidSkinSize { width, height } => {
+                write!(f, "invalid mdl skin size: {}x{}", width, height)
+            }
+            MdlError::VertexOutOfRange { triangle, vertex } => write!(
+                f,
+                "mdl triangle {} references missing vertex {}",
+                triangle, vertex
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MdlError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MdlHeader {
+    pub version: i32,
+    pub scale: [f32; 3],
+    pub translate: [f32; 3],
+    pub bounding_radius: f32,
+    pub eye_position: [f32; 3],
+    pub skin_width: u32,
+    pub skin_height: u32,
+    pub num_skins: usize,
+    pub num_verts: usize,
+    pub num_tris: usize,
+    pub num_frames: usize,
+    pub sync_type: i32,
+    pub flags: i32,
+    pub size: f32,
+}
+
+#[derive(Debug, Clone)]
+pub enum Skin {
+    Single(IndexedImage),
+    /// An animated skin; `intervals` are cumulative end times in seconds.
+    Group {
+        intervals: Vec<f32>,
+        images: Vec<IndexedImage>,
+    },
+}
+
+impl Skin {
+    pub fn images(&self) -> &[IndexedImage] {
+        match self {
+            Skin::Single(image) => std::slice::from_ref(image),
+            Skin::Group { images, .. } => images,
+        }
+    }
+
+    /// Every image of the skin as RGBA8.
+    pub fn to_rgba8(&self, palette: &Palette) -> Vec<Vec<u8>> {
+        self.images()
+            .iter()
+            .map(|image| image.to_rgba8(palette))
+            .collect()
+    }
+}
+
+/// Skin coordinates in texels. Vertices on the seam between the front and
+/// back halves of the skin are shifted half a skin right on back faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TexCoord {
+    pub on_seam: bool,
+    pub s: i32,
+    pub t: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Triangle {
+    pub faces_front: bool,
+    pub vertices: [usize; 3],
+}
+
+/// One decompressed pose: vertex positions in model units and their indices
+/// into Quake's precomputed normal table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FramePose {
+    pub name: String,
+    pub bbox_min: [f32; 3],
+    pub bbox_max: [f32; 3],
+    pub vertices: Vec<[f32; 3]>,
+    pub normal_indices: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    Single(FramePose),
+    /// Poses played in sequence; `intervals` are cumulative end times.
+    Group {
+        intervals: Vec<f32>,
+        poses: Vec<FramePose>,
+    },
+}
+
+impl Frame {
+    pub fn poses(&self) -> &[FramePose] {
+        match self {
+            Frame::Single(pose) => std::slice::from_ref(pose),
+            Frame::Group { poses, .. } => poses,
+        }
+    }
+
+    /// The name of the first pose.
+    pub fn name(&self) -> &str {
+        self.poses().first().map_or("", |pose| pose.name.as_str())
+    }
+}
+
+/// Consecutive frames whose names share a prefix, e.g. `run1`..`run8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Animation {
+    pub name: String,
+    pub frames: Range<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Mdl {
+    pub header: MdlHeader,
+    pub skins: Vec<Skin>,
+    pub texcoords: Vec<TexCoord>,
+    pub triangles: Vec<Triangle>,
+    pub frames: Vec<Frame>,
+}
+
+impl Mdl {
+    /// Normalized skin coordinates for a triangle's corners, with the seam
+    /// shift applied to back faces.
+    pub fn triangle_uvs(&self, triangle: &Triangle) -> [[f32; 2]; 3] {
+        let width = self.header.skin_width as f32;
+        let height = self.header.skin_height as f32;
+        triangle.vertices.map(|vertex| {
+            let coord = self.texcoords[vertex];
+            let mut s = coord.s as f32;
+            if coord.on_seam && !triangle.faces_front {
+                s += width * 0.5;
+            }
+            [(s + 0.5) / width, (coord.t as f32 + 0.5) / height]
+        })
+    }
+
+    /// Groups frames into animations by name with trailing digits removed.
+    pub fn animations(&self) -> Vec<Animation> {
+        let mut animations: Vec<Animation> = Vec::new();
+        for (index, frame) in self.frames.iter().enumerate() {
+            let name = frame.name().trim_end_matches(|c: char| c.is_ascii_digit());
+            match animations.last_mut() {
+                Some(last) if last.name == name && last.frames.end == index => {
+                    last.frames.end = index + 1;
+                }
+                _ => animations.push(Animation {
+                    name: name.to_string(),
+                    frames: index..index + 1,
+                }),
+            }
+        }
+        animations
+    }
+
+    /// Frame range of the first animation called `name`.
+    pub fn animation(&self, name: &str) -> Option<Range<usize>> {
+        self.animations()
+            .into_iter()
+            .find(|animation| animation.name == name)
+            .map(|animation| animation.frames)
+    }
+}
+
+pub fn parse_mdl(data: &[u8]) -> Result<Mdl, MdlError> {
+    let header = parse_header(data)?;
+    let mut reader = Reader {
+        data,
+        pos: HEADER_LEN,
+    };
+
+    let skin_len = header.skin_width as usize * header.skin_height as usize;
+    let mut skins = Vec::with_capacity(header.num_skins);
+    for _ in 0..header.num_skins {
+        let group = reader.i32("skins")?;
+        if group == 0 {
+            skins.push(Skin::Single(read_skin(&mut reader, &header, skin_len)?));
+            continue;
+        }
+        let count = read_count(&mut reader, "skin group")?;
+        let intervals = reader.f32s(count, "skin group")?;
+        let images = (0..count)
+            .map(|_| read_skin(&mut reader, &header, skin_len))
+            .collect::<Result<_, _>>()?;
+        skins.push(Skin::Group { intervals, images });
+    }
+
+    let coords = reader.take(header.num_verts * 12, "texcoords")?;
+    let texcoords = coords
+        .chunks_exact(12)
+        .map(|chunk| TexCoord {
+            on_seam: read_i32_le(&chunk[0..4]) != 0,
+            s: read_i32_le(&chunk[4..8]),
+            t: read_i32_le(&chunk[8..12]),
+        })
+        .collect();
+
+    let tris = reader.take(header.num_tris * 16, "triangles")?;
+    let mut triangles = Vec::with_capacity(header.num_tris);
+    for (index, chunk) in tris.chunks_exact(16).enumerate() {
+        let mut vertices = [0usize; 3];
+        for (corner, vertex) in vertices.iter_mut().enumerate() {
+            let base = 4 + corner * 4;
+            let raw = read_i32_le(&chunk[base..base + 4]);
+            *vertex = usize::try_from(raw)
+                .ok()
+                .filter(|vertex| *vertex < header.num_verts)
+                .ok_or(MdlError::VertexOutOfRange {
+                    triangle: index,
+                    vertex: raw,
+                })?;
+        }
+        triangles.push(Triangle {
+            faces_front: read_i32_le(&chunk[0..4]) != 0,
+            vertices,
+        });
+    }
+
+    let mut frames = Vec::with_capacity(header.num_frames);
+    for _ in 0..header.num_frames {
+        let kind = reader.i32("frames")?;
+        if kind == 0 {
+            frames.push(Frame::Single(read_pose(&mut reader, &header)?));
+            continue;
+        }
+        let count = read_count(&mut reader, "frame group")?;
+        // Group bounds; each pose carries its own.
+        reader.take(8, "frame group")?;
+        let intervals = reader.f32s(count, "frame group")?;
+        let poses = (0..count)
+            .map(|_| read_pose(&mut reader, &header))
+            .collect::<Result<_, _>>()?;
+        frames.push(Frame::Group { intervals, poses });
+    }
+
+    Ok(Mdl {
+        header,
+        skins,
+        texcoords,
+        triangles,
+        frames,
+    })
+}
+
+fn parse_header(data: &[u8]) -> Result<MdlHeader, MdlError> {
+    if data.len() < HEADER_LEN || &data[0..4] != MDL_IDENT {
+        return Err(MdlError::InvalidHeader);
+    }
+    let version = read_i32_le(&data[4..8]);
+    if version != MDL_VERSION {
+        return Err(MdlError::UnsupportedVersion(version));
+    }
+    let skin_width = read_i32_le(&data[52..56]);
+    let skin_height = read_i32_le(&data[56..60]);
+    if !(1..=MAX_SKIN_SIZE).contains(&skin_width) || !(1..=MAX_SKIN_SIZE).contains(&skin_height) {
+        return Err(MdlError::InvalidSkinSize {
+            width: skin_width,
+            height: skin_height,
+        });
+    }
+    let count = |field: &'static str, offset: usize, min: i32| {
+        let value = read_i32_le(&data[offset..offset + 4]);
+        if (min..=MAX_ELEMENTS).contains(&value) {
+            Ok(value as usize)
+        } else {
+            Err(MdlError::InvalidCount { field, value })
+        }
+    };
+    Ok(MdlHeader {
+        version,
+        scale: read_vec3(&data[8..20]),
+        translate: read_vec3(&data[20..32]),
+        bounding_radius: read_f32_le(&data[32..36]),
+        eye_position: read_vec3(&data[36..48]),
+        skin_width: skin_width as u32,
+        skin_height: skin_height as u32,
+        num_skins: count("skin", 48, 0)?,
+        num_verts: count("vertex", 60, 1)?,
+        num_tris: count("triangle", 64, 1)?,
+        num_frames: count("frame", 68, 1)?,
+        sync_type: read_i32_le(&data[72..76]),
+        flags: read_i32_le(&data[76..80]),
+        size: read_f32_le(&data[80..84]),
+    })
+}
+
+fn read_count(reader: &mut Reader<'_>, section: &'static str) -> Result<usize, MdlError> {
+    let value = reader.i32(section)?;
+    if (1..=MAX_ELEMENTS).contains(&value) {
+        Ok(value as usize)
+    } else {
+        Err(MdlError::InvalidCount {
+            field: section,
+            value,
+        })
+    }
+}
+
+fn read_skin(
+    reader: &mut Reader<'_>,
+    header: &MdlHeader,
+    skin_len: usize,
+) -> Result<IndexedImage, MdlError> {
+    Ok(IndexedImage {
+        width: header.skin_width,
+        height: header.skin_height,
+        indices: reader.take(skin_len, "skins")?.to_vec(),
+    })
+}
+
+fn read_pose(reader: &mut Reader<'_>, header: &MdlHeader) -> Result<FramePose, MdlError> {
+    let decompress = |packed: &[u8]| {
+        [0, 1, 2].map(|axis| header.scale[axis] * f32::from(packed[axis]) + header.translate[axis])
+    };
+    let bounds = reader.take(8, "frames")?;
+    let bbox_min = decompress(&bounds[0..4]);
+    let bbox_max = decompress(&bounds[4..8]);
+    let name = reader.take(16, "frames")?;
+    let name_len = name.iter().position(|&b| b == 0).unwrap_or(16);
+    let name = String::from_utf8_lossy(&name[..name_len]).into_owned();
+    let packed = reader.take(header.num_verts * 4, "frames")?;
+    Ok(FramePose {
+        name,
+        bbox_min,
+        bbox_max,
+        vertices: packed.chunks_exact(4).map(decompress).collect(),
+        normal_indices: packed.chunks_exact(4).map(|vertex| vertex[3]).collect(),
+    })
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize, section: &'static str) -> Result<&'a [u8], MdlError> {
+        let bytes = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.pos..end))
+            .ok_or(MdlError::Truncated { section })?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn i32(&mut self, section: &'static str) -> Result<i32, MdlError> {
+        self.take(4, section).map(read_i32_le)
+    }
+
+    fn f32s(&mut self, count: usize, section: &'static str) -> Result<Vec<f32>, MdlError> {
+        Ok(self
+            .take(count * 4, section)?
+            .chunks_exact(4)
+            .map(read_f32_le)
+            .collect())
+    }
+}
+
+fn read_i32_le(bytes: &[u8]) -> i32 {
+    i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn read_f32_le(bytes: &[u8]) -> f32 {
+    f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn read_vec3(bytes: &[u8]) -> [f32; 3] {
+    [
+        read_f32_le(&bytes[0..4]),
+        read_f32_le(&bytes[4..8]),
+        read_f32_le(&bytes[8..12]),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lmp::{parse_palette, PALETTE_LEN};
+
+    fn push_i32(out: &mut Vec<u8>, value: i32) {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn push_f32(out: &mut Vec<u8>, value: f32) {
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn push_pose(out: &mut Vec<u8>, name: &str, packed: &[[u8; 4]]) {
+        out.extend_from_slice(&[0, 0, 0, 0, 255, 255, 255, 0]);
+        let mut bytes = [0u8; 16];
+        bytes[..name.len()].copy_from_slice(name.as_bytes());
+        out.extend_from_slice(&bytes);
+        for vertex in packed {
+            out.extend_from_slice(vertex);
+        }
+    }
+
+    /// A 4x2 skin, three vertices, one back-facing triangle and four frames:
+    /// `stand1`, `stand2`, a two-pose group `run1`, then `stand3`.
+    fn sample_mdl() -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(b"IDPO");
+        push_i32(&mut data, 6);
+        for value in [2.0, 2.0, 2.0, -10.0, -10.0, 0.0, 32.0, 0.0, 0.0, 22.0] {
+            push_f32(&mut data, value);
+        }
+        for value in [2, 4, 2, 3, 1, 4, 0, 0] {
+            push_i32(&mut data, value);
+        }
+        push_f32(&mut data, 1.0);
+
+        push_i32(&mut data, 0);
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        push_i32(&mut data, 1);
+        push_i32(&mut data, 2);
+        push_f32(&mut data, 0.1);
+        push_f32(&mut data, 0.2);
+        data.extend_from_slice(&[9; 8]);
+        data.extend_from_slice(&[10; 8]);
+
+        for (on_seam, s, t) in [(1, 0, 0), (0, 1, 1), (0, 3, 0)] {
+            push_i32(&mut data, on_seam);
+            push_i32(&mut data, s);
+            push_i32(&mut data, t);
+        }
+        for value in [0, 0, 1, 2] {
+            push_i32(&mut data, value);
+        }
+
+        let packed = [[0, 0, 0, 1], [5, 5, 5, 2], [10, 0, 0, 3]];
+        for name in ["stand1", "stand2"] {
+            push_i32(&mut data, 0);
+            push_pose(&mut data, name, &packed);
+        }
+        push_i32(&mut data, 1);
+        push_i32(&mut data, 2);
+        data.extend_from_slice(&[0; 8]);
+        push_f32(&mut data, 0.1);
+        push_f32(&mut data, 0.2);
+        push_pose(&mut data, "run1", &packed);
+        push_pose(&mut data, "run2", &packed);
+        push_i32(&mut data, 0);
+        push_pose(&mut data, "stand3", &packed);
+        data
+    }
+
+    #[test]
+    fn parse_mdl_decodes_skins_frames_and_seams() {
+        let mdl = parse_mdl(&sample_mdl()).expect("mdl parse");
+        assert_eq!(mdl.header.num_verts, 3);
+        assert_eq!((mdl.header.skin_width, mdl.header.skin_height), (4, 2));
+
+        assert_eq!(mdl.skins.len(), 2);
+        assert_eq!(mdl.skins[0].images()[0].indices, [1, 2, 3, 4, 5, 6, 7, 8]);
+        let Skin::Group { intervals, images } = &mdl.skins[1] else {
+            panic!("expected a skin group");
+        };
+        assert_eq!(intervals, &[0.1, 0.2]);
+        assert_eq!(images[1].indices, [10; 8]);
+        let mut palette = vec![0u8; PALETTE_LEN];
+        palette[3..6].copy_from_slice(&[40, 50, 60]);
+        let palette = parse_palette(&palette).expect("palette");
+        assert_eq!(&mdl.skins[0].to_rgba8(&palette)[0][..4], &[40, 50, 60, 255]);
+
+        let triangle = mdl.triangles[0];
+        assert!(!triangle.faces_front);
+        assert_eq!(triangle.vertices, [0, 1, 2]);
+        let uvs = mdl.triangle_uvs(&triangle);
+        assert_eq!(uvs[0], [2.5 / 4.0, 0.25]);
+        assert_eq!(uvs[1], [1.5 / 4.0, 0.75]);
+
+        assert_eq!(mdl.frames.len(), 4);
+        let pose = &mdl.frames[0].poses()[0];
+        assert_eq!(pose.vertices[1], [0.0, 0.0, 10.0]);
+        assert_eq!(pose.normal_indices, [1, 2, 3]);
+        assert_eq!(pose.bbox_max, [500.0, 500.0, 510.0]);
+        assert_eq!(mdl.frames[2].poses()[1].name, "run2");
+
+        let names: Vec<_> = mdl
+            .animations()
+            .into_iter()
+            .map(|animation| (animation.name, animation.frames))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("stand".to_string(), 0..2),
+                ("run".to_string(), 2..3),
+                ("stand".to_string(), 3..4),
+            ]
+        );
+        assert_eq!(mdl.animation("run"), Some(2..3));
+        assert_eq!(mdl.animation("walk"), None);
+    }
+
+    #[test]
+    fn parse_mdl_rejects_bad_input() {
+        let data = sample_mdl();
+        for len in [0, HEADER_LEN, data.len() - 1] {
+            assert!(parse_mdl(&data[..len]).is_err(), "accepted {} bytes", len);
+        }
+
+        let mut bad_version = data.clone();
+        bad_version[4..8].copy_from_slice(&8i32.to_le_bytes());
+        assert!(matches!(
+            parse_mdl(&bad_version),
+            Err(MdlError::UnsupportedVersion(8))
+        ));
+
+        let mut bad_vertex = data.clone();
+        let triangle = HEADER_LEN + 12 + 4 + 8 + 20 + 36;
+        bad_vertex[triangle + 12..triangle + 16].copy_from_slice(&3i32.to_le_bytes());
+        assert!(matches!(
+            parse_mdl(&bad_vertex),
+            Err(MdlError::VertexOutOfRange {
+                triangle: 0,
+                vertex: 3
+            })
+        ));
+
+        let mut huge = data;
+        huge[60..64].copy_from_slice(&i32::MAX.to_le_bytes());
+        assert!(matches!(
+            parse_mdl(&huge),
+            Err(MdlError::InvalidCount {
+                field: "vertex",
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,402 @@
+use std::fmt;
+
+use crate::bsp::MipTexture;
+use crate::lmp::{self, IndexedImage, LmpError};
+
+/// Side of the square `conchars` font sheet: 16x16 glyphs of 8x8 pixels.
+pub const CONCHARS_SIZE: u32 = 128;
+/// Palette index Quake draws as transparent in HUD pictures.
+pub const TRANSPARENT_INDEX: u8 = 255;
+
+const MIPTEX_HEADER: usize = 40;
+
+#[derive(Debug)]
+pub enum WadError {
+    InvalidHeader,
+    DirectoryOutOfBounds,
+    TooManyLumps { lumps: usize },
+    LumpOutOfBounds { name: String },
+    Compressed { name: String },
+    NotFound { name: String },
+    NotAnImage { name: String, lump_type: LumpType },
+    InvalidMipTexture { name: String },
+    Image(LmpError),
+}
+
+impl fmt::Display for WadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WadError::InvalidHeader => write!(f, "invalid wad header"),
+            WadError::DirectoryOutOfBounds => write!(f, "wad directory out of bounds"),
+            WadError::TooManyLumps { lumps } => {
+                write!(f, "wad directory has too many lumps: {}", lumps)
+            }
+            WadError::LumpOutOfBounds { name } => write!(f, "wad lump out of bounds: {}", name),
+            WadError::Compressed { name } => {
+                write!(f, "wad lump is compressed: {}", name)
+            }
+            WadError::NotFound { name } => write!(f, "wad lump not found: {}", name),
+            WadError::NotAnImage { name, lump_type } => {
+                write!(f, "wad lump {} is not an image ({:?})", name, lump_type)
+            }
+            WadError::InvalidMipTexture { name } => {
+                write!(f, "invalid wad miptex: {}", name)
+            }
+            WadError::Image(err) => write!(f, "wad image error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for WadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WadError::Image(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<LmpError> for WadError {
+    fn from(err: LmpError) -> Self {
+        WadError::Image(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LumpType {
+    Palette,
+    StatusBar,
+    Pic,
+    Sound,
+    MipTex,
+    Other(u8),
+}
+
+impl LumpType {
+    fn from_byte(value: u8) -> Self {
+        match value {
+            0x40 => LumpType::Palette,
+            0x41 => LumpType::StatusBar,
+            0x42 => LumpType::Pic,
+            0x43 => LumpType::Sound,
+            0x44 => LumpType::MipTex,
+            other => LumpType::Other(other),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct WadLump {
+    /// Lower-cased, as lookups are case-insensitive.
+    pub name: String,
+    pub lump_type: LumpType,
+    pub offset: u32,
+    pub size: u32,
+    pub compression: u8,
+}
+
+#[derive(Debug)]
+pub struct WadFile {
+    data: Vec<u8>,
+    lumps: Vec<WadLump>,
+}
+
+impl WadFile {
+    pub fn lumps(&self) -> &[WadLump] {
+        &self.lumps
+    }
+
+    pub fn lump_by_name(&self, name: &str) -> Option<&WadLump> {
+        let needle = name.to_ascii_lowercase();
+        self.lumps.iter().find(|lump| lump.name == needle)
+    }
+
+    pub fn lump_data(&self, name: &str) -> Result<Option<&[u8]>, WadError> {
+        let Some(lump) = self.lump_by_name(name) else {
+            return Ok(None);
+        };
+        if lump.compression != 0 {
+            return Err(WadError::Compressed {
+                name: lump.name.clone(),
+            });
+        }
+        let start = lump.offset as usize;
+        Ok(Some(&self.data[start..start + lump.size as usize]))
+    }
+
+    /// Decodes a picture lump: a qpic, the `conchars` sheet, or mip level 0
+    /// of a miptex.
+    pub fn image(&self, name: &str) -> Result<IndexedImage, WadError> {
+        let data = self.lump_data(name)?.ok_or_else(|| WadError::NotFound {
+            name: name.to_string(),
+        })?;
+        let lump = self.lump_by_name(name).expect("lump exists");
+        match lump.lump_type {
+            _ if lump.name == "conchars" => parse_conchars(data),
+            LumpType::Pic | LumpType::StatusBar => Ok(lmp::parse_lmp_image(data)?),
+            LumpType::MipTex => mip_level_image(parse_mip_texture(&lump.name, data)?),
+            lump_type => Err(WadError::NotAnImage {
+                name: lump.name.clone(),
+                lump_type,
+            }),
+        }
+    }
+
+    pub fn mip_texture(&self, name: &str) -> Result<MipTexture, WadError> {
+        let data = self.lump_data(name)?.ok_or_else(|| WadError::NotFound {
+            name: name.to_string(),
+        })?;
+        parse_mip_texture(name, data)
+    }
+
+    /// Miptex lumps in directory order, as used by map compilers.
+    pub fn mip_textures(&self) -> impl Iterator<Item = &WadLump> {
+        self.lumps
+            .iter()
+            .filter(|lump| lump.lump_type == LumpType::MipTex && lump.name != "conchars")
+    }
+}
+
+pub fn parse_wad(data: Vec<u8>) -> Result<WadFile, WadError> {
+    const MAX_WAD_LUMPS: usize = 65_536;
+
+    if data.len() < 12 || &data[0..4] != b"WAD2" {
+        return Err(WadError::InvalidHeader);
+    }
+    let count = usize::try_from(read_i32_le(&data[4..8])).map_err(|_| WadError::InvalidHeader)?;
+    if count > MAX_WAD_LUMPS {
+        return Err(WadError::TooManyLumps { lumps: count });
+    }
+    let dir_offset =
+        usize::try_from(read_i32_le(&data[8..12])).map_err(|_| WadError::DirectoryOutOfBounds)?;
+    let directory = dir_offset
+        .checked_add(count * 32)
+        .and_then(|end| data.get(dir_offset..end))
+        .ok_or(WadError::DirectoryOutOfBounds)?;
+
+    let mut lumps = Vec::with_capacity(count);
+    for entry in directory.chunks_exact(32) {
+        let name_bytes = &entry[16..32];
+        let name_len = name_bytes.iter().position(|&b| b == 0).unwrap_or(16);
+        let name = String::from_utf8_lossy(&name_bytes[..name_len]).to_ascii_lowercase();
+        let offset = read_u32_le(&entry[0..4]);
+        let disk_size = read_u32_le(&entry[4..8]);
+        let end = (offset as usize).checked_add(disk_size as usize);
+        if end.is_none_or(|end| end > data.len()) {
+            return Err(WadError::LumpOutOfBounds { name });
+        }
+        lumps.push(WadLump {
+            name,
+            lump_type: LumpType::from_byte(entry[12]),
+            offset,
+            size: disk_size,
+            compression: entry[13],
+        });
+    }
+
+    Ok(WadFile { data, lumps })
+}
+
+/// Decodes raw lump bytes addressed by name alone, e.g. a VFS read of
+/// `gfx.wad#conback`: `conchars` is the font sheet, anything else is tried
+/// as a qpic and then as a miptex.
+pub fn parse_lump_image(name: &str, data: &[u8]) -> Result<IndexedImage, WadError> {
+    if name.eq_ignore_ascii_case("conchars") {
+        return parse_conchars(data);
+    }
+    match lmp::parse_lmp_image(data) {
+        Ok(image) if image.indices.len() + 8 == data.len() => Ok(image),
+        pic => match parse_mip_texture(name, data) {
+            Ok(texture) => mip_level_image(texture),
+            Err(_) => Ok(pic?),
+        },
+    }
+}
+
+/// The headerless 128x128 console font, with glyph background index 0
+/// remapped to [`TRANSPARENT_INDEX`] the way the engine loads it.
+pub fn parse_conchars(data: &[u8]) -> Result<IndexedImage, WadError> {
+    let len = (CONCHARS_SIZE * CONCHARS_SIZE) as usize;
+    let pixels = data.get(..len).ok_or(LmpError::DataTooShort {
+        expected: len,
+        actual: data.len(),
+    })?;
+    Ok(IndexedImage {
+        width: CONCHARS_SIZE,
+        height: CONCHARS_SIZE,
+        indices: pixels
+            .iter()
+            .map(|&index| if index == 0 { TRANSPARENT_INDEX } else { index })
+            .collect(),
+    })
+}
+
+pub fn parse_mip_texture(name: &str, data: &[u8]) -> Result<MipTexture, WadError> {
+    let invalid = || WadError::InvalidMipTexture {
+        name: name.to_string(),
+    };
+    let header = data.get(..MIPTEX_HEADER).ok_or_else(invalid)?;
+    let name_len = header[..16].iter().position(|&b| b == 0).unwrap_or(16);
+    let width = read_u32_le(&header[16..20]);
+    let height = read_u32_le(&header[20..24]);
+    if width == 0 || height == 0 || !width.is_multiple_of(8) || !height.is_multiple_of(8) {
+        return Err(invalid());
+    }
+    let mut mips: [Vec<u8>; 4] = Default::default();
+    for (level, mip) in mips.iter_mut().enumerate() {
+        let offset = read_u32_le(&header[24 + level * 4..28 + level * 4]) as usize;
+        let size = ((width >> level) as usize)
+            .checked_mul((height >> level) as usize)
+            .ok_or_else(invalid)?;
+        let pixels = offset
+            .checked_add(size)
+            .and_then(|end| data.get(offset..end))
+            .filter(|_| offset >= MIPTEX_HEADER)
+            .ok_or_else(invalid)?;
+        *mip = pixels.to_vec();
+    }
+    Ok(MipTexture {
+        name: String::from_utf8_lossy(&header[..name_len]).into_owned(),
+        width,
+        height,
+        mips,
+    })
+}
+
+fn mip_level_image(texture: MipTexture) -> Result<IndexedImage, WadError> {
+    let [level0, ..] = texture.mips;
+    Ok(IndexedImage {
+        width: texture.width,
+        height: texture.height,
+        indices: level0,
+    })
+}
+
+fn read_u32_le(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn read_i32_le(bytes: &[u8]) -> i32 {
+    i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qpic(width: u32, height: u32, fill: u8) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&width.to_le_bytes());
+        data.extend_from_slice(&height.to_le_bytes());
+        data.resize(8 + (width * height) as usize, fill);
+        data
+    }
+
+    fn miptex(name: &str, width: u32, height: u32) -> Vec<u8> {
+        let mut data = vec![0u8; MIPTEX_HEADER];
+        data[..name.len()].copy_from_slice(name.as_bytes());
+        data[16..20].copy_from_slice(&width.to_le_bytes());
+        data[20..24].copy_from_slice(&height.to_le_bytes());
+        for level in 0..4 {
+            let offset = data.len() as u32;
+            data[24 + level * 4..28 + level * 4].copy_from_slice(&offset.to_le_bytes());
+            let size = ((width >> level) * (height >> level)) as usize;
+            data.extend(std::iter::repeat_n(level as u8 + 1, size));
+        }
+        data
+    }
+
+    fn build_wad(lumps: &[(&str, u8, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![0u8; 12];
+        let mut directory = Vec::new();
+        for (name, lump_type, bytes) in lumps {
+            directory.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            directory.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            directory.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            directory.extend_from_slice(&[*lump_type, 0, 0, 0]);
+            let mut raw_name = [0u8; 16];
+            raw_name[..name.len()].copy_from_slice(name.as_bytes());
+            directory.extend_from_slice(&raw_name);
+            data.extend_from_slice(bytes);
+        }
+        let dir_offset = data.len() as u32;
+        data.extend_from_slice(&directory);
+        data[0..4].copy_from_slice(b"WAD2");
+        data[4..8].copy_from_slice(&(lumps.len() as u32).to_le_bytes());
+        data[8..12].copy_from_slice(&dir_offset.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn parse_wad_decodes_pics_conchars_and_miptex() {
+        let mut conchars = vec![0u8; 128 * 128];
+        conchars[1] = 15;
+        let data = build_wad(&[
+            ("CONBACK", 0x42, qpic(4, 2, 7)),
+            ("conchars", 0x44, conchars.clone()),
+            ("+0button", 0x44, miptex("+0button", 16, 8)),
+        ]);
+        let wad = parse_wad(data).expect("wad parse");
+        assert_eq!(wad.lumps().len(), 3);
+
+        let pic = wad.image("conback").expect("qpic");
+        assert_eq!((pic.width, pic.height), (4, 2));
+        assert_eq!(pic.indices, [7; 8]);
+
+        let font = wad.image("CONCHARS").expect("conchars");
+        assert_eq!((font.width, font.height), (128, 128));
+        assert_eq!(&font.indices[..2], &[TRANSPARENT_INDEX, 15]);
+        let raw = parse_lump_image("conchars", &conchars).expect("raw conchars");
+        assert_eq!(raw.indices, font.indices);
+
+        let names: Vec<_> = wad.mip_textures().map(|lump| lump.name.as_str()).collect();
+        assert_eq!(names, ["+0button"]);
+        let texture = wad.mip_texture("+0button").expect("miptex");
+        assert_eq!((texture.width, texture.height), (16, 8));
+        assert_eq!(texture.mips[3], [4; 2]);
+        let image = wad.image("+0button").expect("miptex image");
+        assert_eq!(image.indices, [1; 128]);
+
+        let bytes = wad.lump_data("+0button").unwrap().unwrap();
+        let image = parse_lump_image("+0button", bytes).expect("raw miptex");
+        assert_eq!((image.width, image.height), (16, 8));
+        let image = parse_lump_image("conback", &qpic(4, 2, 7)).expect("raw qpic");
+        assert_eq!((image.width, image.height), (4, 2));
+    }
+
+    #[test]
+    fn parse_wad_rejects_bad_input() {
+        assert!(matches!(
+            parse_wad(b"PACK\0\0\0\0\0\0\0\0".to_vec()),
+            Err(WadError::InvalidHeader)
+        ));
+
+        let mut data = build_wad(&[("conback", 0x42, qpic(4, 2, 7))]);
+        let dir_offset = data.len() - 32;
+        data[dir_offset + 4..dir_offset + 8].copy_from_slice(&1000u32.to_le_bytes());
+        assert!(matches!(
+            parse_wad(data),
+            Err(WadError::LumpOutOfBounds { .. })
+        ));
+
+        let mut data = build_wad(&[("conback", 0x42, qpic(4, 2, 7))]);
+        data[4..8].copy_from_slice(&2u32.to_le_bytes());
+        assert!(matches!(
+            parse_wad(data),
+            Err(WadError::DirectoryOutOfBounds)
+        ));
+
+        let mut data = build_wad(&[("conback", 0x42, qpic(4, 2, 7))]);
+        let dir_offset = data.len() - 32;
+        data[dir_offset + 13] = 1;
+        let wad = parse_wad(data).expect("wad parse");
+        assert!(matches!(
+            wad.image("conback"),
+            Err(WadError::Compressed { .. })
+        ));
+        assert!(matches!(
+            wad.image("missing"),
+            Err(WadError::NotFound { .. })
+        ));
+    }
+}
@@ -19,6 +19,16 @@ function on_spawn(id, x, y, z, yaw)
   log(string.format("spawned %d at %.2f %.2f %.2f yaw %.2f", id, x, y, z, yaw))
 end
 
+function on_trigger(name, other, phase)
+  if phase ~= "stay" then
+    log(string.format("trigger %s %s by %s", name, phase, other))
+  end
+end
+
+function on_liquid_damage(kind, amount, entity)
+  log(string.format("%s burns %s for %.1f", kind, entity, amount))
+end
+
 register_command("spawn", function(args)
   local x = tonumber(args[1]) or 0
   local y = tonumber(args[2]) or 0
@@ -27,6 +37,24 @@ register_command("spawn", function(args)
   spawn_entity(x, y, z, yaw)
 end)
 
+register_command("crate", function(args)
+  local mass = tonumber(args[1]) or 10
+  cmd(string.format("prop_spawn box 0.6 %.1f", mass))
+end)
+
+register_command("look", function(args)
+  if not trace_ray_all then
+    log("look: no scene loaded")
+    return
+  end
+  local x = tonumber(args[1]) or 0
+  local y = tonumber(args[2]) or 1
+  local z = tonumber(args[3]) or 0
+  for _, hit in ipairs(trace_ray_all(x, y, z, 0, -1, 0, 64, args[4])) do
+    log(string.format("look: %s %s at %.2f (%.2f %.2f %.2f)", hit.kind, hit.id, hit.distance, hit.x, hit.y, hit.z))
+  end
+end)
+
 register_command("sound", function(args)
   local asset = args[1] or "sound/misc/menu1.wav"
   play_sound(asset)
@@ -5,6 +5,12 @@ use std::hash::{Hash, Hasher};
 use bevy_ecs::prelude::*;
 use bevy_ecs::schedule::{Schedule, ScheduleLabel};
 
+pub mod rollback;
+pub mod save;
+
+pub use rollback::{RollbackBuffer, RollbackError};
+pub use save::{SaveRegistry, SaveState, SavedEntity, WorldSave};
+
 #[derive(Component, Copy, Clone, Debug, Default, PartialEq)]
 pub struct Transform {
     pub position: Vec3,
@@ -88,7 +94,7 @@ pub struct InputCommand {
     pub move_axis: Vec3,
 }
 
-#[derive(Resource, Debug, Default)]
+#[derive(Resource, Clone, Debug, Default)]
 pub struct InputStream {
     commands: Vec<InputCommand>,
     cursor: usize,
@@ -102,6 +108,22 @@ impl InputStream {
         }
     }
 
+    pub fn push(&mut self, command: InputCommand) {
+        self.commands.push(command);
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
     fn next(&mut self) -> Option<InputCommand> {
         let command = self.commands.get(self.cursor).copied();
         if command.is_some() {
@@ -114,6 +136,10 @@ impl InputStream {
 #[derive(Resource, Copy, Clone, Debug, Default)]
 pub struct CurrentInput(pub InputCommand);
 
+/// Number of fixed ticks simulated so far; advanced at the end of every `run_fixed`.
+#[derive(Resource, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FixedTick(pub u64);
+
 #[derive(ScheduleLabel, Clone, Copy, Debug, Hash, PartialEq, Eq)]
 pub struct FixedUpdate;
 
@@ -128,7 +154,7 @@ pub struct EcsSchedules {
 impl EcsSchedules {
     pub fn new() -> Self {
         let mut fixed = Schedule::new(FixedUpdate);
-        fixed.add_systems((apply_input, integrate_velocity));
+        fixed.add_systems((apply_input, integrate_velocity, advance_tick).chain());
         let update = Schedule::new(Update);
         Self { fixed, update }
     }
@@ -153,6 +179,7 @@ pub fn new_world() -> World {
     world.insert_resource(FixedTimeStep::default());
     world.insert_resource(CurrentInput::default());
     world.insert_resource(InputStream::default());
+    world.insert_resource(FixedTick::default());
     world
 }
 
@@ -175,6 +202,10 @@ fn integrate_velocity(mut query: Query<(&mut Transform, &Velocity)>, time: Res<F
     }
 }
 
+fn advance_tick(mut tick: ResMut<FixedTick>) {
+    tick.0 += 1;
+}
+
 pub fn hash_entity_state(world: &World, entity: Entity) -> Option<u64> {
     let transform = world.get::<Transform>(entity)?;
     let velocity = world.get::<Velocity>(entity)?;
@@ -0,0 +1,406 @@
+//! Tick-indexed world snapshots for rollback and resimulation.
+//!
+//! Only registered component and resource types are captured. Restoring a
+//! snapshot rewrites those types on entities that still exist; entities are
+//! expected to be stable across the rollback window.
+
+use std::any::Any;
+use std::fmt;
+
+use bevy_ecs::prelude::*;
+
+use crate::{CurrentInput, EcsSchedules, FixedTick, InputStream, Transform, Velocity};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RollbackError {
+    TickNotBuffered {
+        tick: u64,
+        oldest: Option<u64>,
+        newest: Option<u64>,
+    },
+    TickInPast {
+        tick: u64,
+        current: u64,
+    },
+}
+
+impl fmt::Display for RollbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RollbackError::TickNotBuffered {
+                tick,
+                oldest,
+                newest,
+            } => match (oldest, newest) {
+                (Some(oldest), Some(newest)) => write!(
+                    f,
+                    "tick {} not buffered (available {}..={})",
+                    tick, oldest, newest
+                ),
+                _ => write!(f, "tick {} not buffered (buffer empty)", tick),
+            },
+            RollbackError::TickInPast { tick, current } => {
+                write!(f, "tick {} is behind current tick {}", tick, current)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RollbackError {}
+
+trait Snapshotter: Send + Sync {
+    fn capture(&self, world: &mut World) -> Box<dyn Any + Send + Sync>;
+    fn restore(&self, world: &mut World, data: &(dyn Any + Send + Sync));
+}
+
+struct ComponentSnapshotter<T>(std::marker::PhantomData<fn() -> T>);
+
+impl<T: Component + Clone> Snapshotter for ComponentSnapshotter<T> {
+    fn capture(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        let mut query = world.query::<(Entity, &T)>();
+        let mut values: Vec<(Entity, T)> = query
+            .iter(world)
+            .map(|(entity, value)| (entity, value.clone()))
+            .collect();
+        values.sort_by_key(|(entity, _)| *entity);
+        Box::new(values)
+    }
+
+    fn restore(&self, world: &mut World, data: &(dyn Any + Send + Sync)) {
+        let Some(values) = data.downcast_ref::<Vec<(Entity, T)>>() else {
+            return;
+        };
+        let mut query = world.query_filtered::<Entity, With<T>>();
+        let stale: Vec<Entity> = query
+            .iter(world)
+            .filter(|entity| {
+                values
+                    .binary_search_by_key(entity, |(saved, _)| *saved)
+                    .is_err()
+            })
+            .collect();
+        for entity in stale {
+            world.entity_mut(entity).remove::<T>();
+        }
+        for (entity, value) in values {
+            if let Some(mut entity_mut) = world.get_entity_mut(*entity) {
+                entity_mut.insert(value.clone());
+            }
+        }
+    }
+}
+
+struct ResourceSnapshotter<T>(std::marker::PhantomData<fn() -> T>);
+
+impl<T: Resource + Clone> Snapshotter for ResourceSnapshotter<T> {
+    fn capture(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(world.get_resource::<T>().cloned())
+    }
+
+    fn restore(&self, world: &mut World, data: &(dyn Any + Send + Sync)) {
+        let Some(value) = data.downcast_ref::<Option<T>>() else {
+            return;
+        };
+        match value {
+            Some(value) => world.insert_resource(value.clone()),
+            None => {
+                world.remove_resource::<T>();
+            }
+        }
+    }
+}
+
+/// Captures only the `InputStream` read position. The command log itself stays
+/// outside snapshots so inputs that arrive after a save survive a rollback and
+/// feed the resimulation.
+struct InputCursorSnapshotter;
+
+impl Snapshotter for InputCursorSnapshotter {
+    fn capture(&self, world: &mut World) -> Box<dyn Any + Send + Sync> {
+        Box::new(
+            world
+                .get_resource::<InputStream>()
+                .map(|stream| stream.cursor),
+        )
+    }
+
+    fn restore(&self, world: &mut World, data: &(dyn Any + Send + Sync)) {
+        let Some(Some(cursor)) = data.downcast_ref::<Option<usize>>() else {
+            return;
+        };
+        if let Some(mut stream) = world.get_resource_mut::<InputStream>() {
+            stream.cursor = (*cursor).min(stream.commands.len());
+        }
+    }
+}
+
+struct RollbackFrame {
+    tick: u64,
+    entries: Vec<Box<dyn Any + Send + Sync>>,
+}
+
+/// Ring buffer of snapshots keyed by `FixedTick`.
+pub struct RollbackBuffer {
+    snapshotters: Vec<Box<dyn Snapshotter>>,
+    frames: Vec<Option<RollbackFrame>>,
+}
+
+impl RollbackBuffer {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            snapshotters: Vec::new(),
+            frames: (0..capacity).map(|_| None).collect(),
+        }
+    }
+
+    /// Buffer with the core simulation state registered: transforms, velocities,
+    /// the current input, the input stream cursor (not its command log) and the
+    /// tick counter.
+    pub fn with_core_state(capacity: usize) -> Self {
+        let mut buffer = Self::new(capacity);
+        buffer
+            .register_component::<Transform>()
+            .register_component::<Velocity>()
+            .register_resource::<CurrentInput>()
+            .register_input_cursor()
+            .register_resource::<FixedTick>();
+        buffer
+    }
+
+    /// Registers the `InputStream` cursor without snapshotting its commands.
+    pub fn register_input_cursor(&mut self) -> &mut Self {
+        self.snapshotters.push(Box::new(InputCursorSnapshotter));
+        self.clear();
+        self
+    }
+
+    pub fn register_component<T: Component + Clone>(&mut self) -> &mut Self {
+        self.snapshotters.push(Box::new(ComponentSnapshotter::<T>(
+            std::marker::PhantomData,
+        )));
+        self.clear();
+        self
+    }
+
+    pub fn register_resource<T: Resource + Clone>(&mut self) -> &mut Self {
+        self.snapshotters
+            .push(Box::new(ResourceSnapshotter::<T>(std::marker::PhantomData)));
+        self.clear();
+        self
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn clear(&mut self) {
+        for frame in &mut self.frames {
+            *frame = None;
+        }
+    }
+
+    pub fn oldest_tick(&self) -> Option<u64> {
+        self.frames.iter().flatten().map(|frame| frame.tick).min()
+    }
+
+    pub fn newest_tick(&self) -> Option<u64> {
+        self.frames.iter().flatten().map(|frame| frame.tick).max()
+    }
+
+    pub fn contains(&self, tick: u64) -> bool {
+        self.frame(tick).is_some()
+    }
+
+    /// Captures the registered state at the world's current `FixedTick`.
+    pub fn save(&mut self, world: &mut World) -> u64 {
+        let tick = current_tick(world);
+        let entries = self
+            .snapshotters
+            .iter()
+            .map(|snapshotter| snapshotter.capture(world))
+            .collect();
+        let slot = self.slot(tick);
+        self.frames[slot] = Some(RollbackFrame { tick, entries });
+        tick
+    }
+
+    /// Restores the snapshot taken at `tick`. Snapshots newer than `tick` are
+    /// dropped since resimulation will overwrite them.
+    pub fn rollback_to(&mut self, world: &mut World, tick: u64) -> Result<(), RollbackError> {
+        let Some(frame) = self.frame(tick) else {
+            return Err(RollbackError::TickNotBuffered {
+                tick,
+                oldest: self.oldest_tick(),
+                newest: self.newest_tick(),
+            });
+        };
+        for (snapshotter, entry) in self.snapshotters.iter().zip(frame.entries.iter()) {
+            snapshotter.restore(world, entry.as_ref());
+        }
+        world.insert_resource(FixedTick(tick));
+        for frame in &mut self.frames {
+            if frame.as_ref().is_some_and(|frame| frame.tick > tick) {
+                *frame = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves a snapshot and runs one fixed tick.
+    pub fn advance(&mut self, world: &mut World, schedules: &mut EcsSchedules) {
+        self.save(world);
+        schedules.run_fixed(world);
+    }
+
+    /// Runs fixed ticks until the world reaches `target_tick`, snapshotting each one.
+    pub fn resimulate(
+        &mut self,
+        world: &mut World,
+        schedules: &mut EcsSchedules,
+        target_tick: u64,
+    ) -> Result<(), RollbackError> {
+        let current = current_tick(world);
+        if target_tick < current {
+            return Err(RollbackError::TickInPast {
+                tick: target_tick,
+                current,
+            });
+        }
+        while current_tick(world) < target_tick {
+            self.advance(world, schedules);
+        }
+        Ok(())
+    }
+
+    fn frame(&self, tick: u64) -> Option<&RollbackFrame> {
+        self.frames[self.slot(tick)]
+            .as_ref()
+            .filter(|frame| frame.tick == tick)
+    }
+
+    fn slot(&self, tick: u64) -> usize {
+        (tick % self.frames.len() as u64) as usize
+    }
+}
+
+fn current_tick(world: &World) -> u64 {
+    world
+        .get_resource::<FixedTick>()
+        .map(|tick| tick.0)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{hash_entity_state, new_world, InputCommand, PlayerTag, Vec3};
+
+    fn inputs() -> Vec<InputCommand> {
+        (0..90)
+            .map(|index| {
+                let phase = index as f32 * 0.1;
+                InputCommand {
+                    move_axis: Vec3 {
+                        x: phase.cos(),
+                        y: 0.0,
+                        z: phase.sin(),
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn setup() -> (World, Entity) {
+        let mut world = new_world();
+        world.insert_resource(InputStream::new(inputs()));
+        let entity = world
+            .spawn((Transform::default(), Velocity::default(), PlayerTag))
+            .id();
+        (world, entity)
+    }
+
+    #[test]
+    fn rollback_then_resimulate_matches_straight_run() {
+        let (mut straight, straight_entity) = setup();
+        let mut schedules = EcsSchedules::new();
+        for _ in 0..120 {
+            schedules.run_fixed(&mut straight);
+        }
+        let expected = hash_entity_state(&straight, straight_entity);
+
+        let (mut world, entity) = setup();
+        let mut schedules = EcsSchedules::new();
+        let mut buffer = RollbackBuffer::with_core_state(64);
+        for _ in 0..120 {
+            buffer.advance(&mut world, &mut schedules);
+        }
+        assert_eq!(hash_entity_state(&world, entity), expected);
+
+        buffer.rollback_to(&mut world, 80).expect("rollback");
+        assert_eq!(world.resource::<FixedTick>().0, 80);
+        assert_ne!(hash_entity_state(&world, entity), expected);
+
+        buffer
+            .resimulate(&mut world, &mut schedules, 120)
+            .expect("resimulate");
+        assert_eq!(hash_entity_state(&world, entity), expected);
+    }
+
+    #[test]
+    fn rollback_keeps_inputs_pushed_after_save() {
+        let mut world = new_world();
+        world.insert_resource(InputStream::new(inputs()[..10].to_vec()));
+        let entity = world
+            .spawn((Transform::default(), Velocity::default(), PlayerTag))
+            .id();
+        let mut schedules = EcsSchedules::new();
+        let mut buffer = RollbackBuffer::with_core_state(32);
+        for _ in 0..10 {
+            buffer.advance(&mut world, &mut schedules);
+        }
+        let late = InputCommand {
+            move_axis: Vec3 {
+                x: 0.0,
+                y: 1.0,
+                z: 0.0,
+            },
+        };
+        world.resource_mut::<InputStream>().push(late);
+
+        buffer.rollback_to(&mut world, 5).expect("rollback");
+        let stream = world.resource::<InputStream>();
+        assert_eq!(stream.len(), 11);
+        assert_eq!(stream.cursor(), 5);
+
+        buffer
+            .resimulate(&mut world, &mut schedules, 11)
+            .expect("resimulate");
+        assert_eq!(world.resource::<InputStream>().cursor(), 11);
+        assert_eq!(world.resource::<CurrentInput>().0.move_axis, late.move_axis);
+        assert_eq!(
+            world.get::<Velocity>(entity).unwrap().linear,
+            late.move_axis
+        );
+    }
+
+    #[test]
+    fn rollback_rejects_evicted_ticks() {
+        let (mut world, _) = setup();
+        let mut schedules = EcsSchedules::new();
+        let mut buffer = RollbackBuffer::with_core_state(16);
+        for _ in 0..40 {
+            buffer.advance(&mut world, &mut schedules);
+        }
+        assert_eq!(buffer.oldest_tick(), Some(24));
+        assert_eq!(
+            buffer.rollback_to(&mut world, 10),
+            Err(RollbackError::TickNotBuffered {
+                tick: 10,
+                oldest: Some(24),
+                newest: Some(39),
+            })
+        );
+        assert_eq!(world.resource::<FixedTick>().0, 40);
+    }
+}
@@ -150,6 +150,7 @@ pub struct EngineScriptId(AssetKey);
 pub struct EngineLevelId(AssetKey);
 pub struct EngineCollisionWorldId(AssetKey);
 pub struct EngineTestMapId(AssetKey);
+pub struct EnginePrefabId(AssetKey);
 pub struct Quake1RawId(AssetKey);
 
 impl EngineTextureId {
@@ -212,6 +213,16 @@ impl EngineTestMapId {
     }
 }
 
+impl EnginePrefabId {
+    pub fn new(path: &str) -> Result<Self, AssetKeyError> {
+        AssetKey::from_parts("engine", "prefab", path).map(Self)
+    }
+
+    pub fn key(&self) -> &AssetKey {
+        &self.0
+    }
+}
+
 impl Quake1RawId {
     pub fn new(path: &str) -> Result<Self, AssetKeyError> {
         AssetKey::from_parts("quake1", "raw", path).map(Self)
@@ -222,11 +233,12 @@ impl Quake1RawId {
     }
 }
 
-const ENGINE_KINDS: [&str; 8] = [
+const ENGINE_KINDS: [&str; 9] = [
     "blob",
     "collision_world",
     "config",
     "level",
+    "prefab",
     "script",
     "test_map",
     "text",
@@ -296,6 +308,7 @@ fn is_valid_path(value: &str) -> bool {
             || ch == '_'
             || ch == '-'
             || ch == '.'
+            || ch == '#'
     })
 }
 
@@ -363,12 +376,24 @@ mod tests {
         assert_eq!(key.to_string(), "quake1:raw/gfx/conback.lmp");
     }
 
+    #[test]
+    fn parse_accepts_wad_lump_path() {
+        let key = AssetKey::parse("quake1:raw/gfx.wad#CONCHARS").unwrap();
+        assert_eq!(key.path(), "gfx.wad#conchars");
+    }
+
     #[test]
     fn engine_texture_id_builds_key() {
         let id = EngineTextureId::new("ui/console_bg").unwrap();
         assert_eq!(id.key().canonical(), "engine:texture/ui/console_bg");
     }
 
+    #[test]
+    fn engine_prefab_id_builds_key() {
+        let id = EnginePrefabId::new("props/crate.toml").unwrap();
+        assert_eq!(id.key().canonical(), "engine:prefab/props/crate.toml");
+    }
+
     #[test]
     fn engine_collision_world_id_builds_key() {
         let id = EngineCollisionWorldId::new("fixtures/arena.toml").unwrap();
@@ -11,6 +11,7 @@ use crate::asset_resolver::{AssetResolver, ResolvedPath};
 use crate::jobs::{JobError, JobHandle, JobQueue, Jobs, JobsConfig};
 use crate::logging;
 use crate::path_policy::PathPolicy;
+use crate::prefab::PrefabDef;
 use crate::vfs::Vfs;
 use collision_world::CollisionWorld;
 use test_map::{ResolvedSolid, TestMap};
@@ -78,6 +79,7 @@ pub enum AssetKind {
     EngineText,
     EngineBlob,
     EngineTestMap,
+    EnginePrefab,
     Quake1Raw,
     EngineTexture,
 }
@@ -91,6 +93,7 @@ impl AssetKind {
             AssetKind::EngineText => "engine:text",
             AssetKind::EngineBlob => "engine:blob",
             AssetKind::EngineTestMap => "engine:test_map",
+            AssetKind::EnginePrefab => "engine:prefab",
             AssetKind::Quake1Raw => "quake1:raw",
             AssetKind::EngineTexture => "engine:texture",
         }
@@ -143,6 +146,12 @@ pub struct TestMapAsset {
     source_len: usize,
 }
 
+/// Prefab as authored; resolve `extends` with [`crate::prefab::resolve_prefab`].
+pub struct PrefabAsset {
+    pub prefab: PrefabDef,
+    source_len: usize,
+}
+
 pub struct CollisionWorldAsset {
     pub world: CollisionWorld,
     source_len: usize,
@@ -243,6 +252,27 @@ impl AssetPayload for TestMapAsset {
     }
 }
 
+impl AssetPayload for PrefabAsset {
+    const KIND: AssetKind = AssetKind::EnginePrefab;
+
+    fn accepts(key: &AssetKey) -> bool {
+        key.namespace() == "engine" && key.kind() == "prefab"
+    }
+
+    fn decode(_key: &AssetKey, bytes: Vec<u8>) -> Result<Self, String> {
+        let text = String::from_utf8(bytes).map_err(|err| err.to_string())?;
+        let prefab = PrefabDef::parse(&text).map_err(|err| err.to_string())?;
+        Ok(Self {
+            prefab,
+            source_len: text.len(),
+        })
+    }
+
+    fn decoded_size(&self) -> usize {
+        self.source_len
+    }
+}
+
 impl AssetPayload for CollisionWorldAsset {
     const KIND: AssetKind = AssetKind::EngineCollisionWorld;
 
@@ -522,6 +552,10 @@ impl AssetManager {
         Arc::clone(&self.inner.jobs)
     }
 
+    pub fn path_policy(&self) -> &PathPolicy {
+        &self.inner.path_policy
+    }
+
     pub fn set_decode_budget_ms_per_tick(&self, budget_ms: u64) {
         let mut guard = self
             .inner
@@ -1101,6 +1135,15 @@ fn decode_for_kind(
                 content_hash,
             })
         }
+        AssetKind::EnginePrefab => {
+            let asset = PrefabAsset::decode(key, bytes)?;
+            let bytes = asset.decoded_size();
+            Ok(DecodedPayload {
+                value: Arc::new(asset),
+                decoded_bytes: bytes,
+                content_hash,
+            })
+        }
         AssetKind::EngineCollisionWorld => {
             let asset = CollisionWorldAsset::decode(key, bytes)?;
             let bytes = asset.decoded_size();
@@ -60,6 +60,8 @@ impl CvarFlags {
     pub const READ_ONLY: Self = Self(1 << 1);
     pub const NO_PERSIST: Self = Self(1 << 2);
     pub const DEV_ONLY: Self = Self(1 << 3);
+    /// Captured into save games and restored on load.
+    pub const SAVEGAME: Self = Self(1 << 4);
 
     pub fn contains(self, other: Self) -> bool {
         (self.0 & other.0) != 0
@@ -1069,6 +1071,29 @@ pub fn register_pallet_command_specs<U>(
         "Load config profile.",
         "cfg_load <name>",
     ))?;
+    registry.register_spec(CommandSpec::new(
+        "save",
+        "Save the running game session.",
+        "save <name>",
+    ))?;
+    registry.register_spec(CommandSpec::new(
+        "load",
+        "Load a saved game session.",
+        "load <name>",
+    ))?;
+    registry.register_spec(CommandSpec::new(
+        "prop_spawn",
+        "Spawn a dynamic prop in front of the player.",
+        "prop_spawn <box|sphere> [size] [mass]",
+    ))?;
+    registry.register_spec(
+        CommandSpec::new(
+            "dev_trace",
+            "Trace a ray from the camera and list every hit.",
+            "dev_trace [distance] [mask]",
+        )
+        .with_flags(CommandFlags::DEV_ONLY),
+    )?;
     registry.register_spec(
         CommandSpec::new(
             "dev_collision_draw",
@@ -1088,11 +1113,21 @@ pub fn register_pallet_command_specs<U>(
     registry.register_spec(
         CommandSpec::new(
             "player_set_profile",
-            "Switch movement profile.",
-            "player_set_profile <arena|rpg>",
+            "Switch motor or load an engine:config/motors profile.",
+            "player_set_profile <motor|profile>",
         )
         .with_flags(CommandFlags::DEV_ONLY),
     )?;
+    registry.register_spec(CommandSpec::new(
+        "cam_mode",
+        "Switch camera mode.",
+        "cam_mode <first|third|spectator|chase>",
+    ))?;
+    registry.register_spec(CommandSpec::new(
+        "cam_chase",
+        "Chase an entity with the camera, or stop chasing.",
+        "cam_chase <entity|off>",
+    ))?;
     registry.register_spec(
         CommandSpec::new(
             "player_dump_state",
@@ -1133,6 +1168,14 @@ pub fn register_pallet_command_specs<U>(
         )
         .with_flags(CommandFlags::DEV_ONLY),
     )?;
+    registry.register_spec(
+        CommandSpec::new(
+            "dev_movement_record",
+            "Record per-tick movement telemetry to CSV.",
+            "dev_movement_record <start <name>|stop>",
+        )
+        .with_flags(CommandFlags::DEV_ONLY),
+    )?;
     registry.register_spec(
         CommandSpec::new(
             "dev_input_replay",
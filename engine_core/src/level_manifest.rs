@@ -26,12 +26,23 @@ pub struct LevelManifestLines {
     pub requires: Option<usize>,
 }
 
+/// One `[[prefab]]` entry: an `engine:prefab` instance placed in world space.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PrefabPlacement {
+    pub prefab: AssetKey,
+    pub origin: [f32; 3],
+    pub yaw_deg: Option<f32>,
+    /// Line of the `[[prefab]]` header.
+    pub line: usize,
+}
+
 #[derive(Clone, Debug)]
 pub struct LevelManifest {
     pub version: u32,
     pub geometry: Option<AssetKey>,
     pub assets: Vec<AssetKey>,
     pub requires: Vec<AssetKey>,
+    pub prefabs: Vec<PrefabPlacement>,
     pub lines: LevelManifestLines,
 }
 
@@ -42,6 +53,7 @@ impl Default for LevelManifest {
             geometry: None,
             assets: Vec::new(),
             requires: Vec::new(),
+            prefabs: Vec::new(),
             lines: LevelManifestLines::default(),
         }
     }
@@ -55,6 +67,11 @@ impl LevelManifest {
         }
         deps.extend(self.assets.iter().cloned());
         deps.extend(self.requires.iter().cloned());
+        for placement in &self.prefabs {
+            if !deps.contains(&placement.prefab) {
+                deps.push(placement.prefab.clone());
+            }
+        }
         deps
     }
 }
@@ -235,10 +252,21 @@ fn level_relative_path(level_path: &str) -> PathBuf {
     Path::new(level_path).join("level.toml")
 }
 
+#[derive(Default)]
+struct PlacementDraft {
+    line: usize,
+    seen: HashSet<String>,
+    prefab: Option<AssetKey>,
+    origin: [f32; 3],
+    yaw_deg: Option<f32>,
+}
+
 fn parse_level_manifest(path: &Path, contents: &str) -> Result<LevelManifest, LevelManifestError> {
     let mut manifest = LevelManifest::default();
     let mut seen = HashSet::new();
     let mut pending: Option<(String, usize, String)> = None;
+    let mut placements: Vec<PlacementDraft> = Vec::new();
+    let mut in_placement = false;
 
     for (idx, raw_line) in contents.lines().enumerate() {
         let line_no = idx + 1;
@@ -248,6 +276,13 @@ fn parse_level_manifest(path: &Path, contents: &str) -> Result<LevelManifest, Le
             continue;
         }
         if line.starts_with('[') && line.ends_with(']') && !line.contains('=') {
+            in_placement = line == "[[prefab]]";
+            if in_placement {
+                placements.push(PlacementDraft {
+                    line: line_no,
+                    ..PlacementDraft::default()
+                });
+            }
             continue;
         }
 
@@ -268,14 +303,17 @@ fn parse_level_manifest(path: &Path, contents: &str) -> Result<LevelManifest, Le
                     });
                 }
             }
-            apply_field(
-                &mut manifest,
-                &mut seen,
-                path,
-                start_line,
-                &key,
-                buffer.trim(),
-            )?;
+            match placements.last_mut().filter(|_| in_placement) {
+                Some(draft) => apply_placement_field(draft, path, start_line, &key, buffer.trim())?,
+                None => apply_field(
+                    &mut manifest,
+                    &mut seen,
+                    path,
+                    start_line,
+                    &key,
+                    buffer.trim(),
+                )?,
+            }
             continue;
         }
 
@@ -299,7 +337,10 @@ fn parse_level_manifest(path: &Path, contents: &str) -> Result<LevelManifest, Le
                 });
             }
         }
-        apply_field(&mut manifest, &mut seen, path, line_no, &key, value.trim())?;
+        match placements.last_mut().filter(|_| in_placement) {
+            Some(draft) => apply_placement_field(draft, path, line_no, &key, value.trim())?,
+            None => apply_field(&mut manifest, &mut seen, path, line_no, &key, value.trim())?,
+        }
     }
 
     if let Some((key, line_no, _)) = pending {
@@ -311,9 +352,56 @@ fn parse_level_manifest(path: &Path, contents: &str) -> Result<LevelManifest, Le
         });
     }
 
+    for draft in placements {
+        let prefab = draft.prefab.ok_or_else(|| LevelManifestError {
+            path: path.to_path_buf(),
+            line: Some(draft.line),
+            field: Some("prefab".to_string()),
+            message: "[[prefab]] entry missing prefab key".to_string(),
+        })?;
+        manifest.prefabs.push(PrefabPlacement {
+            prefab,
+            origin: draft.origin,
+            yaw_deg: draft.yaw_deg,
+            line: draft.line,
+        });
+    }
+
     Ok(manifest)
 }
 
+fn apply_placement_field(
+    draft: &mut PlacementDraft,
+    path: &Path,
+    line_no: usize,
+    key: &str,
+    value: &str,
+) -> Result<(), LevelManifestError> {
+    let error = |message: String| LevelManifestError {
+        path: path.to_path_buf(),
+        line: Some(line_no),
+        field: Some(format!("prefab.{}", key)),
+        message,
+    };
+    if !draft.seen.insert(key.to_string()) {
+        return Err(error("duplicate field".to_string()));
+    }
+    match key {
+        "prefab" => {
+            let value = parse_string_value(value).map_err(error)?;
+            let key_value = AssetKey::parse(&value).map_err(|err| error(err.to_string()))?;
+            if key_value.namespace() != "engine" || key_value.kind() != "prefab" {
+                return Err(error("prefab must be engine:prefab/<path>".to_string()));
+            }
+            draft.prefab = Some(key_value);
+        }
+        "origin" => draft.origin = parse_vec3(value).map_err(error)?,
+        "yaw_deg" => draft.yaw_deg = Some(parse_f32(value).map_err(error)?),
+        _ => {}
+    }
+    Ok(())
+}
+
 fn apply_field(
     manifest: &mut LevelManifest,
     seen: &mut HashSet<String>,
@@ -434,7 +522,7 @@ fn apply_field(
     Ok(())
 }
 
-fn split_assignment(line: &str) -> Result<(String, String), String> {
+pub(crate) fn split_assignment(line: &str) -> Result<(String, String), String> {
     let mut parts = line.splitn(2, '=');
     let key = parts
         .next()
@@ -451,7 +539,7 @@ fn split_assignment(line: &str) -> Result<(String, String), String> {
     Ok((key, value))
 }
 
-fn strip_comment(line: &str) -> String {
+pub(crate) fn strip_comment(line: &str) -> String {
     let mut out = String::new();
     let mut in_quotes = false;
     let chars = line.chars().peekable();
@@ -498,7 +586,7 @@ fn bracket_balance(value: &str) -> Option<i32> {
     }
 }
 
-fn parse_string_value(value: &str) -> Result<String, String> {
+pub(crate) fn parse_string_value(value: &str) -> Result<String, String> {
     let trimmed = value.trim();
     if trimmed.starts_with('"') {
         parse_quoted_string(trimmed)
@@ -507,7 +595,7 @@ fn parse_string_value(value: &str) -> Result<String, String> {
     }
 }
 
-fn parse_array_strings(value: &str) -> Result<Vec<String>, String> {
+pub(crate) fn parse_array_strings(value: &str) -> Result<Vec<String>, String> {
     let trimmed = value.trim();
     let mut chars = trimmed.chars().peekable();
     match chars.next() {
@@ -610,9 +698,58 @@ fn parse_bare_value(
     Ok(out.trim().to_string())
 }
 
-fn parse_u32(value: &str) -> Result<u32, String> {
+pub(crate) fn parse_u32(value: &str) -> Result<u32, String> {
     value
         .trim()
         .parse()
         .map_err(|_| "invalid integer".to_string())
 }
+
+pub(crate) fn parse_f32(value: &str) -> Result<f32, String> {
+    value
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| "invalid number".to_string())
+}
+
+pub(crate) fn parse_vec3(value: &str) -> Result<[f32; 3], String> {
+    let items = parse_array_strings(value)?;
+    match items.as_slice() {
+        [x, y, z] => Ok([parse_f32(x)?, parse_f32(y)?, parse_f32(z)?]),
+        _ => Err(format!("expected 3 numbers, got {}", items.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_prefab_placements() {
+        let contents = r#"
+version = 1
+assets = ["engine:prefab/props/crate.toml"]
+
+[[prefab]]
+prefab = "engine:prefab/props/crate.toml"
+origin = [1.0, 0.5, -2.0]
+yaw_deg = 90
+
+[[prefab]]
+prefab = "engine:prefab/props/barrel.toml"
+"#;
+        let path = Path::new("level.toml");
+        let manifest = parse_level_manifest(path, contents).expect("manifest");
+        assert_eq!(manifest.prefabs.len(), 2);
+        assert_eq!(manifest.prefabs[0].origin, [1.0, 0.5, -2.0]);
+        assert_eq!(manifest.prefabs[0].yaw_deg, Some(90.0));
+        assert_eq!(manifest.prefabs[1].line, 10);
+        assert_eq!(manifest.dependencies().len(), 2);
+
+        let err = parse_level_manifest(path, "[[prefab]]\norigin = [0, 0]\n").unwrap_err();
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.field.as_deref(), Some("prefab.origin"));
+    }
+}
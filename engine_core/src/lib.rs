@@ -10,6 +10,7 @@ pub mod logging;
 pub mod mount_manifest;
 pub mod observability;
 pub mod path_policy;
+pub mod prefab;
 pub mod quake_index;
 pub mod vfs;
 
@@ -138,6 +138,11 @@ impl PathPolicy {
         &self.user_config_root
     }
 
+    /// User-data directory for save games (`<user_config_root>/saves`).
+    pub fn saves_dir(&self) -> PathBuf {
+        self.user_config_root.join("saves")
+    }
+
     pub fn resolve_config_file(
         &self,
         kind: ConfigKind,
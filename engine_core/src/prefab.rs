@@ -0,0 +1,414 @@
+//! `engine:prefab/<name>.toml` entity templates.
+//!
+//! A prefab is a flat TOML subset (one value per line) split into optional
+//! sections: `[transform]`, `[collider]`, `[controller]`, `[script]` and
+//! `[sounds]`. Top-level `extends` names a parent prefab; unset fields are
+//! inherited from it when resolved.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+use crate::asset_id::AssetKey;
+use crate::level_manifest::{
+    parse_f32, parse_string_value, parse_u32, parse_vec3, split_assignment, strip_comment,
+};
+
+const MAX_PREFAB_DEPTH: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColliderSpec {
+    Box {
+        half_extents: [f32; 3],
+    },
+    Sphere {
+        radius: f32,
+    },
+    /// `height` is the cylinder length between the sphere centers.
+    Capsule {
+        radius: f32,
+        height: f32,
+    },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PrefabLines {
+    pub version: Option<usize>,
+    pub extends: Option<usize>,
+    pub transform: Option<usize>,
+    pub collider: Option<usize>,
+    pub controller: Option<usize>,
+    pub script: Option<usize>,
+    pub sounds: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct PrefabDef {
+    pub version: u32,
+    pub extends: Option<AssetKey>,
+    /// Offset from the placement origin.
+    pub origin: Option<[f32; 3]>,
+    pub yaw_deg: Option<f32>,
+    pub collider: Option<ColliderSpec>,
+    pub controller_profile: Option<String>,
+    pub script: Option<AssetKey>,
+    /// Sound slot name to `engine:` sound asset.
+    pub sounds: BTreeMap<String, AssetKey>,
+    pub lines: PrefabLines,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrefabError {
+    pub line: Option<usize>,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl PrefabError {
+    fn at(line: usize, field: &str, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            field: Some(field.to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for PrefabError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "prefab error")?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        if let Some(field) = &self.field {
+            write!(f, " [{}]", field)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for PrefabError {}
+
+#[derive(Default)]
+struct ColliderDraft {
+    line: usize,
+    shape: Option<String>,
+    half_extents: Option<[f32; 3]>,
+    radius: Option<f32>,
+    height: Option<f32>,
+}
+
+impl ColliderDraft {
+    fn finish(self) -> Result<ColliderSpec, PrefabError> {
+        let missing = |field: &str| {
+            PrefabError::at(
+                self.line,
+                "collider",
+                format!(
+                    "{} collider requires {}",
+                    self.shape.as_deref().unwrap_or(""),
+                    field
+                ),
+            )
+        };
+        let positive = |value: f32, field: &str| {
+            if value > 0.0 {
+                Ok(value)
+            } else {
+                Err(PrefabError::at(
+                    self.line,
+                    "collider",
+                    format!("{} must be > 0", field),
+                ))
+            }
+        };
+        match self.shape.as_deref() {
+            Some("box") => {
+                let half_extents = self.half_extents.ok_or_else(|| missing("half_extents"))?;
+                for value in half_extents {
+                    positive(value, "half_extents")?;
+                }
+                Ok(ColliderSpec::Box { half_extents })
+            }
+            Some("sphere") => Ok(ColliderSpec::Sphere {
+                radius: positive(self.radius.ok_or_else(|| missing("radius"))?, "radius")?,
+            }),
+            Some("capsule") => Ok(ColliderSpec::Capsule {
+                radius: positive(self.radius.ok_or_else(|| missing("radius"))?, "radius")?,
+                height: positive(self.height.ok_or_else(|| missing("height"))?, "height")?,
+            }),
+            Some(other) => Err(PrefabError::at(
+                self.line,
+                "collider.shape",
+                format!("unknown collider shape '{}' (box, sphere, capsule)", other),
+            )),
+            None => Err(PrefabError::at(
+                self.line,
+                "collider.shape",
+                "collider section requires shape",
+            )),
+        }
+    }
+}
+
+impl PrefabDef {
+    pub fn parse(contents: &str) -> Result<Self, PrefabError> {
+        let mut prefab = PrefabDef {
+            version: 1,
+            ..PrefabDef::default()
+        };
+        let mut section = String::new();
+        let mut seen = HashSet::new();
+        let mut collider: Option<ColliderDraft> = None;
+
+        for (idx, raw_line) in contents.lines().enumerate() {
+            let line_no = idx + 1;
+            let line = strip_comment(raw_line);
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') && !line.contains('=') {
+                section = line[1..line.len() - 1].trim().to_string();
+                let slot = match section.as_str() {
+                    "transform" => &mut prefab.lines.transform,
+                    "collider" => &mut prefab.lines.collider,
+                    "controller" => &mut prefab.lines.controller,
+                    "script" => &mut prefab.lines.script,
+                    "sounds" => &mut prefab.lines.sounds,
+                    _ => {
+                        return Err(PrefabError::at(
+                            line_no,
+                            &section,
+                            format!("unknown section [{}]", section),
+                        ))
+                    }
+                };
+                if slot.replace(line_no).is_some() {
+                    return Err(PrefabError::at(line_no, &section, "duplicate section"));
+                }
+                if section == "collider" {
+                    collider = Some(ColliderDraft {
+                        line: line_no,
+                        ..ColliderDraft::default()
+                    });
+                }
+                continue;
+            }
+
+            let (key, value) = split_assignment(line).map_err(|message| PrefabError {
+                line: Some(line_no),
+                field: None,
+                message,
+            })?;
+            let field = if section.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", section, key)
+            };
+            if !seen.insert(field.clone()) {
+                return Err(PrefabError::at(line_no, &field, "duplicate field"));
+            }
+            let error = |message: String| PrefabError::at(line_no, &field, message);
+            match (section.as_str(), key.as_str()) {
+                ("", "version") => {
+                    let version = parse_u32(&value).map_err(error)?;
+                    if version != 1 {
+                        return Err(error(format!("unsupported version {}", version)));
+                    }
+                    prefab.version = version;
+                    prefab.lines.version = Some(line_no);
+                }
+                ("", "extends") => {
+                    prefab.extends = Some(parse_prefab_key(&value).map_err(error)?);
+                    prefab.lines.extends = Some(line_no);
+                }
+                ("transform", "origin") => {
+                    prefab.origin = Some(parse_vec3(&value).map_err(error)?);
+                }
+                ("transform", "yaw_deg") => {
+                    prefab.yaw_deg = Some(parse_f32(&value).map_err(error)?);
+                }
+                ("collider", _) => {
+                    let draft = collider.as_mut().expect("collider section draft");
+                    match key.as_str() {
+                        "shape" => draft.shape = Some(parse_string_value(&value).map_err(error)?),
+                        "half_extents" => {
+                            draft.half_extents = Some(parse_vec3(&value).map_err(error)?)
+                        }
+                        "radius" => draft.radius = Some(parse_f32(&value).map_err(error)?),
+                        "height" => draft.height = Some(parse_f32(&value).map_err(error)?),
+                        _ => return Err(error("unknown field".to_string())),
+                    }
+                }
+                ("controller", "profile") => {
+                    let profile = parse_string_value(&value).map_err(error)?;
+                    if profile.trim().is_empty() {
+                        return Err(error("profile must not be empty".to_string()));
+                    }
+                    prefab.controller_profile = Some(profile);
+                }
+                ("script", "asset") => {
+                    let key = parse_engine_key(&value, "script").map_err(error)?;
+                    prefab.script = Some(key);
+                }
+                ("sounds", slot) => {
+                    let asset = parse_string_value(&value).map_err(error)?;
+                    let asset = AssetKey::parse(&asset).map_err(|err| error(err.to_string()))?;
+                    prefab.sounds.insert(slot.to_string(), asset);
+                }
+                _ => return Err(error("unknown field".to_string())),
+            }
+        }
+
+        if let Some(draft) = collider {
+            prefab.collider = Some(draft.finish()?);
+        }
+        Ok(prefab)
+    }
+
+    /// Fills every field left unset here from `parent`. Sound slots merge,
+    /// with this prefab's entries winning.
+    pub fn inherit_from(&mut self, parent: &PrefabDef) {
+        self.origin = self.origin.or(parent.origin);
+        self.yaw_deg = self.yaw_deg.or(parent.yaw_deg);
+        self.collider = self.collider.or(parent.collider);
+        if self.controller_profile.is_none() {
+            self.controller_profile = parent.controller_profile.clone();
+        }
+        if self.script.is_none() {
+            self.script = parent.script.clone();
+        }
+        for (slot, asset) in &parent.sounds {
+            self.sounds
+                .entry(slot.clone())
+                .or_insert_with(|| asset.clone());
+        }
+    }
+}
+
+/// Loads `key` and walks its `extends` chain through `load`, returning the
+/// flattened prefab. Cycles and chains deeper than 16 are rejected.
+pub fn resolve_prefab<F>(key: &AssetKey, mut load: F) -> Result<PrefabDef, String>
+where
+    F: FnMut(&AssetKey) -> Result<PrefabDef, String>,
+{
+    let mut resolved = load(key).map_err(|err| format!("{}: {}", key, err))?;
+    let mut chain = vec![key.clone()];
+    let mut parent_key = resolved.extends.clone();
+    while let Some(parent) = parent_key {
+        if chain.contains(&parent) {
+            return Err(format!("prefab inheritance cycle at {}", parent));
+        }
+        if chain.len() >= MAX_PREFAB_DEPTH {
+            return Err(format!(
+                "prefab inheritance deeper than {} at {}",
+                MAX_PREFAB_DEPTH, parent
+            ));
+        }
+        let parent_def = load(&parent).map_err(|err| format!("{}: {}", parent, err))?;
+        resolved.inherit_from(&parent_def);
+        parent_key = parent_def.extends.clone();
+        chain.push(parent);
+    }
+    Ok(resolved)
+}
+
+fn parse_prefab_key(value: &str) -> Result<AssetKey, String> {
+    parse_engine_key(value, "prefab")
+}
+
+fn parse_engine_key(value: &str, kind: &str) -> Result<AssetKey, String> {
+    let value = parse_string_value(value)?;
+    let key = AssetKey::parse(&value).map_err(|err| err.to_string())?;
+    if key.namespace() != "engine" || key.kind() != kind {
+        return Err(format!("expected engine:{}/<path>, got {}", kind, key));
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+version = 1
+
+[collider]
+shape = "box"
+half_extents = [0.5, 0.5, 0.5]
+
+[sounds]
+hit = "engine:blob/sound/crate_hit.wav"
+break = "engine:blob/sound/crate_break.wav"
+"#;
+
+    const EXPLOSIVE: &str = r#"
+version = 1
+extends = "engine:prefab/props/crate.toml"
+
+[transform]
+yaw_deg = 45.0
+
+[script]
+asset = "engine:script/props/explosive.lua"
+
+[sounds]
+break = "engine:blob/sound/explode.wav" # overrides the base slot
+"#;
+
+    #[test]
+    fn resolve_merges_parent_fields() {
+        let resolved = resolve_prefab(
+            &AssetKey::parse("engine:prefab/props/explosive_crate.toml").unwrap(),
+            |key| match key.path() {
+                "props/crate.toml" => PrefabDef::parse(BASE).map_err(|err| err.to_string()),
+                "props/explosive_crate.toml" => {
+                    PrefabDef::parse(EXPLOSIVE).map_err(|err| err.to_string())
+                }
+                other => Err(format!("missing {}", other)),
+            },
+        )
+        .expect("resolve");
+        assert_eq!(
+            resolved.collider,
+            Some(ColliderSpec::Box {
+                half_extents: [0.5, 0.5, 0.5]
+            })
+        );
+        assert_eq!(resolved.yaw_deg, Some(45.0));
+        assert_eq!(
+            resolved.script.as_ref().map(|key| key.canonical()),
+            Some("engine:script/props/explosive.lua")
+        );
+        assert_eq!(
+            resolved.sounds["break"].canonical(),
+            "engine:blob/sound/explode.wav"
+        );
+        assert_eq!(
+            resolved.sounds["hit"].canonical(),
+            "engine:blob/sound/crate_hit.wav"
+        );
+    }
+
+    #[test]
+    fn parse_reports_line_and_field() {
+        let err = PrefabDef::parse("version = 1\n\n[collider]\nshape = \"cone\"\n").unwrap_err();
+        assert_eq!(err.line, Some(3));
+        assert_eq!(err.field.as_deref(), Some("collider.shape"));
+
+        let err = PrefabDef::parse("version = 1\n[transform]\norigin = [1, 2]\n").unwrap_err();
+        assert_eq!(err.line, Some(3));
+        assert_eq!(err.field.as_deref(), Some("transform.origin"));
+    }
+
+    #[test]
+    fn resolve_rejects_cycles() {
+        let err = resolve_prefab(&AssetKey::parse("engine:prefab/a.toml").unwrap(), |key| {
+            let parent = if key.path() == "a.toml" { "b" } else { "a" };
+            PrefabDef::parse(&format!("extends = \"engine:prefab/{}.toml\"", parent))
+                .map_err(|err| err.to_string())
+        })
+        .unwrap_err();
+        assert!(err.contains("cycle"), "{}", err);
+    }
+}
@@ -3,8 +3,10 @@ use std::fmt;
 use std::fs;
 use std::io::Read;
 use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 
-use compat_quake::pak;
+use compat_quake::{pak, wad};
 use zip::read::ZipArchive;
 
 #[derive(Debug)]
@@ -14,6 +16,7 @@ pub enum VfsError {
     UnsafePath(String),
     Pak(String),
     Pk3(String),
+    Wad(String),
 }
 
 impl fmt::Display for VfsError {
@@ -24,6 +27,7 @@ impl fmt::Display for VfsError {
             VfsError::UnsafePath(path) => write!(f, "unsafe path: {}", path),
             VfsError::Pak(message) => write!(f, "pak error: {}", message),
             VfsError::Pk3(message) => write!(f, "pk3 error: {}", message),
+            VfsError::Wad(message) => write!(f, "wad error: {}", message),
         }
     }
 }
@@ -101,6 +105,16 @@ struct VfsMount {
     kind: MountKind,
     source: PathBuf,
     payload: MountPayload,
+    wads: Mutex<HashMap<String, CachedWad>>,
+}
+
+/// Parsed WAD directory kept per container so `#lump` lookups don't re-read
+/// the archive. Directory mounts stamp entries with size and mtime so edited
+/// files are picked up again.
+#[derive(Debug)]
+struct CachedWad {
+    stamp: Option<(u64, Option<SystemTime>)>,
+    wad: Arc<wad::WadFile>,
 }
 
 #[derive(Debug)]
@@ -149,6 +163,7 @@ impl Vfs {
             kind: MountKind::Dir,
             source: path.clone(),
             payload: MountPayload::Dir(path),
+            wads: Mutex::default(),
         });
         Ok(())
     }
@@ -167,6 +182,7 @@ impl Vfs {
             kind: MountKind::Pak,
             source: path.clone(),
             payload: MountPayload::Pak(PakMount { pak }),
+            wads: Mutex::default(),
         });
         Ok(())
     }
@@ -206,6 +222,7 @@ impl Vfs {
             kind: MountKind::Pk3,
             source: path.clone(),
             payload: MountPayload::Pk3(Pk3Mount { entries, lookup }),
+            wads: Mutex::default(),
         });
         Ok(())
     }
@@ -253,6 +270,8 @@ impl Vfs {
         Err(VfsError::NotFound(virtual_path.to_string()))
     }
 
+    /// Reads a file, or a lump inside a WAD when the path names one as
+    /// `gfx.wad#conback`.
     pub fn read(&self, virtual_path: &str) -> Result<Vec<u8>, VfsError> {
         self.read_with_provenance(virtual_path)
             .map(|(data, _)| data)
@@ -337,6 +356,58 @@ impl Vfs {
 
 impl VfsMount {
     fn read(&self, rel: &VirtualPath) -> Result<Option<Vec<u8>>, VfsError> {
+        let Some((container, lump)) = rel.split_lump() else {
+            return self.read_file(rel);
+        };
+        let Some(wad) = self.wad(&container)? else {
+            return Ok(None);
+        };
+        let bytes = wad
+            .lump_data(lump)
+            .map_err(|err| VfsError::Wad(err.to_string()))?;
+        Ok(bytes.map(<[u8]>::to_vec))
+    }
+
+    fn wad(&self, container: &VirtualPath) -> Result<Option<Arc<wad::WadFile>>, VfsError> {
+        let key = container.normalized();
+        let stamp = self.file_stamp(container);
+        if let Some(cached) = self
+            .wads
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(&key)
+        {
+            if cached.stamp == stamp {
+                return Ok(Some(Arc::clone(&cached.wad)));
+            }
+        }
+        let Some(data) = self.read_file(container)? else {
+            return Ok(None);
+        };
+        let wad = Arc::new(wad::parse_wad(data).map_err(|err| VfsError::Wad(err.to_string()))?);
+        self.wads
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .insert(
+                key,
+                CachedWad {
+                    stamp,
+                    wad: Arc::clone(&wad),
+                },
+            );
+        Ok(Some(wad))
+    }
+
+    fn file_stamp(&self, rel: &VirtualPath) -> Option<(u64, Option<SystemTime>)> {
+        // Archive mounts are immutable once mounted; only loose files can change.
+        let MountPayload::Dir(root) = &self.payload else {
+            return None;
+        };
+        let metadata = fs::metadata(safe_join(root, rel).ok()?).ok()?;
+        Some((metadata.len(), metadata.modified().ok()))
+    }
+
+    fn read_file(&self, rel: &VirtualPath) -> Result<Option<Vec<u8>>, VfsError> {
         if rel.components.is_empty() {
             return Ok(None);
         }
@@ -382,6 +453,9 @@ impl VfsMount {
         if rel.components.is_empty() {
             return false;
         }
+        if let Some((container, lump)) = rel.split_lump() {
+            return matches!(self.wad(&container), Ok(Some(wad)) if wad.lump_by_name(lump).is_some());
+        }
         match &self.payload {
             MountPayload::Dir(root) => safe_join(root, rel)
                 .ok()
@@ -441,6 +515,18 @@ impl VirtualPath {
     fn normalized(&self) -> String {
         join_components(&self.components)
     }
+
+    /// Splits `dir/file.wad#lump` into the container path and lump name.
+    fn split_lump(&self) -> Option<(VirtualPath, &str)> {
+        let (last, parents) = self.components.split_last()?;
+        let (file, lump) = last.split_once('#')?;
+        if file.is_empty() || lump.is_empty() {
+            return None;
+        }
+        let mut components = parents.to_vec();
+        components.push(file.to_string());
+        Some((VirtualPath { components }, lump))
+    }
 }
 
 impl VirtualRoot {
@@ -621,4 +707,33 @@ mod tests {
         let data = vfs.read("raw/q3/docs/readme.txt").unwrap();
         assert_eq!(data, b"hello");
     }
+
+    #[test]
+    fn vfs_reads_wad_lumps() {
+        let root = temp_dir("wad");
+        fs::create_dir_all(root.join("gfx")).unwrap();
+        let mut wad = b"WAD2".to_vec();
+        wad.extend_from_slice(&1u32.to_le_bytes());
+        wad.extend_from_slice(&15u32.to_le_bytes());
+        wad.extend_from_slice(b"pic");
+        wad.extend_from_slice(&12u32.to_le_bytes());
+        wad.extend_from_slice(&3u32.to_le_bytes());
+        wad.extend_from_slice(&3u32.to_le_bytes());
+        wad.extend_from_slice(&[0x42, 0, 0, 0]);
+        let mut name = [0u8; 16];
+        name[..7].copy_from_slice(b"CONBACK");
+        wad.extend_from_slice(&name);
+        fs::write(root.join("gfx").join("gfx.wad"), &wad).unwrap();
+
+        let mut vfs = Vfs::new();
+        vfs.add_dir_mount("raw/quake", &root).unwrap();
+        assert_eq!(vfs.read("raw/quake/gfx/gfx.wad#conback").unwrap(), b"pic");
+        assert!(vfs.exists("raw/quake/gfx/gfx.wad#conback"));
+        assert!(!vfs.exists("raw/quake/gfx/gfx.wad#missing"));
+        assert_eq!(vfs.mounts[0].wads.lock().unwrap().len(), 1);
+        assert!(matches!(
+            vfs.read("raw/quake/gfx/gfx.wad#missing"),
+            Err(VfsError::NotFound(_))
+        ));
+    }
 }
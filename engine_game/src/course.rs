@@ -0,0 +1,458 @@
+//! Test map courses: the physics world, session and player a host builds for
+//! a cooked test map. Pallet and the headless movement runner share this so
+//! recorded input plays out the same way in both.
+
+use bevy_ecs::entity::Entity;
+use character_collision::CollisionProfile;
+use character_motor_arena::{ArenaMotorConfig, FrictionlessJumpMode};
+use character_motor_rpg::RpgMotorConfig;
+use collision_world::{CollisionGroups, CollisionLayerTable, CollisionWorld, SurfaceTable};
+use map_cook::build_test_map_colliders;
+use physics_rapier::{layer_groups, ColliderSource, LiquidKind, PhysicsWorld, SurfaceMaterial};
+use player_camera::PlayerCamera;
+use player_controller::{DirectInputAdapter, PlayerController, PlayerFrame, RawInput};
+use rapier3d::math::{Isometry, Vector};
+use rapier3d::prelude::{ColliderHandle, InteractionGroups, Real, RigidBodyHandle};
+use test_map::{TestMap, CLIMBABLE_TAG};
+
+use crate::motor::{MotorKind, MotorRegistry, MotorSet};
+use crate::mover::{mover_from_test_map, MoverBody};
+use crate::prop::prop_from_test_map;
+use crate::rules::{GameRules, SandboxRules};
+use crate::session::GameSession;
+use crate::spawn::{capsule_offset, drop_to_ground, SpawnPoint};
+
+pub const TEST_MAP_MOVE_SPEED: f32 = 4.5;
+pub const TEST_MAP_CROUCH_SPEED: f32 = TEST_MAP_MOVE_SPEED * 0.5;
+pub const TEST_MAP_SWIM_SPEED: f32 = TEST_MAP_MOVE_SPEED * 0.7;
+pub const TEST_MAP_ACCEL: f32 = 30.0;
+pub const TEST_MAP_AIR_ACCEL: f32 = 18.0;
+pub const TEST_MAP_FRICTION: f32 = 18.0;
+pub const TEST_MAP_STOP_SPEED: f32 = 4.0;
+pub const TEST_MAP_GRAVITY: f32 = 9.81;
+pub const TEST_MAP_JUMP_SPEED: f32 = 4.5;
+pub const TEST_MAP_MAX_AIR_SPEED: f32 = 6.0;
+pub const TEST_MAP_AIR_RESISTANCE: f32 = 1.0;
+pub const TEST_MAP_GOLDEN_ANGLE_TARGET: f32 = 45.0 * std::f32::consts::PI / 180.0;
+pub const TEST_MAP_GOLDEN_ANGLE_GAIN_MIN: f32 = 1.0;
+pub const TEST_MAP_GOLDEN_ANGLE_GAIN_PEAK: f32 = 1.25;
+pub const TEST_MAP_GOLDEN_ANGLE_BLEND_START: f32 = TEST_MAP_MOVE_SPEED;
+pub const TEST_MAP_GOLDEN_ANGLE_BLEND_END: f32 = TEST_MAP_MOVE_SPEED * 1.4;
+pub const TEST_MAP_CORRIDOR_SHAPING_STRENGTH_DEG: f32 = 80.0;
+pub const TEST_MAP_CORRIDOR_SHAPING_MIN_SPEED: f32 = 10.0;
+pub const TEST_MAP_CORRIDOR_SHAPING_MAX_ANGLE_DEG: f32 = 6.0;
+pub const TEST_MAP_CORRIDOR_SHAPING_MIN_ALIGNMENT: f32 = 0.2;
+pub const TEST_MAP_JUMP_BUFFER_WINDOW: f32 = 0.1;
+pub const TEST_MAP_BHOP_GRACE: f32 = 0.1;
+pub const TEST_MAP_BHOP_FRICTION_SCALE: f32 = 0.25;
+pub const TEST_MAP_BHOP_FRICTION_SCALE_BEST_ANGLE: f32 = 0.0;
+pub const TEST_MAP_EYE_HEIGHT: f32 = 1.6;
+/// How far below the course bounds a player falls before respawning.
+pub const TEST_MAP_KILL_DEPTH: f32 = 32.0;
+
+pub fn test_map_arena_config() -> ArenaMotorConfig {
+    ArenaMotorConfig {
+        max_speed_ground: TEST_MAP_MOVE_SPEED,
+        max_speed_air: TEST_MAP_MAX_AIR_SPEED,
+        max_speed_crouch: TEST_MAP_CROUCH_SPEED,
+        ground_accel: TEST_MAP_ACCEL,
+        air_accel: TEST_MAP_AIR_ACCEL,
+        friction: TEST_MAP_FRICTION,
+        stop_speed: TEST_MAP_STOP_SPEED,
+        gravity: TEST_MAP_GRAVITY,
+        jump_speed: TEST_MAP_JUMP_SPEED,
+        air_resistance: TEST_MAP_AIR_RESISTANCE,
+        air_resistance_speed_scale: TEST_MAP_MAX_AIR_SPEED * 16.0,
+        golden_angle_target: TEST_MAP_GOLDEN_ANGLE_TARGET,
+        golden_angle_gain_min: TEST_MAP_GOLDEN_ANGLE_GAIN_MIN,
+        golden_angle_gain_peak: TEST_MAP_GOLDEN_ANGLE_GAIN_PEAK,
+        golden_angle_bonus_scale: 0.25,
+        golden_angle_blend_speed_start: TEST_MAP_GOLDEN_ANGLE_BLEND_START,
+        golden_angle_blend_speed_end: TEST_MAP_GOLDEN_ANGLE_BLEND_END,
+        corridor_shaping_strength: TEST_MAP_CORRIDOR_SHAPING_STRENGTH_DEG.to_radians(),
+        corridor_shaping_min_speed: TEST_MAP_CORRIDOR_SHAPING_MIN_SPEED,
+        corridor_shaping_max_angle_per_tick: TEST_MAP_CORRIDOR_SHAPING_MAX_ANGLE_DEG.to_radians(),
+        corridor_shaping_min_alignment: TEST_MAP_CORRIDOR_SHAPING_MIN_ALIGNMENT,
+        jump_buffer_enabled: true,
+        jump_buffer_window: TEST_MAP_JUMP_BUFFER_WINDOW,
+        frictionless_jump_mode: FrictionlessJumpMode::Soft,
+        frictionless_jump_grace: TEST_MAP_BHOP_GRACE,
+        frictionless_jump_friction_scale: TEST_MAP_BHOP_FRICTION_SCALE,
+        frictionless_jump_friction_scale_best_angle: TEST_MAP_BHOP_FRICTION_SCALE_BEST_ANGLE,
+        swim_level: 0.5,
+        max_speed_swim: TEST_MAP_SWIM_SPEED,
+        swim_accel: 10.0,
+        swim_drag: 2.5,
+        swim_buoyancy: 1.2,
+        water_exit_level: 0.7,
+        water_exit_jump_speed: TEST_MAP_JUMP_SPEED * 1.1,
+        ladder_speed: 3.0,
+        ladder_jump_impulse: 4.0,
+        ladder_jump_lift: TEST_MAP_JUMP_SPEED * 0.5,
+    }
+}
+
+pub fn test_map_rpg_config() -> RpgMotorConfig {
+    RpgMotorConfig::default()
+}
+
+pub fn rapier_groups(groups: CollisionGroups) -> InteractionGroups {
+    layer_groups(groups.memberships, groups.filter)
+}
+
+/// Physics-side tuning for the named surface; `None` when the table lacks it.
+pub fn surface_material(table: &SurfaceTable, surface: &str) -> Option<SurfaceMaterial> {
+    let id = table.index(surface)?;
+    let spec = table.get(id)?;
+    Some(SurfaceMaterial {
+        id,
+        friction: spec.friction,
+        acceleration: spec.acceleration,
+        slide: spec.slide,
+        footsteps: spec.footsteps.as_slice().into(),
+    })
+}
+
+/// What a course is built from.
+#[derive(Clone, Copy, Debug)]
+pub struct CourseSource<'a> {
+    /// Map name the session enters, e.g. the test map's asset key.
+    pub name: &'a str,
+    pub map: &'a TestMap,
+    pub collision_world: &'a CollisionWorld,
+    pub layers: &'a CollisionLayerTable,
+    pub surfaces: &'a SurfaceTable,
+}
+
+/// Colliders a course added to the physics world.
+#[derive(Clone, Debug, Default)]
+pub struct CoursePhysics {
+    pub loaded_chunks: Vec<u32>,
+    pub collider_handles: Vec<ColliderHandle>,
+    /// Sensors built from `trigger` solids, which the collision world omits.
+    pub trigger_handles: Vec<(String, ColliderHandle)>,
+    /// Kinematic bodies for solids moved by `[[movers]]`, keyed by solid id.
+    pub mover_bodies: Vec<(String, RigidBodyHandle)>,
+    pub triangle_count: u64,
+    /// Chunks whose payload had no matching solid.
+    pub warnings: Vec<String>,
+}
+
+/// Inserts every chunk of `collision_world` plus the trigger, liquid and
+/// mover solids it leaves out.
+pub fn build_course_physics(
+    world: &mut PhysicsWorld,
+    map: &TestMap,
+    collision_world: &CollisionWorld,
+    layers: &CollisionLayerTable,
+    surfaces: &SurfaceTable,
+) -> Result<CoursePhysics, String> {
+    let colliders = build_test_map_colliders(map)
+        .map_err(|err| format!("test map collider build failed: {}", err))?;
+    let mut physics = CoursePhysics::default();
+    let mut collider_by_id = std::collections::HashMap::new();
+    for mut collider in colliders.colliders {
+        let groups = layers.groups_for_tags(&collider.tags);
+        collider
+            .collider
+            .set_collision_groups(rapier_groups(groups));
+        let source = ColliderSource::Solid {
+            id: collider.id.clone(),
+            tags: collider.tags.clone(),
+        };
+        let liquid = collider.liquid_tag().and_then(LiquidKind::from_tag);
+        if collider.is_trigger() {
+            let climbable = collider.is_climbable();
+            let handle = world.insert_sensor_collider(collider.collider);
+            world.set_collider_source(handle, source);
+            world.set_climbable(handle, climbable);
+            physics.trigger_handles.push((collider.id, handle));
+        } else if let Some(kind) = liquid {
+            let handle = world.insert_liquid_collider(collider.collider, kind);
+            world.set_collider_source(handle, source);
+        } else if map.is_mover_solid(&collider.id) {
+            let surface = surface_material(surfaces, surfaces.surface_for_tags(&collider.tags));
+            let (body, handle) = world.insert_kinematic_collider(collider.collider);
+            world.set_collider_source(handle, source);
+            world.set_surface(handle, surface);
+            physics.mover_bodies.push((collider.id, body));
+        } else {
+            collider_by_id.insert(collider.id.clone(), (collider.collider, source));
+        }
+    }
+
+    physics.loaded_chunks = collision_world
+        .chunk_bounds_bvh
+        .select_intersecting(&collision_world.chunks, &collision_world.root_bounds);
+    if physics.loaded_chunks.is_empty() {
+        return Err("collision world has no selectable chunks".to_string());
+    }
+    for chunk_index in &physics.loaded_chunks {
+        let Some(chunk) = collision_world.chunks.get(*chunk_index as usize) else {
+            continue;
+        };
+        let payload_id = chunk
+            .payload_ref
+            .strip_prefix("inline:test_map/")
+            .unwrap_or(&chunk.chunk_id);
+        let Some((mut collider, source)) = collider_by_id.remove(payload_id) else {
+            physics.warnings.push(format!(
+                "collision world chunk missing collider: {} (payload {})",
+                chunk.chunk_id, chunk.payload_ref
+            ));
+            continue;
+        };
+        collider.set_collision_groups(rapier_groups(layers.chunk_groups(chunk)));
+        let climbable = source.tags().iter().any(|tag| tag == CLIMBABLE_TAG);
+        let surface = surfaces.chunk_surface(chunk, surfaces.surface_for_tags(source.tags()));
+        let surface = surface_material(surfaces, surface);
+        let handle = world.insert_static_collider(collider);
+        world.set_climbable(handle, climbable);
+        world.set_surface(handle, surface);
+        let source = if chunk.payload_ref.starts_with("inline:test_map/") {
+            source
+        } else {
+            ColliderSource::Chunk {
+                chunk_id: chunk.chunk_id.clone(),
+            }
+        };
+        world.set_collider_source(handle, source);
+        physics.collider_handles.push(handle);
+        physics.triangle_count = physics
+            .triangle_count
+            .saturating_add(chunk.triangle_count as u64);
+    }
+    if physics.collider_handles.is_empty() {
+        return Err("collision world produced zero colliders".to_string());
+    }
+    Ok(physics)
+}
+
+/// Spawns the course's triggers, movers and props into `session`. Returns
+/// the ids of props without a usable shape.
+pub fn spawn_course_entities(
+    session: &mut GameSession,
+    world: &mut PhysicsWorld,
+    map: &TestMap,
+    physics: &CoursePhysics,
+) -> Vec<String> {
+    for (name, handle) in &physics.trigger_handles {
+        session.spawn_trigger(name.clone(), *handle);
+    }
+    let map_scale = map.map_to_world_scale.unwrap_or(1.0);
+    for spec in &map.movers {
+        let Some((_, body)) = physics
+            .mover_bodies
+            .iter()
+            .find(|(solid, _)| *solid == spec.solid)
+        else {
+            continue;
+        };
+        let Some(base) = world.bodies().get(*body).map(|body| *body.position()) else {
+            continue;
+        };
+        session.spawn_mover(
+            mover_from_test_map(spec, map_scale),
+            MoverBody { body: *body, base },
+        );
+    }
+    let mut unusable = Vec::new();
+    for spec in &map.props {
+        let desc = prop_from_test_map(spec, map_scale);
+        if session.spawn_prop(world, spec.id.clone(), &desc).is_none() {
+            unusable.push(spec.id.clone());
+        }
+    }
+    unusable
+}
+
+/// Velocity the player carries into the next tick: ceilings stop upward
+/// motion and grounded players only keep falling speed on slopes.
+pub fn settle_velocity(frame: &PlayerFrame) -> Vector<Real> {
+    let mut velocity = frame.kinematics.velocity;
+    if frame.collision.hit_ceiling && velocity.y > 0.0 {
+        velocity.y = 0.0;
+    }
+    if frame.kinematics.grounded && velocity.y < 0.0 {
+        let allow_downhill = frame
+            .kinematics
+            .ground_normal
+            .map(|normal| normal.y < 0.99)
+            .unwrap_or(false);
+        if !allow_downhill {
+            velocity.y = 0.0;
+        }
+    }
+    velocity
+}
+
+/// Sandbox rules for a course: players falling `TEST_MAP_KILL_DEPTH` below
+/// the collision bounds respawn.
+pub fn course_rules(collision_world: &CollisionWorld) -> SandboxRules {
+    SandboxRules {
+        kill_height: collision_world.root_bounds.min[1] - TEST_MAP_KILL_DEPTH,
+    }
+}
+
+/// A loaded course: the player starts above the middle of the collision
+/// bounds, drops to the ground, and is driven one fixed tick at a time.
+pub struct Course {
+    pub world: PhysicsWorld,
+    /// Colliders the course added to `world`.
+    pub colliders: CoursePhysics,
+    pub session: GameSession,
+    pub player: Entity,
+    pub controller: PlayerController<DirectInputAdapter, MotorSet>,
+    /// Problems that did not stop the load, such as props without a shape.
+    pub warnings: Vec<String>,
+}
+
+impl Course {
+    /// Loads a course under [`course_rules`] with `motor` selected, as the
+    /// headless movement runner does.
+    pub fn build(
+        map: &TestMap,
+        collision_world: &CollisionWorld,
+        layers: &CollisionLayerTable,
+        surfaces: &SurfaceTable,
+        motor: MotorKind,
+    ) -> Result<Self, String> {
+        let source = CourseSource {
+            name: &map.name,
+            map,
+            collision_world,
+            layers,
+            surfaces,
+        };
+        let mut course = Self::load(course_rules(collision_world), &source)?;
+        course.controller.motor_mut().set_kind(motor)?;
+        Ok(course)
+    }
+
+    /// Enters the course under `rules`: builds its physics, spawns its
+    /// triggers, movers and props, then spawns player 0 and snaps them to
+    /// the ground below the spawn.
+    pub fn load(rules: impl GameRules, source: &CourseSource) -> Result<Self, String> {
+        let CourseSource {
+            name,
+            map,
+            collision_world,
+            layers,
+            surfaces,
+        } = *source;
+        let arena_config = test_map_arena_config();
+        let mut world = PhysicsWorld::new(Vector::new(0.0, -arena_config.gravity, 0.0));
+        let physics = build_course_physics(&mut world, map, collision_world, layers, surfaces)?;
+        world.step(1.0 / 60.0);
+
+        let bounds = collision_world.root_bounds;
+        let center = [
+            (bounds.min[0] + bounds.max[0]) * 0.5,
+            bounds.max[1] + 1.0,
+            (bounds.min[2] + bounds.max[2]) * 0.5,
+        ];
+        let mut session = GameSession::new(rules);
+        session.enter_map(name, vec![SpawnPoint::new(center)]);
+        let mut warnings = physics.warnings.clone();
+        for prop in spawn_course_entities(&mut session, &mut world, map, &physics) {
+            warnings.push(format!("test map prop '{}' has no usable shape", prop));
+        }
+        let player = session.add_player(0);
+        let spawn = session
+            .tick()
+            .into_iter()
+            .next()
+            .map(|spawned| spawned.point.origin)
+            .ok_or_else(|| "course has no spawn point".to_string())?;
+
+        let mut profile = CollisionProfile::arena_default();
+        profile.layer_mask = layers.named_mask("player");
+        let offset = capsule_offset(&profile);
+        let position = Isometry::translation(spawn[0], spawn[1] + offset, spawn[2]);
+        let motor = MotorSet::new(&MotorRegistry::builtin());
+        let camera = PlayerCamera::new(TEST_MAP_EYE_HEIGHT);
+        let mut controller =
+            PlayerController::new(DirectInputAdapter, motor, profile, camera, position);
+        let extent = [
+            bounds.max[0] - bounds.min[0],
+            bounds.max[1] - bounds.min[1],
+            bounds.max[2] - bounds.min[2],
+        ];
+        let drop = (extent[1] + extent[0].max(extent[2])).max(1.0) + offset + 2.0;
+        let landed = drop_to_ground(
+            controller.collision_mut(),
+            &world,
+            position,
+            drop,
+            session.fixed_dt(),
+        );
+        let state = controller.state_mut();
+        state.position = landed.position;
+        state.grounded = landed.grounded;
+        state.ground_normal = landed.ground_normal;
+        Ok(Self {
+            world,
+            colliders: physics,
+            session,
+            player,
+            controller,
+            warnings,
+        })
+    }
+
+    /// Runs one tick with absolute look angles, as pallet drives its test
+    /// map player.
+    pub fn step(&mut self, raw: RawInput, yaw: Real, pitch: Real, dt: Real) -> PlayerFrame {
+        self.world.step(dt);
+        self.session.sync_props(&self.world);
+        self.controller.camera_mut().set_look(yaw, pitch);
+        let mut frame = self.controller.tick(&self.world, raw, dt);
+        self.controller
+            .collision()
+            .push_dynamic_bodies(&mut self.world, dt);
+        let velocity = settle_velocity(&frame);
+        frame.kinematics.velocity = velocity;
+        self.controller.state_mut().velocity = velocity;
+
+        let origin = frame.kinematics.position.translation;
+        self.session.set_player_position(
+            self.player,
+            ecs::Vec3 {
+                x: origin.x,
+                y: origin.y - self.controller.collision().foot_offset(),
+                z: origin.z,
+            },
+        );
+        if frame.collision.crushed {
+            self.session.request_respawn(self.player);
+        }
+        let liquid = frame
+            .kinematics
+            .immersion
+            .map(|immersion| (immersion.kind, immersion.level));
+        self.session.set_player_liquid(self.player, liquid);
+        self.session.sense_triggers(
+            &self.world,
+            self.player,
+            self.controller.collision().capsule(),
+            &frame.kinematics.position,
+        );
+        let events = self.world.drain_events();
+        self.session.apply_physics_events(&events);
+        self.session.set_fixed_dt(dt);
+        for spawned in self.session.tick() {
+            if spawned.entity == self.player {
+                let origin = spawned.point.origin;
+                let offset = self.controller.collision().foot_offset();
+                let state = self.controller.state_mut();
+                state.position = Isometry::translation(origin[0], origin[1] + offset, origin[2]);
+                state.velocity = Vector::zeros();
+                state.grounded = false;
+            }
+        }
+        self.session.apply_mover_targets(&mut self.world);
+        self.session.drain_trigger_events();
+        self.session.drain_liquid_damage_events();
+        frame
+    }
+}
@@ -0,0 +1,300 @@
+//! BSP levels: the session, physics world and player a host builds when it
+//! enters a Quake map. Everything here is world meters.
+
+use bevy_ecs::entity::Entity;
+use engine_core::asset_id::AssetKey;
+use engine_core::level_manifest::PrefabPlacement;
+use engine_core::prefab::PrefabDef;
+use map_cook::{build_quake1_submodels, BspKind, MapSidecar};
+use physics_rapier::PhysicsWorld;
+use rapier3d::prelude::Vector;
+
+use crate::liquid::insert_bsp_liquids;
+use crate::map_entities::{
+    spawn_map_entities, spawn_points_from_entities, spawn_quake_movers, MapEntity,
+    MapEntitySpawnReport, QUAKE_CLASSNAME_PREFABS, QUAKE_SPAWN_CLASSNAMES,
+};
+use crate::prefab::spawn_placements;
+use crate::rules::GameRules;
+use crate::session::GameSession;
+use crate::spawn::{spawn_points_from_sidecar, SpawnPoint};
+
+/// What a BSP level is built from.
+#[derive(Clone, Copy, Debug)]
+pub struct QuakeLevelSource<'a> {
+    pub map: &'a str,
+    /// BSP entities, already in world meters at `scale`.
+    pub entities: &'a [MapEntity],
+    pub sidecar: Option<&'a MapSidecar>,
+    /// Raw BSP, for brush model movers and liquid volumes.
+    pub bsp: &'a [u8],
+    /// Meters per Quake unit.
+    pub scale: f32,
+    /// `[[prefab]]` entries of the level manifest, in world meters.
+    pub placements: &'a [PrefabPlacement],
+}
+
+pub struct QuakeLevel {
+    pub session: GameSession,
+    pub physics: PhysicsWorld,
+    pub player: Entity,
+    /// Where the player entered the map; `None` when it has no spawns.
+    pub spawn: Option<SpawnPoint>,
+    pub movers: Vec<Entity>,
+    pub liquids: usize,
+    pub entities: MapEntitySpawnReport,
+    /// Entities spawned from the level manifest's placements.
+    pub placed: Vec<Entity>,
+    /// Parts of the map that failed to load; the rest of the level works.
+    pub warnings: Vec<String>,
+}
+
+impl QuakeLevel {
+    /// Enters `source.map` under `rules` and spawns player 0.
+    ///
+    /// Authored sidecar spawns replace the BSP's `info_player_*` entities.
+    /// Sidecar triggers, `func_door`/`func_plat` movers, liquid volumes,
+    /// prefab-backed map entities and manifest placements are spawned before
+    /// the first tick, which places the player. `load_prefab` resolves the
+    /// classname and placement prefabs.
+    pub fn load<F>(rules: impl GameRules, source: &QuakeLevelSource, mut load_prefab: F) -> Self
+    where
+        F: FnMut(&AssetKey) -> Result<PrefabDef, String>,
+    {
+        let mut session = GameSession::new(rules);
+        let spawns = match source.sidecar.map(spawn_points_from_sidecar) {
+            Some(spawns) if !spawns.is_empty() => spawns,
+            _ => spawn_points_from_entities(source.entities, QUAKE_SPAWN_CLASSNAMES),
+        };
+        session.enter_map(source.map, spawns);
+        let mut physics = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        if let Some(sidecar) = source.sidecar {
+            session.spawn_sidecar_triggers(&mut physics, sidecar);
+        }
+        let mut warnings = Vec::new();
+        let movers = match build_quake1_submodels(source.bsp, source.scale) {
+            Ok(submodels) => spawn_quake_movers(
+                &mut session,
+                &mut physics,
+                source.entities,
+                &submodels,
+                source.scale,
+            ),
+            Err(err) => {
+                warnings.push(format!("bsp submodel build failed: {}", err));
+                Vec::new()
+            }
+        };
+        let liquids =
+            match insert_bsp_liquids(&mut physics, BspKind::Quake1, source.bsp, source.scale) {
+                Ok(count) => count,
+                Err(err) => {
+                    warnings.push(format!("bsp liquid build failed: {}", err));
+                    0
+                }
+            };
+        let entities = match spawn_map_entities(
+            session.world_mut(),
+            source.entities,
+            QUAKE_CLASSNAME_PREFABS,
+            &mut load_prefab,
+        ) {
+            Ok(report) => report,
+            Err(err) => {
+                warnings.push(format!("map entity spawn failed: {}", err));
+                MapEntitySpawnReport::default()
+            }
+        };
+        let placed =
+            match spawn_placements(session.world_mut(), source.placements, &mut load_prefab) {
+                Ok(placed) => placed,
+                Err(err) => {
+                    warnings.push(format!("level placement spawn failed: {}", err));
+                    Vec::new()
+                }
+            };
+        let player = session.add_player(0);
+        let spawn = session
+            .tick()
+            .into_iter()
+            .find(|spawned| spawned.entity == player)
+            .map(|spawned| spawned.point);
+        Self {
+            session,
+            physics,
+            player,
+            spawn,
+            movers,
+            liquids,
+            entities,
+            placed,
+            warnings,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map_entities::tests::bsp_with_box_model;
+    use crate::map_entities::BrushModel;
+    use crate::mover::Mover;
+    use crate::prefab::{Facing, PrefabInstance};
+    use crate::rules::SandboxRules;
+    use ecs::{Transform, Vec3};
+    use engine_core::level_manifest::load_level_manifest;
+
+    #[test]
+    fn quake_level_spawns_player_movers_and_entities() {
+        let scale = 1.0 / 32.0;
+        let bsp = bsp_with_box_model([0.0, 0.0, 0.0], [64.0, 16.0, 96.0]);
+        let mut door = MapEntity {
+            classname: "func_door".to_string(),
+            brush_model: Some(1),
+            ..MapEntity::default()
+        };
+        door.keys = vec![("angle".to_string(), "-1".to_string())];
+        let start = MapEntity {
+            classname: "info_player_start".to_string(),
+            origin: [1.0, 2.0, 3.0],
+            yaw_deg: Some(90.0),
+            ..MapEntity::default()
+        };
+        let light = MapEntity {
+            classname: "light".to_string(),
+            ..MapEntity::default()
+        };
+        let entities = [door, start, light];
+        let source = QuakeLevelSource {
+            map: "e1m1",
+            entities: &entities,
+            sidecar: None,
+            bsp: &bsp,
+            scale,
+            placements: &[],
+        };
+
+        let mut level = QuakeLevel::load(SandboxRules::default(), &source, |_| {
+            Ok(PrefabDef::default())
+        });
+        // The test BSP has no node tree, so only the liquid pass gives up.
+        assert_eq!(level.warnings.len(), 1, "{:?}", level.warnings);
+        assert!(level.warnings[0].starts_with("bsp liquid"));
+        let spawn = level.spawn.expect("player spawned");
+        assert_eq!(spawn.origin, [1.0, 2.0, 3.0]);
+        assert_eq!(spawn.yaw_deg, Some(90.0));
+        assert_eq!(level.movers.len(), 1);
+        let world = level.session.world();
+        assert!(world.get::<Mover>(level.movers[0]).is_some());
+        assert_eq!(
+            world.get::<BrushModel>(level.movers[0]),
+            Some(&BrushModel(1))
+        );
+        assert_eq!(level.entities.spawned.len(), 2);
+        assert_eq!(level.entities.unmapped.get("info_player_start"), Some(&1));
+        assert_eq!(level.session.brush_model_offsets().len(), 1);
+    }
+
+    #[test]
+    fn manifest_placements_spawn_with_the_level() {
+        let dir = std::env::temp_dir().join(format!("pallet_level_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("temp dir");
+        let path = dir.join("level.toml");
+        std::fs::write(
+            &path,
+            r#"
+version = 1
+geometry = "quake1:bsp/e1m1"
+
+[[prefab]]
+prefab = "engine:prefab/props/crate.toml"
+origin = [1.0, 0.5, -2.0]
+yaw_deg = 90
+
+[[prefab]]
+prefab = "engine:prefab/props/barrel.toml"
+origin = [4.0, 0.0, 0.0]
+"#,
+        )
+        .expect("write manifest");
+        let manifest = load_level_manifest(&path).expect("manifest");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let bsp = bsp_with_box_model([0.0, 0.0, 0.0], [64.0, 16.0, 96.0]);
+        let source = QuakeLevelSource {
+            map: "e1m1",
+            entities: &[],
+            sidecar: None,
+            bsp: &bsp,
+            scale: 1.0 / 32.0,
+            placements: &manifest.prefabs,
+        };
+        let mut requested = Vec::new();
+        let mut level = QuakeLevel::load(SandboxRules::default(), &source, |key| {
+            requested.push(key.canonical().to_string());
+            Ok(PrefabDef {
+                origin: Some([0.0, 0.25, 0.0]),
+                ..PrefabDef::default()
+            })
+        });
+        assert_eq!(
+            requested,
+            [
+                "engine:prefab/props/crate.toml",
+                "engine:prefab/props/barrel.toml"
+            ]
+        );
+        assert_eq!(level.placed.len(), 2);
+
+        let world = level.session.world_mut();
+        let mut query = world.query::<(Entity, &PrefabInstance, &Transform, &Facing)>();
+        let mut found: Vec<_> = query
+            .iter(world)
+            .map(|(entity, instance, transform, facing)| {
+                (
+                    entity,
+                    instance.prefab.canonical().to_string(),
+                    transform.position,
+                    facing.yaw_deg,
+                )
+            })
+            .collect();
+        found.sort_by_key(|(entity, ..)| *entity);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, level.placed[0]);
+        assert_eq!(found[0].1, "engine:prefab/props/crate.toml");
+        assert_eq!(
+            found[0].2,
+            Vec3 {
+                x: 1.0,
+                y: 0.75,
+                z: -2.0
+            }
+        );
+        assert_eq!(found[0].3, 90.0);
+        assert_eq!(found[1].1, "engine:prefab/props/barrel.toml");
+    }
+
+    #[test]
+    fn broken_bsp_still_loads_spawns() {
+        let start = MapEntity {
+            classname: "info_player_start".to_string(),
+            origin: [4.0, 0.0, 0.0],
+            ..MapEntity::default()
+        };
+        let source = QuakeLevelSource {
+            map: "broken",
+            entities: std::slice::from_ref(&start),
+            sidecar: None,
+            bsp: &[1, 2, 3],
+            scale: 1.0 / 32.0,
+            placements: &[],
+        };
+        let level = QuakeLevel::load(SandboxRules::default(), &source, |_| {
+            Err("no prefabs".to_string())
+        });
+        assert_eq!(level.warnings.len(), 2, "{:?}", level.warnings);
+        assert!(level.movers.is_empty());
+        assert_eq!(level.spawn.map(|spawn| spawn.origin), Some([4.0, 0.0, 0.0]));
+    }
+}
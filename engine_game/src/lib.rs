@@ -1,3 +1,29 @@
+//! Game rules layer: session state, saves and gameplay orchestration.
 #![forbid(unsafe_code)]
 
-pub fn init() {}
+pub mod course;
+pub mod level;
+pub mod liquid;
+pub mod map_entities;
+pub mod motor;
+pub mod movement_telemetry;
+pub mod movement_trace;
+pub mod mover;
+pub mod prefab;
+pub mod prop;
+pub mod rules;
+pub mod save;
+pub mod session;
+pub mod spawn;
+pub mod trigger;
+
+pub use course::Course;
+pub use level::{QuakeLevel, QuakeLevelSource};
+pub use liquid::{LiquidDamage, LiquidDamageEvent};
+pub use motor::{MotorKind, MotorProfile, MotorRegistry, MotorSet};
+pub use mover::{Mover, MoverBody, MoverMode};
+pub use prop::Prop;
+pub use rules::{GameRules, SandboxRules};
+pub use session::{GameSession, PlayerSpawned};
+pub use spawn::SpawnPoint;
+pub use trigger::{TriggerEvent, TriggerPhase};
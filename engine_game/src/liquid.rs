@@ -0,0 +1,201 @@
+//! Players standing in liquid volumes, and the damage slime and lava deal.
+//!
+//! Hosts report each player's immersion with
+//! [`crate::GameSession::set_player_liquid`]; the rules schedule turns harmful
+//! contact into [`LiquidDamageEvent`]s at the rate the active rules ask for.
+
+use bevy_ecs::prelude::*;
+use ecs::{FixedTick, FixedTimeStep, SaveState};
+use map_cook::{build_quake1_liquids, build_quake3_liquids, BspKind};
+use physics_rapier::{LiquidKind, PhysicsWorld};
+
+use crate::save::parse_floats;
+use crate::session::{ActiveRules, PendingSpawn};
+
+/// `amount` damage every `interval` seconds while a player stays in a liquid.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LiquidDamage {
+    pub amount: f32,
+    pub interval: f32,
+}
+
+impl LiquidDamage {
+    /// Default rates: water is harmless, slime hurts once a second and lava
+    /// five times a second, both scaled by how deep the player is.
+    pub fn for_liquid(kind: LiquidKind, level: f32) -> Option<Self> {
+        let level = level.clamp(0.0, 1.0);
+        match kind {
+            LiquidKind::Water => None,
+            LiquidKind::Slime => Some(Self {
+                amount: 12.0 * level,
+                interval: 1.0,
+            }),
+            LiquidKind::Lava => Some(Self {
+                amount: 30.0 * level,
+                interval: 0.2,
+            }),
+        }
+    }
+}
+
+/// The liquid a player currently stands in.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct InLiquid {
+    pub kind: LiquidKind,
+    /// Immersion level, 0 dry to 1 fully submerged.
+    pub level: f32,
+    /// Seconds until the next damage tick; entering damages at once.
+    pub next_damage: f32,
+}
+
+/// Encoded as `<kind> <level> <next_damage>`.
+impl SaveState for InLiquid {
+    const SAVE_KEY: &'static str = "in_liquid";
+
+    fn encode(&self) -> String {
+        format!("{} {} {}", self.kind.as_str(), self.level, self.next_damage)
+    }
+
+    fn decode(value: &str) -> Result<Self, String> {
+        let (kind, rest) = value
+            .trim()
+            .split_once(' ')
+            .ok_or_else(|| format!("invalid liquid state '{}'", value))?;
+        let kind =
+            LiquidKind::from_tag(kind).ok_or_else(|| format!("unknown liquid '{}'", kind))?;
+        let [level, next_damage] = parse_floats(rest)?;
+        Ok(Self {
+            kind,
+            level,
+            next_damage,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiquidDamageEvent {
+    pub entity: Entity,
+    pub kind: LiquidKind,
+    pub amount: f32,
+    /// Fixed tick the event was raised on.
+    pub tick: u64,
+}
+
+/// Liquid damage raised since the host last drained it.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct LiquidDamageEvents(pub Vec<LiquidDamageEvent>);
+
+/// Adds a BSP's water, slime and lava volumes to `physics` as liquid sensors,
+/// in world meters at `scale`; returns how many were added.
+pub fn insert_bsp_liquids(
+    physics: &mut PhysicsWorld,
+    kind: BspKind,
+    bytes: &[u8],
+    scale: f32,
+) -> Result<usize, String> {
+    let liquids = match kind {
+        BspKind::Quake1 => build_quake1_liquids(bytes, scale)?,
+        BspKind::Quake3 => build_quake3_liquids(bytes, scale)?,
+    };
+    let mut inserted = 0;
+    for liquid in liquids {
+        if let Some(kind) = LiquidKind::from_tag(liquid.tag) {
+            physics.insert_liquid_collider(liquid.collider, kind);
+            inserted += 1;
+        }
+    }
+    Ok(inserted)
+}
+
+pub(crate) fn emit_liquid_damage(
+    rules: Res<ActiveRules>,
+    time: Res<FixedTimeStep>,
+    tick: Res<FixedTick>,
+    mut events: ResMut<LiquidDamageEvents>,
+    mut players: Query<(Entity, &mut InLiquid), Without<PendingSpawn>>,
+) {
+    let dt = time.dt_seconds.max(0.0);
+    for (entity, mut contact) in &mut players {
+        let Some(damage) = rules.0.liquid_damage(contact.kind, contact.level) else {
+            continue;
+        };
+        contact.next_damage -= dt;
+        // Due within half a tick counts as now, so float drift cannot push a
+        // damage tick one step late.
+        if contact.next_damage > dt * 0.5 {
+            continue;
+        }
+        contact.next_damage = damage.interval.max(dt);
+        if damage.amount > 0.0 {
+            events.0.push(LiquidDamageEvent {
+                entity,
+                kind: contact.kind,
+                amount: damage.amount,
+                tick: tick.0,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rapier3d::prelude::{Point, Vector};
+
+    /// Quake 1 BSP whose world is one node splitting a 64 unit cube at
+    /// z = 16: empty above, `contents` below.
+    fn bsp_with_pool(contents: i32) -> Vec<u8> {
+        let mut planes = Vec::new();
+        for value in [0.0f32, 0.0, 1.0, 16.0] {
+            planes.extend_from_slice(&value.to_le_bytes());
+        }
+        planes.extend_from_slice(&2i32.to_le_bytes());
+        let mut nodes = Vec::new();
+        nodes.extend_from_slice(&0i32.to_le_bytes());
+        nodes.extend_from_slice(&(-1i16).to_le_bytes());
+        nodes.extend_from_slice(&(-2i16).to_le_bytes());
+        nodes.extend_from_slice(&[0u8; 16]);
+        let mut leaves = Vec::new();
+        for contents in [-1i32, contents] {
+            leaves.extend_from_slice(&contents.to_le_bytes());
+            leaves.extend_from_slice(&(-1i32).to_le_bytes());
+            for value in [0i16, 0, 0, 64, 64, 64] {
+                leaves.extend_from_slice(&value.to_le_bytes());
+            }
+            leaves.extend_from_slice(&[0u8; 8]);
+        }
+        // Lump indices: planes 1, nodes 5, leaves 10, models 14.
+        let lumps = [(1, planes), (5, nodes), (10, leaves), (14, vec![0u8; 64])];
+        let mut data = vec![0u8; 4 + 15 * 8];
+        data[0..4].copy_from_slice(&29u32.to_le_bytes());
+        for (lump, bytes) in lumps {
+            let entry = 4 + lump * 8;
+            let offset = data.len() as u32;
+            data[entry..entry + 4].copy_from_slice(&offset.to_le_bytes());
+            data[entry + 4..entry + 8].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+            data.extend_from_slice(&bytes);
+        }
+        data
+    }
+
+    #[test]
+    fn bsp_slime_leaf_becomes_a_liquid_volume() {
+        let scale = 1.0 / 32.0;
+        let mut physics = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        let inserted = insert_bsp_liquids(&mut physics, BspKind::Quake1, &bsp_with_pool(-4), scale)
+            .expect("liquids");
+        assert_eq!(inserted, 1);
+        physics.step(1.0 / 60.0);
+
+        // Quake (32, 32, 8) is world (1, 0.25, -1); the surface is at 0.5 m.
+        let (_, kind) = physics
+            .liquid_at(Point::new(1.0, 0.25, -1.0))
+            .expect("in slime");
+        assert_eq!(kind, LiquidKind::Slime);
+        assert!(physics.liquid_at(Point::new(1.0, 1.0, -1.0)).is_none());
+        let contact = physics
+            .liquid_depth(Point::new(1.0, 0.0, -1.0), 1.75)
+            .expect("depth");
+        assert!((contact.depth - 0.5).abs() < 1.0e-3);
+    }
+}
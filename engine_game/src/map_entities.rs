@@ -0,0 +1,431 @@
+//! Map-authored entities and the classname to prefab table.
+//!
+//! Format adapters (e.g. the Quake BSP entity lump) convert their entities to
+//! [`MapEntity`] in world space; this module decides which prefab each one
+//! becomes and spawns it.
+
+use std::collections::BTreeMap;
+
+use bevy_ecs::prelude::*;
+use engine_core::asset_id::AssetKey;
+use engine_core::prefab::PrefabDef;
+use map_cook::BspSubmodel;
+use physics_rapier::PhysicsWorld;
+use rapier3d::prelude::ColliderBuilder;
+
+use crate::mover::{mover_from_quake_entity, Mover, MoverBody};
+use crate::prefab::spawn_prefab;
+use crate::session::GameSession;
+use crate::spawn::SpawnPoint;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MapEntity {
+    pub classname: String,
+    /// World space; `[0, 0, 0]` for entities without an origin.
+    pub origin: [f32; 3],
+    pub yaw_deg: Option<f32>,
+    pub brush_model: Option<u32>,
+    pub targetname: Option<String>,
+    pub target: Option<String>,
+    pub keys: Vec<(String, String)>,
+}
+
+/// Maps a classname to a prefab. A trailing `*` in `classname` matches by
+/// prefix; the first matching row wins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClassnamePrefab {
+    pub classname: &'static str,
+    pub prefab: &'static str,
+}
+
+const fn row(classname: &'static str, prefab: &'static str) -> ClassnamePrefab {
+    ClassnamePrefab { classname, prefab }
+}
+
+pub const QUAKE_CLASSNAME_PREFABS: &[ClassnamePrefab] = &[
+    row("light*", "engine:prefab/quake/light.toml"),
+    row("item_health", "engine:prefab/quake/item_health.toml"),
+    row("item_armor*", "engine:prefab/quake/item_armor.toml"),
+    row("item_*", "engine:prefab/quake/item.toml"),
+    row("weapon_*", "engine:prefab/quake/weapon.toml"),
+    row(
+        "trigger_teleport",
+        "engine:prefab/quake/trigger_teleport.toml",
+    ),
+    row("trigger_*", "engine:prefab/quake/trigger.toml"),
+    row("func_door*", "engine:prefab/quake/door.toml"),
+    row("func_plat", "engine:prefab/quake/plat.toml"),
+    row("func_button", "engine:prefab/quake/button.toml"),
+    row(
+        "info_teleport_destination",
+        "engine:prefab/quake/teleport_destination.toml",
+    ),
+];
+
+/// Meters per Quake unit for maps without a sidecar scale; 32 units is
+/// about one meter at Quake's player scale.
+pub const QUAKE_MAP_TO_WORLD_SCALE: f32 = 1.0 / 32.0;
+
+/// Classnames that become player spawn points, in preference order.
+pub const QUAKE_SPAWN_CLASSNAMES: &[&str] = &["info_player_start", "info_player_deathmatch"];
+
+pub fn prefab_for_classname(table: &[ClassnamePrefab], classname: &str) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|row| match row.classname.strip_suffix('*') {
+            Some(prefix) => classname.starts_with(prefix),
+            None => classname == row.classname,
+        })
+        .map(|row| row.prefab)
+}
+
+/// Spawn points for `classnames`, grouped in the order the classnames are listed.
+pub fn spawn_points_from_entities(entities: &[MapEntity], classnames: &[&str]) -> Vec<SpawnPoint> {
+    classnames
+        .iter()
+        .flat_map(|classname| {
+            entities
+                .iter()
+                .filter(move |entity| entity.classname == *classname)
+        })
+        .map(|entity| SpawnPoint {
+            id: entity.targetname.clone(),
+            origin: entity.origin,
+            yaw_deg: entity.yaw_deg,
+        })
+        .collect()
+}
+
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct MapEntityInfo {
+    pub classname: String,
+    pub targetname: Option<String>,
+    pub target: Option<String>,
+    pub keys: Vec<(String, String)>,
+}
+
+/// Inline brush model the entity's geometry and collision come from.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct BrushModel(pub u32);
+
+#[derive(Clone, Debug, Default)]
+pub struct MapEntitySpawnReport {
+    pub spawned: Vec<Entity>,
+    /// Entity count per classname with no prefab mapping.
+    pub unmapped: BTreeMap<String, usize>,
+}
+
+/// Spawns every entity with a prefab mapping. `resolve` loads a flattened
+/// prefab and is called once per distinct prefab.
+pub fn spawn_map_entities<F>(
+    world: &mut World,
+    entities: &[MapEntity],
+    table: &[ClassnamePrefab],
+    mut resolve: F,
+) -> Result<MapEntitySpawnReport, String>
+where
+    F: FnMut(&AssetKey) -> Result<PrefabDef, String>,
+{
+    let mut report = MapEntitySpawnReport::default();
+    let mut prefabs: BTreeMap<&'static str, (AssetKey, PrefabDef)> = BTreeMap::new();
+    for entity in entities {
+        let Some(prefab) = prefab_for_classname(table, &entity.classname) else {
+            *report.unmapped.entry(entity.classname.clone()).or_default() += 1;
+            continue;
+        };
+        if !prefabs.contains_key(prefab) {
+            let key = AssetKey::parse(prefab).map_err(|err| format!("{}: {}", prefab, err))?;
+            let def = resolve(&key).map_err(|err| format!("{}: {}", entity.classname, err))?;
+            prefabs.insert(prefab, (key, def));
+        }
+        let (key, def) = &prefabs[prefab];
+        let spawned = spawn_prefab(world, key, def, entity.origin, entity.yaw_deg)?;
+        let mut spawned_mut = world.entity_mut(spawned);
+        spawned_mut.insert(MapEntityInfo {
+            classname: entity.classname.clone(),
+            targetname: entity.targetname.clone(),
+            target: entity.target.clone(),
+            keys: entity.keys.clone(),
+        });
+        if let Some(model) = entity.brush_model {
+            spawned_mut.insert(BrushModel(model));
+        }
+        report.spawned.push(spawned);
+    }
+    Ok(report)
+}
+
+/// Spawns a mover for every `func_door`/`func_plat` brush entity and a
+/// sensor for each volume that opens one; returns the movers, tagged with
+/// their [`BrushModel`] so hosts can move the model's geometry along. `submodels`
+/// come from [`map_cook::build_quake1_submodels`] at the same `scale`.
+///
+/// `trigger_*` brushes become triggers named after their `target`, so they
+/// open the movers of that `targetname`. Untargeted movers open on touch,
+/// through a field around the brush sized like Quake's.
+pub fn spawn_quake_movers(
+    session: &mut GameSession,
+    physics: &mut PhysicsWorld,
+    entities: &[MapEntity],
+    submodels: &[BspSubmodel],
+    scale: f32,
+) -> Vec<Entity> {
+    let mut movers = Vec::new();
+    for entity in entities {
+        let Some(submodel) = entity
+            .brush_model
+            .and_then(|model| submodels.iter().find(|submodel| submodel.model == model))
+        else {
+            continue;
+        };
+        if entity.classname.starts_with("trigger_") {
+            if let Some(target) = &entity.target {
+                let sensor = physics.insert_sensor_collider(submodel.collider.clone());
+                session.spawn_trigger(target.clone(), sensor);
+            }
+            continue;
+        }
+        let size = [
+            submodel.max[0] - submodel.min[0],
+            submodel.max[1] - submodel.min[1],
+            submodel.max[2] - submodel.min[2],
+        ];
+        let Some(mover) = mover_from_quake_entity(entity, size, scale) else {
+            continue;
+        };
+        if let (None, Some(trigger)) = (&entity.targetname, &mover.trigger) {
+            let (min, max) = quake_touch_field(entity, &mover, submodel, scale);
+            let half = [
+                (max[0] - min[0]) * 0.5,
+                (max[1] - min[1]) * 0.5,
+                (max[2] - min[2]) * 0.5,
+            ];
+            let field = ColliderBuilder::cuboid(half[0], half[1], half[2])
+                .translation([min[0] + half[0], min[1] + half[1], min[2] + half[2]].into())
+                .build();
+            let sensor = physics.insert_sensor_collider(field);
+            session.spawn_trigger(trigger.clone(), sensor);
+        }
+        let base = *submodel.collider.position();
+        let (body, _) = physics.insert_kinematic_collider(submodel.collider.clone());
+        let spawned = session.spawn_mover(mover, MoverBody { body, base });
+        session
+            .world_mut()
+            .entity_mut(spawned)
+            .insert(BrushModel(submodel.model));
+        movers.push(spawned);
+    }
+    movers
+}
+
+/// Doors open for anything within 60 units sideways and 8 above or below;
+/// plats for anything over their top, from the lowered to the raised height.
+fn quake_touch_field(
+    entity: &MapEntity,
+    mover: &Mover,
+    submodel: &BspSubmodel,
+    scale: f32,
+) -> ([f32; 3], [f32; 3]) {
+    let (mut min, mut max) = (submodel.min, submodel.max);
+    if entity.classname == "func_plat" {
+        let inset = 25.0 * scale;
+        for axis in [0, 2] {
+            if max[axis] - min[axis] > 2.0 * inset {
+                min[axis] += inset;
+                max[axis] -= inset;
+            }
+        }
+        let lowered = mover.points.first().map_or(0.0, |point| point[1]);
+        min[1] = max[1] + lowered;
+        max[1] += 8.0 * scale;
+    } else {
+        for axis in [0, 2] {
+            min[axis] -= 60.0 * scale;
+            max[axis] += 60.0 * scale;
+        }
+        min[1] -= 8.0 * scale;
+        max[1] += 8.0 * scale;
+    }
+    (min, max)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::rules::SandboxRules;
+    use map_cook::build_quake1_submodels;
+    use rapier3d::prelude::{Capsule, Isometry, Vector};
+
+    fn entity(classname: &str, origin: [f32; 3]) -> MapEntity {
+        MapEntity {
+            classname: classname.to_string(),
+            origin,
+            ..MapEntity::default()
+        }
+    }
+
+    #[test]
+    fn table_prefers_exact_rows_over_prefixes() {
+        let table = QUAKE_CLASSNAME_PREFABS;
+        assert_eq!(
+            prefab_for_classname(table, "item_health"),
+            Some("engine:prefab/quake/item_health.toml")
+        );
+        assert_eq!(
+            prefab_for_classname(table, "item_shells"),
+            Some("engine:prefab/quake/item.toml")
+        );
+        assert_eq!(
+            prefab_for_classname(table, "light_torch_small_walltorch"),
+            Some("engine:prefab/quake/light.toml")
+        );
+        assert_eq!(prefab_for_classname(table, "worldspawn"), None);
+    }
+
+    #[test]
+    fn spawns_mapped_entities_and_reports_the_rest() {
+        let mut door = entity("func_door", [0.0; 3]);
+        door.brush_model = Some(2);
+        door.targetname = Some("gate".to_string());
+        let entities = vec![
+            entity("worldspawn", [0.0; 3]),
+            entity("info_player_start", [1.0, 2.0, 3.0]),
+            entity("light", [0.0, 4.0, 0.0]),
+            entity("light", [0.0, 8.0, 0.0]),
+            door,
+        ];
+        let mut world = World::new();
+        let mut loads = 0;
+        let report = spawn_map_entities(&mut world, &entities, QUAKE_CLASSNAME_PREFABS, |_| {
+            loads += 1;
+            Ok(PrefabDef::default())
+        })
+        .expect("spawn");
+        assert_eq!(report.spawned.len(), 3);
+        assert_eq!(loads, 2);
+        assert_eq!(report.unmapped.get("worldspawn"), Some(&1));
+        let door = world.entity(report.spawned[2]);
+        assert_eq!(door.get::<BrushModel>(), Some(&BrushModel(2)));
+        assert_eq!(
+            door.get::<MapEntityInfo>().unwrap().targetname.as_deref(),
+            Some("gate")
+        );
+
+        let spawns = spawn_points_from_entities(&entities, QUAKE_SPAWN_CLASSNAMES);
+        assert_eq!(spawns.len(), 1);
+        assert_eq!(spawns[0].origin, [1.0, 2.0, 3.0]);
+    }
+
+    /// Quake 1 BSP whose brush model 1 is the box `min..max` (Quake units);
+    /// the world model has no faces.
+    pub(crate) fn bsp_with_box_model(min: [f32; 3], max: [f32; 3]) -> Vec<u8> {
+        let corner = |index: usize| {
+            [
+                if index & 1 == 0 { min[0] } else { max[0] },
+                if index & 2 == 0 { min[1] } else { max[1] },
+                if index & 4 == 0 { min[2] } else { max[2] },
+            ]
+        };
+        let quads = [
+            [0, 2, 6, 4],
+            [1, 5, 7, 3],
+            [0, 4, 5, 1],
+            [2, 3, 7, 6],
+            [0, 1, 3, 2],
+            [4, 6, 7, 5],
+        ];
+        let mut vertices = Vec::new();
+        for index in 0..8 {
+            for value in corner(index) {
+                vertices.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+        // Edge 0 is unused: surfedge signs cannot reference it.
+        let mut edges = vec![0u8; 4];
+        let mut surfedges = Vec::new();
+        let mut faces = Vec::new();
+        for (face, quad) in quads.iter().enumerate() {
+            for corner in 0..4 {
+                let edge = (face * 4 + corner + 1) as i32;
+                edges.extend_from_slice(&(quad[corner] as u16).to_le_bytes());
+                edges.extend_from_slice(&(quad[(corner + 1) % 4] as u16).to_le_bytes());
+                surfedges.extend_from_slice(&edge.to_le_bytes());
+            }
+            faces.extend_from_slice(&[0u8; 4]);
+            faces.extend_from_slice(&((face * 4) as i32).to_le_bytes());
+            faces.extend_from_slice(&4u16.to_le_bytes());
+            faces.extend_from_slice(&[0u8; 10]);
+        }
+        let mut models = vec![0u8; 64];
+        let mut door = vec![0u8; 56];
+        door.extend_from_slice(&0i32.to_le_bytes());
+        door.extend_from_slice(&(quads.len() as i32).to_le_bytes());
+        models.extend_from_slice(&door);
+
+        // Lump indices: vertices 3, faces 7, edges 12, surfedges 13, models 14.
+        let lumps = [
+            (3, vertices),
+            (7, faces),
+            (12, edges),
+            (13, surfedges),
+            (14, models),
+        ];
+        let mut data = vec![0u8; 4 + 15 * 8];
+        data[0..4].copy_from_slice(&29u32.to_le_bytes());
+        for (lump, bytes) in lumps {
+            let entry = 4 + lump * 8;
+            let offset = data.len() as u32;
+            data[entry..entry + 4].copy_from_slice(&offset.to_le_bytes());
+            data[entry + 4..entry + 8].copy_from_slice(&(bytes.len() as u32).to_le_bytes());
+            data.extend_from_slice(&bytes);
+        }
+        data
+    }
+
+    #[test]
+    fn bsp_door_opens_when_the_player_walks_up() {
+        let scale = 1.0 / 32.0;
+        let bytes = bsp_with_box_model([0.0, 0.0, 0.0], [64.0, 16.0, 96.0]);
+        let submodels = build_quake1_submodels(&bytes, scale).expect("submodels");
+        let mut door = entity("func_door", [0.0; 3]);
+        door.brush_model = Some(1);
+        door.keys = vec![("angle".to_string(), "-1".to_string())];
+        let mut physics = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        let mut session = GameSession::new(SandboxRules::default());
+        session.enter_map("door", vec![SpawnPoint::new([1.0, 0.0, 1.0])]);
+        let player = session.add_player(0);
+        session.tick();
+
+        let movers = spawn_quake_movers(&mut session, &mut physics, &[door], &submodels, scale);
+        assert_eq!(movers.len(), 1);
+        let mover = session.world().get::<Mover>(movers[0]).expect("mover");
+        assert_eq!(mover.name, "func_door*1");
+        let body = session
+            .world()
+            .get::<MoverBody>(movers[0])
+            .expect("body")
+            .body;
+        physics.step(1.0 / 60.0);
+
+        // The door spans x 0..2, z -0.5..0 in meters; the player stands in front.
+        let capsule = Capsule::new_y(0.5, 0.4);
+        let position = Isometry::translation(1.0, 0.9, 1.0);
+        for _ in 0..30 {
+            session.sense_triggers(&physics, player, &capsule, &position);
+            session.tick();
+            session.apply_mover_targets(&mut physics);
+            physics.step(1.0 / 60.0);
+        }
+        let lift = physics
+            .bodies()
+            .get(body)
+            .expect("door body")
+            .translation()
+            .y;
+        assert!(lift > 1.0, "door rose {lift}");
+        let offsets = session.brush_model_offsets();
+        assert_eq!(offsets.len(), 1);
+        assert_eq!(offsets[0].0, 1);
+        assert!((offsets[0].1[1] - lift).abs() < 1.0e-4, "{offsets:?}");
+    }
+}
@@ -0,0 +1,484 @@
+//! Registry of player motors and the switchable motor set every host drives.
+//!
+//! Motors are identified by name. [`MotorRegistry::builtin`] holds the arena
+//! and rpg motors tuned for the test maps; other crates append their own with
+//! [`MotorRegistry::register`]. Tuning goes through the reflective
+//! [`Motor::params`] descriptors, which [`MotorProfile`] files also target.
+
+use std::any::Any;
+
+use character_collision::LedgeQuery;
+use character_motor_arena::ArenaMotor;
+use character_motor_rpg::RpgMotor;
+use player_controller::{
+    find_param, InputIntent, Motor, MotorContext, MotorOutput, MotorParam, PlayerKinematics,
+};
+use rapier3d::prelude::Real;
+
+use crate::course::{test_map_arena_config, test_map_rpg_config};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MotorKind(&'static str);
+
+impl MotorKind {
+    pub const ARENA: MotorKind = MotorKind("arena");
+    pub const RPG: MotorKind = MotorKind("rpg");
+
+    pub const fn new(name: &'static str) -> Self {
+        Self(name)
+    }
+
+    pub fn label(self) -> &'static str {
+        self.0
+    }
+
+    /// Resolves a builtin motor by name or `dev_motor` number.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        MotorRegistry::builtin().parse(input)
+    }
+}
+
+struct MotorEntry {
+    kind: MotorKind,
+    build: fn() -> Box<dyn Motor>,
+}
+
+/// Known motors in `dev_motor` order (the first is `1`).
+pub struct MotorRegistry {
+    entries: Vec<MotorEntry>,
+}
+
+impl MotorRegistry {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(MotorKind::ARENA, || {
+                Box::new(ArenaMotor::new(test_map_arena_config()))
+            })
+            .expect("arena motor");
+        registry
+            .register(MotorKind::RPG, || {
+                Box::new(RpgMotor::new(test_map_rpg_config()))
+            })
+            .expect("rpg motor");
+        registry
+    }
+
+    pub fn register(
+        &mut self,
+        kind: MotorKind,
+        build: fn() -> Box<dyn Motor>,
+    ) -> Result<(), String> {
+        if self.entries.iter().any(|entry| entry.kind == kind) {
+            return Err(format!("motor already registered: {}", kind.label()));
+        }
+        self.entries.push(MotorEntry { kind, build });
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn kinds(&self) -> impl Iterator<Item = MotorKind> + '_ {
+        self.entries.iter().map(|entry| entry.kind)
+    }
+
+    /// A freshly configured motor of `kind`.
+    pub fn build(&self, kind: MotorKind) -> Option<Box<dyn Motor>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.kind == kind)
+            .map(|entry| (entry.build)())
+    }
+
+    /// Accepts a motor name or its 1-based `dev_motor` number.
+    pub fn parse(&self, input: &str) -> Result<MotorKind, String> {
+        let input = input.trim();
+        let by_number = input
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| number.checked_sub(1))
+            .and_then(|index| self.entries.get(index));
+        by_number
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .find(|entry| entry.kind.label() == input)
+            })
+            .map(|entry| entry.kind)
+            .ok_or_else(|| format!("expected {}", self.names().join(" or ")))
+    }
+
+    /// Maps a `dev_motor` value; out-of-range values select the first motor.
+    pub fn from_cvar(&self, value: i32) -> Option<MotorKind> {
+        self.kinds().nth(cvar_index(value, self.len()))
+    }
+
+    pub fn cvar_value(&self, kind: MotorKind) -> Option<i32> {
+        self.entries
+            .iter()
+            .position(|entry| entry.kind == kind)
+            .map(|index| index as i32 + 1)
+    }
+
+    fn names(&self) -> Vec<&'static str> {
+        self.kinds().map(MotorKind::label).collect()
+    }
+}
+
+impl Default for MotorRegistry {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+fn cvar_index(value: i32, len: usize) -> usize {
+    usize::try_from(value - 1)
+        .ok()
+        .filter(|index| *index < len)
+        .unwrap_or(0)
+}
+
+/// One motor per registered kind, with one active. Inactive motors keep
+/// their tuning so switching back restores it.
+pub struct MotorSet {
+    active: usize,
+    motors: Vec<(MotorKind, Box<dyn Motor>)>,
+}
+
+impl MotorSet {
+    /// Builds every registered motor with the first active. Panics if
+    /// `registry` is empty.
+    pub fn new(registry: &MotorRegistry) -> Self {
+        assert!(!registry.is_empty(), "motor registry is empty");
+        Self {
+            active: 0,
+            motors: registry
+                .entries
+                .iter()
+                .map(|entry| (entry.kind, (entry.build)()))
+                .collect(),
+        }
+    }
+
+    pub fn kind(&self) -> MotorKind {
+        self.motors[self.active].0
+    }
+
+    pub fn kinds(&self) -> impl Iterator<Item = MotorKind> + '_ {
+        self.motors.iter().map(|(kind, _)| *kind)
+    }
+
+    /// Maps a `dev_motor` value the way [`MotorRegistry::from_cvar`] does.
+    pub fn kind_from_cvar(&self, value: i32) -> MotorKind {
+        self.motors[cvar_index(value, self.motors.len())].0
+    }
+
+    /// Switches motors; every motor state is reset when the kind changes.
+    pub fn set_kind(&mut self, kind: MotorKind) -> Result<(), String> {
+        let index = self
+            .motors
+            .iter()
+            .position(|(entry, _)| *entry == kind)
+            .ok_or_else(|| format!("unknown motor: {}", kind.label()))?;
+        if index != self.active {
+            self.active = index;
+            self.reset_states();
+        }
+        Ok(())
+    }
+
+    pub fn reset_states(&mut self) {
+        for (_, motor) in &mut self.motors {
+            motor.reset_state();
+        }
+    }
+
+    pub fn active(&self) -> &dyn Motor {
+        self.motors[self.active].1.as_ref()
+    }
+
+    pub fn active_mut(&mut self) -> &mut dyn Motor {
+        self.motors[self.active].1.as_mut()
+    }
+
+    pub fn motor(&self, kind: MotorKind) -> Option<&dyn Motor> {
+        self.motors
+            .iter()
+            .find(|(entry, _)| *entry == kind)
+            .map(|(_, motor)| motor.as_ref())
+    }
+
+    pub fn motor_mut(&mut self, kind: MotorKind) -> Option<&mut dyn Motor> {
+        self.motors
+            .iter_mut()
+            .find(|(entry, _)| *entry == kind)
+            .map(|(_, motor)| motor.as_mut())
+    }
+
+    /// The first motor of concrete type `T`, active or not.
+    pub fn get<T: Motor>(&self) -> Option<&T> {
+        self.motors
+            .iter()
+            .find_map(|(_, motor)| (motor.as_ref() as &dyn Any).downcast_ref::<T>())
+    }
+
+    pub fn get_mut<T: Motor>(&mut self) -> Option<&mut T> {
+        self.motors
+            .iter_mut()
+            .find_map(|(_, motor)| (motor.as_mut() as &mut dyn Any).downcast_mut::<T>())
+    }
+
+    /// The active motor if it is a `T`.
+    pub fn active_as<T: Motor>(&self) -> Option<&T> {
+        (self.active() as &dyn Any).downcast_ref::<T>()
+    }
+}
+
+impl Motor for MotorSet {
+    fn step(
+        &mut self,
+        input: &InputIntent,
+        state: &PlayerKinematics,
+        ctx: MotorContext,
+    ) -> MotorOutput {
+        self.active_mut().step(input, state, ctx)
+    }
+
+    fn ledge_query(&self) -> Option<LedgeQuery> {
+        self.active().ledge_query()
+    }
+
+    fn params(&self) -> &'static [MotorParam] {
+        self.active().params()
+    }
+
+    fn param(&self, name: &str) -> Option<Real> {
+        self.active().param(name)
+    }
+
+    fn set_param(&mut self, name: &str, value: Real) -> Result<(), String> {
+        self.active_mut().set_param(name, value)
+    }
+
+    fn reset_state(&mut self) {
+        self.reset_states();
+    }
+}
+
+/// A named set of parameter overrides for one motor, loaded from
+/// `engine:config/motors/<name>.toml`:
+///
+/// ```toml
+/// motor = "rpg"
+///
+/// [params]
+/// mantle_enabled = false
+/// ground_max_speed = 5.0
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MotorProfile {
+    pub motor: MotorKind,
+    /// Values in parameter units; flags are 1 or 0.
+    pub params: Vec<(&'static str, Real)>,
+}
+
+impl MotorProfile {
+    pub fn parse_toml(text: &str, registry: &MotorRegistry) -> Result<Self, String> {
+        let table: toml::Table =
+            toml::from_str(text).map_err(|err| format!("motor profile parse failed: {}", err))?;
+        let motor = table
+            .get("motor")
+            .and_then(toml::Value::as_str)
+            .ok_or_else(|| "motor profile needs a motor name".to_string())?;
+        let motor = registry.parse(motor)?;
+        let descriptors = registry
+            .build(motor)
+            .map(|motor| motor.params())
+            .unwrap_or_default();
+        let mut params = Vec::new();
+        if let Some(values) = table.get("params") {
+            let values = values
+                .as_table()
+                .ok_or_else(|| "motor profile params must be a table".to_string())?;
+            for (name, value) in values {
+                let param = find_param(descriptors, name)?;
+                let value = match value {
+                    toml::Value::Boolean(flag) => {
+                        if *flag {
+                            1.0
+                        } else {
+                            0.0
+                        }
+                    }
+                    toml::Value::Integer(number) => *number as Real,
+                    toml::Value::Float(number) => *number as Real,
+                    _ => return Err(format!("motor param {} must be a number", name)),
+                };
+                params.push((param.name, param.clamp(value)));
+            }
+        }
+        Ok(Self { motor, params })
+    }
+
+    pub fn apply(&self, motors: &mut MotorSet) -> Result<(), String> {
+        let motor = motors
+            .motor_mut(self.motor)
+            .ok_or_else(|| format!("unknown motor: {}", self.motor.label()))?;
+        for (name, value) in &self.params {
+            motor.set_param(name, *value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use character_collision::CollisionProfile;
+    use map_cook::build_test_map_colliders;
+    use physics_rapier::PhysicsWorld;
+    use player_camera::PlayerCamera;
+    use player_controller::{DirectInputAdapter, PlayerController, RawInput, SimpleMotor};
+    use rapier3d::math::{Isometry, Vector};
+    use test_map::TestMap;
+
+    const LEDGES: &str = include_str!("../../content/test_maps/ledges_and_landings.toml");
+    const DT: f32 = 1.0 / 60.0;
+
+    fn ledges_world() -> PhysicsWorld {
+        let map = TestMap::parse_toml(LEDGES).expect("ledges map");
+        let mut world = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        for solid in build_test_map_colliders(&map).expect("colliders").colliders {
+            world.insert_static_collider(solid.collider);
+        }
+        world.step(DT);
+        world
+    }
+
+    /// Runs at a ledge along +X holding forward, jumping until a mantle
+    /// starts; returns whether one started, the highest the feet got and
+    /// where they ended up.
+    fn run_at_ledge(world: &PhysicsWorld, start: [f32; 2], mantle: bool) -> (bool, f32, [f32; 3]) {
+        let mut motor = MotorSet::new(&MotorRegistry::builtin());
+        motor.set_kind(MotorKind::RPG).expect("rpg motor");
+        let flag = if mantle { 1.0 } else { 0.0 };
+        motor
+            .set_param("mantle_enabled", flag)
+            .expect("mantle param");
+        let profile = CollisionProfile::rpg_default();
+        let foot_offset = profile.capsule_height * 0.5 + profile.capsule_radius;
+        let mut camera = PlayerCamera::new(1.6);
+        camera.set_look(std::f32::consts::FRAC_PI_2, 0.0);
+        let position = Isometry::translation(start[0], foot_offset + 0.05, start[1]);
+        let mut controller =
+            PlayerController::new(DirectInputAdapter, motor, profile, camera, position);
+        let mut mantled = false;
+        let mut highest = 0.0_f32;
+        for _ in 0..240 {
+            let input = RawInput {
+                move_y: 1.0,
+                jump: !mantled,
+                ..Default::default()
+            };
+            controller.tick(world, input, DT);
+            mantled |= controller
+                .motor()
+                .active_as::<RpgMotor>()
+                .and_then(RpgMotor::mantle)
+                .is_some();
+            let feet = controller.state().position.translation.y - foot_offset;
+            highest = highest.max(feet);
+        }
+        let end = controller.state().position.translation.vector;
+        (mantled, highest, [end.x, end.y - foot_offset, end.z])
+    }
+
+    #[test]
+    fn rpg_mantles_ledges_and_landings() {
+        let world = ledges_world();
+        for (start, top) in [([-16.0, 0.0], 1.0), ([-16.0, 10.0], 1.5)] {
+            let (mantled, _, end) = run_at_ledge(&world, start, true);
+            assert!(mantled, "{start:?} never mantled");
+            assert!(end[0] > -12.5, "{start:?} ended at {end:?}");
+            assert!((end[1] - top).abs() < 0.1, "{start:?} ended at {end:?}");
+
+            let (mantled, _, _) = run_at_ledge(&world, start, false);
+            assert!(!mantled, "{start:?} mantled while disabled");
+        }
+        let (mantled, highest, end) = run_at_ledge(&world, [-5.5, 0.0], true);
+        assert!(!mantled, "mantled the 3m ledge: {end:?}");
+        assert!(highest < 1.5);
+        assert!(end[0] < -3.0);
+    }
+
+    #[test]
+    fn registry_accepts_motors_from_other_crates() {
+        let mut registry = MotorRegistry::builtin();
+        let simple = MotorKind::new("simple");
+        registry
+            .register(simple, || Box::new(SimpleMotor { move_speed: 3.0 }))
+            .expect("register");
+        assert!(registry
+            .register(MotorKind::RPG, || Box::new(SimpleMotor::default()))
+            .is_err());
+        assert_eq!(registry.parse("3"), Ok(simple));
+        assert_eq!(registry.parse("simple"), Ok(simple));
+        assert_eq!(registry.from_cvar(9), Some(MotorKind::ARENA));
+        assert_eq!(registry.cvar_value(simple), Some(3));
+
+        let mut motors = MotorSet::new(&registry);
+        assert_eq!(motors.kind(), MotorKind::ARENA);
+        motors.set_kind(simple).expect("switch");
+        assert_eq!(motors.param("move_speed"), Some(3.0));
+        motors.set_param("move_speed", -1.0).expect("set");
+        assert_eq!(
+            motors.active_as::<SimpleMotor>().map(|m| m.move_speed),
+            Some(0.0)
+        );
+        assert!(motors.get::<ArenaMotor>().is_some());
+    }
+
+    #[test]
+    fn motor_profiles_set_params_in_their_units() {
+        let registry = MotorRegistry::builtin();
+        let profile = MotorProfile::parse_toml(
+            "motor = \"rpg\"\n[params]\nmantle_enabled = false\nmantle_facing_deg = 120\n",
+            &registry,
+        )
+        .expect("profile");
+        assert_eq!(profile.motor, MotorKind::RPG);
+        assert_eq!(
+            profile.params,
+            vec![("mantle_enabled", 0.0), ("mantle_facing_deg", 90.0)]
+        );
+        let mut motors = MotorSet::new(&registry);
+        profile.apply(&mut motors).expect("apply");
+        let rpg = motors.get::<RpgMotor>().expect("rpg");
+        assert!(!rpg.config().mantle_enabled);
+        assert!((rpg.config().mantle_facing_angle - std::f32::consts::FRAC_PI_2).abs() < 1e-5);
+
+        for text in [
+            include_str!("../../content/config/motors/arena_vanilla.toml"),
+            include_str!("../../content/config/motors/rpg_no_mantle.toml"),
+        ] {
+            let profile = MotorProfile::parse_toml(text, &registry).expect("shipped profile");
+            assert!(!profile.params.is_empty());
+        }
+
+        let err = MotorProfile::parse_toml("motor = \"arena\"\n[params]\nwings = 1\n", &registry)
+            .expect_err("unknown param");
+        assert!(err.contains("wings"), "{err}");
+    }
+}
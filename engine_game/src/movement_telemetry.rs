@@ -0,0 +1,453 @@
+//! Per-tick movement telemetry and per-jump summaries.
+//!
+//! Recordings (`pallet_movement_telemetry_v1`) are CSV with one row per fixed
+//! tick: position, velocity, wish direction, arena golden-angle metrics, the
+//! arena jump-buffer and bhop-grace timers, ground contact and wall hits.
+//! Golden-angle columns are empty when the motor or speed gives no angle.
+
+use std::fmt;
+
+use character_motor_arena::{golden_angle_metrics, ArenaMotor};
+use player_controller::{PlayerFrame, RawInput};
+use rapier3d::math::Vector;
+use rapier3d::prelude::Real;
+
+use crate::motor::{MotorKind, MotorSet};
+
+pub const TELEMETRY_HEADER: &str = "# pallet_movement_telemetry_v1";
+const TELEMETRY_COLUMNS: &str = "tick,time,px,py,pz,vx,vy,vz,speed,wish_x,wish_z,wish_mag,\
+theta,gain,quality,jump_buffer,bhop_grace,grounded,wall_hit";
+const TELEMETRY_FIELDS: usize = 19;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct MovementSample {
+    pub tick: u64,
+    /// Seconds since the recording started, at the end of this tick.
+    pub time: Real,
+    pub position: [Real; 3],
+    pub velocity: [Real; 3],
+    /// Horizontal speed.
+    pub speed: Real,
+    /// Horizontal wish direction (x, z) as the motor applies it, and the
+    /// input magnitude in [0, 1].
+    pub wish_dir: [Real; 2],
+    pub wish_mag: Real,
+    /// Arena golden-angle metrics for the view against the velocity.
+    pub golden: Option<GoldenSample>,
+    pub jump_buffer: Real,
+    pub bhop_grace: Real,
+    pub grounded: bool,
+    pub wall_hit: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GoldenSample {
+    /// Radians between the view and the velocity.
+    pub theta: Real,
+    pub gain: Real,
+    pub quality: Real,
+}
+
+/// Collects samples while `dev_movement_record` (or a headless run) is active.
+#[derive(Clone, Debug)]
+pub struct MovementRecorder {
+    motor: MotorKind,
+    samples: Vec<MovementSample>,
+    time: Real,
+}
+
+impl MovementRecorder {
+    pub fn new(motor: MotorKind) -> Self {
+        Self {
+            motor,
+            samples: Vec::new(),
+            time: 0.0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Records the tick that produced `frame`. The wish direction is rebuilt
+    /// from `raw` and `yaw` the way the active motor builds it.
+    pub fn record(
+        &mut self,
+        motor: &MotorSet,
+        raw: RawInput,
+        yaw: Real,
+        dt: Real,
+        frame: &PlayerFrame,
+    ) {
+        self.time += dt.max(0.0);
+        let kinematics = &frame.kinematics;
+        let axis = [raw.move_x, raw.move_y];
+        let arena = motor.active_as::<ArenaMotor>();
+        let (wish, wish_mag) = if arena.is_some() {
+            let intent = character_motor_arena::build_move_intent(
+                yaw,
+                axis,
+                kinematics.grounded,
+                kinematics.ground_normal,
+            );
+            (intent.dir, intent.mag)
+        } else {
+            let intent = character_motor_rpg::build_move_intent(
+                yaw,
+                axis,
+                kinematics.grounded,
+                kinematics.ground_normal,
+            );
+            (intent.dir, intent.mag)
+        };
+        let velocity = kinematics.velocity;
+        let planar = Vector::new(velocity.x, 0.0, velocity.z);
+        let golden = arena.and_then(|arena| {
+            let view_forward = Vector::new(yaw.sin(), 0.0, -yaw.cos());
+            golden_angle_metrics(&arena.config(), planar, view_forward).map(|metrics| {
+                GoldenSample {
+                    theta: metrics.theta,
+                    gain: metrics.gain,
+                    quality: metrics.quality,
+                }
+            })
+        });
+        let timers = motor
+            .get::<ArenaMotor>()
+            .map(ArenaMotor::timers)
+            .unwrap_or_default();
+        let position = kinematics.position.translation.vector;
+        self.samples.push(MovementSample {
+            tick: self.samples.len() as u64,
+            time: self.time,
+            position: [position.x, position.y, position.z],
+            velocity: [velocity.x, velocity.y, velocity.z],
+            speed: planar.norm(),
+            wish_dir: [wish.x, wish.z],
+            wish_mag,
+            golden,
+            jump_buffer: timers.jump_buffer_time,
+            bhop_grace: timers.bhop_grace_time,
+            grounded: kinematics.grounded,
+            wall_hit: frame.collision.hit_wall,
+        });
+    }
+
+    pub fn finish(self) -> MovementRecording {
+        MovementRecording {
+            motor: self.motor,
+            samples: self.samples,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovementRecording {
+    pub motor: MotorKind,
+    pub samples: Vec<MovementSample>,
+}
+
+impl MovementRecording {
+    pub fn to_csv(&self) -> String {
+        let mut text = format!(
+            "{}\n# motor {}\n{}\n",
+            TELEMETRY_HEADER,
+            self.motor.label(),
+            TELEMETRY_COLUMNS
+        );
+        for sample in &self.samples {
+            let [px, py, pz] = sample.position;
+            let [vx, vy, vz] = sample.velocity;
+            let golden = match sample.golden {
+                Some(golden) => format!(
+                    "{:.5},{:.5},{:.5}",
+                    golden.theta, golden.gain, golden.quality
+                ),
+                None => ",,".to_string(),
+            };
+            text.push_str(&format!(
+                "{},{:.5},{:.5},{:.5},{:.5},{:.5},{:.5},{:.5},{:.5},{:.5},{:.5},{:.5},{},{:.5},{:.5},{},{}\n",
+                sample.tick,
+                sample.time,
+                px,
+                py,
+                pz,
+                vx,
+                vy,
+                vz,
+                sample.speed,
+                sample.wish_dir[0],
+                sample.wish_dir[1],
+                sample.wish_mag,
+                golden,
+                sample.jump_buffer,
+                sample.bhop_grace,
+                u8::from(sample.grounded),
+                u8::from(sample.wall_hit)
+            ));
+        }
+        text
+    }
+
+    pub fn parse_csv(text: &str) -> Result<Self, String> {
+        let mut motor = None;
+        let mut samples = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            if let Some(comment) = trimmed.strip_prefix('#') {
+                if let Some(value) = comment.trim().strip_prefix("motor ") {
+                    motor = Some(MotorKind::parse(value.trim())?);
+                }
+                continue;
+            }
+            if trimmed.is_empty() || trimmed == TELEMETRY_COLUMNS {
+                continue;
+            }
+            let fields: Vec<&str> = trimmed.split(',').collect();
+            if fields.len() != TELEMETRY_FIELDS {
+                return Err(format!("telemetry line {} malformed", index + 1));
+            }
+            let number = |column: usize| {
+                fields[column]
+                    .parse::<Real>()
+                    .map_err(|_| format!("telemetry line {} column {} invalid", index + 1, column))
+            };
+            let bit = |column: usize| match fields[column] {
+                "0" => Ok(false),
+                "1" => Ok(true),
+                _ => Err(format!(
+                    "telemetry line {} column {} invalid",
+                    index + 1,
+                    column
+                )),
+            };
+            let golden = if fields[12].is_empty() {
+                None
+            } else {
+                Some(GoldenSample {
+                    theta: number(12)?,
+                    gain: number(13)?,
+                    quality: number(14)?,
+                })
+            };
+            samples.push(MovementSample {
+                tick: fields[0]
+                    .parse()
+                    .map_err(|_| format!("telemetry line {} tick invalid", index + 1))?,
+                time: number(1)?,
+                position: [number(2)?, number(3)?, number(4)?],
+                velocity: [number(5)?, number(6)?, number(7)?],
+                speed: number(8)?,
+                wish_dir: [number(9)?, number(10)?],
+                wish_mag: number(11)?,
+                golden,
+                jump_buffer: number(15)?,
+                bhop_grace: number(16)?,
+                grounded: bit(17)?,
+                wall_hit: bit(18)?,
+            });
+        }
+        Ok(Self {
+            motor: motor.ok_or("telemetry is missing its motor header")?,
+            samples,
+        })
+    }
+
+    pub fn summary(&self) -> MovementSummary {
+        let samples = &self.samples;
+        let duration = samples.last().map_or(0.0, |sample| sample.time);
+        let speed_sum: Real = samples.iter().map(|sample| sample.speed).sum();
+        let golden: Vec<Real> = samples
+            .iter()
+            .filter(|sample| !sample.grounded)
+            .filter_map(|sample| sample.golden.map(|golden| golden.quality))
+            .collect();
+        MovementSummary {
+            motor: self.motor,
+            ticks: samples.len(),
+            duration,
+            mean_speed: speed_sum / samples.len().max(1) as Real,
+            max_speed: samples
+                .iter()
+                .map(|sample| sample.speed)
+                .fold(0.0, Real::max),
+            air_quality: mean(&golden),
+            wall_hits: samples.iter().filter(|sample| sample.wall_hit).count(),
+            jumps: summarize_jumps(samples),
+        }
+    }
+}
+
+/// One airborne stretch, from the last grounded tick to touchdown.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JumpSummary {
+    pub takeoff_tick: u64,
+    /// Seconds off the ground.
+    pub airtime: Real,
+    pub takeoff_speed: Real,
+    /// Horizontal speed on the landing tick.
+    pub landing_speed: Real,
+    /// `landing_speed - takeoff_speed`.
+    pub speed_gained: Real,
+    /// Downward speed just before touchdown.
+    pub fall_speed: Real,
+    /// Mean golden-angle quality while airborne, if the motor reports one.
+    pub air_quality: Option<Real>,
+    pub wall_hits: usize,
+}
+
+/// Splits `samples` into airborne stretches that start on the ground and end
+/// back on it. Stretches still airborne when the recording ends are dropped.
+pub fn summarize_jumps(samples: &[MovementSample]) -> Vec<JumpSummary> {
+    let mut jumps = Vec::new();
+    let mut takeoff: Option<usize> = None;
+    for (index, pair) in samples.windows(2).enumerate() {
+        let (before, after) = (&pair[0], &pair[1]);
+        if before.grounded && !after.grounded {
+            takeoff = Some(index);
+        } else if !before.grounded && after.grounded {
+            let Some(start) = takeoff.take() else {
+                continue;
+            };
+            let launch = &samples[start];
+            let air = &samples[start + 1..=index];
+            let quality: Vec<Real> = air
+                .iter()
+                .filter_map(|sample| sample.golden.map(|golden| golden.quality))
+                .collect();
+            jumps.push(JumpSummary {
+                takeoff_tick: launch.tick,
+                airtime: after.time - launch.time,
+                takeoff_speed: launch.speed,
+                landing_speed: after.speed,
+                speed_gained: after.speed - launch.speed,
+                fall_speed: (-before.velocity[1]).max(0.0),
+                air_quality: mean(&quality),
+                wall_hits: air.iter().filter(|sample| sample.wall_hit).count(),
+            });
+        }
+    }
+    jumps
+}
+
+fn mean(values: &[Real]) -> Option<Real> {
+    (!values.is_empty()).then(|| values.iter().sum::<Real>() / values.len() as Real)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovementSummary {
+    pub motor: MotorKind,
+    pub ticks: usize,
+    pub duration: Real,
+    pub mean_speed: Real,
+    pub max_speed: Real,
+    /// Mean golden-angle quality over every airborne tick.
+    pub air_quality: Option<Real>,
+    pub wall_hits: usize,
+    pub jumps: Vec<JumpSummary>,
+}
+
+impl fmt::Display for MovementSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let percent = |quality: Option<Real>| match quality {
+            Some(quality) => format!("{:.0}%", quality * 100.0),
+            None => "--".to_string(),
+        };
+        writeln!(
+            f,
+            "motor={} ticks={} duration={:.2}s mean_speed={:.2} max_speed={:.2} \
+air_quality={} wall_hits={} jumps={}",
+            self.motor.label(),
+            self.ticks,
+            self.duration,
+            self.mean_speed,
+            self.max_speed,
+            percent(self.air_quality),
+            self.wall_hits,
+            self.jumps.len()
+        )?;
+        for (index, jump) in self.jumps.iter().enumerate() {
+            writeln!(
+                f,
+                "jump {}: tick={} airtime={:.2}s takeoff={:.2} landing={:.2} gained={:+.2} \
+fall={:.2} quality={} wall_hits={}",
+                index + 1,
+                jump.takeoff_tick,
+                jump.airtime,
+                jump.takeoff_speed,
+                jump.landing_speed,
+                jump.speed_gained,
+                jump.fall_speed,
+                percent(jump.air_quality),
+                jump.wall_hits
+            )?;
+        }
+        if !self.jumps.is_empty() {
+            let gained: Real = self.jumps.iter().map(|jump| jump.speed_gained).sum();
+            write!(
+                f,
+                "jumps: mean_gain={:+.2} mean_airtime={:.2}s",
+                gained / self.jumps.len() as Real,
+                self.jumps.iter().map(|jump| jump.airtime).sum::<Real>() / self.jumps.len() as Real
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(tick: u64, speed: Real, vy: Real, grounded: bool) -> MovementSample {
+        MovementSample {
+            tick,
+            time: tick as Real * 0.25,
+            velocity: [speed, vy, 0.0],
+            speed,
+            grounded,
+            golden: (!grounded).then_some(GoldenSample {
+                theta: 0.3,
+                gain: 1.1,
+                quality: 0.5,
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn jumps_report_gain_airtime_and_landing() {
+        let samples = vec![
+            sample(0, 8.0, 0.0, true),
+            sample(1, 8.5, 2.0, false),
+            sample(2, 9.0, -1.0, false),
+            sample(3, 9.5, -4.0, false),
+            sample(4, 9.4, 0.0, true),
+            sample(5, 9.6, 2.0, false),
+        ];
+        let jumps = summarize_jumps(&samples);
+        assert_eq!(jumps.len(), 1);
+        let jump = jumps[0];
+        assert_eq!(jump.takeoff_tick, 0);
+        assert_eq!(jump.airtime, 1.0);
+        assert!((jump.speed_gained - 1.4).abs() < 1.0e-5);
+        assert_eq!(jump.fall_speed, 4.0);
+        assert_eq!(jump.air_quality, Some(0.5));
+    }
+
+    #[test]
+    fn csv_round_trips_with_and_without_golden_metrics() {
+        let recording = MovementRecording {
+            motor: MotorKind::ARENA,
+            samples: vec![sample(0, 4.0, 0.0, true), sample(1, 4.25, 3.5, false)],
+        };
+        let parsed = MovementRecording::parse_csv(&recording.to_csv()).expect("csv");
+        assert_eq!(parsed, recording);
+        assert_eq!(parsed.summary().ticks, 2);
+        assert!(MovementRecording::parse_csv("# motor arena\n0,0,0").is_err());
+    }
+}
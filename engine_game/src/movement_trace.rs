@@ -0,0 +1,354 @@
+//! Recorded movement input and the trajectories it produces.
+//!
+//! Input traces (`pallet_input_trace_v1`) hold one line per frame: dt, yaw,
+//! pitch, then forward/back/left/right/jump/crouch bits. Golden trajectories
+//! (`pallet_movement_golden_v1`) hold the player's position, velocity and
+//! grounded state after each tick of a headless course run, headed by the
+//! test map, motor and trace that produced them.
+
+use std::fmt;
+
+use player_controller::{PlayerFrame, RawInput};
+use rapier3d::prelude::Real;
+
+use crate::motor::MotorKind;
+
+pub const INPUT_TRACE_HEADER: &str = "# pallet_input_trace_v1";
+pub const TRAJECTORY_HEADER: &str = "# pallet_movement_golden_v1";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct InputTraceFrame {
+    pub dt: Real,
+    pub yaw: Real,
+    pub pitch: Real,
+    pub forward: bool,
+    pub back: bool,
+    pub left: bool,
+    pub right: bool,
+    pub jump: bool,
+    pub down: bool,
+}
+
+impl InputTraceFrame {
+    pub fn raw_input(&self) -> RawInput {
+        RawInput {
+            move_x: bit_axis(self.right, self.left),
+            move_y: bit_axis(self.forward, self.back),
+            jump: self.jump,
+            crouch: self.down,
+            look_delta: [0.0, 0.0],
+        }
+    }
+}
+
+fn bit_axis(positive: bool, negative: bool) -> Real {
+    match (positive, negative) {
+        (true, false) => 1.0,
+        (false, true) => -1.0,
+        _ => 0.0,
+    }
+}
+
+pub fn serialize_input_trace(frames: &[InputTraceFrame]) -> String {
+    let mut text = format!("{}\n", INPUT_TRACE_HEADER);
+    for frame in frames {
+        text.push_str(&format!(
+            "{:.6} {:.6} {:.6} {} {} {} {} {} {}\n",
+            frame.dt,
+            frame.yaw,
+            frame.pitch,
+            u8::from(frame.forward),
+            u8::from(frame.back),
+            u8::from(frame.left),
+            u8::from(frame.right),
+            u8::from(frame.jump),
+            u8::from(frame.down),
+        ));
+    }
+    text
+}
+
+pub fn parse_input_trace(text: &str) -> Result<Vec<InputTraceFrame>, String> {
+    let mut frames = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let parts: Vec<&str> = trimmed.split_whitespace().collect();
+        if parts.len() != 9 {
+            return Err(format!("trace line {} malformed", index + 1));
+        }
+        let number = |value: &str, field: &str| {
+            value
+                .parse::<Real>()
+                .map_err(|_| format!("trace line {} {} invalid", index + 1, field))
+        };
+        frames.push(InputTraceFrame {
+            dt: number(parts[0], "dt")?,
+            yaw: number(parts[1], "yaw")?,
+            pitch: number(parts[2], "pitch")?,
+            forward: parse_bit(parts[3], index)?,
+            back: parse_bit(parts[4], index)?,
+            left: parse_bit(parts[5], index)?,
+            right: parse_bit(parts[6], index)?,
+            jump: parse_bit(parts[7], index)?,
+            down: parse_bit(parts[8], index)?,
+        });
+    }
+    Ok(frames)
+}
+
+fn parse_bit(value: &str, line_index: usize) -> Result<bool, String> {
+    match value {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(format!("trace line {} bool invalid", line_index + 1)),
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TrajectorySample {
+    pub position: [Real; 3],
+    pub velocity: [Real; 3],
+    pub grounded: bool,
+}
+
+impl TrajectorySample {
+    pub fn from_frame(frame: &PlayerFrame) -> Self {
+        let position = frame.kinematics.position.translation.vector;
+        let velocity = frame.kinematics.velocity;
+        Self {
+            position: [position.x, position.y, position.z],
+            velocity: [velocity.x, velocity.y, velocity.z],
+            grounded: frame.kinematics.grounded,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GoldenTrajectory {
+    /// Test map asset key, e.g. `engine:test_map/stairs_and_steps.toml`.
+    pub test_map: String,
+    pub motor: MotorKind,
+    /// Trace file name, relative to the golden file.
+    pub trace: String,
+    pub samples: Vec<TrajectorySample>,
+}
+
+impl GoldenTrajectory {
+    pub fn serialize(&self) -> String {
+        let mut text = format!(
+            "{}\n# test_map {}\n# motor {}\n# trace {}\n",
+            TRAJECTORY_HEADER,
+            self.test_map,
+            self.motor.label(),
+            self.trace
+        );
+        for sample in &self.samples {
+            let [px, py, pz] = sample.position;
+            let [vx, vy, vz] = sample.velocity;
+            text.push_str(&format!(
+                "{:.5} {:.5} {:.5} {:.5} {:.5} {:.5} {}\n",
+                px,
+                py,
+                pz,
+                vx,
+                vy,
+                vz,
+                u8::from(sample.grounded)
+            ));
+        }
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut test_map = None;
+        let mut motor = None;
+        let mut trace = None;
+        let mut samples = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            if let Some(comment) = trimmed.strip_prefix('#') {
+                match comment.trim().split_once(' ') {
+                    Some(("test_map", value)) => test_map = Some(value.trim().to_string()),
+                    Some(("motor", value)) => motor = Some(MotorKind::parse(value.trim())?),
+                    Some(("trace", value)) => trace = Some(value.trim().to_string()),
+                    _ => {}
+                }
+                continue;
+            }
+            if trimmed.is_empty() {
+                continue;
+            }
+            let parts: Vec<&str> = trimmed.split_whitespace().collect();
+            if parts.len() != 7 {
+                return Err(format!("golden line {} malformed", index + 1));
+            }
+            let mut values = [0.0; 6];
+            for (value, part) in values.iter_mut().zip(&parts) {
+                *value = part
+                    .parse::<Real>()
+                    .map_err(|_| format!("golden line {} number invalid", index + 1))?;
+            }
+            samples.push(TrajectorySample {
+                position: [values[0], values[1], values[2]],
+                velocity: [values[3], values[4], values[5]],
+                grounded: parse_bit(parts[6], index)?,
+            });
+        }
+        Ok(Self {
+            test_map: test_map.ok_or("golden is missing its test_map header")?,
+            motor: motor.ok_or("golden is missing its motor header")?,
+            trace: trace.ok_or("golden is missing its trace header")?,
+            samples,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TrajectoryTolerance {
+    /// Largest per-axis position difference, in meters.
+    pub position: Real,
+    /// Largest per-axis velocity difference, in m/s.
+    pub velocity: Real,
+}
+
+impl Default for TrajectoryTolerance {
+    fn default() -> Self {
+        Self {
+            position: 1.0e-3,
+            velocity: 1.0e-2,
+        }
+    }
+}
+
+/// Where a run first left its golden trajectory.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Divergence {
+    pub tick: usize,
+    pub field: &'static str,
+    /// `None` when one trajectory ended before the other.
+    pub actual: Option<TrajectorySample>,
+    pub expected: Option<TrajectorySample>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tick {}: {} differs", self.tick, self.field)?;
+        let describe = |sample: Option<TrajectorySample>| match sample {
+            Some(sample) => format!(
+                "pos=({:.4}, {:.4}, {:.4}) vel=({:.4}, {:.4}, {:.4}) grounded={}",
+                sample.position[0],
+                sample.position[1],
+                sample.position[2],
+                sample.velocity[0],
+                sample.velocity[1],
+                sample.velocity[2],
+                sample.grounded
+            ),
+            None => "<ended>".to_string(),
+        };
+        write!(
+            f,
+            "\n  actual:   {}\n  expected: {}",
+            describe(self.actual),
+            describe(self.expected)
+        )
+    }
+}
+
+pub fn first_divergence(
+    actual: &[TrajectorySample],
+    expected: &[TrajectorySample],
+    tolerance: TrajectoryTolerance,
+) -> Option<Divergence> {
+    let within = |a: [Real; 3], b: [Real; 3], limit: Real| {
+        a.iter().zip(&b).all(|(a, b)| (a - b).abs() <= limit)
+    };
+    for tick in 0..actual.len().max(expected.len()) {
+        let (a, e) = (actual.get(tick).copied(), expected.get(tick).copied());
+        let field = match (a, e) {
+            (Some(a), Some(e)) => {
+                if !within(a.position, e.position, tolerance.position) {
+                    "position"
+                } else if !within(a.velocity, e.velocity, tolerance.velocity) {
+                    "velocity"
+                } else if a.grounded != e.grounded {
+                    "grounded"
+                } else {
+                    continue;
+                }
+            }
+            _ => "length",
+        };
+        return Some(Divergence {
+            tick,
+            field,
+            actual: a,
+            expected: e,
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_and_golden_round_trip() {
+        let frames = vec![
+            InputTraceFrame {
+                dt: 1.0 / 60.0,
+                yaw: 0.5,
+                forward: true,
+                jump: true,
+                ..Default::default()
+            },
+            InputTraceFrame {
+                dt: 1.0 / 60.0,
+                pitch: -0.25,
+                left: true,
+                down: true,
+                ..Default::default()
+            },
+        ];
+        let parsed = parse_input_trace(&serialize_input_trace(&frames)).expect("trace");
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].raw_input().move_y, 1.0);
+        assert_eq!(parsed[1].raw_input().move_x, -1.0);
+        assert!(parse_input_trace("0.016 0 0 1 0 0 0 2 0").is_err());
+
+        let golden = GoldenTrajectory {
+            test_map: "engine:test_map/flat_friction_lane.toml".to_string(),
+            motor: MotorKind::RPG,
+            trace: "flat_friction_lane.trace".to_string(),
+            samples: vec![TrajectorySample {
+                position: [1.0, 0.9, -2.5],
+                velocity: [4.5, 0.0, 0.0],
+                grounded: true,
+            }],
+        };
+        assert_eq!(GoldenTrajectory::parse(&golden.serialize()), Ok(golden));
+    }
+
+    #[test]
+    fn divergence_reports_first_tick_out_of_tolerance() {
+        let sample = TrajectorySample {
+            position: [0.0, 1.0, 0.0],
+            velocity: [1.0, 0.0, 0.0],
+            grounded: true,
+        };
+        let mut drifted = vec![sample; 4];
+        drifted[2].velocity[0] += 0.5;
+        drifted[3].position[1] += 0.1;
+        let golden = vec![sample; 4];
+        let tolerance = TrajectoryTolerance::default();
+        assert_eq!(first_divergence(&golden, &golden, tolerance), None);
+        let divergence = first_divergence(&drifted, &golden, tolerance).expect("diverges");
+        assert_eq!((divergence.tick, divergence.field), (2, "velocity"));
+        let short = first_divergence(&golden[..3], &golden, tolerance).expect("short");
+        assert_eq!((short.tick, short.field), (3, "length"));
+    }
+}
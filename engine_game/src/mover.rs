@@ -0,0 +1,568 @@
+//! Doors, elevators and path-following platforms.
+//!
+//! A [`Mover`] only computes where its body should be; hosts push the result
+//! into physics with [`crate::GameSession::apply_mover_targets`] before the
+//! next physics step, so the character controller sees the motion as a
+//! kinematic displacement.
+
+use bevy_ecs::prelude::*;
+use ecs::{FixedTick, FixedTimeStep, SaveState};
+use rapier3d::prelude::{Isometry, Real, RigidBodyHandle, Translation, Vector};
+use test_map::{MoverKind, MoverSpec};
+
+use crate::map_entities::MapEntity;
+use crate::save::parse_floats;
+use crate::trigger::{TriggerEvents, TriggerPhase};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoverMode {
+    /// Visits every point in order, then wraps back to the first.
+    Loop,
+    /// Runs to the last point and back again.
+    PingPong,
+    /// Rests at the first point until activated, runs to the last, waits,
+    /// then returns.
+    Door,
+    /// Like `Door`, but stays at the last point once it gets there (Quake's
+    /// `wait -1`).
+    StayOpen,
+}
+
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct Mover {
+    pub name: String,
+    pub mode: MoverMode,
+    /// Offsets from the body's base pose, in world units.
+    pub points: Vec<[f32; 3]>,
+    /// World units per second.
+    pub speed: f32,
+    /// Seconds to rest at each stop; doors only rest while open.
+    pub wait: f32,
+    /// Trigger name whose enter event calls [`Mover::activate`].
+    pub trigger: Option<String>,
+    at: usize,
+    target: Option<usize>,
+    progress: f32,
+    wait_left: f32,
+    forward: bool,
+    active: bool,
+}
+
+/// Kinematic body a mover drives, with the pose its offsets are relative to.
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub struct MoverBody {
+    pub body: RigidBodyHandle,
+    pub base: Isometry<Real>,
+}
+
+impl Mover {
+    pub fn new(
+        name: impl Into<String>,
+        mode: MoverMode,
+        points: Vec<[f32; 3]>,
+        speed: f32,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            mode,
+            points,
+            speed,
+            wait: 0.0,
+            trigger: None,
+            at: 0,
+            target: None,
+            progress: 0.0,
+            wait_left: 0.0,
+            forward: true,
+            active: false,
+        }
+    }
+
+    pub fn with_wait(mut self, wait: f32) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    pub fn with_trigger(mut self, trigger: Option<String>) -> Self {
+        self.trigger = trigger;
+        self
+    }
+
+    /// Opens a door, reversing it if it is closing. No effect on other modes.
+    pub fn activate(&mut self) {
+        if !matches!(self.mode, MoverMode::Door | MoverMode::StayOpen) {
+            return;
+        }
+        self.active = true;
+        let last = self.points.len().saturating_sub(1);
+        match self.target {
+            Some(target) if target < self.at => {
+                let length = self.segment_length(self.at, target);
+                self.progress = (length - self.progress).max(0.0);
+                self.target = Some(self.at);
+                self.at = target;
+            }
+            None if self.at == last => self.wait_left = self.wait,
+            _ => {}
+        }
+    }
+
+    pub fn is_moving(&self) -> bool {
+        self.target.is_some()
+    }
+
+    /// Current offset from the base pose.
+    pub fn offset(&self) -> [f32; 3] {
+        let Some(from) = self.points.get(self.at).copied() else {
+            return [0.0; 3];
+        };
+        let Some(to) = self.target.and_then(|target| self.points.get(target)) else {
+            return from;
+        };
+        let length = self.segment_length(self.at, self.target.unwrap_or(self.at));
+        let t = if length > 0.0 {
+            (self.progress / length).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        [
+            from[0] + (to[0] - from[0]) * t,
+            from[1] + (to[1] - from[1]) * t,
+            from[2] + (to[2] - from[2]) * t,
+        ]
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        if self.points.len() < 2 || !self.speed.is_finite() || self.speed <= 0.0 {
+            return;
+        }
+        let mut time = dt.max(0.0);
+        // Arrivals that used no time; past one lap of the path the mover is
+        // cycling through zero-length segments and cannot get anywhere.
+        let mut idle_arrivals = 0;
+        while time > 0.0 {
+            if self.wait_left > 0.0 {
+                let waited = self.wait_left.min(time);
+                self.wait_left -= waited;
+                time -= waited;
+                continue;
+            }
+            let Some(target) = self.target.or_else(|| self.next_target()) else {
+                return;
+            };
+            self.target = Some(target);
+            let remaining = self.segment_length(self.at, target) - self.progress;
+            let travel = self.speed * time;
+            if travel < remaining {
+                self.progress += travel;
+                return;
+            }
+            let before = time;
+            time -= remaining.max(0.0) / self.speed;
+            self.at = target;
+            self.target = None;
+            self.progress = 0.0;
+            self.wait_left = self.wait_at(target);
+            if time < before || self.wait_left > 0.0 {
+                idle_arrivals = 0;
+            } else {
+                idle_arrivals += 1;
+                if idle_arrivals > self.points.len() {
+                    return;
+                }
+            }
+        }
+    }
+
+    fn next_target(&mut self) -> Option<usize> {
+        let last = self.points.len() - 1;
+        match self.mode {
+            MoverMode::Loop => Some((self.at + 1) % self.points.len()),
+            MoverMode::PingPong => {
+                if self.at == last {
+                    self.forward = false;
+                } else if self.at == 0 {
+                    self.forward = true;
+                }
+                Some(if self.forward {
+                    self.at + 1
+                } else {
+                    self.at - 1
+                })
+            }
+            MoverMode::Door => {
+                if self.at == last {
+                    self.active = false;
+                }
+                if self.active {
+                    Some(self.at + 1)
+                } else if self.at > 0 {
+                    Some(self.at - 1)
+                } else {
+                    None
+                }
+            }
+            MoverMode::StayOpen => (self.active && self.at < last).then_some(self.at + 1),
+        }
+    }
+
+    fn wait_at(&self, point: usize) -> f32 {
+        match self.mode {
+            MoverMode::Door if point + 1 != self.points.len() => 0.0,
+            MoverMode::StayOpen => 0.0,
+            _ => self.wait.max(0.0),
+        }
+    }
+
+    fn segment_length(&self, from: usize, to: usize) -> f32 {
+        let (Some(a), Some(b)) = (self.points.get(from), self.points.get(to)) else {
+            return 0.0;
+        };
+        let d = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+        (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+    }
+}
+
+impl MoverMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            MoverMode::Loop => "loop",
+            MoverMode::PingPong => "ping_pong",
+            MoverMode::Door => "door",
+            MoverMode::StayOpen => "stay_open",
+        }
+    }
+
+    fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "loop" => Some(MoverMode::Loop),
+            "ping_pong" => Some(MoverMode::PingPong),
+            "door" => Some(MoverMode::Door),
+            "stay_open" => Some(MoverMode::StayOpen),
+            _ => None,
+        }
+    }
+}
+
+/// Encoded as `<mode> <speed> <wait> <at> <target> <progress> <wait_left>
+/// <forward> <active>;<x y z>,<x y z>...;<trigger>;<name>`, with `-` for no
+/// target and an empty trigger for none. The name goes last so it may
+/// contain `;`.
+impl SaveState for Mover {
+    const SAVE_KEY: &'static str = "mover";
+
+    fn encode(&self) -> String {
+        let target = self
+            .target
+            .map_or_else(|| "-".to_string(), |target| target.to_string());
+        let points: Vec<String> = self
+            .points
+            .iter()
+            .map(|point| format!("{} {} {}", point[0], point[1], point[2]))
+            .collect();
+        format!(
+            "{} {} {} {} {} {} {} {} {};{};{};{}",
+            self.mode.as_str(),
+            self.speed,
+            self.wait,
+            self.at,
+            target,
+            self.progress,
+            self.wait_left,
+            u8::from(self.forward),
+            u8::from(self.active),
+            points.join(","),
+            self.trigger.as_deref().unwrap_or_default(),
+            self.name
+        )
+    }
+
+    fn decode(value: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = value.splitn(4, ';').collect();
+        let [state, points, trigger, name] = parts.as_slice() else {
+            return Err(format!("expected 4 mover parts, got '{}'", value));
+        };
+        let fields: Vec<&str> = state.split_whitespace().collect();
+        let [mode, speed, wait, at, target, progress, wait_left, forward, active] =
+            fields.as_slice()
+        else {
+            return Err(format!("expected 9 mover fields, got '{}'", state));
+        };
+        let mode =
+            MoverMode::from_str(mode).ok_or_else(|| format!("unknown mover mode '{}'", mode))?;
+        let index = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("invalid mover point index '{}'", value))
+        };
+        let bit = |value: &str| match value {
+            "0" => Ok(false),
+            "1" => Ok(true),
+            _ => Err(format!("expected 0 or 1, got '{}'", value)),
+        };
+        let float = |value: &str| {
+            value
+                .parse::<f32>()
+                .map_err(|_| format!("invalid float '{}'", value))
+        };
+        let points = if points.trim().is_empty() {
+            Vec::new()
+        } else {
+            points
+                .split(',')
+                .map(parse_floats)
+                .collect::<Result<Vec<[f32; 3]>, _>>()?
+        };
+        let at = index(at)?;
+        let target = match *target {
+            "-" => None,
+            target => Some(index(target)?),
+        };
+        if at >= points.len().max(1) || target.is_some_and(|target| target >= points.len()) {
+            return Err(format!(
+                "mover point index out of range for {} points",
+                points.len()
+            ));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            mode,
+            points,
+            speed: float(speed)?,
+            wait: float(wait)?,
+            trigger: (!trigger.is_empty()).then(|| trigger.to_string()),
+            at,
+            target,
+            progress: float(progress)?,
+            wait_left: float(wait_left)?,
+            forward: bit(forward)?,
+            active: bit(active)?,
+        })
+    }
+}
+
+/// Builds the mover for a test map `[[movers]]` entry.
+pub fn mover_from_test_map(spec: &MoverSpec, scale: f32) -> Mover {
+    let mode = match spec.kind {
+        MoverKind::Door => MoverMode::Door,
+        MoverKind::Elevator => MoverMode::PingPong,
+        MoverKind::Platform => MoverMode::Loop,
+    };
+    let points = spec
+        .path
+        .iter()
+        .map(|point| [point[0] * scale, point[1] * scale, point[2] * scale])
+        .collect();
+    Mover::new(spec.id.clone(), mode, points, spec.speed * scale)
+        .with_wait(spec.wait)
+        .with_trigger(spec.trigger.clone())
+}
+
+/// Movers for Quake `func_door` and `func_plat`. `size` is the brush model's
+/// world-space extent; `scale` converts Quake units to meters.
+///
+/// Doors slide along `angle` (-1 up, -2 down) by their size minus `lip`;
+/// plats rest lowered by `height` and rise when triggered, like a door. A
+/// negative `wait` keeps the mover open for good.
+/// Untargeted movers are named `<classname>*<model>` after their brush model.
+/// The trigger is the entity's `targetname`, or `<name>_touch` for movers the
+/// host should open on contact.
+pub fn mover_from_quake_entity(entity: &MapEntity, size: [f32; 3], scale: f32) -> Option<Mover> {
+    let number = |key: &str| {
+        entity
+            .keys
+            .iter()
+            .find(|(name, _)| name == key)
+            .and_then(|(_, value)| value.trim().parse::<f32>().ok())
+    };
+    let name = entity
+        .targetname
+        .clone()
+        .unwrap_or_else(|| match entity.brush_model {
+            Some(model) => format!("{}*{}", entity.classname, model),
+            None => entity.classname.clone(),
+        });
+    let (points, speed) = if entity.classname.starts_with("func_door") {
+        let angle = number("angle").unwrap_or(0.0);
+        let direction = match angle as i32 {
+            -1 => [0.0, 1.0, 0.0],
+            -2 => [0.0, -1.0, 0.0],
+            _ => {
+                let radians = angle.to_radians();
+                [radians.cos(), 0.0, -radians.sin()]
+            }
+        };
+        let extent = (direction[0] * size[0]).abs()
+            + (direction[1] * size[1]).abs()
+            + (direction[2] * size[2]).abs();
+        let distance = (extent - number("lip").unwrap_or(8.0) * scale).max(0.0);
+        let open = [
+            direction[0] * distance,
+            direction[1] * distance,
+            direction[2] * distance,
+        ];
+        (vec![[0.0; 3], open], number("speed").unwrap_or(100.0))
+    } else if entity.classname == "func_plat" {
+        let height = number("height")
+            .map(|height| height * scale)
+            .unwrap_or(size[1] - 8.0 * scale)
+            .max(0.0);
+        (
+            vec![[0.0, -height, 0.0], [0.0; 3]],
+            number("speed").unwrap_or(150.0),
+        )
+    } else {
+        return None;
+    };
+    let trigger = entity
+        .targetname
+        .clone()
+        .or_else(|| Some(format!("{}_touch", name)));
+    let wait = number("wait").unwrap_or(3.0);
+    let mode = if wait < 0.0 {
+        MoverMode::StayOpen
+    } else {
+        MoverMode::Door
+    };
+    Some(
+        Mover::new(name, mode, points, speed * scale)
+            .with_wait(wait.max(0.0))
+            .with_trigger(trigger),
+    )
+}
+
+pub(crate) fn activate_triggered_movers(
+    events: Res<TriggerEvents>,
+    tick: Res<FixedTick>,
+    mut movers: Query<&mut Mover>,
+) {
+    for event in &events.0 {
+        if event.phase != TriggerPhase::Enter || event.tick != tick.0 {
+            continue;
+        }
+        for mut mover in &mut movers {
+            if mover.trigger.as_deref() == Some(event.name.as_str()) {
+                mover.activate();
+            }
+        }
+    }
+}
+
+pub(crate) fn advance_movers(time: Res<FixedTimeStep>, mut movers: Query<&mut Mover>) {
+    for mut mover in &mut movers {
+        mover.advance(time.dt_seconds);
+    }
+}
+
+/// World pose for `mover` driving `body`.
+pub fn mover_pose(mover: &Mover, body: &MoverBody) -> Isometry<Real> {
+    let offset = mover.offset();
+    Translation::from(Vector::new(offset[0], offset[1], offset[2])) * body.base
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn elevator_runs_back_and_forth_with_waits() {
+        let mut lift = Mover::new(
+            "lift",
+            MoverMode::PingPong,
+            vec![[0.0; 3], [0.0, 4.0, 0.0]],
+            2.0,
+        )
+        .with_wait(1.0);
+        lift.advance(1.0);
+        assert_eq!(lift.offset(), [0.0, 2.0, 0.0]);
+        lift.advance(1.5);
+        assert_eq!(lift.offset(), [0.0, 4.0, 0.0]);
+        assert!(!lift.is_moving());
+        lift.advance(1.0);
+        assert_eq!(lift.offset(), [0.0, 3.0, 0.0]);
+    }
+
+    #[test]
+    fn door_opens_when_activated_and_reverses_while_closing() {
+        let mut door = Mover::new(
+            "gate",
+            MoverMode::Door,
+            vec![[0.0; 3], [0.0, 3.0, 0.0]],
+            3.0,
+        )
+        .with_wait(2.0);
+        door.advance(5.0);
+        assert_eq!(door.offset(), [0.0; 3]);
+        door.activate();
+        door.advance(1.0);
+        assert_eq!(door.offset(), [0.0, 3.0, 0.0]);
+        door.advance(2.5);
+        assert_eq!(door.offset(), [0.0, 1.5, 0.0]);
+        door.activate();
+        door.advance(0.5);
+        assert_eq!(door.offset(), [0.0, 3.0, 0.0]);
+        door.advance(3.0);
+        assert_eq!(door.offset(), [0.0; 3]);
+        assert!(!door.is_moving());
+    }
+
+    #[test]
+    fn duplicate_points_without_wait_do_not_hang() {
+        for mode in [MoverMode::Loop, MoverMode::PingPong] {
+            let mut stuck = Mover::new("stuck", mode, vec![[1.0, 0.0, 0.0]; 2], 2.0);
+            stuck.advance(0.5);
+            assert_eq!(stuck.offset(), [1.0, 0.0, 0.0]);
+        }
+        let mut platform = Mover::new(
+            "platform",
+            MoverMode::Loop,
+            vec![[0.0; 3], [0.0; 3], [0.0, 2.0, 0.0]],
+            2.0,
+        );
+        platform.advance(0.5);
+        assert_eq!(platform.offset(), [0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn quake_door_with_negative_wait_stays_open() {
+        let entity = MapEntity {
+            classname: "func_door".to_string(),
+            keys: vec![
+                ("angle".to_string(), "-1".to_string()),
+                ("wait".to_string(), "-1".to_string()),
+            ],
+            brush_model: Some(1),
+            ..MapEntity::default()
+        };
+        let mut door = mover_from_quake_entity(&entity, [4.0, 3.0, 6.0], 0.125).expect("door");
+        assert_eq!(door.mode, MoverMode::StayOpen);
+        door.activate();
+        door.advance(10.0);
+        let open = door.offset();
+        assert!(open[1] > 1.0);
+        door.advance(10.0);
+        assert_eq!(door.offset(), open);
+        assert!(!door.is_moving());
+    }
+
+    #[test]
+    fn quake_door_slides_its_width_minus_lip() {
+        let entity = MapEntity {
+            classname: "func_door".to_string(),
+            targetname: Some("gate".to_string()),
+            keys: vec![
+                ("angle".to_string(), "90".to_string()),
+                ("lip".to_string(), "16".to_string()),
+            ],
+            ..MapEntity::default()
+        };
+        let door = mover_from_quake_entity(&entity, [4.0, 3.0, 6.0], 0.125).expect("door");
+        assert_eq!(door.mode, MoverMode::Door);
+        assert_eq!(door.trigger.as_deref(), Some("gate"));
+        let open = door.points[1];
+        assert!(open[0].abs() < 1.0e-5 && open[1] == 0.0);
+        assert!((open[2] + 4.0).abs() < 1.0e-5);
+        assert!((door.speed - 12.5).abs() < 1.0e-5);
+        assert_eq!(door.wait, 3.0);
+    }
+}
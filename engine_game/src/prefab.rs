@@ -0,0 +1,154 @@
+//! Instantiates resolved prefabs as ECS entities.
+
+use std::collections::BTreeMap;
+
+use bevy_ecs::prelude::*;
+use ecs::{Transform, Vec3};
+use engine_core::asset_id::AssetKey;
+use engine_core::level_manifest::PrefabPlacement;
+use engine_core::prefab::{ColliderSpec, PrefabDef};
+
+use crate::motor::MotorKind;
+
+/// The prefab an entity was spawned from.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct PrefabInstance {
+    pub prefab: AssetKey,
+}
+
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub struct Facing {
+    pub yaw_deg: f32,
+}
+
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub struct Collider(pub ColliderSpec);
+
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ControllerProfile(pub MotorKind);
+
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct ScriptRef(pub AssetKey);
+
+#[derive(Component, Clone, Debug, Default, PartialEq)]
+pub struct SoundSet(pub BTreeMap<String, AssetKey>);
+
+/// Spawns `prefab` (already resolved) at `origin`. The prefab's own origin is
+/// an offset; `yaw_deg` overrides the prefab yaw when set.
+pub fn spawn_prefab(
+    world: &mut World,
+    key: &AssetKey,
+    prefab: &PrefabDef,
+    origin: [f32; 3],
+    yaw_deg: Option<f32>,
+) -> Result<Entity, String> {
+    let controller = prefab
+        .controller_profile
+        .as_deref()
+        .map(MotorKind::parse)
+        .transpose()
+        .map_err(|err| format!("{} controller profile: {}", key, err))?;
+    let offset = prefab.origin.unwrap_or_default();
+    let mut entity = world.spawn((
+        PrefabInstance {
+            prefab: key.clone(),
+        },
+        Transform {
+            position: Vec3 {
+                x: origin[0] + offset[0],
+                y: origin[1] + offset[1],
+                z: origin[2] + offset[2],
+            },
+        },
+        Facing {
+            yaw_deg: yaw_deg.or(prefab.yaw_deg).unwrap_or(0.0),
+        },
+    ));
+    if let Some(collider) = prefab.collider {
+        entity.insert(Collider(collider));
+    }
+    if let Some(kind) = controller {
+        entity.insert(ControllerProfile(kind));
+    }
+    if let Some(script) = &prefab.script {
+        entity.insert(ScriptRef(script.clone()));
+    }
+    if !prefab.sounds.is_empty() {
+        entity.insert(SoundSet(prefab.sounds.clone()));
+    }
+    Ok(entity.id())
+}
+
+/// Spawns every level manifest placement; `resolve` returns the flattened
+/// prefab for a key (see `engine_core::prefab::resolve_prefab`).
+pub fn spawn_placements<F>(
+    world: &mut World,
+    placements: &[PrefabPlacement],
+    mut resolve: F,
+) -> Result<Vec<Entity>, String>
+where
+    F: FnMut(&AssetKey) -> Result<PrefabDef, String>,
+{
+    let mut spawned = Vec::with_capacity(placements.len());
+    for placement in placements {
+        let prefab = resolve(&placement.prefab)
+            .map_err(|err| format!("prefab at line {}: {}", placement.line, err))?;
+        spawned.push(spawn_prefab(
+            world,
+            &placement.prefab,
+            &prefab,
+            placement.origin,
+            placement.yaw_deg,
+        )?);
+    }
+    Ok(spawned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placement_spawns_prefab_components() {
+        let prefab = PrefabDef::parse(
+            r#"
+[transform]
+origin = [0.0, 0.5, 0.0]
+yaw_deg = 30
+
+[collider]
+shape = "capsule"
+radius = 0.4
+height = 1.0
+
+[controller]
+profile = "rpg"
+
+[sounds]
+step = "engine:blob/sound/step.wav"
+"#,
+        )
+        .expect("prefab");
+        let key = AssetKey::parse("engine:prefab/npc/guard.toml").unwrap();
+        let placements = [PrefabPlacement {
+            prefab: key.clone(),
+            origin: [2.0, 1.0, 3.0],
+            yaw_deg: None,
+            line: 4,
+        }];
+        let mut world = World::new();
+        let spawned =
+            spawn_placements(&mut world, &placements, |_| Ok(prefab.clone())).expect("spawn");
+        let entity = world.entity(spawned[0]);
+        assert_eq!(entity.get::<PrefabInstance>().unwrap().prefab, key);
+        assert_eq!(entity.get::<Transform>().unwrap().position.y, 1.5);
+        assert_eq!(entity.get::<Facing>().unwrap().yaw_deg, 30.0);
+        assert_eq!(
+            entity.get::<ControllerProfile>(),
+            Some(&ControllerProfile(MotorKind::RPG))
+        );
+        assert!(entity.get::<Collider>().is_some());
+        assert!(entity.get::<ScriptRef>().is_none());
+        assert_eq!(entity.get::<SoundSet>().unwrap().0.len(), 1);
+    }
+}
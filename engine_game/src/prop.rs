@@ -0,0 +1,150 @@
+//! Dynamic props: crates, barrels and other free rigid bodies.
+//!
+//! Physics owns the simulation. The session keeps one entity per prop so
+//! scripts and saves can find it; hosts mirror body poses back with
+//! [`crate::GameSession::sync_props`] after each physics step.
+
+use bevy_ecs::prelude::*;
+use ecs::SaveState;
+use physics_rapier::{DynamicBodyDesc, DynamicBodyShape};
+use rapier3d::na::{Quaternion, UnitQuaternion};
+use rapier3d::prelude::{Isometry, Real, RigidBodyHandle, Rotation, Translation, Vector};
+use test_map::{PropKind, PropSpec};
+
+use crate::save::parse_floats;
+
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct Prop {
+    pub name: String,
+    pub body: RigidBodyHandle,
+}
+
+/// Body pose and velocities of a prop as of the last `sync_props`, so a save
+/// can put the body back mid-flight.
+#[derive(Component, Copy, Clone, Debug, PartialEq)]
+pub struct PropMotion {
+    pub position: Isometry<Real>,
+    pub linvel: Vector<Real>,
+    pub angvel: Vector<Real>,
+}
+
+/// Encoded as `<x y z>;<i j k w>;<linvel>;<angvel>`.
+impl SaveState for PropMotion {
+    const SAVE_KEY: &'static str = "prop_motion";
+
+    fn encode(&self) -> String {
+        let t = self.position.translation.vector;
+        let r = self.position.rotation.quaternion();
+        format!(
+            "{} {} {};{} {} {} {};{} {} {};{} {} {}",
+            t.x,
+            t.y,
+            t.z,
+            r.i,
+            r.j,
+            r.k,
+            r.w,
+            self.linvel.x,
+            self.linvel.y,
+            self.linvel.z,
+            self.angvel.x,
+            self.angvel.y,
+            self.angvel.z
+        )
+    }
+
+    fn decode(value: &str) -> Result<Self, String> {
+        let parts: Vec<&str> = value.split(';').collect();
+        let [translation, rotation, linvel, angvel] = parts.as_slice() else {
+            return Err(format!("expected 4 prop motion parts, got '{}'", value));
+        };
+        let [x, y, z] = parse_floats(translation)?;
+        let [i, j, k, w] = parse_floats(rotation)?;
+        let [lx, ly, lz] = parse_floats(linvel)?;
+        let [ax, ay, az] = parse_floats(angvel)?;
+        Ok(Self {
+            position: Isometry::from_parts(
+                Translation::new(x, y, z),
+                UnitQuaternion::new_unchecked(Quaternion::new(w, i, j, k)),
+            ),
+            linvel: Vector::new(lx, ly, lz),
+            angvel: Vector::new(ax, ay, az),
+        })
+    }
+}
+
+/// Body description for a test map `[[props]]` entry; lengths are scaled to
+/// world units, mass is left in kilograms.
+pub fn prop_from_test_map(spec: &PropSpec, scale: f32) -> DynamicBodyDesc {
+    let shape = match spec.kind {
+        PropKind::Box => {
+            let size = spec.size.unwrap_or([1.0; 3]);
+            DynamicBodyShape::Box {
+                half_extents: [
+                    size[0] * 0.5 * scale,
+                    size[1] * 0.5 * scale,
+                    size[2] * 0.5 * scale,
+                ],
+            }
+        }
+        PropKind::Sphere => DynamicBodyShape::Sphere {
+            radius: spec.radius.unwrap_or(0.5) * scale,
+        },
+        PropKind::ConvexHull => DynamicBodyShape::ConvexHull {
+            points: spec
+                .points
+                .iter()
+                .map(|point| [point[0] * scale, point[1] * scale, point[2] * scale])
+                .collect(),
+        },
+    };
+    let translation = Translation::from(Vector::new(
+        spec.pos[0] * scale,
+        spec.pos[1] * scale,
+        spec.pos[2] * scale,
+    ));
+    let rotation = Rotation::from_euler_angles(0.0, spec.yaw_deg.unwrap_or(0.0).to_radians(), 0.0);
+    let mut desc = DynamicBodyDesc::new(
+        shape,
+        Isometry::from_parts(translation, rotation),
+        spec.mass,
+    );
+    desc.friction = spec.friction;
+    desc.restitution = spec.restitution;
+    desc.ccd = spec.ccd;
+    desc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_prop_scales_lengths_but_not_mass() {
+        let spec = PropSpec {
+            id: "crate".to_string(),
+            kind: PropKind::Box,
+            pos: [8.0, 4.0, 0.0],
+            size: Some([4.0, 4.0, 8.0]),
+            radius: None,
+            points: Vec::new(),
+            yaw_deg: Some(90.0),
+            mass: 25.0,
+            friction: 0.8,
+            restitution: 0.1,
+            ccd: true,
+        };
+        let desc = prop_from_test_map(&spec, 0.25);
+        assert_eq!(
+            desc.shape,
+            DynamicBodyShape::Box {
+                half_extents: [0.5, 0.5, 1.0]
+            }
+        );
+        assert_eq!(desc.position.translation.vector, Vector::new(2.0, 1.0, 0.0));
+        assert!((desc.position.rotation.angle() - 90.0_f32.to_radians()).abs() < 1.0e-5);
+        assert_eq!(desc.mass, 25.0);
+        assert_eq!(desc.friction, 0.8);
+        assert!(desc.ccd);
+    }
+}
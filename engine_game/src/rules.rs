@@ -0,0 +1,68 @@
+//! Game mode hooks consulted by the session systems.
+
+use ecs::Vec3;
+use physics_rapier::LiquidKind;
+
+use crate::liquid::LiquidDamage;
+use crate::spawn::SpawnPoint;
+
+pub trait GameRules: Send + Sync + 'static {
+    fn name(&self) -> &str;
+
+    /// Chooses where player `slot` (re)spawns; `spawn_count` counts earlier
+    /// spawns of that player on the current map.
+    fn select_spawn<'a>(
+        &mut self,
+        spawns: &'a [SpawnPoint],
+        slot: u32,
+        spawn_count: u32,
+    ) -> Option<&'a SpawnPoint>;
+
+    /// Whether a live player at `position` should be sent back to a spawn.
+    fn needs_respawn(&self, position: Vec3) -> bool;
+
+    fn on_map_enter(&mut self, _map: &str) {}
+
+    /// Damage for a player at immersion `level` in `kind`; `None` is harmless.
+    fn liquid_damage(&self, kind: LiquidKind, level: f32) -> Option<LiquidDamage> {
+        LiquidDamage::for_liquid(kind, level)
+    }
+}
+
+/// Free-roam mode: spawns rotate through the map's spawn list and players
+/// that fall below `kill_height` are respawned.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SandboxRules {
+    pub kill_height: f32,
+}
+
+impl Default for SandboxRules {
+    fn default() -> Self {
+        Self {
+            kill_height: -100.0,
+        }
+    }
+}
+
+impl GameRules for SandboxRules {
+    fn name(&self) -> &str {
+        "sandbox"
+    }
+
+    fn select_spawn<'a>(
+        &mut self,
+        spawns: &'a [SpawnPoint],
+        slot: u32,
+        spawn_count: u32,
+    ) -> Option<&'a SpawnPoint> {
+        if spawns.is_empty() {
+            return None;
+        }
+        let index = (slot as usize + spawn_count as usize) % spawns.len();
+        spawns.get(index)
+    }
+
+    fn needs_respawn(&self, position: Vec3) -> bool {
+        !position.y.is_finite() || position.y < self.kill_height
+    }
+}
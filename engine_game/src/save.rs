@@ -0,0 +1,889 @@
+//! Save game format.
+//!
+//! Saves are line-oriented text: a header (`version`, `schema`, `level`) followed by
+//! `[player]`, `[script]`, `[cvars]`, `[ecs.resources]` and repeated `[ecs.entity]`
+//! sections. Floats use `Display`, which round-trips exactly, so a loaded save
+//! continues the simulation deterministically.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use character_motor_arena::ArenaMotorTimers;
+use ecs::{SaveRegistry, SavedEntity, WorldSave};
+use physics_rapier::{ClimbContact, LiquidKind, SurfaceMaterial};
+use player_controller::{Immersion, PlayerKinematics};
+use rapier3d::math::{Isometry, Vector};
+use rapier3d::na::{Quaternion, Translation3, UnitQuaternion};
+use rapier3d::prelude::{ColliderHandle, Real};
+
+use crate::liquid::InLiquid;
+use crate::mover::Mover;
+use crate::prop::PropMotion;
+use crate::session::{PendingSpawn, Player};
+use crate::trigger::TriggerOverlaps;
+
+pub const SAVE_FORMAT_VERSION: u32 = 1;
+pub const SAVE_FILE_EXTENSION: &str = "sav";
+
+const SAVE_MAGIC: &str = "pallet_save";
+
+const PLAYER_FIELDS: &[&str] = &[
+    "motor",
+    "position",
+    "rotation",
+    "velocity",
+    "grounded",
+    "ground_normal",
+    "crouched",
+    "immersion",
+    "ladder",
+    "ground_surface",
+    "yaw",
+    "pitch",
+    "arena_jump_buffer",
+    "arena_bhop_grace",
+    "arena_was_grounded",
+    "rpg_smoothed_axis",
+];
+
+#[derive(Clone, Debug)]
+pub struct SaveError {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl SaveError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            line: None,
+            message: message.into(),
+        }
+    }
+
+    fn at(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "save error")?;
+        if let Some(line) = self.line {
+            write!(f, " (line {})", line)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+#[derive(Clone, Debug)]
+pub struct PlayerSave {
+    /// Motor label as understood by the host (e.g. `arena`, `rpg`).
+    pub motor: String,
+    pub kinematics: PlayerKinematics,
+    pub yaw: Real,
+    pub pitch: Real,
+    pub arena_timers: ArenaMotorTimers,
+    pub rpg_smoothed_axis: [Real; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ScriptEntitySave {
+    pub id: u32,
+    pub position: [f32; 3],
+    pub yaw: f32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ScriptHostSave {
+    pub next_id: u32,
+    pub entities: Vec<ScriptEntitySave>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SaveGame {
+    pub version: u32,
+    pub schema_hash: u64,
+    pub level: Option<String>,
+    pub player: Option<PlayerSave>,
+    pub script: ScriptHostSave,
+    pub cvars: Vec<(String, String)>,
+    pub world: WorldSave,
+}
+
+impl SaveGame {
+    /// Empty save stamped with the current version and the schema of `registry`.
+    pub fn new(registry: &SaveRegistry) -> Self {
+        Self {
+            version: SAVE_FORMAT_VERSION,
+            schema_hash: save_schema_hash(registry),
+            level: None,
+            player: None,
+            script: ScriptHostSave::default(),
+            cvars: Vec::new(),
+            world: WorldSave::default(),
+        }
+    }
+
+    /// Fails when the save was written against a different component/resource set.
+    pub fn check_schema(&self, registry: &SaveRegistry) -> Result<(), SaveError> {
+        let expected = save_schema_hash(registry);
+        if self.schema_hash != expected {
+            return Err(SaveError::new(format!(
+                "schema mismatch (save {:016x}, expected {:016x})",
+                self.schema_hash, expected
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            format!("# {}", SAVE_MAGIC),
+            format!("version={}", self.version),
+            format!("schema={:016x}", self.schema_hash),
+        ];
+        if let Some(level) = &self.level {
+            lines.push(format!("level={}", level));
+        }
+        if let Some(player) = &self.player {
+            let kinematics = &player.kinematics;
+            let translation = kinematics.position.translation.vector;
+            let rotation = kinematics.position.rotation.quaternion();
+            lines.push(String::new());
+            lines.push("[player]".to_string());
+            lines.push(format!("motor={}", player.motor));
+            lines.push(format!(
+                "position={} {} {}",
+                translation.x, translation.y, translation.z
+            ));
+            lines.push(format!(
+                "rotation={} {} {} {}",
+                rotation.i, rotation.j, rotation.k, rotation.w
+            ));
+            lines.push(format!(
+                "velocity={} {} {}",
+                kinematics.velocity.x, kinematics.velocity.y, kinematics.velocity.z
+            ));
+            lines.push(format!("grounded={}", bool_to_bit(kinematics.grounded)));
+            if let Some(normal) = kinematics.ground_normal {
+                lines.push(format!(
+                    "ground_normal={} {} {}",
+                    normal.x, normal.y, normal.z
+                ));
+            }
+            lines.push(format!("crouched={}", bool_to_bit(kinematics.crouched)));
+            if let Some(immersion) = &kinematics.immersion {
+                lines.push(format!(
+                    "immersion={} {} {}",
+                    immersion.kind.as_str(),
+                    immersion.depth,
+                    immersion.level
+                ));
+            }
+            if let Some(ladder) = &kinematics.ladder {
+                let (index, generation) = ladder.collider.into_raw_parts();
+                lines.push(format!(
+                    "ladder={} {} {} {} {} {}",
+                    index,
+                    generation,
+                    ladder.normal.x,
+                    ladder.normal.y,
+                    ladder.normal.z,
+                    ladder.distance
+                ));
+            }
+            if let Some(surface) = &kinematics.ground_surface {
+                let mut line = format!(
+                    "ground_surface={} {} {} {}",
+                    surface.id,
+                    surface.friction,
+                    surface.acceleration,
+                    bool_to_bit(surface.slide)
+                );
+                for footstep in surface.footsteps.iter() {
+                    line.push(' ');
+                    line.push_str(footstep);
+                }
+                lines.push(line);
+            }
+            lines.push(format!("yaw={}", player.yaw));
+            lines.push(format!("pitch={}", player.pitch));
+            lines.push(format!(
+                "arena_jump_buffer={}",
+                player.arena_timers.jump_buffer_time
+            ));
+            lines.push(format!(
+                "arena_bhop_grace={}",
+                player.arena_timers.bhop_grace_time
+            ));
+            lines.push(format!(
+                "arena_was_grounded={}",
+                bool_to_bit(player.arena_timers.was_grounded)
+            ));
+            lines.push(format!(
+                "rpg_smoothed_axis={} {}",
+                player.rpg_smoothed_axis[0], player.rpg_smoothed_axis[1]
+            ));
+        }
+        lines.push(String::new());
+        lines.push("[script]".to_string());
+        lines.push(format!("next_id={}", self.script.next_id));
+        for entity in &self.script.entities {
+            lines.push(format!(
+                "entity={} {} {} {} {}",
+                entity.id, entity.position[0], entity.position[1], entity.position[2], entity.yaw
+            ));
+        }
+        if !self.cvars.is_empty() {
+            lines.push(String::new());
+            lines.push("[cvars]".to_string());
+            for (name, value) in &self.cvars {
+                lines.push(format!("{}={}", name, value));
+            }
+        }
+        if !self.world.resources.is_empty() {
+            lines.push(String::new());
+            lines.push("[ecs.resources]".to_string());
+            for (key, value) in &self.world.resources {
+                lines.push(format!("{}={}", key, value));
+            }
+        }
+        for entity in &self.world.entities {
+            lines.push(String::new());
+            lines.push("[ecs.entity]".to_string());
+            for (key, value) in &entity.components {
+                lines.push(format!("{}={}", key, value));
+            }
+        }
+        let mut text = lines.join("\n");
+        text.push('\n');
+        text
+    }
+
+    pub fn parse(text: &str) -> Result<Self, SaveError> {
+        let mut version = None;
+        let mut schema_hash = None;
+        let mut level = None;
+        let mut player = PlayerFields::default();
+        let mut saw_player = false;
+        let mut script = ScriptHostSave::default();
+        let mut cvars = Vec::new();
+        let mut world = WorldSave::default();
+        let mut section = Section::Header;
+
+        for (index, raw) in text.lines().enumerate() {
+            let line_no = index + 1;
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                section = match name {
+                    "player" => {
+                        saw_player = true;
+                        Section::Player
+                    }
+                    "script" => Section::Script,
+                    "cvars" => Section::Cvars,
+                    "ecs.resources" => Section::EcsResources,
+                    "ecs.entity" => {
+                        world.entities.push(SavedEntity::default());
+                        Section::EcsEntity
+                    }
+                    _ => {
+                        return Err(SaveError::at(
+                            line_no,
+                            format!("unknown section [{}]", name),
+                        ))
+                    }
+                };
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or_else(|| SaveError::at(line_no, "expected key=value"))?;
+            match section {
+                Section::Header => match key {
+                    "version" => {
+                        let parsed = value
+                            .parse::<u32>()
+                            .map_err(|_| SaveError::at(line_no, "invalid version"))?;
+                        if parsed != SAVE_FORMAT_VERSION {
+                            return Err(SaveError::at(
+                                line_no,
+                                format!(
+                                    "unsupported save version {} (expected {})",
+                                    parsed, SAVE_FORMAT_VERSION
+                                ),
+                            ));
+                        }
+                        version = Some(parsed);
+                    }
+                    "schema" => {
+                        schema_hash = Some(
+                            u64::from_str_radix(value, 16)
+                                .map_err(|_| SaveError::at(line_no, "invalid schema hash"))?,
+                        );
+                    }
+                    "level" => level = Some(value.to_string()),
+                    _ => return Err(SaveError::at(line_no, format!("unknown field '{}'", key))),
+                },
+                Section::Player => player
+                    .apply(key, value)
+                    .map_err(|message| SaveError::at(line_no, message))?,
+                Section::Script => match key {
+                    "next_id" => {
+                        script.next_id = value
+                            .parse::<u32>()
+                            .map_err(|_| SaveError::at(line_no, "invalid next_id"))?;
+                    }
+                    "entity" => script.entities.push(
+                        parse_script_entity(value)
+                            .map_err(|message| SaveError::at(line_no, message))?,
+                    ),
+                    _ => return Err(SaveError::at(line_no, format!("unknown field '{}'", key))),
+                },
+                Section::Cvars => cvars.push((key.to_string(), value.to_string())),
+                Section::EcsResources => world.resources.push((key.to_string(), value.to_string())),
+                Section::EcsEntity => {
+                    if let Some(entity) = world.entities.last_mut() {
+                        entity.components.push((key.to_string(), value.to_string()));
+                    }
+                }
+            }
+        }
+
+        let version = version.ok_or_else(|| SaveError::new("missing version"))?;
+        let schema_hash = schema_hash.ok_or_else(|| SaveError::new("missing schema"))?;
+        let player = if saw_player {
+            Some(player.finish().map_err(SaveError::new)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            version,
+            schema_hash,
+            level,
+            player,
+            script,
+            cvars,
+            world,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Section {
+    Header,
+    Player,
+    Script,
+    Cvars,
+    EcsResources,
+    EcsEntity,
+}
+
+#[derive(Default)]
+struct PlayerFields {
+    motor: Option<String>,
+    position: Option<[Real; 3]>,
+    rotation: Option<[Real; 4]>,
+    velocity: Option<[Real; 3]>,
+    grounded: bool,
+    ground_normal: Option<[Real; 3]>,
+    crouched: bool,
+    immersion: Option<Immersion>,
+    ladder: Option<ClimbContact>,
+    ground_surface: Option<SurfaceMaterial>,
+    yaw: Real,
+    pitch: Real,
+    arena_timers: ArenaMotorTimers,
+    rpg_smoothed_axis: [Real; 2],
+}
+
+impl PlayerFields {
+    fn apply(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "motor" => self.motor = Some(value.to_string()),
+            "position" => self.position = Some(parse_floats(value)?),
+            "rotation" => self.rotation = Some(parse_floats(value)?),
+            "velocity" => self.velocity = Some(parse_floats(value)?),
+            "grounded" => self.grounded = parse_bit(value)?,
+            "ground_normal" => self.ground_normal = Some(parse_floats(value)?),
+            "crouched" => self.crouched = parse_bit(value)?,
+            "immersion" => self.immersion = Some(parse_immersion(value)?),
+            "ladder" => self.ladder = Some(parse_ladder(value)?),
+            "ground_surface" => self.ground_surface = Some(parse_surface(value)?),
+            "yaw" => self.yaw = parse_float(value)?,
+            "pitch" => self.pitch = parse_float(value)?,
+            "arena_jump_buffer" => self.arena_timers.jump_buffer_time = parse_float(value)?,
+            "arena_bhop_grace" => self.arena_timers.bhop_grace_time = parse_float(value)?,
+            "arena_was_grounded" => self.arena_timers.was_grounded = parse_bit(value)?,
+            "rpg_smoothed_axis" => self.rpg_smoothed_axis = parse_floats(value)?,
+            _ => return Err(format!("unknown player field '{}'", key)),
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<PlayerSave, String> {
+        let motor = self.motor.ok_or("player section missing motor")?;
+        let [x, y, z] = self.position.ok_or("player section missing position")?;
+        let [i, j, k, w] = self.rotation.unwrap_or([0.0, 0.0, 0.0, 1.0]);
+        let [vx, vy, vz] = self.velocity.unwrap_or_default();
+        let position = Isometry::from_parts(
+            Translation3::new(x, y, z),
+            UnitQuaternion::new_unchecked(Quaternion::new(w, i, j, k)),
+        );
+        let kinematics = PlayerKinematics {
+            position,
+            velocity: Vector::new(vx, vy, vz),
+            grounded: self.grounded,
+            ground_normal: self
+                .ground_normal
+                .map(|[nx, ny, nz]| Vector::new(nx, ny, nz)),
+            crouched: self.crouched,
+            immersion: self.immersion,
+            ladder: self.ladder,
+            // The controller probes for a ledge before the motor reads it,
+            // so the first tick after loading finds it again.
+            ledge: None,
+            ground_surface: self.ground_surface,
+        };
+        Ok(PlayerSave {
+            motor,
+            kinematics,
+            yaw: self.yaw,
+            pitch: self.pitch,
+            arena_timers: self.arena_timers,
+            rpg_smoothed_axis: self.rpg_smoothed_axis,
+        })
+    }
+}
+
+/// Registry for a [`crate::GameSession`] world: the core ECS state plus
+/// players, props, movers, liquid and trigger state.
+pub fn game_save_registry() -> SaveRegistry {
+    let mut registry = SaveRegistry::with_core_state();
+    registry
+        .register_component::<Player>()
+        .register_component::<PendingSpawn>()
+        .register_component::<InLiquid>()
+        .register_component::<PropMotion>()
+        .register_component::<Mover>()
+        .register_resource::<TriggerOverlaps>();
+    registry
+}
+
+/// Hash of the save layout: format version, player fields and the registered ECS keys.
+pub fn save_schema_hash(registry: &SaveRegistry) -> u64 {
+    let mut schema = format!("{}:{}", SAVE_MAGIC, SAVE_FORMAT_VERSION);
+    for field in PLAYER_FIELDS {
+        schema.push_str(";player.");
+        schema.push_str(field);
+    }
+    for key in registry.schema_keys() {
+        schema.push_str(";ecs.");
+        schema.push_str(&key);
+    }
+    fnv1a64(schema.as_bytes())
+}
+
+/// Normalizes a console-supplied save name into a file name (`<name>.sav`).
+pub fn save_file_name(input: &str) -> Result<String, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Err("save name is empty".to_string());
+    }
+    if trimmed.contains('/') || trimmed.contains('\\') || trimmed.contains(':') {
+        return Err("save name must be a file name (no path separators)".to_string());
+    }
+    let suffix = format!(".{}", SAVE_FILE_EXTENSION);
+    if trimmed.to_ascii_lowercase().ends_with(&suffix) {
+        Ok(trimmed.to_string())
+    } else {
+        Ok(format!("{}{}", trimmed, suffix))
+    }
+}
+
+pub fn save_path(saves_dir: &Path, name: &str) -> Result<PathBuf, String> {
+    Ok(saves_dir.join(save_file_name(name)?))
+}
+
+pub fn write_save_file(path: &Path, save: &SaveGame) -> Result<(), SaveError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| SaveError::new(format!("create {}: {}", parent.display(), err)))?;
+    }
+    std::fs::write(path, save.to_text())
+        .map_err(|err| SaveError::new(format!("write {}: {}", path.display(), err)))
+}
+
+pub fn read_save_file(path: &Path) -> Result<SaveGame, SaveError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| SaveError::new(format!("read {}: {}", path.display(), err)))?;
+    SaveGame::parse(&text)
+}
+
+fn parse_script_entity(value: &str) -> Result<ScriptEntitySave, String> {
+    let mut parts = value.split_whitespace();
+    let id = parts
+        .next()
+        .and_then(|part| part.parse::<u32>().ok())
+        .ok_or_else(|| format!("invalid script entity '{}'", value))?;
+    let rest: Vec<&str> = parts.collect();
+    let [x, y, z, yaw]: [f32; 4] = parse_floats(&rest.join(" "))?;
+    Ok(ScriptEntitySave {
+        id,
+        position: [x, y, z],
+        yaw,
+    })
+}
+
+/// `<kind> <depth> <level>`.
+fn parse_immersion(value: &str) -> Result<Immersion, String> {
+    let (kind, rest) = value
+        .split_once(' ')
+        .ok_or_else(|| format!("invalid immersion '{}'", value))?;
+    let kind = LiquidKind::from_tag(kind).ok_or_else(|| format!("unknown liquid '{}'", kind))?;
+    let [depth, level] = parse_floats(rest)?;
+    Ok(Immersion { kind, depth, level })
+}
+
+/// `<collider index> <collider generation> <normal> <distance>`. The collider
+/// handle is only valid on a physics world rebuilt from the same level.
+fn parse_ladder(value: &str) -> Result<ClimbContact, String> {
+    let mut parts = value.splitn(3, ' ');
+    let mut handle_part = || {
+        parts
+            .next()
+            .and_then(|part| part.parse::<u32>().ok())
+            .ok_or_else(|| format!("invalid ladder collider in '{}'", value))
+    };
+    let index = handle_part()?;
+    let generation = handle_part()?;
+    let [nx, ny, nz, distance] = parse_floats(parts.next().unwrap_or_default())?;
+    Ok(ClimbContact {
+        collider: ColliderHandle::from_raw_parts(index, generation),
+        normal: Vector::new(nx, ny, nz),
+        distance,
+    })
+}
+
+/// `<id> <friction> <acceleration> <slide> <footstep>...`.
+fn parse_surface(value: &str) -> Result<SurfaceMaterial, String> {
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [id, friction, acceleration, slide, footsteps @ ..] = parts.as_slice() else {
+        return Err(format!("invalid ground surface '{}'", value));
+    };
+    Ok(SurfaceMaterial {
+        id: id
+            .parse::<u16>()
+            .map_err(|_| format!("invalid surface id '{}'", id))?,
+        friction: parse_float(friction)?,
+        acceleration: parse_float(acceleration)?,
+        slide: parse_bit(slide)?,
+        footsteps: footsteps
+            .iter()
+            .map(|footstep| footstep.to_string())
+            .collect(),
+    })
+}
+
+fn parse_float(value: &str) -> Result<Real, String> {
+    value
+        .parse::<Real>()
+        .map_err(|_| format!("invalid float '{}'", value))
+}
+
+pub(crate) fn parse_floats<const N: usize>(value: &str) -> Result<[Real; N], String> {
+    let parts = value
+        .split_whitespace()
+        .map(parse_float)
+        .collect::<Result<Vec<_>, _>>()?;
+    parts
+        .try_into()
+        .map_err(|_| format!("expected {} floats, got '{}'", N, value))
+}
+
+fn parse_bit(value: &str) -> Result<bool, String> {
+    match value {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(format!("expected 0 or 1, got '{}'", value)),
+    }
+}
+
+fn bool_to_bit(value: bool) -> u8 {
+    u8::from(value)
+}
+
+fn fnv1a64(data: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in data {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use character_collision::CollisionProfile;
+    use character_motor_arena::{ArenaMotor, ArenaMotorConfig};
+    use ecs::{EcsSchedules, InputCommand, InputStream, PlayerTag, Transform, Velocity};
+    use physics_rapier::PhysicsWorld;
+    use player_camera::PlayerCamera;
+    use player_controller::{DirectInputAdapter, PlayerController, RawInput};
+    use rapier3d::prelude::ColliderBuilder;
+
+    struct Session {
+        physics: PhysicsWorld,
+        controller: PlayerController<DirectInputAdapter, ArenaMotor>,
+        world: bevy_ecs::world::World,
+        schedules: EcsSchedules,
+    }
+
+    const DT: f32 = 1.0 / 60.0;
+
+    fn new_session() -> Session {
+        let mut physics = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        physics.insert_static_collider(
+            ColliderBuilder::cuboid(30.0, 0.1, 30.0)
+                .translation(Vector::new(0.0, -0.1, 0.0))
+                .build(),
+        );
+        physics.step(DT);
+        let controller = PlayerController::new(
+            DirectInputAdapter,
+            ArenaMotor::new(ArenaMotorConfig::default()),
+            CollisionProfile::arena_default(),
+            PlayerCamera::new(1.6),
+            Isometry::translation(0.0, 1.0, 0.0),
+        );
+        let mut world = ecs::new_world();
+        let inputs = (0..200)
+            .map(|index| InputCommand {
+                move_axis: ecs::Vec3 {
+                    x: (index as f32 * 0.1).cos(),
+                    y: 0.0,
+                    z: (index as f32 * 0.1).sin(),
+                },
+            })
+            .collect();
+        world.insert_resource(InputStream::new(inputs));
+        Session {
+            physics,
+            controller,
+            world,
+            schedules: EcsSchedules::new(),
+        }
+    }
+
+    fn raw_input(tick: u32) -> RawInput {
+        RawInput {
+            move_x: if tick % 40 < 20 { 1.0 } else { -0.5 },
+            move_y: 1.0,
+            jump: tick.is_multiple_of(23),
+            crouch: false,
+            look_delta: [0.01, 0.0],
+        }
+    }
+
+    fn step(session: &mut Session, tick: u32) {
+        session.physics.step(DT);
+        session
+            .controller
+            .tick(&session.physics, raw_input(tick), DT);
+        session.schedules.run_fixed(&mut session.world);
+    }
+
+    fn capture(session: &mut Session, registry: &SaveRegistry) -> SaveGame {
+        let mut save = SaveGame::new(registry);
+        save.level = Some("engine:test_map/test_maps/flat.toml".to_string());
+        save.player = Some(PlayerSave {
+            motor: "arena".to_string(),
+            kinematics: session.controller.state().clone(),
+            yaw: session.controller.camera().yaw(),
+            pitch: session.controller.camera().pitch(),
+            arena_timers: session.controller.motor().timers(),
+            rpg_smoothed_axis: [0.0, 0.0],
+        });
+        save.script = ScriptHostSave {
+            next_id: 3,
+            entities: vec![ScriptEntitySave {
+                id: 2,
+                position: [1.5, 0.25, -3.0],
+                yaw: 0.7,
+            }],
+        };
+        save.cvars = vec![("arena_air_accel".to_string(), "18".to_string())];
+        save.world = registry.capture(&mut session.world);
+        save
+    }
+
+    fn restore(save: &SaveGame, registry: &SaveRegistry) -> Session {
+        save.check_schema(registry).expect("schema");
+        let mut session = new_session();
+        session.world = ecs::new_world();
+        registry
+            .restore(&mut session.world, &save.world)
+            .expect("ecs restore");
+        let player = save.player.as_ref().expect("player");
+        *session.controller.state_mut() = player.kinematics.clone();
+        if player.kinematics.crouched {
+            session.controller.collision_mut().crouch();
+        }
+        session
+            .controller
+            .camera_mut()
+            .set_look(player.yaw, player.pitch);
+        session
+            .controller
+            .motor_mut()
+            .set_timers(player.arena_timers);
+        session
+    }
+
+    #[test]
+    fn save_round_trip_continues_deterministically() {
+        let registry = SaveRegistry::with_core_state();
+        let mut original = new_session();
+        original
+            .world
+            .spawn((Transform::default(), Velocity::default(), PlayerTag));
+        for tick in 0..90 {
+            step(&mut original, tick);
+        }
+
+        let text = capture(&mut original, &registry).to_text();
+        let loaded = SaveGame::parse(&text).expect("parse");
+        assert_eq!(loaded.to_text(), text);
+        assert_eq!(loaded.script.entities.len(), 1);
+        assert_eq!(loaded.cvars[0].0, "arena_air_accel");
+        let mut resumed = restore(&loaded, &registry);
+
+        for tick in 90..180 {
+            step(&mut original, tick);
+            step(&mut resumed, tick);
+        }
+        let a = original.controller.state();
+        let b = resumed.controller.state();
+        assert_eq!(a.position, b.position);
+        assert_eq!(a.velocity, b.velocity);
+        assert_eq!(a.grounded, b.grounded);
+        assert_eq!(
+            original.controller.motor().timers(),
+            resumed.controller.motor().timers()
+        );
+        assert_eq!(
+            registry.capture(&mut original.world),
+            registry.capture(&mut resumed.world)
+        );
+    }
+
+    #[test]
+    fn parse_rejects_version_and_schema_mismatch() {
+        let registry = SaveRegistry::with_core_state();
+        let err = SaveGame::parse("version=99\nschema=0\n").unwrap_err();
+        assert_eq!(err.line, Some(1));
+
+        let mut save = SaveGame::new(&registry);
+        save.schema_hash ^= 1;
+        let loaded = SaveGame::parse(&save.to_text()).expect("parse");
+        assert!(loaded.check_schema(&registry).is_err());
+        assert!(loaded.check_schema(&SaveRegistry::new()).is_err());
+    }
+
+    #[test]
+    fn player_contact_state_round_trips() {
+        let registry = SaveRegistry::with_core_state();
+        let mut session = new_session();
+        let mut save = capture(&mut session, &registry);
+        let kinematics = &mut save.player.as_mut().expect("player").kinematics;
+        kinematics.immersion = Some(Immersion {
+            kind: LiquidKind::Slime,
+            depth: 0.75,
+            level: 0.4,
+        });
+        kinematics.ladder = Some(ClimbContact {
+            collider: ColliderHandle::from_raw_parts(7, 2),
+            normal: Vector::new(0.0, 0.0, 1.0),
+            distance: 0.125,
+        });
+        kinematics.ground_surface = Some(SurfaceMaterial {
+            id: 3,
+            friction: 0.2,
+            acceleration: 0.5,
+            slide: true,
+            footsteps: vec!["sounds/ice1.wav".to_string(), "sounds/ice2.wav".to_string()].into(),
+        });
+        let expected = kinematics.clone();
+
+        let loaded = SaveGame::parse(&save.to_text()).expect("parse");
+        let restored = &loaded.player.expect("player").kinematics;
+        assert_eq!(restored.immersion, expected.immersion);
+        assert_eq!(restored.ladder, expected.ladder);
+        assert_eq!(restored.ground_surface, expected.ground_surface);
+    }
+
+    #[test]
+    fn crouched_save_reloads_crouched_under_a_low_ceiling() {
+        let registry = SaveRegistry::with_core_state();
+        let mut original = new_session();
+        for _ in 0..60 {
+            original.physics.step(DT);
+            original.controller.tick(
+                &original.physics,
+                RawInput {
+                    crouch: true,
+                    ..raw_input(1)
+                },
+                DT,
+            );
+        }
+        assert!(original.controller.state().crouched);
+
+        let loaded = SaveGame::parse(&capture(&mut original, &registry).to_text()).expect("parse");
+        assert!(loaded.player.as_ref().expect("player").kinematics.crouched);
+        let mut resumed = restore(&loaded, &registry);
+        let position = resumed.controller.state().position;
+        let feet = position.translation.y - resumed.controller.collision().foot_offset();
+        resumed.physics.insert_static_collider(
+            ColliderBuilder::cuboid(30.0, 0.1, 30.0)
+                .translation(Vector::new(0.0, feet + 1.9, 0.0))
+                .build(),
+        );
+        resumed.physics.step(DT);
+        assert!(resumed.controller.collision().is_crouched());
+        assert!(!resumed
+            .controller
+            .collision()
+            .can_stand(&resumed.physics, position));
+
+        // Releasing crouch under the ceiling keeps the player crouched.
+        for _ in 0..30 {
+            resumed.physics.step(DT);
+            resumed.controller.tick(
+                &resumed.physics,
+                RawInput {
+                    crouch: false,
+                    move_x: 0.0,
+                    move_y: 0.0,
+                    ..raw_input(1)
+                },
+                DT,
+            );
+        }
+        assert!(resumed.controller.state().crouched);
+        assert!(resumed.controller.collision().is_crouched());
+    }
+}
@@ -0,0 +1,920 @@
+//! ECS-side game session: players, spawning, respawn, map transitions,
+//! trigger events, liquid damage and movers.
+//!
+//! Hosts that simulate movement themselves (pallet's controller, the server
+//! sim) mirror player positions into `Transform` each tick and apply the
+//! returned [`PlayerSpawned`] events back onto their own state.
+
+use bevy_ecs::prelude::*;
+use bevy_ecs::schedule::{Schedule, ScheduleLabel};
+use ecs::{
+    new_world, EcsSchedules, FixedTimeStep, SaveRegistry, SaveState, Transform, Vec3, Velocity,
+    WorldSave,
+};
+use map_cook::{build_marker_sensor, MapSidecar};
+use physics_rapier::{ColliderSource, DynamicBodyDesc, LiquidKind, PhysicsEvent, PhysicsWorld};
+use rapier3d::prelude::{ColliderHandle, Isometry, Real, Shape, Vector};
+
+use crate::liquid::{emit_liquid_damage, InLiquid, LiquidDamageEvent, LiquidDamageEvents};
+use crate::map_entities::BrushModel;
+use crate::mover::{activate_triggered_movers, advance_movers, mover_pose, Mover, MoverBody};
+use crate::prop::{Prop, PropMotion};
+use crate::rules::GameRules;
+use crate::spawn::SpawnPoint;
+use crate::trigger::{
+    apply_physics_events, emit_trigger_events, ColliderEntities, TriggerEvent, TriggerEvents,
+    TriggerOverlaps, TriggerVolume,
+};
+
+#[derive(ScheduleLabel, Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct GameRulesUpdate;
+
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Player {
+    pub slot: u32,
+    /// Spawns on the current map, reset by map transitions.
+    pub spawn_count: u32,
+}
+
+/// Marks a player waiting for a spawn point; cleared once placed.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PendingSpawn {
+    pub respawn: bool,
+}
+
+/// Encoded as `<slot> <spawn_count>`.
+impl SaveState for Player {
+    const SAVE_KEY: &'static str = "player";
+
+    fn encode(&self) -> String {
+        format!("{} {}", self.slot, self.spawn_count)
+    }
+
+    fn decode(value: &str) -> Result<Self, String> {
+        let parsed = value
+            .split_whitespace()
+            .map(|part| part.parse::<u32>())
+            .collect::<Result<Vec<_>, _>>();
+        match parsed.as_deref() {
+            Ok([slot, spawn_count]) => Ok(Self {
+                slot: *slot,
+                spawn_count: *spawn_count,
+            }),
+            _ => Err(format!("invalid player '{}'", value)),
+        }
+    }
+}
+
+impl SaveState for PendingSpawn {
+    const SAVE_KEY: &'static str = "pending_spawn";
+
+    fn encode(&self) -> String {
+        u8::from(self.respawn).to_string()
+    }
+
+    fn decode(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "0" => Ok(Self { respawn: false }),
+            "1" => Ok(Self { respawn: true }),
+            _ => Err(format!("expected 0 or 1, got '{}'", value)),
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct ActiveRules(pub Box<dyn GameRules>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MapTransition {
+    pub map: String,
+    pub spawns: Vec<SpawnPoint>,
+}
+
+#[derive(Resource, Clone, Debug, Default)]
+pub struct MapState {
+    pub current: Option<String>,
+    pub spawns: Vec<SpawnPoint>,
+    pub pending: Option<MapTransition>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlayerSpawned {
+    pub entity: Entity,
+    pub slot: u32,
+    pub point: SpawnPoint,
+    pub respawn: bool,
+}
+
+/// Spawns placed since the host last drained them.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct SpawnEvents(pub Vec<PlayerSpawned>);
+
+/// Inserts the session resources into an existing world.
+pub fn init_game_world(world: &mut World, rules: Box<dyn GameRules>) {
+    world.insert_resource(ActiveRules(rules));
+    world.insert_resource(MapState::default());
+    world.insert_resource(SpawnEvents::default());
+    world.insert_resource(ColliderEntities::default());
+    world.insert_resource(TriggerOverlaps::default());
+    world.insert_resource(TriggerEvents::default());
+    world.insert_resource(LiquidDamageEvents::default());
+}
+
+/// Map transition, respawn checks, spawn placement, trigger events, liquid
+/// damage and movers, in that order.
+pub fn add_game_rules_systems(schedule: &mut Schedule) {
+    schedule.add_systems(
+        (
+            apply_map_transition,
+            respawn_out_of_bounds,
+            spawn_pending_players,
+            emit_trigger_events,
+            emit_liquid_damage,
+            activate_triggered_movers,
+            advance_movers,
+        )
+            .chain(),
+    );
+}
+
+fn apply_map_transition(
+    mut commands: Commands,
+    mut map_state: ResMut<MapState>,
+    mut rules: ResMut<ActiveRules>,
+    mut players: Query<(Entity, &mut Player)>,
+) {
+    let Some(transition) = map_state.pending.take() else {
+        return;
+    };
+    rules.0.on_map_enter(&transition.map);
+    map_state.current = Some(transition.map);
+    map_state.spawns = transition.spawns;
+    for (entity, mut player) in &mut players {
+        player.spawn_count = 0;
+        commands
+            .entity(entity)
+            .insert(PendingSpawn { respawn: false });
+    }
+}
+
+type LivePlayers<'w, 's> =
+    Query<'w, 's, (Entity, &'static Transform), (With<Player>, Without<PendingSpawn>)>;
+
+fn respawn_out_of_bounds(mut commands: Commands, rules: Res<ActiveRules>, players: LivePlayers) {
+    for (entity, transform) in &players {
+        if rules.0.needs_respawn(transform.position) {
+            commands
+                .entity(entity)
+                .insert(PendingSpawn { respawn: true });
+        }
+    }
+}
+
+fn spawn_pending_players(
+    mut commands: Commands,
+    map_state: Res<MapState>,
+    mut rules: ResMut<ActiveRules>,
+    mut events: ResMut<SpawnEvents>,
+    mut players: Query<(
+        Entity,
+        &mut Player,
+        &mut Transform,
+        Option<&mut Velocity>,
+        &PendingSpawn,
+    )>,
+) {
+    if map_state.current.is_none() {
+        return;
+    }
+    for (entity, mut player, mut transform, velocity, pending) in &mut players {
+        let Some(point) = rules
+            .0
+            .select_spawn(&map_state.spawns, player.slot, player.spawn_count)
+        else {
+            continue;
+        };
+        transform.position = Vec3 {
+            x: point.origin[0],
+            y: point.origin[1],
+            z: point.origin[2],
+        };
+        if let Some(mut velocity) = velocity {
+            velocity.linear = Vec3::ZERO;
+        }
+        player.spawn_count = player.spawn_count.saturating_add(1);
+        events.0.push(PlayerSpawned {
+            entity,
+            slot: player.slot,
+            point: point.clone(),
+            respawn: pending.respawn,
+        });
+        commands.entity(entity).remove::<PendingSpawn>();
+    }
+}
+
+/// Owns an ECS world running the core fixed schedule followed by the game
+/// rules systems.
+pub struct GameSession {
+    world: World,
+    schedules: EcsSchedules,
+    rules_schedule: Schedule,
+}
+
+impl GameSession {
+    pub fn new(rules: impl GameRules) -> Self {
+        let mut world = new_world();
+        init_game_world(&mut world, Box::new(rules));
+        let mut rules_schedule = Schedule::new(GameRulesUpdate);
+        add_game_rules_systems(&mut rules_schedule);
+        Self {
+            world,
+            schedules: EcsSchedules::new(),
+            rules_schedule,
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    pub fn rules_name(&self) -> &str {
+        self.world.resource::<ActiveRules>().0.name()
+    }
+
+    pub fn current_map(&self) -> Option<&str> {
+        self.world.resource::<MapState>().current.as_deref()
+    }
+
+    /// Queues a transition; every player respawns on the new map next tick.
+    pub fn enter_map(&mut self, map: impl Into<String>, spawns: Vec<SpawnPoint>) {
+        self.world.resource_mut::<MapState>().pending = Some(MapTransition {
+            map: map.into(),
+            spawns,
+        });
+    }
+
+    /// Adds a player that spawns on the next tick with a loaded map.
+    pub fn add_player(&mut self, slot: u32) -> Entity {
+        self.world
+            .spawn((
+                Player {
+                    slot,
+                    spawn_count: 0,
+                },
+                PendingSpawn { respawn: false },
+                Transform::default(),
+            ))
+            .id()
+    }
+
+    pub fn remove_player(&mut self, entity: Entity) -> bool {
+        self.world.resource_mut::<TriggerOverlaps>().forget(entity);
+        self.world.despawn(entity)
+    }
+
+    /// Sends a live player back to a spawn on the next tick, e.g. after being
+    /// crushed by a mover.
+    pub fn request_respawn(&mut self, entity: Entity) {
+        if self.world.get::<Player>(entity).is_some() {
+            self.world
+                .entity_mut(entity)
+                .insert(PendingSpawn { respawn: true });
+        }
+    }
+
+    /// Spawns a named trigger owning the sensor `collider`.
+    pub fn spawn_trigger(&mut self, name: impl Into<String>, collider: ColliderHandle) -> Entity {
+        let entity = self.world.spawn(TriggerVolume { name: name.into() }).id();
+        self.link_collider(collider, entity);
+        entity
+    }
+
+    /// Turns every sidecar marker with a `volume` into a sensor trigger named
+    /// after the marker.
+    pub fn spawn_sidecar_triggers(
+        &mut self,
+        physics: &mut PhysicsWorld,
+        sidecar: &MapSidecar,
+    ) -> Vec<Entity> {
+        sidecar
+            .markers
+            .iter()
+            .filter_map(|marker| {
+                let sensor = physics.insert_sensor_collider(build_marker_sensor(marker)?);
+                Some(self.spawn_trigger(marker.id.clone(), sensor))
+            })
+            .collect()
+    }
+
+    /// Records `entity` as the owner of `collider` for physics events.
+    pub fn link_collider(&mut self, collider: ColliderHandle, entity: Entity) {
+        self.world
+            .resource_mut::<ColliderEntities>()
+            .0
+            .insert(collider, entity);
+    }
+
+    /// Feeds drained `PhysicsWorld` events into the trigger overlaps.
+    pub fn apply_physics_events(&mut self, events: &[PhysicsEvent]) {
+        apply_physics_events(&mut self.world, events);
+    }
+
+    /// Polls the sensors overlapping `entity`'s shape at `position`; used for
+    /// kinematic characters, which raise no physics events.
+    pub fn sense_triggers(
+        &mut self,
+        physics: &PhysicsWorld,
+        entity: Entity,
+        shape: &dyn Shape,
+        position: &Isometry<Real>,
+    ) {
+        let owners = &self.world.resource::<ColliderEntities>().0;
+        let triggers: Vec<Entity> = physics
+            .sensors_intersecting(shape, position)
+            .iter()
+            .filter_map(|handle| owners.get(handle).copied())
+            .filter(|trigger| *trigger != entity)
+            .collect();
+        self.world
+            .resource_mut::<TriggerOverlaps>()
+            .set_overlaps(entity, triggers);
+    }
+
+    /// Records the liquid `entity` stands in and its immersion level, or
+    /// clears it. The damage timer restarts when the liquid kind changes.
+    pub fn set_player_liquid(&mut self, entity: Entity, liquid: Option<(LiquidKind, f32)>) {
+        let Some(mut player) = self.world.get_entity_mut(entity) else {
+            return;
+        };
+        let Some((kind, level)) = liquid else {
+            player.remove::<InLiquid>();
+            return;
+        };
+        match player.get_mut::<InLiquid>() {
+            Some(mut contact) if contact.kind == kind => contact.level = level,
+            _ => {
+                player.insert(InLiquid {
+                    kind,
+                    level,
+                    next_damage: 0.0,
+                });
+            }
+        }
+    }
+
+    /// Liquid damage raised by earlier ticks, oldest first.
+    pub fn drain_liquid_damage_events(&mut self) -> Vec<LiquidDamageEvent> {
+        std::mem::take(&mut self.world.resource_mut::<LiquidDamageEvents>().0)
+    }
+
+    /// Spawns a mover driving the kinematic `body`.
+    pub fn spawn_mover(&mut self, mover: Mover, body: MoverBody) -> Entity {
+        self.world.spawn((mover, body)).id()
+    }
+
+    /// Sends every mover's pose to physics; call after `tick`, before the
+    /// next physics step.
+    pub fn apply_mover_targets(&mut self, physics: &mut PhysicsWorld) {
+        let mut movers = self.world.query::<(&Mover, &MoverBody)>();
+        for (mover, body) in movers.iter(&self.world) {
+            physics.set_kinematic_position(body.body, mover_pose(mover, body));
+        }
+    }
+
+    /// Current offset of every mover that drives an inline brush model,
+    /// keyed by model index, in world units. Hosts use it to move the
+    /// model's render geometry and clip hull.
+    pub fn brush_model_offsets(&mut self) -> Vec<(u32, [f32; 3])> {
+        let mut movers = self.world.query::<(&Mover, &BrushModel)>();
+        let mut offsets: Vec<_> = movers
+            .iter(&self.world)
+            .map(|(mover, model)| (model.0, mover.offset()))
+            .collect();
+        offsets.sort_by_key(|(model, _)| *model);
+        offsets
+    }
+
+    /// Adds a dynamic body to `physics` and an entity tracking it; `None` if
+    /// the shape cannot be built.
+    pub fn spawn_prop(
+        &mut self,
+        physics: &mut PhysicsWorld,
+        name: impl Into<String>,
+        desc: &DynamicBodyDesc,
+    ) -> Option<Entity> {
+        let (body, collider) = physics.insert_dynamic_body(desc)?;
+        let name = name.into();
+        physics.set_collider_source(collider, ColliderSource::Prop { id: name.clone() });
+        let origin = desc.position.translation;
+        let entity = self
+            .world
+            .spawn((
+                Prop { name, body },
+                PropMotion {
+                    position: desc.position,
+                    linvel: Vector::zeros(),
+                    angvel: Vector::zeros(),
+                },
+                Transform {
+                    position: Vec3 {
+                        x: origin.x,
+                        y: origin.y,
+                        z: origin.z,
+                    },
+                },
+            ))
+            .id();
+        self.link_collider(collider, entity);
+        Some(entity)
+    }
+
+    /// Copies prop body poses and velocities into their transforms and
+    /// [`PropMotion`]; call after each physics step.
+    pub fn sync_props(&mut self, physics: &PhysicsWorld) {
+        let mut props = self
+            .world
+            .query::<(&Prop, &mut Transform, &mut PropMotion)>();
+        for (prop, mut transform, mut motion) in props.iter_mut(&mut self.world) {
+            if let Some(body) = physics.bodies().get(prop.body) {
+                let origin = body.translation();
+                transform.position = Vec3 {
+                    x: origin.x,
+                    y: origin.y,
+                    z: origin.z,
+                };
+                *motion = PropMotion {
+                    position: *body.position(),
+                    linvel: *body.linvel(),
+                    angvel: *body.angvel(),
+                };
+            }
+        }
+    }
+
+    /// Savable components and resources of the session world.
+    pub fn capture_world(&mut self, registry: &SaveRegistry) -> WorldSave {
+        registry.capture(&mut self.world)
+    }
+
+    /// Restores a capture taken on the same level, then puts prop bodies back
+    /// at their saved pose and velocity and mover bodies at their restored
+    /// offsets, so the simulation picks up where the save left it.
+    pub fn restore_world(
+        &mut self,
+        physics: &mut PhysicsWorld,
+        registry: &SaveRegistry,
+        save: &WorldSave,
+    ) -> Result<(), String> {
+        registry.restore_onto(&mut self.world, save)?;
+        let mut props = self.world.query::<(&Prop, &PropMotion)>();
+        for (prop, motion) in props.iter(&self.world) {
+            physics.set_body_state(prop.body, motion.position, motion.linvel, motion.angvel);
+        }
+        let mut movers = self.world.query::<(&Mover, &MoverBody)>();
+        for (mover, body) in movers.iter(&self.world) {
+            physics.set_body_state(
+                body.body,
+                mover_pose(mover, body),
+                Vector::zeros(),
+                Vector::zeros(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Seconds simulated per `tick`; defaults to 1/60.
+    pub fn set_fixed_dt(&mut self, dt: f32) {
+        self.world.resource_mut::<FixedTimeStep>().dt_seconds = dt;
+    }
+
+    pub fn fixed_dt(&self) -> f32 {
+        self.world.resource::<FixedTimeStep>().dt_seconds
+    }
+
+    /// Trigger events raised by earlier ticks, oldest first.
+    pub fn drain_trigger_events(&mut self) -> Vec<TriggerEvent> {
+        std::mem::take(&mut self.world.resource_mut::<TriggerEvents>().0)
+    }
+
+    pub fn player_position(&self, entity: Entity) -> Option<Vec3> {
+        self.world
+            .get::<Transform>(entity)
+            .map(|transform| transform.position)
+    }
+
+    /// How scripts see `entity`: `player:<slot>`, `prop:<index>` (the id hosts
+    /// print for props) or `entity:<index>`.
+    pub fn script_name(&self, entity: Entity) -> String {
+        if let Some(player) = self.world.get::<Player>(entity) {
+            format!("player:{}", player.slot)
+        } else if self.world.get::<Prop>(entity).is_some() {
+            format!("prop:{}", entity.index())
+        } else {
+            format!("entity:{}", entity.index())
+        }
+    }
+
+    /// The positioned entity with `index`, as hosts print entities.
+    pub fn entity_by_index(&self, index: u32) -> Option<Entity> {
+        self.world
+            .iter_entities()
+            .filter(|entity| entity.contains::<Transform>())
+            .map(|entity| entity.id())
+            .find(|entity| entity.index() == index)
+    }
+
+    /// Mirrors a host-simulated position into the session.
+    pub fn set_player_position(&mut self, entity: Entity, position: Vec3) {
+        if let Some(mut transform) = self.world.get_mut::<Transform>(entity) {
+            transform.position = position;
+        }
+    }
+
+    /// Runs one fixed tick and returns the spawns it placed.
+    pub fn tick(&mut self) -> Vec<PlayerSpawned> {
+        self.schedules.run_fixed(&mut self.world);
+        self.rules_schedule.run(&mut self.world);
+        std::mem::take(&mut self.world.resource_mut::<SpawnEvents>().0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mover::MoverMode;
+    use crate::rules::SandboxRules;
+    use crate::spawn::spawn_points_from_sidecar;
+    use crate::trigger::TriggerPhase;
+    use rapier3d::prelude::{Capsule, ColliderBuilder, Vector};
+
+    const SIDECAR: &str = r#"
+version = 1
+map_id = "e1m1"
+map_to_world_scale = 0.03125
+
+[[spawns]]
+id = "start"
+origin = [4.0, 1.5, -2.0]
+yaw_deg = 45.0
+
+[[spawns]]
+id = "alt"
+origin = [-8.0, 0.5, 6.0]
+
+[[markers]]
+id = "exit_pad"
+kind = "trigger"
+origin = [0.0, 1.0, 0.0]
+volume = [2.0, 2.0, 2.0]
+"#;
+
+    fn session_on_sidecar_map() -> (GameSession, Entity) {
+        let sidecar = MapSidecar::parse_toml(SIDECAR).expect("sidecar");
+        let mut session = GameSession::new(SandboxRules::default());
+        session.enter_map(sidecar.map_id.clone(), spawn_points_from_sidecar(&sidecar));
+        let player = session.add_player(0);
+        (session, player)
+    }
+
+    #[test]
+    fn player_spawns_at_first_sidecar_spawn() {
+        let (mut session, player) = session_on_sidecar_map();
+        let spawned = session.tick();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].entity, player);
+        assert_eq!(spawned[0].point.id.as_deref(), Some("start"));
+        assert_eq!(spawned[0].point.yaw_deg, Some(45.0));
+        assert!(!spawned[0].respawn);
+        assert_eq!(
+            session.player_position(player),
+            Some(Vec3 {
+                x: 4.0,
+                y: 1.5,
+                z: -2.0
+            })
+        );
+        assert_eq!(session.current_map(), Some("e1m1"));
+        assert!(session.tick().is_empty());
+    }
+
+    #[test]
+    fn falling_below_kill_height_respawns_at_next_spawn() {
+        let (mut session, player) = session_on_sidecar_map();
+        session.tick();
+        session.set_player_position(
+            player,
+            Vec3 {
+                x: 4.0,
+                y: -500.0,
+                z: -2.0,
+            },
+        );
+        let spawned = session.tick();
+        assert_eq!(spawned.len(), 1);
+        assert!(spawned[0].respawn);
+        assert_eq!(spawned[0].point.id.as_deref(), Some("alt"));
+        assert_eq!(session.player_position(player).map(|pos| pos.y), Some(0.5));
+    }
+
+    #[test]
+    fn map_transition_respawns_players_on_new_map() {
+        let (mut session, player) = session_on_sidecar_map();
+        session.tick();
+        session.tick();
+        session.enter_map(
+            "test_map/flat",
+            vec![SpawnPoint::from_quake([0.0, 0.0, 64.0], Some(0.0), 0.5)],
+        );
+        let spawned = session.tick();
+        assert_eq!(spawned.len(), 1);
+        assert_eq!(spawned[0].entity, player);
+        assert!(!spawned[0].respawn);
+        assert_eq!(spawned[0].point.yaw_deg, Some(90.0));
+        assert_eq!(session.current_map(), Some("test_map/flat"));
+        assert_eq!(session.player_position(player).map(|pos| pos.y), Some(32.0));
+    }
+
+    #[test]
+    fn sensed_marker_volume_raises_enter_stay_exit() {
+        let sidecar = MapSidecar::parse_toml(SIDECAR).expect("sidecar");
+        let mut physics = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        let (mut session, player) = session_on_sidecar_map();
+        let triggers = session.spawn_sidecar_triggers(&mut physics, &sidecar);
+        assert_eq!(triggers.len(), 1);
+        let trigger = triggers[0];
+        physics.step(1.0 / 60.0);
+        let capsule = Capsule::new_y(0.9, 0.4);
+
+        let mut phases = Vec::new();
+        for x in [0.0, 0.0, 6.0] {
+            let position = Isometry::translation(x, 1.3, 0.0);
+            session.sense_triggers(&physics, player, &capsule, &position);
+            session.tick();
+            for event in session.drain_trigger_events() {
+                assert_eq!(event.trigger, trigger);
+                assert_eq!(event.other, player);
+                assert_eq!(session.script_name(event.other), "player:0");
+                assert_eq!(event.name, "exit_pad");
+                phases.push(event.phase);
+            }
+        }
+        assert_eq!(
+            phases,
+            vec![TriggerPhase::Enter, TriggerPhase::Stay, TriggerPhase::Exit]
+        );
+    }
+
+    #[test]
+    fn trigger_opens_door_mover() {
+        let mut physics = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        let sensor = physics.insert_sensor_collider(
+            ColliderBuilder::cuboid(1.0, 1.0, 1.0)
+                .translation(Vector::new(0.0, 1.0, 0.0))
+                .build(),
+        );
+        let (body, door_collider) = physics.insert_kinematic_collider(
+            ColliderBuilder::cuboid(0.1, 1.5, 1.0)
+                .translation(Vector::new(3.0, 1.5, 0.0))
+                .build(),
+        );
+        physics.step(1.0 / 60.0);
+        let (mut session, player) = session_on_sidecar_map();
+        session.spawn_trigger("gate_zone", sensor);
+        let base = *physics.colliders().get(door_collider).unwrap().position();
+        let door = Mover::new(
+            "gate",
+            MoverMode::Door,
+            vec![[0.0; 3], [0.0, 3.0, 0.0]],
+            6.0,
+        )
+        .with_wait(1.0)
+        .with_trigger(Some("gate_zone".to_string()));
+        session.spawn_mover(door, MoverBody { body, base });
+
+        let capsule = Capsule::new_y(0.9, 0.4);
+        let mut run = |session: &mut GameSession, position: Isometry<Real>, ticks: usize| {
+            for _ in 0..ticks {
+                session.sense_triggers(&physics, player, &capsule, &position);
+                session.tick();
+                session.apply_mover_targets(&mut physics);
+                physics.step(1.0 / 60.0);
+            }
+            physics
+                .colliders()
+                .get(door_collider)
+                .unwrap()
+                .translation()
+                .y
+        };
+        let door_y = run(&mut session, Isometry::translation(0.0, 1.3, 0.0), 45);
+        assert!((door_y - 4.5).abs() < 1.0e-4, "door at {}", door_y);
+        let door_y = run(&mut session, Isometry::translation(8.0, 1.3, 0.0), 90);
+        assert!((door_y - 1.5).abs() < 1.0e-4, "door at {}", door_y);
+    }
+
+    #[test]
+    fn lava_contact_raises_damage_ticks() {
+        let (mut session, player) = session_on_sidecar_map();
+        session.tick();
+        session.set_player_liquid(player, Some((LiquidKind::Water, 1.0)));
+        session.tick();
+        assert!(session.drain_liquid_damage_events().is_empty());
+
+        session.set_player_liquid(player, Some((LiquidKind::Lava, 0.5)));
+        for _ in 0..30 {
+            session.tick();
+        }
+        let events = session.drain_liquid_damage_events();
+        assert_eq!(events.len(), 3, "{:?}", events);
+        assert!(events
+            .iter()
+            .all(|event| event.entity == player && event.kind == LiquidKind::Lava));
+        assert!((events[0].amount - 15.0).abs() < 1.0e-4);
+        assert_eq!(events[1].tick - events[0].tick, 12);
+
+        session.set_player_liquid(player, None);
+        session.tick();
+        assert!(session.drain_liquid_damage_events().is_empty());
+    }
+
+    #[test]
+    fn saved_world_restores_props_and_tick() {
+        let registry = crate::save::game_save_registry();
+        let mut physics = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        physics.insert_static_collider(
+            ColliderBuilder::cuboid(10.0, 0.1, 10.0)
+                .translation(Vector::new(0.0, -0.1, 0.0))
+                .build(),
+        );
+        let mut session = GameSession::new(SandboxRules::default());
+        let desc = DynamicBodyDesc::new(
+            physics_rapier::DynamicBodyShape::Box {
+                half_extents: [0.25, 0.25, 0.25],
+            },
+            Isometry::translation(0.0, 3.0, 0.0),
+            10.0,
+        );
+        let crate_entity = session
+            .spawn_prop(&mut physics, "crate", &desc)
+            .expect("prop");
+        let run = |session: &mut GameSession, physics: &mut PhysicsWorld, ticks: u32| {
+            for _ in 0..ticks {
+                physics.step(1.0 / 60.0);
+                session.sync_props(physics);
+                session.tick();
+            }
+        };
+        run(&mut session, &mut physics, 10);
+
+        let mut save = crate::save::SaveGame::new(&registry);
+        save.world = session.capture_world(&registry);
+        let save = crate::save::SaveGame::parse(&save.to_text()).expect("parse");
+        let saved_y = session.player_position(crate_entity).expect("prop").y;
+        assert!(saved_y > 2.0, "prop at {}", saved_y);
+
+        run(&mut session, &mut physics, 90);
+        assert!(session.player_position(crate_entity).expect("prop").y < 0.5);
+        session
+            .restore_world(&mut physics, &registry, &save.world)
+            .expect("restore");
+        assert_eq!(session.world().resource::<ecs::FixedTick>().0, 10);
+        session.sync_props(&physics);
+        assert_eq!(
+            session.player_position(crate_entity).expect("prop").y,
+            saved_y
+        );
+    }
+
+    struct MovingLevel {
+        session: GameSession,
+        physics: PhysicsWorld,
+        player: Entity,
+        prop: Entity,
+        door: Entity,
+        door_collider: ColliderHandle,
+    }
+
+    /// A door opened by a zone the player stands in and a crate thrown
+    /// spinning into the air, built the same way every time like a level load.
+    fn moving_level() -> MovingLevel {
+        let mut physics = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        physics.insert_static_collider(
+            ColliderBuilder::cuboid(10.0, 0.1, 10.0)
+                .translation(Vector::new(0.0, -0.1, 0.0))
+                .build(),
+        );
+        let sensor = physics.insert_sensor_collider(
+            ColliderBuilder::cuboid(1.0, 1.0, 1.0)
+                .translation(Vector::new(0.0, 1.0, 0.0))
+                .build(),
+        );
+        let (body, door_collider) = physics.insert_kinematic_collider(
+            ColliderBuilder::cuboid(0.1, 1.5, 1.0)
+                .translation(Vector::new(3.0, 1.5, 0.0))
+                .build(),
+        );
+        let (mut session, player) = session_on_sidecar_map();
+        session.spawn_trigger("gate_zone", sensor);
+        let base = *physics.colliders().get(door_collider).unwrap().position();
+        let door = Mover::new(
+            "gate",
+            MoverMode::Door,
+            vec![[0.0; 3], [0.0, 3.0, 0.0]],
+            2.0,
+        )
+        .with_wait(1.0)
+        .with_trigger(Some("gate_zone".to_string()));
+        let door = session.spawn_mover(door, MoverBody { body, base });
+        let desc = DynamicBodyDesc::new(
+            physics_rapier::DynamicBodyShape::Box {
+                half_extents: [0.25, 0.5, 0.25],
+            },
+            Isometry::translation(-4.0, 2.0, 0.0),
+            10.0,
+        );
+        let prop = session
+            .spawn_prop(&mut physics, "crate", &desc)
+            .expect("prop");
+        let prop_body = session.world().get::<Prop>(prop).unwrap().body;
+        physics.set_body_state(
+            prop_body,
+            desc.position,
+            Vector::new(1.0, 6.0, 0.5),
+            Vector::new(4.0, -2.0, 3.0),
+        );
+        MovingLevel {
+            session,
+            physics,
+            player,
+            prop,
+            door,
+            door_collider,
+        }
+    }
+
+    fn run_moving_level(level: &mut MovingLevel, ticks: u32) {
+        let capsule = Capsule::new_y(0.9, 0.4);
+        let position = Isometry::translation(0.0, 1.3, 0.0);
+        for _ in 0..ticks {
+            level
+                .session
+                .sense_triggers(&level.physics, level.player, &capsule, &position);
+            level.session.tick();
+            level.session.apply_mover_targets(&mut level.physics);
+            level.physics.step(1.0 / 60.0);
+            level.session.sync_props(&level.physics);
+        }
+    }
+
+    #[test]
+    fn saved_world_resumes_tumbling_prop_and_moving_door() {
+        let registry = crate::save::game_save_registry();
+        let mut original = moving_level();
+        run_moving_level(&mut original, 30);
+        let door = original.session.world().get::<Mover>(original.door);
+        assert!(door.is_some_and(Mover::is_moving));
+        let motion = *original
+            .session
+            .world()
+            .get::<PropMotion>(original.prop)
+            .unwrap();
+        assert!(motion.position.translation.y > 2.0);
+        assert!(motion.angvel.norm() > 1.0);
+
+        let mut save = crate::save::SaveGame::new(&registry);
+        save.world = original.session.capture_world(&registry);
+        let save = crate::save::SaveGame::parse(&save.to_text()).expect("parse");
+        let mut resumed = moving_level();
+        run_moving_level(&mut resumed, 5);
+        resumed
+            .session
+            .restore_world(&mut resumed.physics, &registry, &save.world)
+            .expect("restore");
+
+        for _ in 0..3 {
+            run_moving_level(&mut original, 20);
+            run_moving_level(&mut resumed, 20);
+            let world = |level: &MovingLevel| {
+                (
+                    *level.session.world().get::<PropMotion>(level.prop).unwrap(),
+                    level.session.world().get::<Mover>(level.door).cloned(),
+                    *level
+                        .physics
+                        .colliders()
+                        .get(level.door_collider)
+                        .unwrap()
+                        .position(),
+                )
+            };
+            assert_eq!(world(&resumed), world(&original));
+            assert_eq!(
+                resumed.session.capture_world(&registry),
+                original.session.capture_world(&registry)
+            );
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! Spawn points and player placement.
+//!
+//! Spawn points are world space (meters, y-up). Yaw follows the client camera
+//! convention: degrees, where `yaw_deg.to_radians()` is the camera yaw.
+
+use character_collision::{CharacterCollision, CollisionMoveResult, CollisionProfile};
+use map_cook::MapSidecar;
+use physics_rapier::PhysicsWorld;
+use rapier3d::prelude::{Isometry, Real, Vector};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpawnPoint {
+    pub id: Option<String>,
+    pub origin: [f32; 3],
+    pub yaw_deg: Option<f32>,
+}
+
+impl SpawnPoint {
+    pub fn new(origin: [f32; 3]) -> Self {
+        Self {
+            id: None,
+            origin,
+            yaw_deg: None,
+        }
+    }
+
+    /// Converts a BSP `info_player_start` (Quake units, z-up, `angle` measured
+    /// from +x) into world space.
+    pub fn from_quake(origin: [f32; 3], angle: Option<f32>, scale: f32) -> Self {
+        Self {
+            id: None,
+            origin: [origin[0] * scale, origin[2] * scale, -origin[1] * scale],
+            yaw_deg: angle.map(|angle| 90.0 - angle),
+        }
+    }
+}
+
+/// Spawns authored in a map sidecar; sidecar origins are already world space.
+pub fn spawn_points_from_sidecar(sidecar: &MapSidecar) -> Vec<SpawnPoint> {
+    sidecar
+        .spawns
+        .iter()
+        .map(|spawn| SpawnPoint {
+            id: spawn.id.clone(),
+            origin: spawn.origin,
+            yaw_deg: spawn.yaw_deg,
+        })
+        .collect()
+}
+
+/// Height of the capsule center above the character's feet.
+pub fn capsule_offset(profile: &CollisionProfile) -> f32 {
+    profile.capsule_height * 0.5 + profile.capsule_radius
+}
+
+/// Sweeps the character straight down by up to `max_drop` meters so a spawn
+/// placed above the floor starts grounded. `dt` is the caller's fixed step;
+/// it only scales how far moving platforms carry the character. Step-up is
+/// disabled since a vertical sweep never climbs.
+pub fn drop_to_ground(
+    collision: &mut CharacterCollision,
+    world: &PhysicsWorld,
+    position: Isometry<Real>,
+    max_drop: f32,
+    dt: f32,
+) -> CollisionMoveResult {
+    let allow_step = false;
+    collision.move_character(
+        world,
+        position,
+        Vector::new(0.0, -max_drop.max(0.0), 0.0),
+        allow_step,
+        dt,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quake_spawn_maps_to_world_axes() {
+        let spawn = SpawnPoint::from_quake([64.0, 32.0, 24.0], Some(90.0), 0.5);
+        assert_eq!(spawn.origin, [32.0, 12.0, -16.0]);
+        assert_eq!(spawn.yaw_deg, Some(0.0));
+    }
+}
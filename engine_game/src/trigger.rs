@@ -0,0 +1,192 @@
+//! Trigger volumes and enter/stay/exit events.
+//!
+//! Overlaps come from two places: Rapier intersection events for entities
+//! that own colliders, and per-tick sensor queries for kinematic characters
+//! (see [`crate::GameSession::sense_triggers`]). Both feed [`TriggerOverlaps`];
+//! the rules schedule diffs it against the previous tick.
+
+use std::collections::{BTreeSet, HashMap};
+
+use bevy_ecs::prelude::*;
+use ecs::{FixedTick, SaveState};
+use physics_rapier::{PhysicsEvent, PhysicsEventKind};
+use rapier3d::prelude::ColliderHandle;
+
+#[derive(Component, Clone, Debug, PartialEq, Eq)]
+pub struct TriggerVolume {
+    pub name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriggerPhase {
+    Enter,
+    Stay,
+    Exit,
+}
+
+impl TriggerPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            TriggerPhase::Enter => "enter",
+            TriggerPhase::Stay => "stay",
+            TriggerPhase::Exit => "exit",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TriggerEvent {
+    pub trigger: Entity,
+    pub name: String,
+    pub other: Entity,
+    pub phase: TriggerPhase,
+    /// Fixed tick the event was raised on.
+    pub tick: u64,
+}
+
+/// Trigger events raised since the host last drained them.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct TriggerEvents(pub Vec<TriggerEvent>);
+
+/// Which entity owns each physics collider.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct ColliderEntities(pub HashMap<ColliderHandle, Entity>);
+
+/// Current `(trigger, other)` overlaps, plus the set seen last tick.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct TriggerOverlaps {
+    current: BTreeSet<(Entity, Entity)>,
+    previous: BTreeSet<(Entity, Entity)>,
+}
+
+impl TriggerOverlaps {
+    pub fn contains(&self, trigger: Entity, other: Entity) -> bool {
+        self.current.contains(&(trigger, other))
+    }
+
+    pub fn insert(&mut self, trigger: Entity, other: Entity) {
+        self.current.insert((trigger, other));
+    }
+
+    pub fn remove(&mut self, trigger: Entity, other: Entity) {
+        self.current.remove(&(trigger, other));
+    }
+
+    /// Replaces every overlap involving `other` with `triggers`.
+    pub fn set_overlaps(&mut self, other: Entity, triggers: impl IntoIterator<Item = Entity>) {
+        self.current.retain(|(_, entity)| *entity != other);
+        self.current
+            .extend(triggers.into_iter().map(|trigger| (trigger, other)));
+    }
+
+    /// Drops overlaps involving `entity` as either side; they exit next tick.
+    pub fn forget(&mut self, entity: Entity) {
+        self.current
+            .retain(|(trigger, other)| *trigger != entity && *other != entity);
+    }
+}
+
+/// Encoded as `<current pairs>;<previous pairs>`, each pair written as
+/// `<trigger>:<other>` entity bits. Entities are only meaningful on a world
+/// rebuilt from the same level, which is what `restore_onto` expects.
+impl SaveState for TriggerOverlaps {
+    const SAVE_KEY: &'static str = "trigger_overlaps";
+
+    fn encode(&self) -> String {
+        let pairs = |set: &BTreeSet<(Entity, Entity)>| {
+            set.iter()
+                .map(|(trigger, other)| format!("{}:{}", trigger.to_bits(), other.to_bits()))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        format!("{};{}", pairs(&self.current), pairs(&self.previous))
+    }
+
+    fn decode(value: &str) -> Result<Self, String> {
+        let entity = |bits: &str| {
+            bits.parse::<u64>()
+                .ok()
+                .and_then(|bits| Entity::try_from_bits(bits).ok())
+                .ok_or_else(|| format!("invalid entity '{}'", bits))
+        };
+        let pairs = |text: &str| {
+            text.split_whitespace()
+                .map(|pair| {
+                    let (trigger, other) = pair
+                        .split_once(':')
+                        .ok_or_else(|| format!("invalid overlap '{}'", pair))?;
+                    Ok((entity(trigger)?, entity(other)?))
+                })
+                .collect::<Result<BTreeSet<_>, String>>()
+        };
+        let (current, previous) = value
+            .split_once(';')
+            .ok_or_else(|| format!("expected current;previous overlaps, got '{}'", value))?;
+        Ok(Self {
+            current: pairs(current)?,
+            previous: pairs(previous)?,
+        })
+    }
+}
+
+/// Applies Rapier intersection events between a trigger collider and another
+/// entity-owned collider. Contacts and unowned colliders are ignored.
+pub fn apply_physics_events(world: &mut World, events: &[PhysicsEvent]) {
+    for event in events {
+        if event.kind != PhysicsEventKind::Intersection {
+            continue;
+        }
+        let owners = world.resource::<ColliderEntities>();
+        let (Some(&first), Some(&second)) = (
+            owners.0.get(&event.collider1),
+            owners.0.get(&event.collider2),
+        ) else {
+            continue;
+        };
+        let pair = if world.get::<TriggerVolume>(first).is_some() {
+            (first, second)
+        } else if world.get::<TriggerVolume>(second).is_some() {
+            (second, first)
+        } else {
+            continue;
+        };
+        let mut overlaps = world.resource_mut::<TriggerOverlaps>();
+        if event.started {
+            overlaps.insert(pair.0, pair.1);
+        } else {
+            overlaps.remove(pair.0, pair.1);
+        }
+    }
+}
+
+pub(crate) fn emit_trigger_events(
+    mut overlaps: ResMut<TriggerOverlaps>,
+    mut events: ResMut<TriggerEvents>,
+    tick: Res<FixedTick>,
+    triggers: Query<&TriggerVolume>,
+) {
+    let overlaps = &mut *overlaps;
+    let mut push = |(trigger, other): (Entity, Entity), phase| {
+        let name = triggers
+            .get(trigger)
+            .map(|volume| volume.name.clone())
+            .unwrap_or_default();
+        events.0.push(TriggerEvent {
+            trigger,
+            name,
+            other,
+            phase,
+            tick: tick.0,
+        });
+    };
+    for pair in overlaps.current.difference(&overlaps.previous) {
+        push(*pair, TriggerPhase::Enter);
+    }
+    for pair in overlaps.current.intersection(&overlaps.previous) {
+        push(*pair, TriggerPhase::Stay);
+    }
+    for pair in overlaps.previous.difference(&overlaps.current) {
+        push(*pair, TriggerPhase::Exit);
+    }
+    overlaps.previous.clone_from(&overlaps.current);
+}
@@ -1,6 +1,7 @@
 use collision_world::{build_chunk_bounds_bvh, CollisionWorld, PartitionKind};
 use compat_quake::bsp as quake1;
 use compat_quake::bsp_q3 as quake3;
+use rapier3d::prelude::{Collider, ColliderBuilder, Point, Real};
 
 use crate::quadtree::{build_quadtree_chunks, Quadtree2dConfig, Triangle};
 
@@ -16,8 +17,13 @@ pub struct BspCookConfig {
     pub map_to_world_scale: f32,
     pub space_origin: [f32; 3],
     pub quadtree: Quadtree2dConfig,
+    /// Steps per Bezier segment when tessellating Quake 3 patches.
+    pub patch_level: u32,
 }
 
+/// Patch subdivision that keeps curved floors within a few units of the curve.
+pub const DEFAULT_PATCH_LEVEL: u32 = 8;
+
 pub fn build_bsp_collision_world(
     kind: BspKind,
     bytes: &[u8],
@@ -40,7 +46,7 @@ pub fn build_bsp_collision_world(
         }
         BspKind::Quake3 => {
             let bsp = quake3::parse_bsp(bytes).map_err(|err| err.to_string())?;
-            triangles_from_quake3(&bsp, config.map_to_world_scale)?
+            triangles_from_quake3(&bsp, config.map_to_world_scale, config.patch_level)?
         }
     };
     let label = sanitize_label(&config.map_id);
@@ -61,8 +67,261 @@ pub fn build_bsp_collision_world(
     })
 }
 
+/// Inline brush model (`*N`, N >= 1) used by BSP movers such as `func_door`.
+#[derive(Clone, Debug)]
+pub struct BspSubmodel {
+    pub model: u32,
+    /// World-space bounds of the model's faces.
+    pub min: [f32; 3],
+    pub max: [f32; 3],
+    /// Convex hull of the model, positioned in world space.
+    pub collider: Collider,
+}
+
+/// Collision for every non-world brush model; models without faces are skipped.
+pub fn build_quake1_submodels(bytes: &[u8], scale: f32) -> Result<Vec<BspSubmodel>, String> {
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err("map_to_world_scale must be finite and > 0".to_string());
+    }
+    let bsp = quake1::parse_bsp(bytes).map_err(|err| err.to_string())?;
+    let mut submodels = Vec::new();
+    for (index, model) in bsp.models.iter().enumerate().skip(1) {
+        let (Ok(first), Ok(count)) = (
+            usize::try_from(model.first_face),
+            usize::try_from(model.num_faces),
+        ) else {
+            continue;
+        };
+        let triangles = quake1_face_triangles(&bsp, first..first.saturating_add(count), scale)?;
+        let points: Vec<Point<Real>> = triangles
+            .iter()
+            .flat_map(|triangle| [triangle.a, triangle.b, triangle.c])
+            .map(|vertex| Point::new(vertex[0], vertex[1], vertex[2]))
+            .collect();
+        let Some(first_point) = points.first() else {
+            continue;
+        };
+        let mut min = *first_point;
+        let mut max = *first_point;
+        for point in &points {
+            min = min.inf(point);
+            max = max.sup(point);
+        }
+        let collider = match ColliderBuilder::convex_hull(&points) {
+            Some(builder) => builder.build(),
+            None => {
+                let half = (max - min) * 0.5;
+                ColliderBuilder::cuboid(half.x, half.y, half.z)
+                    .translation(min.coords + half)
+                    .build()
+            }
+        };
+        submodels.push(BspSubmodel {
+            model: index as u32,
+            min: [min.x, min.y, min.z],
+            max: [max.x, max.y, max.z],
+            collider,
+        });
+    }
+    Ok(submodels)
+}
+
+/// A liquid volume: a leaf of the Quake 1 world tree or a Quake 3 brush.
+#[derive(Clone, Debug)]
+pub struct BspLiquid {
+    /// Leaf index for Quake 1, brush index for Quake 3.
+    pub index: u32,
+    /// Liquid content tag, matching test map solids: `water`, `slime` or `lava`.
+    pub tag: &'static str,
+    /// Convex hull of the leaf, positioned in world space.
+    pub collider: Collider,
+}
+
+/// Liquid volumes from the world model's leaf contents. Each leaf is the box
+/// of its stored bounds cut by the node planes above it.
+pub fn build_quake1_liquids(bytes: &[u8], scale: f32) -> Result<Vec<BspLiquid>, String> {
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err("map_to_world_scale must be finite and > 0".to_string());
+    }
+    let bsp = quake1::parse_bsp(bytes).map_err(|err| err.to_string())?;
+    let Some(root) = bsp.models.first().map(|model| model.headnode[0]) else {
+        return Ok(Vec::new());
+    };
+    let mut liquids = Vec::new();
+    let mut visited = 0usize;
+    let mut stack: Vec<(i32, Vec<HalfSpace>)> = vec![(root, Vec::new())];
+    while let Some((child, half_spaces)) = stack.pop() {
+        if child < 0 {
+            let index = !child as usize;
+            let leaf = bsp
+                .leaves
+                .get(index)
+                .ok_or_else(|| format!("leaf index out of bounds: {}", index))?;
+            let tag = match leaf.contents {
+                quake1::Contents::Water => "water",
+                quake1::Contents::Slime => "slime",
+                quake1::Contents::Lava => "lava",
+                _ => continue,
+            };
+            let points: Vec<Point<Real>> = leaf_hull_points(leaf, &half_spaces)
+                .into_iter()
+                .map(|point| {
+                    let world = quake_to_world(scale_vec(point, scale));
+                    Point::new(world[0], world[1], world[2])
+                })
+                .collect();
+            if let Some(builder) = ColliderBuilder::convex_hull(&points) {
+                liquids.push(BspLiquid {
+                    index: index as u32,
+                    tag,
+                    collider: builder.build(),
+                });
+            }
+            continue;
+        }
+        visited += 1;
+        if visited > bsp.nodes.len() {
+            return Err("bsp node tree revisits a node".to_string());
+        }
+        let node = bsp
+            .nodes
+            .get(child as usize)
+            .ok_or_else(|| format!("node index out of bounds: {}", child))?;
+        let plane = usize::try_from(node.plane_id)
+            .ok()
+            .and_then(|index| bsp.planes.get(index))
+            .ok_or_else(|| format!("node plane out of bounds: {}", node.plane_id))?;
+        let normal = plane.normal;
+        let mut front = half_spaces.clone();
+        front.push(([-normal[0], -normal[1], -normal[2]], -plane.dist));
+        let mut back = half_spaces;
+        back.push((normal, plane.dist));
+        stack.push((node.children[0], front));
+        stack.push((node.children[1], back));
+    }
+    Ok(liquids)
+}
+
+/// Liquid brushes of the Quake 3 world model, tagged by their shader's
+/// content flags. Lava wins over slime, slime over water.
+pub fn build_quake3_liquids(bytes: &[u8], scale: f32) -> Result<Vec<BspLiquid>, String> {
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err("map_to_world_scale must be finite and > 0".to_string());
+    }
+    let bsp = quake3::parse_bsp(bytes).map_err(|err| err.to_string())?;
+    let first = bsp
+        .models
+        .first()
+        .and_then(|model| usize::try_from(model.first_brush).ok())
+        .unwrap_or(0);
+    let brushes = bsp.model_brushes(0).unwrap_or(&bsp.brushes);
+    let mut liquids = Vec::new();
+    for (offset, brush) in brushes.iter().enumerate() {
+        let Some(shader) = bsp.shader(brush.shader) else {
+            continue;
+        };
+        let tag = if shader.content_flags & quake3::Shader::CONTENTS_LAVA != 0 {
+            "lava"
+        } else if shader.content_flags & quake3::Shader::CONTENTS_SLIME != 0 {
+            "slime"
+        } else if shader.content_flags & quake3::Shader::CONTENTS_WATER != 0 {
+            "water"
+        } else {
+            continue;
+        };
+        let points: Vec<Point<Real>> = hull_points(&quake3_brush_half_spaces(&bsp, brush)?)
+            .into_iter()
+            .map(|point| {
+                let world = quake_to_world(scale_vec(point, scale));
+                Point::new(world[0], world[1], world[2])
+            })
+            .collect();
+        if let Some(builder) = ColliderBuilder::convex_hull(&points) {
+            liquids.push(BspLiquid {
+                index: (first + offset) as u32,
+                tag,
+                collider: builder.build(),
+            });
+        }
+    }
+    Ok(liquids)
+}
+
+/// `normal . p <= dist`, in Quake units.
+type HalfSpace = ([f32; 3], f32);
+
+/// Corners of the convex region inside the leaf bounds and every half-space.
+fn leaf_hull_points(leaf: &quake1::Leaf, half_spaces: &[HalfSpace]) -> Vec<[f32; 3]> {
+    let mut planes = half_spaces.to_vec();
+    for axis in 0..3 {
+        let mut normal = [0.0; 3];
+        normal[axis] = 1.0;
+        planes.push((normal, f32::from(leaf.maxs[axis])));
+        normal[axis] = -1.0;
+        planes.push((normal, -f32::from(leaf.mins[axis])));
+    }
+    hull_points(&planes)
+}
+
+/// Corners of the convex region inside every half-space.
+fn hull_points(half_spaces: &[HalfSpace]) -> Vec<[f32; 3]> {
+    const EPSILON: f64 = 0.05;
+
+    let planes: Vec<([f64; 3], f64)> = half_spaces
+        .iter()
+        .map(|(normal, dist)| (normal.map(f64::from), f64::from(*dist)))
+        .collect();
+    let dot = |a: [f64; 3], b: [f64; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+    let cross = |a: [f64; 3], b: [f64; 3]| {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    };
+    let mut points = Vec::new();
+    for i in 0..planes.len() {
+        for j in i + 1..planes.len() {
+            for k in j + 1..planes.len() {
+                let (n1, d1) = planes[i];
+                let (n2, d2) = planes[j];
+                let (n3, d3) = planes[k];
+                let n2x3 = cross(n2, n3);
+                let det = dot(n1, n2x3);
+                if det.abs() < 1.0e-9 {
+                    continue;
+                }
+                let n3x1 = cross(n3, n1);
+                let n1x2 = cross(n1, n2);
+                let point: [f64; 3] = std::array::from_fn(|axis| {
+                    (n2x3[axis] * d1 + n3x1[axis] * d2 + n1x2[axis] * d3) / det
+                });
+                if planes
+                    .iter()
+                    .all(|(normal, dist)| dot(*normal, point) <= dist + EPSILON)
+                {
+                    points.push(point.map(|value| value as f32));
+                }
+            }
+        }
+    }
+    points
+}
+
 fn triangles_from_quake1(bsp: &quake1::Bsp, scale: f32) -> Result<Vec<Triangle>, String> {
     let face_range = bsp.world_face_range().unwrap_or(0..bsp.faces.len());
+    let triangles = quake1_face_triangles(bsp, face_range, scale)?;
+    if triangles.is_empty() {
+        return Err("quake1 bsp contained no collision triangles".to_string());
+    }
+    Ok(triangles)
+}
+
+fn quake1_face_triangles(
+    bsp: &quake1::Bsp,
+    face_range: std::ops::Range<usize>,
+    scale: f32,
+) -> Result<Vec<Triangle>, String> {
     let mut triangles = Vec::new();
     for face_index in face_range {
         let face = bsp
@@ -120,13 +379,152 @@ fn triangles_from_quake1(bsp: &quake1::Bsp, scale: f32) -> Result<Vec<Triangle>,
             });
         }
     }
+    Ok(triangles)
+}
+
+/// Collision follows the game: solid and player-clip brushes of the world
+/// model plus solid patches. Liquid and nonsolid brushes are left out.
+fn triangles_from_quake3(
+    bsp: &quake3::Bsp,
+    scale: f32,
+    patch_level: u32,
+) -> Result<Vec<Triangle>, String> {
+    let mut triangles = if bsp.brushes.is_empty() {
+        quake3_face_triangles(bsp, scale)?
+    } else {
+        let brushes = bsp.model_brushes(0).unwrap_or(&bsp.brushes);
+        let mut triangles = Vec::new();
+        for brush in brushes {
+            if bsp
+                .shader(brush.shader)
+                .is_some_and(quake3::Shader::blocks_player)
+            {
+                triangles.extend(quake3_brush_triangles(bsp, brush, scale)?);
+            }
+        }
+        triangles
+    };
+    triangles.extend(quake3_patch_triangles(bsp, scale, patch_level)?);
     if triangles.is_empty() {
-        return Err("quake1 bsp contained no collision triangles".to_string());
+        return Err("quake3 bsp contained no collision triangles".to_string());
+    }
+    Ok(triangles)
+}
+
+fn quake3_patch_triangles(
+    bsp: &quake3::Bsp,
+    scale: f32,
+    patch_level: u32,
+) -> Result<Vec<Triangle>, String> {
+    let mut triangles = Vec::new();
+    for (face_index, face) in bsp.faces.iter().enumerate() {
+        if face.face_type != quake3::Face::PATCH {
+            continue;
+        }
+        if bsp
+            .shader(face.shader)
+            .is_some_and(|shader| shader.is_nonsolid() || !shader.blocks_player())
+        {
+            continue;
+        }
+        let mesh = bsp
+            .tessellate_patch(face, patch_level)
+            .map_err(|err| format!("face {}: {}", face_index, err))?;
+        let points: Vec<[f32; 3]> = mesh
+            .vertices
+            .iter()
+            .map(|vertex| quake_to_world(scale_vec(vertex.position, scale)))
+            .collect();
+        for corners in mesh.indices.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|corner| points[corners[corner] as usize]);
+            if !triangle_is_degenerate(a, b, c) {
+                triangles.push(Triangle { a, b, c });
+            }
+        }
+    }
+    Ok(triangles)
+}
+
+/// Brush planes as half-spaces; Quake 3 side planes face out of the brush.
+fn quake3_brush_half_spaces(
+    bsp: &quake3::Bsp,
+    brush: &quake3::Brush,
+) -> Result<Vec<HalfSpace>, String> {
+    let sides = bsp
+        .sides(brush)
+        .ok_or_else(|| format!("brush side range out of bounds: {}", brush.first_side))?;
+    sides
+        .iter()
+        .map(|side| {
+            usize::try_from(side.plane)
+                .ok()
+                .and_then(|index| bsp.planes.get(index))
+                .map(|plane| (plane.normal, plane.dist))
+                .ok_or_else(|| format!("brush side plane out of bounds: {}", side.plane))
+        })
+        .collect()
+}
+
+/// The brush surface: each side's hull corners, ordered around the side
+/// normal and fanned into triangles.
+fn quake3_brush_triangles(
+    bsp: &quake3::Bsp,
+    brush: &quake3::Brush,
+    scale: f32,
+) -> Result<Vec<Triangle>, String> {
+    const ON_PLANE: f32 = 0.1;
+
+    let half_spaces = quake3_brush_half_spaces(bsp, brush)?;
+    let corners = hull_points(&half_spaces);
+    let mut triangles = Vec::new();
+    for (normal, dist) in &half_spaces {
+        let mut polygon: Vec<[f32; 3]> = Vec::new();
+        for corner in &corners {
+            if (dot3(*normal, *corner) - dist).abs() > ON_PLANE {
+                continue;
+            }
+            if !polygon
+                .iter()
+                .any(|point| distance_squared(*point, *corner) < ON_PLANE * ON_PLANE)
+            {
+                polygon.push(*corner);
+            }
+        }
+        if polygon.len() < 3 {
+            continue;
+        }
+        let count = polygon.len() as f32;
+        let center = polygon.iter().fold([0.0; 3], |sum, point| {
+            [sum[0] + point[0], sum[1] + point[1], sum[2] + point[2]]
+        });
+        let center = center.map(|value| value / count);
+        let axis_u = normalize3(sub3(polygon[0], center));
+        let axis_v = cross3(*normal, axis_u);
+        polygon.sort_by(|a, b| {
+            let angle = |point: &[f32; 3]| {
+                let offset = sub3(*point, center);
+                dot3(offset, axis_v).atan2(dot3(offset, axis_u))
+            };
+            angle(a).total_cmp(&angle(b))
+        });
+        let v0 = quake_to_world(scale_vec(polygon[0], scale));
+        for pair in polygon[1..].windows(2) {
+            let v1 = quake_to_world(scale_vec(pair[0], scale));
+            let v2 = quake_to_world(scale_vec(pair[1], scale));
+            if !triangle_is_degenerate(v0, v1, v2) {
+                triangles.push(Triangle {
+                    a: v0,
+                    b: v1,
+                    c: v2,
+                });
+            }
+        }
     }
     Ok(triangles)
 }
 
-fn triangles_from_quake3(bsp: &quake3::Bsp, scale: f32) -> Result<Vec<Triangle>, String> {
+/// Polygon and mesh faces, for maps compiled without brush data.
+fn quake3_face_triangles(bsp: &quake3::Bsp, scale: f32) -> Result<Vec<Triangle>, String> {
     let mut triangles = Vec::new();
     for (face_index, face) in bsp.faces.iter().enumerate() {
         let vertex_start = usize::try_from(face.vertex_start)
@@ -141,11 +539,15 @@ fn triangles_from_quake3(bsp: &quake3::Bsp, scale: f32) -> Result<Vec<Triangle>,
         }
 
         match face.face_type {
-            1 => {
-                let v0 = quake_to_world(scale_vec(bsp.vertices[vertex_start], scale));
+            quake3::Face::POLYGON => {
+                let v0 = quake_to_world(scale_vec(bsp.vertices[vertex_start].position, scale));
                 for i in 1..vertex_count - 1 {
-                    let v1 = quake_to_world(scale_vec(bsp.vertices[vertex_start + i], scale));
-                    let v2 = quake_to_world(scale_vec(bsp.vertices[vertex_start + i + 1], scale));
+                    let v1 =
+                        quake_to_world(scale_vec(bsp.vertices[vertex_start + i].position, scale));
+                    let v2 = quake_to_world(scale_vec(
+                        bsp.vertices[vertex_start + i + 1].position,
+                        scale,
+                    ));
                     if triangle_is_degenerate(v0, v1, v2) {
                         continue;
                     }
@@ -156,7 +558,7 @@ fn triangles_from_quake3(bsp: &quake3::Bsp, scale: f32) -> Result<Vec<Triangle>,
                     });
                 }
             }
-            3 => {
+            quake3::Face::MESH => {
                 let meshvert_start = usize::try_from(face.meshvert_start)
                     .map_err(|_| format!("face {} has negative meshvert_start", face_index))?;
                 let meshvert_count = usize::try_from(face.meshvert_count)
@@ -196,9 +598,6 @@ fn triangles_from_quake3(bsp: &quake3::Bsp, scale: f32) -> Result<Vec<Triangle>,
             }
         }
     }
-    if triangles.is_empty() {
-        return Err("quake3 bsp contained no collision triangles".to_string());
-    }
     Ok(triangles)
 }
 
@@ -218,7 +617,7 @@ fn fetch_q3_vertex(
             face_index, offset, vertex_count
         ));
     }
-    let vertex = bsp.vertices[vertex_start + offset];
+    let vertex = bsp.vertices[vertex_start + offset].position;
     Ok(quake_to_world(scale_vec(vertex, scale)))
 }
 
@@ -230,6 +629,35 @@ fn quake_to_world(value: [f32; 3]) -> [f32; 3] {
     [value[0], value[2], -value[1]]
 }
 
+fn dot3(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn sub3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross3(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize3(value: [f32; 3]) -> [f32; 3] {
+    let length = dot3(value, value).sqrt();
+    if length <= f32::EPSILON {
+        return value;
+    }
+    value.map(|component| component / length)
+}
+
+fn distance_squared(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let delta = sub3(a, b);
+    dot3(delta, delta)
+}
+
 fn triangle_is_degenerate(a: [f32; 3], b: [f32; 3], c: [f32; 3]) -> bool {
     let ab = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
     let ac = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
@@ -253,3 +681,102 @@ fn sanitize_label(map_id: &str) -> String {
 fn vector_is_finite(value: [f32; 3]) -> bool {
     value.iter().all(|component| component.is_finite())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn liquid_leaf_hull_is_cut_by_node_planes() {
+        let leaf = quake1::Leaf {
+            contents: quake1::Contents::Water,
+            vis_offset: -1,
+            mins: [0, 0, 0],
+            maxs: [64, 64, 64],
+            first_mark_surface: 0,
+            num_mark_surfaces: 0,
+            ambient_levels: [0; 4],
+        };
+        let surface: HalfSpace = ([0.0, 0.0, 1.0], 32.0);
+        let points = leaf_hull_points(&leaf, &[surface]);
+        assert!(!points.is_empty());
+        assert!(points.iter().all(|point| point[2] <= 32.05));
+        assert!(points.iter().any(|point| (point[2] - 32.0).abs() < 1.0e-4));
+        assert!(points.iter().any(|point| point[2] == 0.0));
+    }
+
+    fn cube_planes(min: f32, max: f32) -> Vec<quake3::Plane> {
+        (0..3)
+            .flat_map(|axis| {
+                let mut normal = [0.0; 3];
+                normal[axis] = 1.0;
+                let positive = quake3::Plane { normal, dist: max };
+                normal[axis] = -1.0;
+                [positive, quake3::Plane { normal, dist: -min }]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn quake3_collision_keeps_solid_and_clip_brushes_only() {
+        let shader = |name: &str, surface_flags, content_flags| quake3::Shader {
+            name: name.to_string(),
+            surface_flags,
+            content_flags,
+        };
+        let mut planes = cube_planes(0.0, 64.0);
+        planes.extend(cube_planes(100.0, 132.0));
+        planes.extend(cube_planes(200.0, 232.0));
+        let brushes = (0..3)
+            .map(|index| quake3::Brush {
+                first_side: index * 6,
+                num_sides: 6,
+                shader: index,
+            })
+            .collect();
+        let brush_sides = (0..18)
+            .map(|plane| quake3::BrushSide { plane, shader: 0 })
+            .collect();
+        let bsp = quake3::Bsp {
+            header: quake3::BspHeader {
+                version: 46,
+                lumps: [quake3::Lump {
+                    offset: 0,
+                    length: 0,
+                }; 17],
+            },
+            entities: Vec::new(),
+            shaders: vec![
+                shader("floor", 0, quake3::Shader::CONTENTS_SOLID),
+                shader("water", 0, quake3::Shader::CONTENTS_WATER),
+                shader("clip", 0, quake3::Shader::CONTENTS_PLAYERCLIP),
+            ],
+            planes,
+            nodes: Vec::new(),
+            leafs: Vec::new(),
+            leaf_faces: Vec::new(),
+            leaf_brushes: Vec::new(),
+            models: Vec::new(),
+            brushes,
+            brush_sides,
+            vertices: Vec::new(),
+            meshverts: Vec::new(),
+            effects: Vec::new(),
+            faces: Vec::new(),
+            lightmaps: Vec::new(),
+            light_vols: Vec::new(),
+        };
+
+        let triangles = triangles_from_quake3(&bsp, 1.0, DEFAULT_PATCH_LEVEL).expect("triangles");
+        assert_eq!(triangles.len(), 24);
+        let in_range = |value: f32, min: f32, max: f32| value >= min - 0.01 && value <= max + 0.01;
+        for triangle in &triangles {
+            // quake_to_world maps Quake x to world x.
+            let x = [triangle.a[0], triangle.b[0], triangle.c[0]];
+            assert!(
+                x.iter().all(|&x| in_range(x, 0.0, 64.0))
+                    || x.iter().all(|&x| in_range(x, 200.0, 232.0))
+            );
+        }
+    }
+}
@@ -6,15 +6,19 @@ mod quadtree;
 mod sidecar;
 
 use collision_world::{
-    build_chunk_bounds_bvh, Aabb, CollisionChunk, CollisionWorld, PartitionKind,
+    build_chunk_bounds_bvh, Aabb, CollisionChunk, CollisionLayerTable, CollisionWorld,
+    PartitionKind,
 };
 use rapier3d::na::UnitQuaternion;
 use rapier3d::prelude::{Collider, ColliderBuilder, Isometry, Point, Real, Translation, Vector};
-use test_map::{ResolvedSolid, SolidKind, TestMap};
+use test_map::{liquid_tag, ResolvedSolid, SolidKind, TestMap, CLIMBABLE_TAG, TRIGGER_TAG};
 
-pub use bsp_cook::{build_bsp_collision_world, BspCookConfig, BspKind};
+pub use bsp_cook::{
+    build_bsp_collision_world, build_quake1_liquids, build_quake1_submodels, build_quake3_liquids,
+    BspCookConfig, BspKind, BspLiquid, BspSubmodel, DEFAULT_PATCH_LEVEL,
+};
 pub use quadtree::Quadtree2dConfig;
-pub use sidecar::{MapSidecar, MapSidecarValidation, MarkerSpec, SpawnSpec};
+pub use sidecar::{MapSidecar, MapSidecarValidation, MarkerSpec, SpawnSpec, LADDER_MARKER_KIND};
 
 #[derive(Clone, Debug)]
 pub struct TestMapCollider {
@@ -23,12 +27,31 @@ pub struct TestMapCollider {
     pub collider: Collider,
 }
 
+impl TestMapCollider {
+    pub fn is_trigger(&self) -> bool {
+        self.tags.iter().any(|tag| tag == TRIGGER_TAG)
+    }
+
+    pub fn is_climbable(&self) -> bool {
+        self.tags.iter().any(|tag| tag == CLIMBABLE_TAG)
+    }
+
+    pub fn liquid_tag(&self) -> Option<&str> {
+        liquid_tag(&self.tags)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TestMapColliderSet {
     pub colliders: Vec<TestMapCollider>,
 }
 
-pub fn build_test_map_collision_world(map: &TestMap) -> Result<CollisionWorld, String> {
+/// Cooks the static solids of `map`; solids whose tags select a non-default
+/// layer in `layers` record it on their chunk.
+pub fn build_test_map_collision_world(
+    map: &TestMap,
+    layers: &CollisionLayerTable,
+) -> Result<CollisionWorld, String> {
     let solids = map.expanded_solids()?;
     let scale = map.map_to_world_scale.unwrap_or(1.0);
     if !scale.is_finite() || scale <= 0.0 {
@@ -40,8 +63,11 @@ pub fn build_test_map_collision_world(map: &TestMap) -> Result<CollisionWorld, S
     }
 
     let mut chunks = Vec::new();
-    for solid in solids {
-        let scaled = scale_solid(&solid, scale);
+    let static_solids = solids.iter().filter(|solid| {
+        !solid.is_trigger() && solid.liquid_tag().is_none() && !map.is_mover_solid(&solid.id)
+    });
+    for solid in static_solids {
+        let scaled = scale_solid(solid, scale);
         let bounds = solid_bounds(&scaled);
         let triangle_count = solid_triangle_count(&scaled);
         chunks.push(CollisionChunk {
@@ -51,6 +77,8 @@ pub fn build_test_map_collision_world(map: &TestMap) -> Result<CollisionWorld, S
             payload_ref: format!("inline:test_map/{}", scaled.id),
             triangle_count,
             partition_hint: None,
+            layer: chunk_layer(layers, &scaled.tags),
+            surface: None,
         });
     }
 
@@ -104,6 +132,11 @@ pub fn build_test_map_colliders(map: &TestMap) -> Result<TestMapColliderSet, Str
     Ok(TestMapColliderSet { colliders })
 }
 
+fn chunk_layer(layers: &CollisionLayerTable, tags: &[String]) -> Option<String> {
+    let layer = layers.layer_for_tags(tags);
+    (layer != layers.default_layer).then(|| layer.to_string())
+}
+
 fn build_collider(solid: &ResolvedSolid) -> Result<Collider, String> {
     let size = solid.size;
     let half = [size[0] * 0.5, size[1] * 0.5, size[2] * 0.5];
@@ -111,24 +144,36 @@ fn build_collider(solid: &ResolvedSolid) -> Result<Collider, String> {
     let rotation = solid_rotation(solid.yaw_deg, solid.rot_euler_deg);
     let iso = Isometry::from_parts(translation, rotation);
 
-    let collider = match solid.kind {
-        SolidKind::Box | SolidKind::BoxRot => ColliderBuilder::cuboid(half[0], half[1], half[2])
-            .position(iso)
-            .build(),
+    let builder = match solid.kind {
+        SolidKind::Box | SolidKind::BoxRot => ColliderBuilder::cuboid(half[0], half[1], half[2]),
         SolidKind::Cylinder => {
             let radius = half[0].max(half[2]);
             ColliderBuilder::cylinder(half[1], radius)
-                .position(iso)
-                .build()
         }
         SolidKind::Ramp => {
             let (vertices, indices) = ramp_mesh(size[0], size[1], size[2]);
             ColliderBuilder::trimesh(vertices, indices)
-                .position(iso)
-                .build()
         }
     };
-    Ok(collider)
+    let sensor = solid.is_trigger() || solid.liquid_tag().is_some();
+    Ok(builder.position(iso).sensor(sensor).build())
+}
+
+/// Box sensor for a sidecar marker with a `volume`; `None` for point markers.
+pub fn build_marker_sensor(marker: &MarkerSpec) -> Option<Collider> {
+    let volume = marker.volume?;
+    let translation = Translation::from(Vector::new(
+        marker.origin[0],
+        marker.origin[1],
+        marker.origin[2],
+    ));
+    let rotation = solid_rotation(marker.yaw_deg, None);
+    Some(
+        ColliderBuilder::cuboid(volume[0] * 0.5, volume[1] * 0.5, volume[2] * 0.5)
+            .position(Isometry::from_parts(translation, rotation))
+            .sensor(true)
+            .build(),
+    )
 }
 
 fn scale_solid(solid: &ResolvedSolid, scale: f32) -> ResolvedSolid {
@@ -287,7 +332,112 @@ mod tests {
             .join("flat_friction_lane.toml");
         let text = std::fs::read_to_string(&map_path).expect("read test map");
         let map = TestMap::parse_toml(&text).expect("parse map");
-        let world = build_test_map_collision_world(&map).expect("build collision world");
+        let world = build_test_map_collision_world(&map, &CollisionLayerTable::default())
+            .expect("build collision world");
         assert!(!world.chunks.is_empty());
     }
+
+    #[test]
+    fn trigger_solids_become_sensors_outside_collision_world() {
+        let text = r#"
+version = 1
+name = "pad"
+
+[[solids]]
+id = "floor"
+kind = "box"
+pos = [0.0, -0.5, 0.0]
+size = [10.0, 1.0, 10.0]
+
+[[solids]]
+id = "pad"
+kind = "box"
+pos = [0.0, 1.0, 0.0]
+size = [2.0, 2.0, 2.0]
+tags = ["trigger"]
+
+[[solids]]
+id = "pool"
+kind = "box"
+pos = [4.0, 0.5, 0.0]
+size = [2.0, 1.0, 2.0]
+tags = ["lava"]
+"#;
+        let map = TestMap::parse_toml(text).expect("parse map");
+        let colliders = build_test_map_colliders(&map).expect("build colliders");
+        let pad = &colliders.colliders[1];
+        assert!(pad.is_trigger());
+        assert!(pad.collider.is_sensor());
+        let pool = &colliders.colliders[2];
+        assert_eq!(pool.liquid_tag(), Some("lava"));
+        assert!(pool.collider.is_sensor());
+        assert!(!colliders.colliders[0].collider.is_sensor());
+        let world = build_test_map_collision_world(&map, &CollisionLayerTable::default())
+            .expect("build collision world");
+        assert_eq!(world.chunks.len(), 1);
+
+        let sidecar = MapSidecar::parse_toml(
+            r#"
+version = 1
+map_id = "pad"
+map_to_world_scale = 1.0
+
+[[markers]]
+id = "exit"
+kind = "trigger"
+origin = [0.0, 1.0, 5.0]
+volume = [2.0, 2.0, 1.0]
+
+[[markers]]
+id = "lookout"
+kind = "camera"
+origin = [0.0, 4.0, 0.0]
+
+[[markers]]
+id = "rope"
+kind = "ladder"
+origin = [3.0, 2.0, 0.0]
+volume = [1.0, 4.0, 1.0]
+"#,
+        )
+        .expect("sidecar");
+        assert!(sidecar.validate().is_ok());
+        assert!(build_marker_sensor(&sidecar.markers[0]).is_some_and(|sensor| sensor.is_sensor()));
+        assert!(build_marker_sensor(&sidecar.markers[1]).is_none());
+        assert!(sidecar.markers[2].is_ladder());
+        let mut loose = sidecar.clone();
+        loose.markers[2].volume = None;
+        assert_eq!(loose.validate().errors.len(), 1);
+    }
+
+    #[test]
+    fn clip_tags_record_chunk_layers() {
+        let text = r#"
+version = 1
+name = "clip"
+
+[[solids]]
+id = "floor"
+kind = "box"
+pos = [0.0, -0.5, 0.0]
+size = [10.0, 1.0, 10.0]
+tags = ["ground"]
+
+[[solids]]
+id = "monster_wall"
+kind = "box"
+pos = [0.0, 1.0, 0.0]
+size = [0.2, 2.0, 4.0]
+tags = ["wall", "monster_clip"]
+"#;
+        let map = TestMap::parse_toml(text).expect("parse map");
+        let layers = CollisionLayerTable::default();
+        let world = build_test_map_collision_world(&map, &layers).expect("build collision world");
+        assert_eq!(world.chunks[0].layer, None);
+        assert_eq!(world.chunks[1].layer.as_deref(), Some("monster_clip"));
+        let groups = layers.chunk_groups(&world.chunks[1]);
+        assert_eq!(groups.memberships & layers.named_mask("player"), 0);
+        let text = world.to_toml().expect("serialize");
+        assert_eq!(text.matches("layer =").count(), 1);
+    }
 }
@@ -178,6 +178,8 @@ fn emit_chunk(
         payload_ref: format!("inline:{}/chunk_{:04}", label, index),
         triangle_count,
         partition_hint: None,
+        layer: None,
+        surface: None,
     });
 }
 
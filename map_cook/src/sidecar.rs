@@ -22,6 +22,9 @@ pub struct SpawnSpec {
     pub yaw_deg: Option<f32>,
 }
 
+/// Marker kind whose volume is a climbable ladder zone.
+pub const LADDER_MARKER_KIND: &str = "ladder";
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct MarkerSpec {
     pub id: String,
@@ -29,6 +32,15 @@ pub struct MarkerSpec {
     pub origin: [f32; 3],
     #[serde(default)]
     pub yaw_deg: Option<f32>,
+    /// Full box size centered on `origin`; markers with a volume act as triggers.
+    #[serde(default)]
+    pub volume: Option<[f32; 3]>,
+}
+
+impl MarkerSpec {
+    pub fn is_ladder(&self) -> bool {
+        self.kind == LADDER_MARKER_KIND
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -100,6 +112,19 @@ impl MapSidecar {
                     .errors
                     .push("marker origin must be finite".to_string());
             }
+            if marker.is_ladder() && marker.volume.is_none() {
+                validation
+                    .errors
+                    .push(format!("ladder marker {} requires a volume", marker.id));
+            }
+            if let Some(volume) = marker.volume {
+                if !vector_is_finite(volume) || volume.iter().any(|extent| *extent <= 0.0) {
+                    validation.errors.push(format!(
+                        "marker {} volume must be finite and > 0",
+                        marker.id
+                    ));
+                }
+            }
         }
         validation
     }
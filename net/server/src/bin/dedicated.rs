@@ -2,6 +2,7 @@ use std::net::SocketAddr;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use engine_game::{GameSession, SandboxRules, SpawnPoint};
 use net_transport::TransportConfig;
 use server::Server;
 
@@ -30,6 +31,10 @@ fn main() {
             std::process::exit(1);
         }
     };
+    // No map loading yet: everyone spawns at the origin of an empty sandbox.
+    let mut session = GameSession::new(SandboxRules::default());
+    session.enter_map("sandbox", vec![SpawnPoint::new([0.0, 0.0, 0.0])]);
+    server.attach_session(session);
 
     let addr = match server.local_addr() {
         Ok(addr) => addr,
@@ -5,6 +5,9 @@ use std::collections::HashMap;
 use std::fmt;
 use std::net::SocketAddr;
 
+use bevy_ecs::entity::Entity;
+use ecs::Vec3;
+use engine_game::GameSession;
 use net_protocol::{
     Connect, DeltaSnapshot, Disconnect, InputCommand, ProtocolError, ProtocolMessage, Snapshot,
     SnapshotEntity,
@@ -40,6 +43,19 @@ struct ClientState {
     last_input: Option<InputCommand>,
     last_seq: u32,
     last_snapshot: Option<Snapshot>,
+    player: Option<Entity>,
+}
+
+impl ClientState {
+    fn new(player: Option<Entity>) -> Self {
+        Self {
+            entity: EntityState::default(),
+            last_input: None,
+            last_seq: 0,
+            last_snapshot: None,
+            player,
+        }
+    }
 }
 
 pub struct Server {
@@ -47,6 +63,8 @@ pub struct Server {
     tick: u32,
     snapshot_stride: u32,
     clients: HashMap<SocketAddr, ClientState>,
+    session: Option<GameSession>,
+    next_slot: u32,
 }
 
 pub struct TickReport {
@@ -90,6 +108,8 @@ impl Server {
             tick: 0,
             snapshot_stride: snapshot_stride.max(1),
             clients: HashMap::new(),
+            session: None,
+            next_slot: 0,
         })
     }
 
@@ -110,6 +130,26 @@ impl Server {
         self.clients.len()
     }
 
+    /// Hands spawning, respawn and map transitions to `session`. Every client
+    /// becomes a session player; the session sees server-space positions and
+    /// ticks at the server's fixed step.
+    pub fn attach_session(&mut self, mut session: GameSession) {
+        session.set_fixed_dt(FIXED_DT);
+        for client in self.clients.values_mut() {
+            client.player = Some(session.add_player(self.next_slot));
+            self.next_slot = self.next_slot.wrapping_add(1);
+        }
+        self.session = Some(session);
+    }
+
+    pub fn session(&self) -> Option<&GameSession> {
+        self.session.as_ref()
+    }
+
+    pub fn session_mut(&mut self) -> Option<&mut GameSession> {
+        self.session.as_mut()
+    }
+
     pub fn tick(&mut self) -> Result<TickReport, ServerError> {
         let mut report = TickReport {
             new_clients: 0,
@@ -134,12 +174,8 @@ impl Server {
                         Entry::Occupied(entry) => entry.into_mut(),
                         Entry::Vacant(entry) => {
                             report.new_clients += 1;
-                            entry.insert(ClientState {
-                                entity: EntityState::default(),
-                                last_input: None,
-                                last_seq: 0,
-                                last_snapshot: None,
-                            })
+                            let player = add_session_player(&mut self.session, &mut self.next_slot);
+                            entry.insert(ClientState::new(player))
                         }
                     };
                     if cmd.client_seq == client.last_seq
@@ -162,6 +198,7 @@ impl Server {
             client.entity.position[0] += client.entity.velocity[0] * FIXED_DT;
             client.entity.position[2] += client.entity.velocity[2] * FIXED_DT;
         }
+        self.tick_session();
 
         if self.tick.is_multiple_of(self.snapshot_stride) {
             let entities: Vec<SnapshotEntity> = self
@@ -225,22 +262,57 @@ impl Server {
         Ok(report)
     }
 
+    /// Mirrors client positions into the session, then applies the spawns its
+    /// tick placed.
+    fn tick_session(&mut self) {
+        let Some(session) = self.session.as_mut() else {
+            return;
+        };
+        for client in self.clients.values() {
+            if let Some(player) = client.player {
+                let [x, y, z] = client.entity.position;
+                session.set_player_position(player, Vec3 { x, y, z });
+            }
+        }
+        for spawned in session.tick() {
+            let Some(client) = self
+                .clients
+                .values_mut()
+                .find(|client| client.player == Some(spawned.entity))
+            else {
+                continue;
+            };
+            client.entity.position = spawned.point.origin;
+            client.entity.velocity = [0.0, 0.0, 0.0];
+            if let Some(yaw_deg) = spawned.point.yaw_deg {
+                client.entity.yaw = yaw_deg.to_radians();
+            }
+        }
+    }
+
     fn register_client(&mut self, addr: SocketAddr, _connect: Connect) {
         if let Entry::Vacant(entry) = self.clients.entry(addr) {
-            entry.insert(ClientState {
-                entity: EntityState::default(),
-                last_input: None,
-                last_seq: 0,
-                last_snapshot: None,
-            });
+            let player = add_session_player(&mut self.session, &mut self.next_slot);
+            entry.insert(ClientState::new(player));
         }
     }
 
     fn unregister_client(&mut self, addr: SocketAddr, _disconnect: Disconnect) {
-        self.clients.remove(&addr);
+        let removed = self.clients.remove(&addr);
+        let player = removed.and_then(|client| client.player);
+        if let (Some(session), Some(player)) = (self.session.as_mut(), player) {
+            session.remove_player(player);
+        }
     }
 }
 
+fn add_session_player(session: &mut Option<GameSession>, next_slot: &mut u32) -> Option<Entity> {
+    let session = session.as_mut()?;
+    let player = session.add_player(*next_slot);
+    *next_slot = next_slot.wrapping_add(1);
+    Some(player)
+}
+
 fn seq_more_recent(a: u32, b: u32) -> bool {
     let diff = a.wrapping_sub(b);
     diff != 0 && diff < 0x8000_0000
@@ -271,6 +343,7 @@ fn delta_entities(
 mod tests {
     use super::*;
     use client::{Client, ClientInput};
+    use engine_game::{SandboxRules, SpawnPoint};
     use net_protocol::Snapshot;
     use net_transport::{LoopbackTransport, TransportConfig};
 
@@ -309,6 +382,60 @@ mod tests {
         assert_eq!(server.client_count(), 0);
     }
 
+    #[test]
+    fn session_spawns_clients_and_moves_them_on_map_change() {
+        let transport = TransportConfig::default();
+        let mut server_transport =
+            LoopbackTransport::bind(transport.clone()).expect("loopback bind");
+        let mut client_transport = LoopbackTransport::bind(transport).expect("loopback bind");
+        let server_addr = server_transport.local_addr().expect("server addr");
+        let client_addr = client_transport.local_addr().expect("client addr");
+        server_transport.connect_peer(client_addr);
+        client_transport.connect_peer(server_addr);
+
+        let mut server = Server::bind(Box::new(server_transport), 1).expect("server bind");
+        let mut session = GameSession::new(SandboxRules::default());
+        session.enter_map("start", vec![SpawnPoint::new([100.0, 4.0, -50.0])]);
+        server.attach_session(session);
+        let mut client =
+            Client::connect(Box::new(client_transport), server_addr, 1).expect("client connect");
+        let idle = ClientInput {
+            move_x: 0.0,
+            move_y: 0.0,
+            yaw: 0.0,
+            pitch: 0.0,
+            buttons: 0,
+        };
+
+        for _ in 0..3 {
+            client.send_input(idle).expect("send input");
+            server.tick().expect("server tick");
+            client.poll().expect("client poll");
+        }
+        let snapshot = client.last_snapshot().expect("snapshot");
+        assert_eq!(snapshot.entities[0].position, [100.0, 4.0, -50.0]);
+
+        server
+            .session_mut()
+            .expect("session")
+            .enter_map("next", vec![SpawnPoint::new([-8.0, 0.0, 12.0])]);
+        for _ in 0..3 {
+            client.send_input(idle).expect("send input");
+            server.tick().expect("server tick");
+            client.poll().expect("client poll");
+        }
+        let snapshot = client.last_snapshot().expect("snapshot");
+        assert_eq!(snapshot.entities[0].position, [-8.0, 0.0, 12.0]);
+        assert_eq!(
+            server.session().and_then(GameSession::current_map),
+            Some("next")
+        );
+
+        client.disconnect().expect("disconnect");
+        server.tick().expect("server tick");
+        assert_eq!(server.client_count(), 0);
+    }
+
     #[test]
     fn replay_produces_identical_snapshots() {
         let inputs = build_inputs(120);
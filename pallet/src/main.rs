@@ -9,24 +9,21 @@ use std::sync::{mpsc, Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use audio::AudioEngine;
+use bevy_ecs::entity::Entity;
 use character_collision::CollisionProfile;
 use character_motor_arena::{
-    build_move_intent, golden_angle_metrics, ArenaMotor, ArenaMotorConfig, ArenaMotorInput,
-    ArenaMotorState, FrictionlessJumpMode,
-};
-use character_motor_rpg::{
-    build_move_intent as build_move_intent_rpg, RpgMotor, RpgMotorConfig, RpgMotorInput,
-    RpgMotorState,
+    build_move_intent, golden_angle_metrics, ArenaMotor, ArenaMotorTimers,
 };
+use character_motor_rpg::{build_move_intent as build_move_intent_rpg, RpgMotor};
 use client::{Client, ClientInput};
-use collision_world::{Aabb as CollisionAabb, CollisionWorld};
-use compat_quake::bsp::{self, Bsp, SpawnPoint};
-use compat_quake::lmp;
+use collision_world::{Aabb as CollisionAabb, CollisionLayerTable, CollisionWorld, SurfaceTable};
+use compat_quake::bsp::{self, Bsp, BspEntity};
+use compat_quake::{lmp, wad};
 use engine_core::asset_id::AssetKey;
 use engine_core::asset_manager::{
     AssetBudgetTag, AssetEntrySnapshot, AssetManager, AssetPriority, AssetStatus, BlobAsset,
-    CollisionWorldAsset, ConfigAsset, QuakeRawAsset, RequestOpts, ScriptAsset, TestMapAsset,
-    TextAsset, TextureAsset,
+    CollisionWorldAsset, ConfigAsset, PrefabAsset, QuakeRawAsset, RequestOpts, ScriptAsset,
+    TestMapAsset, TextAsset, TextureAsset,
 };
 use engine_core::asset_resolver::{
     AssetLayer, AssetResolver, AssetSource, ResolveReport, ResolvedLocation, ResolvedPath,
@@ -39,35 +36,57 @@ use engine_core::control_plane::{
 use engine_core::jobs::{JobQueue, Jobs};
 use engine_core::level_manifest::{
     discover_level_manifests, load_level_manifest, resolve_level_manifest_path, LevelManifest,
-    LevelManifestPath,
+    LevelManifestPath, PrefabPlacement,
 };
 use engine_core::logging::{self, LogLevel};
 use engine_core::mount_manifest::{load_mount_manifest, MountManifestEntry};
 use engine_core::observability;
 use engine_core::path_policy::{ConfigKind, PathOverrides, PathPolicy};
+use engine_core::prefab::{resolve_prefab, PrefabDef};
 use engine_core::quake_index::{QuakeEntry, QuakeIndex};
 use engine_core::vfs::{MountKind, Vfs, VfsError};
-use map_cook::build_test_map_colliders;
+use engine_game::course::{
+    course_rules, settle_velocity, test_map_arena_config, CoursePhysics, CourseSource,
+    TEST_MAP_EYE_HEIGHT, TEST_MAP_KILL_DEPTH,
+};
+use engine_game::liquid::InLiquid;
+use engine_game::map_entities::{MapEntity, QUAKE_MAP_TO_WORLD_SCALE};
+use engine_game::movement_telemetry::MovementRecorder;
+use engine_game::movement_trace;
+use engine_game::save::{
+    game_save_registry, read_save_file, save_path, write_save_file, PlayerSave, SaveGame,
+    ScriptEntitySave, ScriptHostSave,
+};
+use engine_game::spawn::{capsule_offset, drop_to_ground, SpawnPoint as GameSpawnPoint};
+use engine_game::{
+    Course, GameSession, LiquidDamageEvent, MotorKind, MotorProfile, MotorRegistry, MotorSet,
+    QuakeLevel, QuakeLevelSource, SandboxRules, TriggerEvent,
+};
+use map_cook::MapSidecar;
 use net_transport::{LoopbackTransport, Transport, TransportConfig};
-use physics_rapier::PhysicsWorld;
+use physics_rapier::{
+    layer_mask_groups, ColliderSource, DynamicBodyDesc, DynamicBodyShape, PhysicsWorld,
+};
 use platform_winit::{
     create_window, ControlFlow, CursorGrabMode, DeviceEvent, ElementState, Event, Fullscreen, Ime,
     KeyCode, ModifiersState, MouseButton, MouseScrollDelta, PhysicalKey, PhysicalPosition,
     PhysicalSize, Window, WindowEvent,
 };
-use player_camera::PlayerCamera;
+use player_camera::{CameraEffectsTuning, CameraFeel, CameraMode, CameraModeTuning};
 use player_controller::{
-    DirectInputAdapter, InputIntent, Motor as ControllerMotor, MotorContext, MotorOutput,
-    PlayerController, PlayerKinematics, RawInput,
+    DirectInputAdapter, Footstep, Motor, PlayerController, PlayerKinematics, RawInput,
 };
 use rapier3d::math::{Isometry, Vector};
-use rapier3d::prelude::{ColliderHandle, Real};
+use rapier3d::prelude::{ColliderHandle, Cuboid, InteractionGroups, Point, Real};
 use render_wgpu::{
     FrameCapture, ImageData, MeshData, MeshVertex, RenderCaptureError, RenderError, TextBounds,
     TextFontSystem, TextLayer, TextOverlay, TextOverlayTimings, TextPosition, TextSpan, TextStyle,
     TextViewport, UploadPriority, UploadQueue, YuvImageView,
 };
-use script_lua::{HostCallbacks, ScriptConfig, ScriptEngine, SpawnRequest};
+use script_lua::{
+    HitSource, HostCallbacks, SceneQuery, ScriptConfig, ScriptEngine, ScriptError, SpawnRequest,
+    TraceHit,
+};
 use server::Server;
 use test_map::{ResolvedSolid, SolidKind, TestMap};
 use video::{
@@ -133,29 +152,10 @@ const CONSOLE_SELECTION_LINE_LIMIT: usize = 128;
 const ASSET_LIST_DEFAULT_LIMIT: usize = 200;
 const ASSET_LIST_MAX_LIMIT: usize = 1000;
 const TEST_MAP_CYLINDER_SEGMENTS: usize = 16;
-const TEST_MAP_MOVE_SPEED: f32 = 4.5;
-const TEST_MAP_ACCEL: f32 = 30.0;
-const TEST_MAP_AIR_ACCEL: f32 = 18.0;
-const TEST_MAP_FRICTION: f32 = 18.0;
-const TEST_MAP_STOP_SPEED: f32 = 4.0;
-const TEST_MAP_GRAVITY: f32 = 9.81;
-const TEST_MAP_JUMP_SPEED: f32 = 4.5;
-const TEST_MAP_MAX_AIR_SPEED: f32 = 6.0;
-const TEST_MAP_AIR_RESISTANCE: f32 = 1.0;
-const TEST_MAP_GOLDEN_ANGLE_TARGET: f32 = 45.0 * std::f32::consts::PI / 180.0;
-const TEST_MAP_GOLDEN_ANGLE_GAIN_MIN: f32 = 1.0;
-const TEST_MAP_GOLDEN_ANGLE_GAIN_PEAK: f32 = 1.25;
-const TEST_MAP_GOLDEN_ANGLE_BLEND_START: f32 = TEST_MAP_MOVE_SPEED;
-const TEST_MAP_GOLDEN_ANGLE_BLEND_END: f32 = TEST_MAP_MOVE_SPEED * 1.4;
-const TEST_MAP_CORRIDOR_SHAPING_STRENGTH_DEG: f32 = 80.0;
-const TEST_MAP_CORRIDOR_SHAPING_MIN_SPEED: f32 = 10.0;
-const TEST_MAP_CORRIDOR_SHAPING_MAX_ANGLE_DEG: f32 = 6.0;
-const TEST_MAP_CORRIDOR_SHAPING_MIN_ALIGNMENT: f32 = 0.2;
-const TEST_MAP_JUMP_BUFFER_WINDOW: f32 = 0.1;
-const TEST_MAP_BHOP_GRACE: f32 = 0.1;
-const TEST_MAP_BHOP_FRICTION_SCALE: f32 = 0.25;
-const TEST_MAP_BHOP_FRICTION_SCALE_BEST_ANGLE: f32 = 0.0;
-const TEST_MAP_EYE_HEIGHT: f32 = 1.6;
+const TEST_MAP_SPAWN_DROP: f32 = 4.0;
+const COLLISION_LAYERS_CONFIG: &str = "physics/collision_layers.toml";
+const SURFACES_CONFIG: &str = "physics/surfaces.toml";
+const DEV_TRACE_DISTANCE: f32 = 64.0;
 const COLLISION_INTEREST_RADIUS: f32 = 12.0;
 const KCC_QUERY_SMOOTHING: f32 = 0.1;
 const UI_REGRESSION_MIN_FONT_PX: f32 = 9.0;
@@ -221,6 +221,17 @@ const PLAYER_JUMP_SPEED: f32 = 270.0;
 const PLAYER_EYE_HEIGHT: f32 = 22.0;
 const PLAYER_STEP_HEIGHT: f32 = 18.0;
 const PLAYER_MAX_DROP: f32 = 256.0;
+const PLAYER_SWIM_LEVEL: f32 = 0.5;
+const PLAYER_SWIM_SPEED_SCALE: f32 = 0.7;
+const PLAYER_WATER_SINK_SPEED: f32 = 60.0;
+// Quake's player hull spans 32x32 units and -24..32 around the origin.
+const QUAKE_PLAYER_HALF_WIDTH: f32 = 16.0;
+const QUAKE_PLAYER_HALF_HEIGHT: f32 = 28.0;
+const QUAKE_PLAYER_CENTER_OFFSET: f32 = 4.0;
+const MAP_SESSION_DT: f32 = 1.0 / 60.0;
+const MAP_SESSION_MAX_ACCUM: f32 = 0.25;
+/// Motor label of the scene camera in BSP map saves.
+const MAP_CAMERA_MOTOR: &str = "map_camera";
 const FLOOR_NORMAL_MIN: f32 = 0.7;
 const DIST_EPSILON: f32 = 0.03125;
 const CONTENTS_SOLID: i32 = -2;
@@ -466,124 +477,17 @@ enum SceneKind {
     TestMap,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-enum MotorKind {
-    Arena,
-    Rpg,
-}
-
-impl MotorKind {
-    fn from_cvar(value: i32) -> Self {
-        if value == 2 {
-            MotorKind::Rpg
-        } else {
-            MotorKind::Arena
-        }
-    }
-}
-
-struct DualMotor {
-    kind: MotorKind,
-    arena: ArenaMotor,
-    rpg: RpgMotor,
-}
-
-impl DualMotor {
-    fn new(arena_config: ArenaMotorConfig, rpg_config: RpgMotorConfig) -> Self {
-        Self {
-            kind: MotorKind::Arena,
-            arena: ArenaMotor::new(arena_config),
-            rpg: RpgMotor::new(rpg_config),
-        }
-    }
-
-    fn kind(&self) -> MotorKind {
-        self.kind
-    }
-
-    fn set_kind(&mut self, kind: MotorKind) {
-        if self.kind == kind {
-            return;
-        }
-        self.kind = kind;
-        self.reset_states();
-    }
-
-    fn reset_states(&mut self) {
-        self.arena.reset_state();
-        self.rpg.reset_state();
-    }
-
-    fn arena_config(&self) -> ArenaMotorConfig {
-        self.arena.config()
-    }
-
-    fn arena_config_mut(&mut self) -> &mut ArenaMotorConfig {
-        self.arena.config_mut()
-    }
-
-    fn rpg_config(&self) -> RpgMotorConfig {
-        self.rpg.config()
-    }
-}
-
-impl ControllerMotor for DualMotor {
-    fn step(
-        &mut self,
-        input: &InputIntent,
-        state: &PlayerKinematics,
-        ctx: MotorContext,
-    ) -> MotorOutput {
-        match self.kind {
-            MotorKind::Arena => {
-                let motor_state = ArenaMotorState {
-                    velocity: state.velocity,
-                    grounded: state.grounded,
-                    ground_normal: state.ground_normal,
-                    yaw: ctx.yaw,
-                };
-                let motor_output = self.arena.step(
-                    ArenaMotorInput {
-                        move_axis: input.move_axis,
-                        jump: input.jump,
-                    },
-                    motor_state,
-                    ctx.dt,
-                );
-                MotorOutput {
-                    desired_translation: motor_output.desired_translation,
-                    next_velocity: motor_output.next_velocity,
-                }
-            }
-            MotorKind::Rpg => {
-                let motor_state = RpgMotorState {
-                    velocity: state.velocity,
-                    grounded: state.grounded,
-                    ground_normal: state.ground_normal,
-                    yaw: ctx.yaw,
-                };
-                let motor_output = self.rpg.step(
-                    RpgMotorInput {
-                        move_axis: input.move_axis,
-                        jump: input.jump,
-                    },
-                    motor_state,
-                    ctx.dt,
-                );
-                MotorOutput {
-                    desired_translation: motor_output.desired_translation,
-                    next_velocity: motor_output.next_velocity,
-                }
-            }
-        }
-    }
-}
-
 struct LoadedScene {
     mesh: MeshData,
     bounds: Bounds,
     collision: Option<SceneCollision>,
-    spawn: Option<SpawnPoint>,
+    submodel_meshes: Vec<SubmodelMesh>,
+    entities: Vec<MapEntity>,
+    sidecar: Option<MapSidecar>,
+    /// `[[prefab]]` entries of the map's level manifest.
+    placements: Vec<PrefabPlacement>,
+    /// Raw BSP, kept for the map session's movers.
+    bsp: Option<Arc<Vec<u8>>>,
     kind: SceneKind,
     test_map: Option<TestMapSceneData>,
 }
@@ -593,20 +497,20 @@ struct TestMapSceneData {
     map: TestMap,
     collision_world_key: AssetKey,
     collision_world: CollisionWorld,
+    layers: CollisionLayerTable,
+    surfaces: SurfaceTable,
 }
 
 struct CollisionWorldRuntime {
     world: CollisionWorld,
-    loaded_chunks: Vec<u32>,
-    collider_handles: Vec<ColliderHandle>,
-    triangle_count: u64,
+    physics: CoursePhysics,
 }
 
 struct TestMapRuntime {
     key: AssetKey,
     world: PhysicsWorld,
     collision_world: CollisionWorldRuntime,
-    controller: PlayerController<DirectInputAdapter, DualMotor>,
+    controller: PlayerController<DirectInputAdapter, MotorSet>,
     position: Isometry<Real>,
     prev_position: Isometry<Real>,
     velocity: Vec3,
@@ -615,6 +519,29 @@ struct TestMapRuntime {
     ground_normal: Option<Vector<Real>>,
     capsule_offset: f32,
     kcc_query_ms: f32,
+    session: GameSession,
+    player: Entity,
+    /// Names the masks `dev_trace` and script queries accept.
+    layers: CollisionLayerTable,
+    /// Raised by the fixed ticks since the last frame, forwarded to scripts.
+    trigger_events: Vec<TriggerEvent>,
+    liquid_damage_events: Vec<LiquidDamageEvent>,
+    surfaces: SurfaceTable,
+    /// Taken since the last frame, waiting to be played.
+    footsteps: Vec<Footstep>,
+    /// Decoded footstep sounds by asset; `None` once loading failed.
+    footstep_sounds: HashMap<String, Option<Vec<u8>>>,
+    footstep_count: usize,
+    /// Session entity the chase camera follows.
+    chase_entity: Option<Entity>,
+    /// View positions of the last two ticks, interpolated outside first person.
+    prev_eye: Vector<Real>,
+    eye: Vector<Real>,
+    /// View effects of the last two ticks, interpolated like the eye.
+    prev_feel: CameraFeel,
+    feel: CameraFeel,
+    /// Active `dev_movement_record` name and samples.
+    movement_record: Option<(String, MovementRecorder)>,
 }
 
 enum MapRequest {
@@ -622,6 +549,13 @@ enum MapRequest {
     TestMap(AssetKey),
 }
 
+struct TestMapReloadRequest {
+    /// Test map, or BSP map when loading a save taken on one.
+    map: MapRequest,
+    /// Save to apply once the map has been (re)entered.
+    save: Option<Box<SaveGame>>,
+}
+
 #[allow(clippy::too_many_arguments)]
 fn enter_map_scene(
     renderer: &mut render_wgpu::Renderer,
@@ -633,6 +567,7 @@ fn enter_map_scene(
     camera: &mut CameraState,
     collision: &mut Option<SceneCollision>,
     test_map_runtime: &mut Option<TestMapRuntime>,
+    map_session: &mut Option<MapSession>,
     scene_active: &mut bool,
     mouse_look: &mut bool,
     mouse_grabbed: &mut bool,
@@ -648,29 +583,30 @@ fn enter_map_scene(
 
     *collision = scene.collision;
     *camera = CameraState::from_bounds(&scene.bounds, collision.as_ref());
-    if let Some(spawn) = scene.spawn {
-        let base = quake_to_render(spawn.origin);
-        camera.position = camera.camera_from_origin(base);
-        if let Some(angle) = spawn.angle {
-            camera.yaw = (90.0 - angle).to_radians();
-            camera.pitch = 0.0;
-        }
-        // Test-only spawn from BSP entities; M8 Lua spawning will replace this path.
-        if let Some(scene) = collision.as_ref() {
-            camera.snap_to_floor(scene);
+    *map_session = None;
+    if scene.kind == SceneKind::Bsp {
+        let (map, spawn) = build_map_session(
+            asset_manager,
+            map,
+            &scene.entities,
+            scene.sidecar.as_ref(),
+            &scene.placements,
+            scene.bsp.as_ref().map_or(&[][..], |bytes| bytes.as_slice()),
+            &scene.bounds,
+            scene.submodel_meshes,
+        );
+        if let Some(spawn) = spawn {
+            place_camera_at_map_spawn(&map, camera, collision.as_ref(), &spawn);
         }
+        *map_session = Some(map);
     }
     *test_map_runtime = scene
         .test_map
-        .map(|data| build_test_map_runtime(&data, &scene.bounds))
+        .map(|data| build_test_map_runtime(&data))
         .transpose()?;
     if let Some(runtime) = test_map_runtime.as_mut() {
-        let tuning = match runtime.controller.motor().kind() {
-            MotorKind::Arena => camera_tuning_from_arena(runtime.controller.motor().arena_config()),
-            MotorKind::Rpg => camera_tuning_from_rpg(runtime.controller.motor().rpg_config()),
-        };
+        let tuning = camera_tuning_from_motor(runtime.controller.motor());
         configure_test_map_camera(camera, &scene.bounds, tuning);
-        snap_test_map_runtime_to_ground(runtime, &scene.bounds);
         runtime
             .controller
             .camera_mut()
@@ -740,22 +676,27 @@ struct InputState {
     down: bool,
 }
 
+/// The `<motor>_<param>` cvar that feeds one motor tuning param.
 #[derive(Clone, Copy, Debug)]
+struct MotorParamCvar {
+    motor: MotorKind,
+    param: &'static str,
+    flag: bool,
+    id: CvarId,
+}
+
+#[derive(Clone, Debug)]
 struct MovementCvars {
-    air_max_speed: CvarId,
-    air_accel: CvarId,
-    air_resistance: CvarId,
-    air_resistance_speed_scale: CvarId,
-    golden_target_deg: CvarId,
-    golden_gain_min: CvarId,
-    golden_gain_peak: CvarId,
-    golden_bonus_scale: CvarId,
-    golden_blend_start: CvarId,
-    golden_blend_end: CvarId,
-    corridor_shaping_strength: CvarId,
-    corridor_shaping_min_speed: CvarId,
-    corridor_shaping_max_angle: CvarId,
-    corridor_shaping_min_alignment: CvarId,
+    motor_params: Vec<MotorParamCvar>,
+    cam_mode: CvarId,
+    cam_boom_length: CvarId,
+    cam_chase_distance: CvarId,
+    cam_spectator_speed: CvarId,
+    cam_blend_time: CvarId,
+    cam_bob: CvarId,
+    cam_dip: CvarId,
+    cam_roll: CvarId,
+    cam_fov_kick: CvarId,
     dev_motor: CvarId,
     dev_fixed_dt: CvarId,
     dev_substeps: CvarId,
@@ -2392,6 +2333,7 @@ impl InputRouter {
 
 const INPUT_SCRIPT_STEP_DELAY_MS: u64 = 200;
 const INPUT_TRACE_DIR: &str = ".pallet/input_traces";
+const MOVEMENT_RECORD_DIR: &str = ".pallet/movement_records";
 
 struct InputScript {
     step: usize,
@@ -2478,96 +2420,63 @@ impl InputTracePlayback {
 }
 
 fn input_trace_path(name: &str) -> Result<PathBuf, String> {
+    dev_record_path(INPUT_TRACE_DIR, "input trace", name, "trace")
+}
+
+fn movement_record_path(name: &str) -> Result<PathBuf, String> {
+    dev_record_path(MOVEMENT_RECORD_DIR, "movement record", name, "csv")
+}
+
+fn dev_record_path(dir: &str, what: &str, name: &str, extension: &str) -> Result<PathBuf, String> {
     if name.trim().is_empty() {
-        return Err("input trace name must not be empty".to_string());
+        return Err(format!("{} name must not be empty", what));
     }
     if !name
         .chars()
         .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_' || ch == '-')
     {
-        return Err("input trace name must be [a-z0-9_-]".to_string());
+        return Err(format!("{} name must be [a-z0-9_-]", what));
     }
-    Ok(PathBuf::from(INPUT_TRACE_DIR).join(format!("{name}.trace")))
+    Ok(PathBuf::from(dir).join(format!("{name}.{extension}")))
 }
 
 fn serialize_input_trace(frames: &[InputTraceFrame]) -> String {
-    let mut text = String::from("# pallet_input_trace_v1\n");
-    for frame in frames {
-        text.push_str(&format!(
-            "{:.6} {:.6} {:.6} {} {} {} {} {} {}\n",
-            frame.dt,
-            frame.yaw,
-            frame.pitch,
-            bool_to_bit(frame.input.forward),
-            bool_to_bit(frame.input.back),
-            bool_to_bit(frame.input.left),
-            bool_to_bit(frame.input.right),
-            bool_to_bit(frame.input.jump_active()),
-            bool_to_bit(frame.input.down),
-        ));
-    }
-    text
+    let frames: Vec<_> = frames
+        .iter()
+        .map(|frame| movement_trace::InputTraceFrame {
+            dt: frame.dt,
+            yaw: frame.yaw,
+            pitch: frame.pitch,
+            forward: frame.input.forward,
+            back: frame.input.back,
+            left: frame.input.left,
+            right: frame.input.right,
+            jump: frame.input.jump_active(),
+            down: frame.input.down,
+        })
+        .collect();
+    movement_trace::serialize_input_trace(&frames)
 }
 
 fn parse_input_trace(text: &str) -> Result<Vec<InputTraceFrame>, String> {
-    let mut frames = Vec::new();
-    for (index, line) in text.lines().enumerate() {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed.starts_with('#') {
-            continue;
-        }
-        let parts: Vec<&str> = trimmed.split_whitespace().collect();
-        if parts.len() != 9 {
-            return Err(format!("trace line {} malformed", index + 1));
-        }
-        let dt = parts[0]
-            .parse::<f32>()
-            .map_err(|_| format!("trace line {} dt invalid", index + 1))?;
-        let yaw = parts[1]
-            .parse::<f32>()
-            .map_err(|_| format!("trace line {} yaw invalid", index + 1))?;
-        let pitch = parts[2]
-            .parse::<f32>()
-            .map_err(|_| format!("trace line {} pitch invalid", index + 1))?;
-        let forward = parse_trace_bit(parts[3], index)?;
-        let back = parse_trace_bit(parts[4], index)?;
-        let left = parse_trace_bit(parts[5], index)?;
-        let right = parse_trace_bit(parts[6], index)?;
-        let jump = parse_trace_bit(parts[7], index)?;
-        let down = parse_trace_bit(parts[8], index)?;
-        let input = InputState {
-            forward,
-            back,
-            left,
-            right,
-            jump_keyboard: jump,
-            jump_mouse: false,
-            down,
-        };
-        frames.push(InputTraceFrame {
-            dt,
-            yaw,
-            pitch,
-            input,
-        });
-    }
-    Ok(frames)
-}
-
-fn parse_trace_bit(value: &str, line_index: usize) -> Result<bool, String> {
-    match value {
-        "0" => Ok(false),
-        "1" => Ok(true),
-        _ => Err(format!("trace line {} bool invalid", line_index + 1)),
-    }
-}
-
-fn bool_to_bit(value: bool) -> u8 {
-    if value {
-        1
-    } else {
-        0
-    }
+    let frames = movement_trace::parse_input_trace(text)?;
+    Ok(frames
+        .into_iter()
+        .map(|frame| InputTraceFrame {
+            dt: frame.dt,
+            yaw: frame.yaw,
+            pitch: frame.pitch,
+            input: InputState {
+                forward: frame.forward,
+                back: frame.back,
+                left: frame.left,
+                right: frame.right,
+                jump_keyboard: frame.jump,
+                jump_mouse: false,
+                down: frame.down,
+            },
+        })
+        .collect())
 }
 
 fn write_input_trace(recorder: InputTraceRecorder) -> Result<PathBuf, String> {
@@ -2912,6 +2821,34 @@ impl ScriptHostState {
         id
     }
 
+    fn save_state(&self) -> ScriptHostSave {
+        ScriptHostSave {
+            next_id: self.next_id,
+            entities: self
+                .entities
+                .iter()
+                .map(|entity| ScriptEntitySave {
+                    id: entity.id,
+                    position: [entity.position.x, entity.position.y, entity.position.z],
+                    yaw: entity.yaw,
+                })
+                .collect(),
+        }
+    }
+
+    fn restore_state(&mut self, save: &ScriptHostSave) {
+        self.next_id = save.next_id.max(1);
+        self.entities = save
+            .entities
+            .iter()
+            .map(|entity| ScriptEntity {
+                id: entity.id,
+                position: Vec3::new(entity.position[0], entity.position[1], entity.position[2]),
+                yaw: entity.yaw,
+            })
+            .collect();
+    }
+
     fn play_sound(&mut self, asset: String) -> Result<(), String> {
         let audio = self
             .audio
@@ -2931,7 +2868,7 @@ impl ScriptHostState {
 
 struct ScriptRuntime {
     engine: ScriptEngine,
-    _host: Rc<RefCell<ScriptHostState>>,
+    host: Rc<RefCell<ScriptHostState>>,
 }
 
 struct LoopbackNet {
@@ -3005,6 +2942,12 @@ struct CameraState {
     step_height: f32,
     max_drop: f32,
     sensitivity: f32,
+    /// View roll in radians, from test map camera effects.
+    roll: f32,
+    /// Added to `CAMERA_FOV_Y`, from test map camera effects.
+    fov_offset: f32,
+    /// Immersion in BSP liquids, 0 dry to 1 submerged, from the map session.
+    water_level: f32,
 }
 
 impl CameraState {
@@ -3078,30 +3021,34 @@ impl CameraState {
             wish_dir = wish_dir.sub(right);
         }
 
-        if self.on_ground {
-            let speed = self.velocity.length();
-            if speed > 0.0 {
-                let control = speed.max(PLAYER_STOP_SPEED);
-                let drop = control * self.friction * dt;
-                let new_speed = (speed - drop).max(0.0);
-                self.velocity = self.velocity.scale(new_speed / speed);
+        if self.water_level >= PLAYER_SWIM_LEVEL {
+            self.swim(input, dt);
+        } else {
+            if self.on_ground {
+                let speed = self.velocity.length();
+                if speed > 0.0 {
+                    let control = speed.max(PLAYER_STOP_SPEED);
+                    let drop = control * self.friction * dt;
+                    let new_speed = (speed - drop).max(0.0);
+                    self.velocity = self.velocity.scale(new_speed / speed);
+                }
             }
-        }
-        if wish_dir.length() > 0.0 {
-            let wish_dir = wish_dir.normalize_or_zero();
-            let current_speed = self.velocity.dot(wish_dir);
-            let add_speed = self.speed - current_speed;
-            if add_speed > 0.0 {
-                let accel_speed = (self.accel * dt * self.speed).min(add_speed);
-                self.velocity = self.velocity.add(wish_dir.scale(accel_speed));
+            if wish_dir.length() > 0.0 {
+                let wish_dir = wish_dir.normalize_or_zero();
+                let current_speed = self.velocity.dot(wish_dir);
+                let add_speed = self.speed - current_speed;
+                if add_speed > 0.0 {
+                    let accel_speed = (self.accel * dt * self.speed).min(add_speed);
+                    self.velocity = self.velocity.add(wish_dir.scale(accel_speed));
+                }
             }
-        }
 
-        if self.on_ground && input.jump_active() {
-            self.vertical_velocity = self.jump_speed;
-            self.on_ground = false;
+            if self.on_ground && input.jump_active() {
+                self.vertical_velocity = self.jump_speed;
+                self.on_ground = false;
+            }
+            self.vertical_velocity -= self.gravity * dt;
         }
-        self.vertical_velocity -= self.gravity * dt;
 
         let origin = self.collision_origin();
         let velocity = Vec3::new(self.velocity.x, self.vertical_velocity, self.velocity.z);
@@ -3109,9 +3056,9 @@ impl CameraState {
             let origin = scene.try_unstuck(origin);
             let (mut new_origin, new_velocity, mut on_ground) =
                 scene.move_with_step(origin, velocity, dt, self.step_height);
-            if scene.hull_point_contents(scene.headnode, new_origin) == CONTENTS_SOLID {
+            if scene.point_contents(new_origin) == CONTENTS_SOLID {
                 let unstuck = scene.try_unstuck(new_origin);
-                if scene.hull_point_contents(scene.headnode, unstuck) != CONTENTS_SOLID {
+                if scene.point_contents(unstuck) != CONTENTS_SOLID {
                     new_origin = unstuck;
                 }
             }
@@ -3137,6 +3084,54 @@ impl CameraState {
         }
     }
 
+    /// Quake-style water movement: steers along the full view direction at
+    /// reduced speed, jump and crouch swim up and down, and an idle player
+    /// slowly sinks. Friction applies in every direction.
+    fn swim(&mut self, input: &InputState, dt: f32) {
+        let mut velocity = Vec3::new(self.velocity.x, self.vertical_velocity, self.velocity.z);
+        let speed = velocity.length();
+        if speed > 0.0 {
+            let new_speed = (speed - speed * self.friction * dt).max(0.0);
+            velocity = velocity.scale(new_speed / speed);
+        }
+        let forward = self.forward();
+        let right = self.right_flat();
+        let mut wish = Vec3::zero();
+        if input.forward {
+            wish = wish.add(forward);
+        }
+        if input.back {
+            wish = wish.sub(forward);
+        }
+        if input.right {
+            wish = wish.add(right);
+        }
+        if input.left {
+            wish = wish.sub(right);
+        }
+        if input.jump_active() {
+            wish = wish.add(CAMERA_UP);
+        }
+        if input.down {
+            wish = wish.sub(CAMERA_UP);
+        }
+        let (wish_dir, wish_speed) = if wish.length() > 0.0 {
+            (
+                wish.normalize_or_zero(),
+                self.speed * PLAYER_SWIM_SPEED_SCALE,
+            )
+        } else {
+            (CAMERA_UP.scale(-1.0), PLAYER_WATER_SINK_SPEED)
+        };
+        let add_speed = wish_speed - velocity.dot(wish_dir);
+        if add_speed > 0.0 {
+            let accel_speed = (self.accel * dt * wish_speed).min(add_speed);
+            velocity = velocity.add(wish_dir.scale(accel_speed));
+        }
+        self.velocity = Vec3::new(velocity.x, 0.0, velocity.z);
+        self.vertical_velocity = velocity.y;
+    }
+
     fn update_fly(&mut self, input: &InputState, dt: f32) {
         let mut direction = Vec3::zero();
         let forward = self.forward();
@@ -3202,7 +3197,8 @@ impl CameraState {
 
     fn view_proj(&self, aspect: f32) -> [[f32; 4]; 4] {
         let view = self.view_matrix();
-        let proj = perspective(CAMERA_FOV_Y, aspect, CAMERA_NEAR, CAMERA_FAR);
+        let fov_y = CAMERA_FOV_Y + self.fov_offset;
+        let proj = perspective(fov_y, aspect, CAMERA_NEAR, CAMERA_FAR);
         mat4_mul(proj, view)
     }
 
@@ -3210,6 +3206,11 @@ impl CameraState {
         let forward = self.forward();
         let right = forward.cross(CAMERA_UP).normalize_or_zero();
         let up = right.cross(forward).normalize_or_zero();
+        let (sin_roll, cos_roll) = self.roll.sin_cos();
+        let (right, up) = (
+            right.scale(cos_roll).sub(up.scale(sin_roll)),
+            up.scale(cos_roll).add(right.scale(sin_roll)),
+        );
         [
             [right.x, up.x, -forward.x, 0.0],
             [right.y, up.y, -forward.y, 0.0],
@@ -3242,11 +3243,14 @@ impl Default for CameraState {
             step_height: PLAYER_STEP_HEIGHT,
             max_drop: PLAYER_MAX_DROP,
             sensitivity: 0.0025,
+            roll: 0.0,
+            fov_offset: 0.0,
+            water_level: 0.0,
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 struct Vec3 {
     x: f32,
     y: f32,
@@ -3387,6 +3391,16 @@ struct SceneCollision {
     planes: Vec<CollisionPlane>,
     clipnodes: Vec<ClipNode>,
     headnode: i32,
+    /// Inline brush models that block the player, moved by the map session.
+    submodels: Vec<SubmodelHull>,
+}
+
+/// Player clip hull of an inline brush model, displaced from where the BSP
+/// put it by its mover.
+struct SubmodelHull {
+    model: u32,
+    headnode: i32,
+    offset: Vec3,
 }
 
 struct CollisionPlane {
@@ -3556,7 +3570,32 @@ impl SceneCollision {
         (pos, vel, on_ground, blocked)
     }
 
+    /// Traces the world and every submodel, keeping the nearest hit.
     fn trace(&self, start: Vec3, end: Vec3) -> Trace {
+        let mut trace = self.trace_world(start, end);
+        for submodel in &self.submodels {
+            if trace.start_solid {
+                break;
+            }
+            let hit = self.trace_submodel(submodel, start, end);
+            if hit.start_solid || hit.fraction < trace.fraction {
+                trace = hit;
+            }
+        }
+        trace
+    }
+
+    fn trace_world(&self, start: Vec3, end: Vec3) -> Trace {
+        self.trace_hull(self.headnode, Vec3::zero(), start, end)
+    }
+
+    fn trace_submodel(&self, submodel: &SubmodelHull, start: Vec3, end: Vec3) -> Trace {
+        self.trace_hull(submodel.headnode, submodel.offset, start, end)
+    }
+
+    // Moves the trace into the hull's frame rather than the hull, like
+    // Quake's SV_ClipMoveToEntity.
+    fn trace_hull(&self, headnode: i32, offset: Vec3, start: Vec3, end: Vec3) -> Trace {
         let mut trace = Trace {
             fraction: 1.0,
             end,
@@ -3564,7 +3603,14 @@ impl SceneCollision {
             start_solid: false,
             all_solid: true,
         };
-        let _ = self.recursive_hull_check(self.headnode, 0.0, 1.0, start, end, &mut trace);
+        let _ = self.recursive_hull_check(
+            headnode,
+            0.0,
+            1.0,
+            start.sub(offset),
+            end.sub(offset),
+            &mut trace,
+        );
         if trace.start_solid {
             trace.fraction = 0.0;
             trace.end = start;
@@ -3578,18 +3624,70 @@ impl SceneCollision {
         trace
     }
 
+    /// `CONTENTS_SOLID` inside the world or any submodel, else the world's
+    /// contents at `point`.
+    fn point_contents(&self, point: Vec3) -> i32 {
+        let contents = self.hull_point_contents(self.headnode, point);
+        if contents == CONTENTS_SOLID
+            || self
+                .submodels
+                .iter()
+                .any(|submodel| self.submodel_contents(submodel, point) == CONTENTS_SOLID)
+        {
+            return CONTENTS_SOLID;
+        }
+        contents
+    }
+
+    fn submodel_contents(&self, submodel: &SubmodelHull, point: Vec3) -> i32 {
+        self.hull_point_contents(submodel.headnode, point.sub(submodel.offset))
+    }
+
+    /// Moves submodel `model` to `offset`, carrying the player at `origin`
+    /// along when they stand on it or it moves into them. Pushes stop at
+    /// the world. Returns the player's new origin.
+    fn move_submodel(&mut self, model: u32, offset: Vec3, origin: Vec3, on_ground: bool) -> Vec3 {
+        let Some(index) = self
+            .submodels
+            .iter()
+            .position(|submodel| submodel.model == model)
+        else {
+            return origin;
+        };
+        let delta = offset.sub(self.submodels[index].offset);
+        if delta.length() <= 0.0 {
+            return origin;
+        }
+        let below = origin.add(Vec3::new(0.0, -2.0, 0.0));
+        let riding = on_ground && {
+            let trace = self.trace_submodel(&self.submodels[index], origin, below);
+            !trace.start_solid && trace.fraction < 1.0
+        };
+        self.submodels[index].offset = offset;
+        let blocked = self.submodel_contents(&self.submodels[index], origin) == CONTENTS_SOLID;
+        if !riding && !blocked {
+            return origin;
+        }
+        let trace = self.trace_world(origin, origin.add(delta));
+        if trace.start_solid {
+            origin
+        } else {
+            trace.end
+        }
+    }
+
     fn has_headroom(&self, origin: Vec3, up: Vec3) -> bool {
         let trace = self.trace(origin, up);
         !trace.start_solid && trace.fraction >= 1.0
     }
 
     fn try_unstuck(&self, position: Vec3) -> Vec3 {
-        if self.hull_point_contents(self.headnode, position) != CONTENTS_SOLID {
+        if self.point_contents(position) != CONTENTS_SOLID {
             return position;
         }
         for dy in 1..=64 {
             let candidate = position.add(Vec3::new(0.0, -(dy as f32), 0.0));
-            if self.hull_point_contents(self.headnode, candidate) != CONTENTS_SOLID {
+            if self.point_contents(candidate) != CONTENTS_SOLID {
                 return candidate;
             }
         }
@@ -3603,7 +3701,7 @@ impl SceneCollision {
                             continue;
                         }
                         let candidate = position.add(Vec3::new(dx * radius, dy, dz * radius));
-                        if self.hull_point_contents(self.headnode, candidate) != CONTENTS_SOLID {
+                        if self.point_contents(candidate) != CONTENTS_SOLID {
                             return candidate;
                         }
                     }
@@ -4044,7 +4142,7 @@ fn main() {
         }
         script = Some(ScriptRuntime {
             engine,
-            _host: host_state,
+            host: host_state,
         });
     }
 
@@ -4152,6 +4250,7 @@ fn main() {
     let mut camera = CameraState::default();
     let mut collision: Option<SceneCollision> = None;
     let mut test_map_runtime: Option<TestMapRuntime> = None;
+    let mut map_session: Option<MapSession> = None;
     let mut fly_mode = false;
     let mut scene_active = false;
     let mut loopback: Option<LoopbackNet> = None;
@@ -4160,7 +4259,7 @@ fn main() {
     let mut ignore_cursor_move = false;
     let mut was_mouse_look = false;
     let mut pending_map: Option<String> = None;
-    let mut test_map_reload_requests: VecDeque<AssetKey> = VecDeque::new();
+    let mut test_map_reload_requests: VecDeque<TestMapReloadRequest> = VecDeque::new();
     let mut fixed_dt_accum = 0.0_f32;
 
     if let Some(asset) = args.show_image.as_deref() {
@@ -4502,6 +4601,7 @@ fn main() {
                             &mut test_map_reload_requests,
                             test_map_runtime.as_ref().map(|runtime| runtime.key.clone()),
                             test_map_runtime.as_mut(),
+                            map_session.as_mut(),
                             &mut camera,
                             &mut input_trace_record,
                             &mut input_trace_playback,
@@ -4773,6 +4873,7 @@ fn main() {
                                                 &mut camera,
                                                 &mut collision,
                                                 &mut test_map_runtime,
+                                                &mut map_session,
                                                 &mut scene_active,
                                                 &mut mouse_look,
                                                 &mut mouse_grabbed,
@@ -4836,6 +4937,7 @@ fn main() {
                                         &mut test_map_reload_requests,
                                         test_map_runtime.as_ref().map(|runtime| runtime.key.clone()),
                                         test_map_runtime.as_mut(),
+                                        map_session.as_mut(),
                                         &mut camera,
                                         &mut input_trace_record,
                                         &mut input_trace_playback,
@@ -4898,6 +5000,7 @@ fn main() {
                                         &mut test_map_reload_requests,
                                         test_map_runtime.as_ref().map(|runtime| runtime.key.clone()),
                                         test_map_runtime.as_mut(),
+                                        map_session.as_mut(),
                                         &mut camera,
                                         &mut input_trace_record,
                                         &mut input_trace_playback,
@@ -4942,6 +5045,7 @@ fn main() {
                                         &mut test_map_reload_requests,
                                         test_map_runtime.as_ref().map(|runtime| runtime.key.clone()),
                                         test_map_runtime.as_mut(),
+                                        map_session.as_mut(),
                                         &mut camera,
                                         &mut input_trace_record,
                                         &mut input_trace_playback,
@@ -4993,6 +5097,7 @@ fn main() {
                                         &mut test_map_reload_requests,
                                         test_map_runtime.as_ref().map(|runtime| runtime.key.clone()),
                                         test_map_runtime.as_mut(),
+                                        map_session.as_mut(),
                                         &mut camera,
                                         &mut input_trace_record,
                                         &mut input_trace_playback,
@@ -5037,6 +5142,7 @@ fn main() {
                                         &mut test_map_reload_requests,
                                         test_map_runtime.as_ref().map(|runtime| runtime.key.clone()),
                                         test_map_runtime.as_mut(),
+                                        map_session.as_mut(),
                                         &mut camera,
                                         &mut input_trace_record,
                                         &mut input_trace_playback,
@@ -5239,6 +5345,7 @@ fn main() {
                                 &mut camera,
                                 &mut collision,
                                 &mut test_map_runtime,
+                                &mut map_session,
                                 &mut scene_active,
                                 &mut mouse_look,
                                 &mut mouse_grabbed,
@@ -5286,7 +5393,13 @@ fn main() {
                     }
 
                     if let Some(script) = script.as_mut() {
-                        if let Err(err) = script.engine.on_tick(dt) {
+                        let result = match test_map_runtime.as_ref() {
+                            Some(runtime) => script
+                                .engine
+                                .with_scene(runtime, |engine| engine.on_tick(dt)),
+                            None => script.engine.on_tick(dt),
+                        };
+                        if let Err(err) = result {
                             eprintln!("lua on_tick failed: {}", err);
                         }
                     }
@@ -5309,6 +5422,7 @@ fn main() {
                         &mut test_map_reload_requests,
                         test_map_runtime.as_ref().map(|runtime| runtime.key.clone()),
                         &mut test_map_runtime,
+                        &mut map_session,
                         &mut camera,
                         &mut input_trace_record,
                         &mut input_trace_playback,
@@ -5605,8 +5719,11 @@ fn main() {
                         }
                     }
 
-                    if let Some(key) = test_map_reload_requests.pop_front() {
-                        let map_id = key.canonical().to_string();
+                    if let Some(request) = test_map_reload_requests.pop_front() {
+                        let map_id = match &request.map {
+                            MapRequest::Bsp(name) => name.clone(),
+                            MapRequest::TestMap(key) => key.canonical().to_string(),
+                        };
                         match enter_map_scene(
                             &mut renderer,
                             window,
@@ -5617,6 +5734,7 @@ fn main() {
                             &mut camera,
                             &mut collision,
                             &mut test_map_runtime,
+                            &mut map_session,
                             &mut scene_active,
                             &mut mouse_look,
                             &mut mouse_grabbed,
@@ -5624,15 +5742,42 @@ fn main() {
                             &mut loopback,
                         ) {
                             Ok(()) => {
+                                console.push_line(format!("map reload complete: {}", map_id));
+                                if let Some(save) = request.save {
+                                    let result =
+                                        match (test_map_runtime.as_mut(), map_session.as_mut()) {
+                                            (Some(runtime), _) => apply_save_game(
+                                                &save,
+                                                runtime,
+                                                &mut camera,
+                                                &mut cvars,
+                                                script.as_ref(),
+                                            ),
+                                            (None, Some(map)) => apply_map_save_game(
+                                                &save,
+                                                map,
+                                                &mut camera,
+                                                collision.as_mut(),
+                                                &mut cvars,
+                                                script.as_ref(),
+                                            ),
+                                            (None, None) => {
+                                                Err("map has no game session".to_string())
+                                            }
+                                        };
+                                    match result {
+                                        Ok(()) => console
+                                            .push_line(format!("load complete: {}", map_id)),
+                                        Err(err) => {
+                                            console.push_line(format!("load failed: {}", err))
+                                        }
+                                    }
+                                }
                                 current_map = Some(map_id);
-                                console.push_line(format!(
-                                    "test map reload complete: {}",
-                                    key.canonical()
-                                ));
                             }
                             Err(err) => {
                                 console.push_line(format!(
-                                    "test map reload failed: {}",
+                                    "map reload failed: {}",
                                     err.message
                                 ));
                             }
@@ -5707,9 +5852,46 @@ fn main() {
                             update_test_map_runtime(runtime, &mut camera, &input, dt);
                         }
                     }
+                    let trigger_events = std::mem::take(&mut runtime.trigger_events);
+                    let liquid_damage = std::mem::take(&mut runtime.liquid_damage_events);
+                    let footsteps = std::mem::take(&mut runtime.footsteps);
+                    if let (Some(audio), Some(_)) = (audio.as_ref(), quake_vfs.as_ref()) {
+                        play_footsteps(runtime, audio, &asset_manager, &footsteps);
+                    }
+                    if let Some(script) = script.as_mut() {
+                        let scene: &TestMapRuntime = runtime;
+                        let result = script.engine.with_scene(scene, |engine| {
+                            dispatch_game_events(
+                                engine,
+                                &runtime.session,
+                                &trigger_events,
+                                &liquid_damage,
+                            )
+                        });
+                        if let Err(err) = result {
+                            eprintln!("lua game event hook failed: {}", err);
+                        }
+                    }
                 } else {
                             camera.update(&input, dt, collision.as_ref(), fly_mode);
                         }
+                        if let Some(map) = map_session.as_mut() {
+                            update_map_session(map, &mut camera, collision.as_mut(), dt);
+                            draw_map_submodels(map, &mut renderer);
+                            let trigger_events = std::mem::take(&mut map.trigger_events);
+                            let liquid_damage = std::mem::take(&mut map.liquid_damage_events);
+                            if let Some(script) = script.as_mut() {
+                                let result = dispatch_game_events(
+                                    &mut script.engine,
+                                    &map.session,
+                                    &trigger_events,
+                                    &liquid_damage,
+                                );
+                                if let Err(err) = result {
+                                    eprintln!("lua game event hook failed: {}", err);
+                                }
+                            }
+                        }
                         let aspect = aspect_ratio(renderer.size());
                         renderer.update_camera(camera.view_proj(aspect));
                         if let Some(loopback_net) = loopback.as_mut() {
@@ -5868,9 +6050,9 @@ fn main() {
                                         lines.push(format!(
                                             "collision: chunks={} loaded={} colliders={} tris={}",
                                             collision_world.world.chunks.len(),
-                                            collision_world.loaded_chunks.len(),
-                                            collision_world.collider_handles.len(),
-                                            collision_world.triangle_count
+                                            collision_world.physics.loaded_chunks.len(),
+                                            collision_world.physics.collider_handles.len(),
+                                            collision_world.physics.triangle_count
                                         ));
                                         lines.push(format!(
                                             "collision_near={} kcc_ms={:.3}",
@@ -5886,41 +6068,33 @@ fn main() {
                                             bool_to_axis(input.right, input.left),
                                             bool_to_axis(input.forward, input.back),
                                         ];
-                                        let (intent_mag, max_speed, golden_config) =
-                                            match runtime.controller.motor().kind() {
-                                                MotorKind::Arena => {
-                                                    let config =
-                                                        runtime.controller.motor().arena_config();
-                                                    let intent = build_move_intent(
-                                                        camera.yaw,
-                                                        move_axis,
-                                                        runtime.grounded,
-                                                        runtime.ground_normal,
-                                                    );
-                                                    let max_speed = if runtime.grounded {
-                                                        config.max_speed_ground
-                                                    } else {
-                                                        config.max_speed_air
-                                                    };
-                                                    (intent.mag, max_speed, Some(config))
-                                                }
-                                                MotorKind::Rpg => {
-                                                    let config =
-                                                        runtime.controller.motor().rpg_config();
-                                                    let intent = build_move_intent_rpg(
-                                                        camera.yaw,
-                                                        move_axis,
-                                                        runtime.grounded,
-                                                        runtime.ground_normal,
-                                                    );
-                                                    let max_speed = if runtime.grounded {
-                                                        config.max_speed_ground
-                                                    } else {
-                                                        config.max_speed_air
-                                                    };
-                                                    (intent.mag, max_speed, None)
-                                                }
-                                            };
+                                        let motor = runtime.controller.motor();
+                                        let arena = motor.active_as::<ArenaMotor>();
+                                        let intent_mag = if arena.is_some() {
+                                            build_move_intent(
+                                                camera.yaw,
+                                                move_axis,
+                                                runtime.grounded,
+                                                runtime.ground_normal,
+                                            )
+                                            .mag
+                                        } else {
+                                            build_move_intent_rpg(
+                                                camera.yaw,
+                                                move_axis,
+                                                runtime.grounded,
+                                                runtime.ground_normal,
+                                            )
+                                            .mag
+                                        };
+                                        let max_speed_param = if runtime.grounded {
+                                            "ground_max_speed"
+                                        } else {
+                                            "air_max_speed"
+                                        };
+                                        let max_speed =
+                                            motor.param(max_speed_param).unwrap_or(0.0);
+                                        let golden_config = arena.map(ArenaMotor::config);
                                         let intent_speed = intent_mag * max_speed;
                                         let planar_velocity = Vector::new(
                                             runtime.velocity.x,
@@ -5960,7 +6134,19 @@ fn main() {
                                                 lines.push("golden: theta=-- gain=--".to_string());
                                             }
                                         } else {
-                                            lines.push("golden: -- (rpg motor)".to_string());
+                                            lines.push(format!(
+                                                "golden: -- ({} motor)",
+                                                motor.kind().label()
+                                            ));
+                                        }
+                                        if let Some((name, recorder)) =
+                                            runtime.movement_record.as_ref()
+                                        {
+                                            lines.push(format!(
+                                                "record: {} ticks={}",
+                                                name,
+                                                recorder.len()
+                                            ));
                                         }
                                     } else {
                                         lines.push("move: no test map runtime".to_string());
@@ -7177,6 +7363,7 @@ fn parse_args() -> Result<CliArgs, ArgParseError> {
 fn print_usage() {
     eprintln!("usage: pallet [--quake-dir <path>] [--mount-dir <vroot> <path>] [--mount-pak <vroot> <path>] [--mount-pk3 <vroot> <path>] [--mount-manifest <name-or-path>] [--content-root <path>] [--dev-root <path>] [--config-root <path>] [--show-image <asset>] [--map <name|engine:test_map/...>] [--play-movie <file>] [--playlist <name>] [--script <name>] [--input-script] [--smoke <script> [--gtimeout-ms <ms>]] [--debug-resolution] [--dev-motor <1|2>] [--ui-regression-shot <path> --ui-regression-res <WxH> --ui-regression-dpi <scale> --ui-regression-ui-scale <scale> --ui-regression-screen <main|options>]");
     eprintln!("example: pallet --quake-dir \"C:\\\\Quake\" --show-image gfx/conback.lmp");
+    eprintln!("example: pallet --quake-dir \"C:\\\\Quake\" --show-image gfx.wad#conchars");
     eprintln!("example: pallet --show-image engine:texture/ui/pallet_runner_gui_icon.png");
     eprintln!("example: pallet --quake-dir \"C:\\\\Quake\" --map e1m1");
     eprintln!("example: pallet --quake-dir \"C:\\\\Quake\" --map e1m1 --script demo.lua");
@@ -7713,9 +7900,10 @@ struct PalletCommandUser<'a> {
     capture_requests: &'a mut VecDeque<CaptureRequest>,
     settings: &'a mut Settings,
     settings_flags: &'a mut SettingsChangeFlags,
-    test_map_reload_requests: &'a mut VecDeque<AssetKey>,
+    test_map_reload_requests: &'a mut VecDeque<TestMapReloadRequest>,
     active_test_map: Option<AssetKey>,
     test_map_runtime: Option<&'a mut TestMapRuntime>,
+    map_session: Option<&'a mut MapSession>,
     camera: Option<&'a mut CameraState>,
     input_trace_record: &'a mut Option<InputTraceRecorder>,
     input_trace_playback: &'a mut Option<InputTracePlayback>,
@@ -7773,9 +7961,10 @@ fn dispatch_console_line(
     capture_requests: &mut VecDeque<CaptureRequest>,
     settings: &mut Settings,
     settings_flags: &mut SettingsChangeFlags,
-    test_map_reload_requests: &mut VecDeque<AssetKey>,
+    test_map_reload_requests: &mut VecDeque<TestMapReloadRequest>,
     active_test_map: Option<AssetKey>,
     test_map_runtime: Option<&mut TestMapRuntime>,
+    map_session: Option<&mut MapSession>,
     camera: Option<&mut CameraState>,
     input_trace_record: &mut Option<InputTraceRecorder>,
     input_trace_playback: &mut Option<InputTracePlayback>,
@@ -7797,6 +7986,7 @@ fn dispatch_console_line(
                 test_map_reload_requests,
                 active_test_map,
                 test_map_runtime,
+                map_session,
                 camera,
                 input_trace_record,
                 input_trace_playback,
@@ -7857,6 +8047,7 @@ fn dispatch_smoke_command(
                 test_map_reload_requests: &mut test_map_reload_requests,
                 active_test_map: None,
                 test_map_runtime: None,
+                map_session: None,
                 camera: None,
                 input_trace_record: &mut input_trace_record,
                 input_trace_playback: &mut input_trace_playback,
@@ -7893,9 +8084,10 @@ fn drain_lua_command_queue(
     capture_requests: &mut VecDeque<CaptureRequest>,
     settings: &mut Settings,
     settings_flags: &mut SettingsChangeFlags,
-    test_map_reload_requests: &mut VecDeque<AssetKey>,
+    test_map_reload_requests: &mut VecDeque<TestMapReloadRequest>,
     active_test_map: Option<AssetKey>,
     test_map_runtime: &mut Option<TestMapRuntime>,
+    map_session: &mut Option<MapSession>,
     camera: &mut CameraState,
     input_trace_record: &mut Option<InputTraceRecorder>,
     input_trace_playback: &mut Option<InputTracePlayback>,
@@ -7927,6 +8119,7 @@ fn drain_lua_command_queue(
             test_map_reload_requests,
             active_test_map.clone(),
             test_map_runtime.as_mut(),
+            map_session.as_mut(),
             Some(camera),
             input_trace_record,
             input_trace_playback,
@@ -7992,106 +8185,51 @@ fn register_cvar_alias_command<'a>(
     Ok(())
 }
 
-struct PlayerTuneParam {
-    name: &'static str,
-    cvar_name: &'static str,
-    help: &'static str,
-}
-
-const PLAYER_TUNE_PARAMS: &[PlayerTuneParam] = &[
-    PlayerTuneParam {
-        name: "air_max_speed",
-        cvar_name: "arena_air_max_speed",
-        help: "Arena air max speed.",
-    },
-    PlayerTuneParam {
-        name: "air_accel",
-        cvar_name: "arena_air_accel",
-        help: "Arena air acceleration.",
-    },
-    PlayerTuneParam {
-        name: "air_resist",
-        cvar_name: "arena_air_resistance",
-        help: "Arena air resistance.",
-    },
-    PlayerTuneParam {
-        name: "air_resist_scale",
-        cvar_name: "arena_air_resistance_speed_scale",
-        help: "Arena air resistance speed scaling.",
-    },
-    PlayerTuneParam {
-        name: "golden_target_deg",
-        cvar_name: "arena_golden_target_deg",
-        help: "Golden angle target (degrees).",
-    },
-    PlayerTuneParam {
-        name: "golden_gain_min",
-        cvar_name: "arena_golden_gain_min",
-        help: "Golden angle min gain.",
-    },
-    PlayerTuneParam {
-        name: "golden_gain_peak",
-        cvar_name: "arena_golden_gain_peak",
-        help: "Golden angle peak gain.",
-    },
-    PlayerTuneParam {
-        name: "golden_bonus_scale",
-        cvar_name: "arena_golden_bonus_scale",
-        help: "Golden angle bonus scale.",
-    },
-    PlayerTuneParam {
-        name: "golden_blend_start",
-        cvar_name: "arena_golden_blend_start",
-        help: "Golden angle blend start speed.",
-    },
-    PlayerTuneParam {
-        name: "golden_blend_end",
-        cvar_name: "arena_golden_blend_end",
-        help: "Golden angle blend end speed.",
-    },
-    PlayerTuneParam {
-        name: "cs_strength_deg",
-        cvar_name: "arena_cs_strength_deg",
-        help: "Corridor shaping strength (deg/sec).",
-    },
-    PlayerTuneParam {
-        name: "cs_min_speed",
-        cvar_name: "arena_cs_min_speed",
-        help: "Corridor shaping minimum speed.",
-    },
-    PlayerTuneParam {
-        name: "cs_max_angle_deg",
-        cvar_name: "arena_cs_max_angle_deg",
-        help: "Corridor shaping max angle per tick (degrees).",
-    },
-    PlayerTuneParam {
-        name: "cs_min_align",
-        cvar_name: "arena_cs_min_alignment",
-        help: "Corridor shaping minimum alignment.",
-    },
-];
-
-fn resolve_player_tune_param(name: &str) -> Option<&'static PlayerTuneParam> {
-    PLAYER_TUNE_PARAMS
-        .iter()
-        .find(|param| param.name == name || param.cvar_name == name)
-}
-
-fn parse_motor_kind(input: &str) -> Result<MotorKind, String> {
-    match input {
-        "arena" => Ok(MotorKind::Arena),
-        "rpg" => Ok(MotorKind::Rpg),
-        "1" => Ok(MotorKind::Arena),
-        "2" => Ok(MotorKind::Rpg),
-        _ => Err("expected arena or rpg".to_string()),
+/// Resolves a `player_tune_set` key: a cvar name, a param of the active
+/// motor, or a param of any other registered motor.
+fn resolve_player_tune_cvar(cvars: &CvarRegistry, name: &str) -> Option<String> {
+    if cvars.get_by_name(name).is_some() {
+        return Some(name.to_string());
     }
+    let motors = MotorRegistry::builtin();
+    let active = cvars
+        .get_by_name("dev_motor")
+        .and_then(|entry| match entry.value {
+            CvarValue::Int(value) => motors.from_cvar(value),
+            _ => None,
+        });
+    let found = active
+        .into_iter()
+        .chain(motors.kinds())
+        .map(|kind| motor_param_cvar_name(kind, name))
+        .find(|cvar_name| cvars.get_by_name(cvar_name).is_some());
+    found
 }
 
-fn motor_kind_label(kind: MotorKind) -> &'static str {
-    match kind {
-        MotorKind::Arena => "arena",
-        MotorKind::Rpg => "rpg",
-    }
+/// Loads `engine:config/motors/<name>.toml`.
+fn load_motor_profile(
+    asset_manager: &AssetManager,
+    name: &str,
+    registry: &MotorRegistry,
+) -> Result<MotorProfile, String> {
+    let path = if name.ends_with(".toml") {
+        name.to_string()
+    } else {
+        format!("{}.toml", name)
+    };
+    let key = config_asset_key("motors", &path)?;
+    let handle = asset_manager.request::<ConfigAsset>(
+        key.clone(),
+        RequestOpts {
+            priority: AssetPriority::High,
+            budget_tag: AssetBudgetTag::Boot,
+        },
+    );
+    let asset = asset_manager
+        .await_ready(&handle, Duration::from_secs(2))
+        .map_err(|err| format!("motor profile load failed ({}): {}", key.canonical(), err))?;
+    MotorProfile::parse_toml(&asset.text, registry)
+        .map_err(|err| format!("{}: {}", key.canonical(), err))
 }
 
 fn parse_radius_arg(args: &CommandArgs, default_radius: f32) -> Result<f32, String> {
@@ -8577,7 +8715,12 @@ fn build_command_registry<'a>(
                     .clone()
                     .ok_or_else(|| "no active test map loaded".to_string())?,
             };
-            ctx.user.test_map_reload_requests.push_back(key.clone());
+            ctx.user
+                .test_map_reload_requests
+                .push_back(TestMapReloadRequest {
+                    map: MapRequest::TestMap(key.clone()),
+                    save: None,
+                });
             ctx.output
                 .push_line(format!("test map reload queued: {}", key.canonical()));
             Ok(())
@@ -8643,6 +8786,59 @@ fn build_command_registry<'a>(
             Ok(())
         }),
     )?;
+    commands.set_handler(
+        "dev_trace",
+        Box::new(|ctx, args| {
+            let distance = match args.positional(0) {
+                Some(raw) => match raw.parse::<f32>() {
+                    Ok(value) if value.is_finite() && value > 0.0 => value,
+                    _ => return Err(format!("invalid distance: {}", raw)),
+                },
+                None => DEV_TRACE_DISTANCE,
+            };
+            let runtime = ctx
+                .user
+                .test_map_runtime
+                .as_deref()
+                .ok_or_else(|| "no test map runtime loaded".to_string())?;
+            let groups = query_groups(&runtime.layers, args.positional(1))?;
+            let pose = runtime.controller.camera().pose();
+            let direction = Vector::new(
+                pose.yaw.sin() * pose.pitch.cos(),
+                pose.pitch.sin(),
+                -pose.yaw.cos() * pose.pitch.cos(),
+            );
+            let hits =
+                runtime
+                    .world
+                    .raycast_all(Point::from(pose.eye), direction, distance, groups);
+            ctx.output.push_line(format!(
+                "trace: eye=({:.2}, {:.2}, {:.2}) dir=({:.2}, {:.2}, {:.2}) distance={:.2} hits={}",
+                pose.eye.x,
+                pose.eye.y,
+                pose.eye.z,
+                direction.x,
+                direction.y,
+                direction.z,
+                distance,
+                hits.len()
+            ));
+            for hit in &hits {
+                ctx.output.push_line(format!(
+                    "  toi={:.3} point=({:.2}, {:.2}, {:.2}) normal=({:.2}, {:.2}, {:.2}) {}",
+                    hit.toi,
+                    hit.point.x,
+                    hit.point.y,
+                    hit.point.z,
+                    hit.normal.x,
+                    hit.normal.y,
+                    hit.normal.z,
+                    format_hit_source(&hit_source(hit.collider, hit.source.as_ref()))
+                ));
+            }
+            Ok(())
+        }),
+    )?;
     commands.set_handler(
         "dev_collision_dump_near_player",
         Box::new(|ctx, args| {
@@ -8672,7 +8868,7 @@ fn build_command_registry<'a>(
                     Some(chunk) => chunk,
                     None => continue,
                 };
-                let loaded = runtime.collision_world.loaded_chunks.contains(&index);
+                let loaded = runtime.collision_world.physics.loaded_chunks.contains(&index);
                 ctx.output.push_line(format!(
                     "- {} loaded={} tris={} bounds=({:.2},{:.2},{:.2})..({:.2},{:.2},{:.2}) payload={}",
                     chunk.chunk_id,
@@ -8695,21 +8891,136 @@ fn build_command_registry<'a>(
         Box::new(|ctx, args| {
             let value = args
                 .positional(0)
-                .ok_or_else(|| "usage: player_set_profile <arena|rpg>".to_string())?;
-            let kind = parse_motor_kind(value)?;
-            let cvar_value = if matches!(kind, MotorKind::Arena) {
-                "1"
-            } else {
-                "2"
+                .ok_or_else(|| "usage: player_set_profile <motor|profile>".to_string())?;
+            let registry = MotorRegistry::builtin();
+            let (kind, params) = match registry.parse(value) {
+                Ok(kind) => (kind, Vec::new()),
+                Err(_) => {
+                    let profile = load_motor_profile(ctx.user.asset_manager, value, &registry)?;
+                    (profile.motor, profile.params)
+                }
             };
-            ctx.cvars.set_from_str("dev_motor", cvar_value)?;
+            for (param, param_value) in &params {
+                ctx.cvars.set_from_str(
+                    &motor_param_cvar_name(kind, param),
+                    &param_value.to_string(),
+                )?;
+            }
+            let number = registry.cvar_value(kind).unwrap_or(1);
+            ctx.cvars.set_from_str("dev_motor", &number.to_string())?;
             if let (Some(runtime), Some(camera)) =
                 (ctx.user.test_map_runtime.as_mut(), ctx.user.camera.as_mut())
             {
                 switch_test_map_motor(runtime, camera, kind);
             }
+            if params.is_empty() {
+                ctx.output
+                    .push_line(format!("player profile: {}", kind.label()));
+            } else {
+                ctx.output.push_line(format!(
+                    "player profile: {} ({}, {} params)",
+                    value,
+                    kind.label(),
+                    params.len()
+                ));
+            }
+            Ok(())
+        }),
+    )?;
+    commands.set_handler(
+        "cam_mode",
+        Box::new(|ctx, args| {
+            let value = args
+                .positional(0)
+                .ok_or_else(|| "usage: cam_mode <first|third|spectator|chase>".to_string())?;
+            let mode = CameraMode::parse(value)?;
+            ctx.cvars.set_from_str("cam_mode", mode.cvar_value())?;
+            if let Some(runtime) = ctx.user.test_map_runtime.as_mut() {
+                runtime.controller.camera_mut().set_mode(mode);
+            }
             ctx.output
-                .push_line(format!("player profile: {}", motor_kind_label(kind)));
+                .push_line(format!("camera mode: {}", mode.label()));
+            Ok(())
+        }),
+    )?;
+    commands.set_handler(
+        "cam_chase",
+        Box::new(|ctx, args| {
+            let value = args
+                .positional(0)
+                .ok_or_else(|| "usage: cam_chase <entity|off>".to_string())?;
+            let runtime = ctx
+                .user
+                .test_map_runtime
+                .as_mut()
+                .ok_or_else(|| "no test map runtime loaded".to_string())?;
+            if value == "off" {
+                runtime.chase_entity = None;
+                ctx.output.push_line("chase: off".to_string());
+                return Ok(());
+            }
+            let index = value
+                .parse::<u32>()
+                .map_err(|_| format!("invalid entity: {}", value))?;
+            let entity = runtime
+                .session
+                .entity_by_index(index)
+                .ok_or_else(|| format!("no entity {}", index))?;
+            runtime.chase_entity = Some(entity);
+            ctx.cvars
+                .set_from_str("cam_mode", CameraMode::Chase.cvar_value())?;
+            runtime.controller.camera_mut().set_mode(CameraMode::Chase);
+            ctx.output.push_line(format!("chase: entity {}", index));
+            Ok(())
+        }),
+    )?;
+    commands.set_handler(
+        "prop_spawn",
+        Box::new(|ctx, args| {
+            let usage = "usage: prop_spawn <box|sphere> [size] [mass]";
+            let kind = args.positional(0).ok_or_else(|| usage.to_string())?;
+            let number = |index: usize, default: f32| -> Result<f32, String> {
+                match args.positional(index) {
+                    Some(raw) => match raw.parse::<f32>() {
+                        Ok(value) if value.is_finite() && value > 0.0 => Ok(value),
+                        _ => Err(format!("invalid number: {}", raw)),
+                    },
+                    None => Ok(default),
+                }
+            };
+            let size = number(1, 0.5)?;
+            let mass = number(2, 10.0)?;
+            let shape = match kind {
+                "box" => DynamicBodyShape::Box {
+                    half_extents: [size * 0.5; 3],
+                },
+                "sphere" => DynamicBodyShape::Sphere { radius: size * 0.5 },
+                _ => return Err(usage.to_string()),
+            };
+            let runtime = ctx
+                .user
+                .test_map_runtime
+                .as_deref_mut()
+                .ok_or_else(|| "no test map runtime loaded".to_string())?;
+            let yaw = runtime.controller.camera().yaw();
+            let forward = Vector::new(yaw.sin(), 0.0, -yaw.cos());
+            let origin = runtime.position.translation.vector + forward * 1.5;
+            let desc = DynamicBodyDesc::new(
+                shape,
+                Isometry::translation(origin.x, origin.y + size, origin.z),
+                mass,
+            );
+            let entity = runtime
+                .session
+                .spawn_prop(&mut runtime.world, kind, &desc)
+                .ok_or_else(|| "prop shape rejected".to_string())?;
+            ctx.output.push_line(format!(
+                "prop: {} id={} size={:.2} mass={:.1}",
+                kind,
+                entity.index(),
+                size,
+                mass
+            ));
             Ok(())
         }),
     )?;
@@ -8725,10 +9036,41 @@ fn build_command_registry<'a>(
             let vel = runtime.velocity;
             let speed = (vel.x * vel.x + vel.y * vel.y + vel.z * vel.z).sqrt();
             ctx.output.push_line(format!(
-                "player: motor={} grounded={}",
-                motor_kind_label(runtime.controller.motor().kind()),
-                runtime.grounded
+                "player: motor={} grounded={} crouched={}",
+                runtime.controller.motor().kind().label(),
+                runtime.grounded,
+                runtime.controller.state().crouched
             ));
+            if let Some(immersion) = runtime.controller.state().immersion {
+                ctx.output.push_line(format!(
+                    "liquid: {} depth={:.3} level={:.2} underwater={}",
+                    immersion.kind.as_str(),
+                    immersion.depth,
+                    immersion.level,
+                    runtime.controller.camera().is_underwater()
+                ));
+            }
+            if let Some(mantle) = runtime
+                .controller
+                .motor()
+                .active_as::<RpgMotor>()
+                .and_then(RpgMotor::mantle)
+            {
+                ctx.output.push_line(format!(
+                    "mantle: {:.2}s rise={:.3}",
+                    mantle.elapsed, mantle.offset.y
+                ));
+            }
+            if let Some(ladder) = runtime.controller.state().ladder {
+                let source = runtime
+                    .world
+                    .collider_source(ladder.collider)
+                    .map_or("-", |source| source.id());
+                ctx.output.push_line(format!(
+                    "ladder: {} normal={:.3} {:.3} {:.3}",
+                    source, ladder.normal.x, ladder.normal.y, ladder.normal.z
+                ));
+            }
             ctx.output
                 .push_line(format!("pos: {:.3} {:.3} {:.3}", pos.x, pos.y, pos.z));
             ctx.output.push_line(format!(
@@ -8741,13 +9083,24 @@ fn build_command_registry<'a>(
                     normal.x, normal.y, normal.z
                 ));
             }
+            if let Some(surface) = &runtime.controller.state().ground_surface {
+                let name = runtime
+                    .surfaces
+                    .get(surface.id)
+                    .map_or("-", |spec| spec.name.as_str());
+                ctx.output.push_line(format!(
+                    "surface: {} friction={:.2} accel={:.2} slide={}",
+                    name, surface.friction, surface.acceleration, surface.slide
+                ));
+            }
             ctx.output.push_line(format!(
                 "capsule_offset: {:.3} kcc_ms={:.3}",
                 runtime.capsule_offset, runtime.kcc_query_ms
             ));
             if let Some(camera) = ctx.user.camera.as_ref() {
                 ctx.output.push_line(format!(
-                    "camera: yaw={:.2} pitch={:.2}",
+                    "camera: {} yaw={:.2} pitch={:.2}",
+                    runtime.controller.camera().mode().label(),
                     camera.yaw.to_degrees(),
                     camera.pitch.to_degrees()
                 ));
@@ -8764,12 +9117,11 @@ fn build_command_registry<'a>(
             let value = args
                 .positional(1)
                 .ok_or_else(|| "usage: player_tune_set <param> <value>".to_string())?;
-            let cvar_name = resolve_player_tune_param(param)
-                .map(|param| param.cvar_name)
-                .unwrap_or(param);
+            let cvar_name = resolve_player_tune_cvar(ctx.cvars, param)
+                .ok_or_else(|| format!("unknown tune param: {param} (use player_tune_list)"))?;
             let parsed = ctx
                 .cvars
-                .set_from_str(cvar_name, value)
+                .set_from_str(&cvar_name, value)
                 .map_err(|err| format!("{err} (use player_tune_list)"))?;
             ctx.output
                 .push_line(format!("{cvar_name} = {}", parsed.display()));
@@ -8779,16 +9131,25 @@ fn build_command_registry<'a>(
     commands.set_handler(
         "player_tune_list",
         Box::new(|ctx, _args| {
-            ctx.output.push_line("player tune params:".to_string());
-            for param in PLAYER_TUNE_PARAMS {
-                if let Some(entry) = ctx.cvars.get_by_name(param.cvar_name) {
-                    ctx.output.push_line(format!(
-                        "{} = {} ({}) - {}",
-                        param.name,
-                        entry.value.display(),
-                        param.cvar_name,
-                        param.help
-                    ));
+            let registry = MotorRegistry::builtin();
+            for kind in registry.kinds() {
+                let Some(motor) = registry.build(kind) else {
+                    continue;
+                };
+                ctx.output
+                    .push_line(format!("{} motor tune params:", kind.label()));
+                for param in motor.params() {
+                    let cvar_name = motor_param_cvar_name(kind, param.name);
+                    if let Some(entry) = ctx.cvars.get_by_name(&cvar_name) {
+                        ctx.output.push_line(format!(
+                            "{} = {} {} ({}) - {}",
+                            param.name,
+                            entry.value.display(),
+                            param.unit.label(),
+                            cvar_name,
+                            param.help
+                        ));
+                    }
                 }
             }
             Ok(())
@@ -8826,6 +9187,53 @@ fn build_command_registry<'a>(
             Ok(())
         }),
     )?;
+    commands.set_handler(
+        "dev_movement_record",
+        Box::new(|ctx, args| {
+            let usage = || "usage: dev_movement_record <start <name>|stop>".to_string();
+            let runtime = ctx
+                .user
+                .test_map_runtime
+                .as_deref_mut()
+                .ok_or_else(|| "no test map runtime loaded".to_string())?;
+            match args.positional(0) {
+                Some("start") => {
+                    let name = args.positional(1).ok_or_else(usage)?;
+                    movement_record_path(name)?;
+                    if runtime.movement_record.is_some() {
+                        return Err("movement record already active".to_string());
+                    }
+                    let recorder = MovementRecorder::new(runtime.controller.motor().kind());
+                    runtime.movement_record = Some((name.to_string(), recorder));
+                    ctx.output
+                        .push_line(format!("movement record: started {}", name));
+                }
+                Some("stop") => {
+                    let (name, recorder) = runtime
+                        .movement_record
+                        .take()
+                        .ok_or_else(|| "movement record not active".to_string())?;
+                    let recording = recorder.finish();
+                    let path = movement_record_path(&name)?;
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)
+                            .map_err(|err| format!("record dir create failed: {}", err))?;
+                    }
+                    std::fs::write(&path, recording.to_csv())
+                        .map_err(|err| format!("record write failed: {}", err))?;
+                    let summary = recording.summary();
+                    ctx.output.push_line(format!(
+                        "movement record: wrote {} (ticks={} jumps={})",
+                        path.display(),
+                        summary.ticks,
+                        summary.jumps.len()
+                    ));
+                }
+                _ => return Err(usage()),
+            }
+            Ok(())
+        }),
+    )?;
     commands.set_handler(
         "dev_input_replay",
         Box::new(|ctx, args| {
@@ -9014,6 +9422,95 @@ fn build_command_registry<'a>(
             Ok(())
         }),
     )?;
+    commands.set_handler(
+        "save",
+        Box::new(|ctx, args| {
+            let name = args
+                .positional(0)
+                .ok_or_else(|| "usage: save <name>".to_string())?;
+            let path = save_path(&ctx.user.path_policy.saves_dir(), name)?;
+            let camera = ctx
+                .user
+                .camera
+                .as_deref()
+                .ok_or_else(|| "save requires an active camera".to_string())?;
+            let script = ctx.user.script.as_deref();
+            let save = match (
+                ctx.user.test_map_runtime.as_deref_mut(),
+                ctx.user.map_session.as_deref_mut(),
+            ) {
+                (Some(runtime), _) => capture_save_game(runtime, camera, ctx.cvars, script),
+                (None, Some(map)) => capture_map_save_game(map, camera, ctx.cvars, script),
+                (None, None) => return Err("save requires an active map".to_string()),
+            };
+            write_save_file(&path, &save).map_err(|err| err.to_string())?;
+            ctx.output.push_line(format!("save: {}", path.display()));
+            Ok(())
+        }),
+    )?;
+    commands.set_handler(
+        "load",
+        Box::new(|ctx, args| {
+            let name = args
+                .positional(0)
+                .ok_or_else(|| "usage: load <name>".to_string())?;
+            let path = save_path(&ctx.user.path_policy.saves_dir(), name)?;
+            let save = read_save_file(&path).map_err(|err| err.to_string())?;
+            save.check_schema(&game_save_registry())
+                .map_err(|err| err.to_string())?;
+            let level = save
+                .level
+                .as_deref()
+                .ok_or_else(|| "save has no level".to_string())?;
+            // BSP saves always re-enter the map: the save applies onto the
+            // level as it was first built.
+            let key = match parse_map_request(level)? {
+                MapRequest::TestMap(key) => key,
+                MapRequest::Bsp(name) => {
+                    ctx.user
+                        .test_map_reload_requests
+                        .push_back(TestMapReloadRequest {
+                            map: MapRequest::Bsp(name.clone()),
+                            save: Some(Box::new(save)),
+                        });
+                    ctx.output
+                        .push_line(format!("load: queued {} ({})", name, path.display()));
+                    return Ok(());
+                }
+            };
+            let same_level = ctx.user.active_test_map.as_ref() == Some(&key);
+            match (
+                same_level,
+                ctx.user.test_map_runtime.as_deref_mut(),
+                ctx.user.camera.as_deref_mut(),
+            ) {
+                (true, Some(runtime), Some(camera)) => {
+                    apply_save_game(
+                        &save,
+                        runtime,
+                        camera,
+                        ctx.cvars,
+                        ctx.user.script.as_deref(),
+                    )?;
+                    ctx.output.push_line(format!("load: {}", path.display()));
+                }
+                _ => {
+                    ctx.user
+                        .test_map_reload_requests
+                        .push_back(TestMapReloadRequest {
+                            map: MapRequest::TestMap(key.clone()),
+                            save: Some(Box::new(save)),
+                        });
+                    ctx.output.push_line(format!(
+                        "load: queued {} ({})",
+                        key.canonical(),
+                        path.display()
+                    ));
+                }
+            }
+            Ok(())
+        }),
+    )?;
     commands.set_handler(
         "cfg_load",
         Box::new(|ctx, args| {
@@ -9144,7 +9641,13 @@ fn build_command_registry<'a>(
     )?;
     commands.set_fallback(Box::new(|ctx, name, args| {
         if let Some(script) = ctx.user.script.as_deref_mut() {
-            match script.engine.run_command(name, args.raw_tokens()) {
+            let result = match ctx.user.test_map_runtime.as_deref() {
+                Some(runtime) => script.engine.with_scene(runtime, |engine| {
+                    engine.run_command(name, args.raw_tokens())
+                }),
+                None => script.engine.run_command(name, args.raw_tokens()),
+            };
+            match result {
                 Ok(true) => Ok(()),
                 Ok(false) => Err(format!("unknown command: {}", name)),
                 Err(err) => Err(format!("lua command failed: {}", err)),
@@ -9513,6 +10016,10 @@ fn request_asset_reload(asset_manager: &AssetManager, key: AssetKey) -> Result<(
             let _ = asset_manager.reload::<BlobAsset>(key, opts)?;
             Ok(())
         }
+        ("engine", "prefab") => {
+            let _ = asset_manager.reload::<PrefabAsset>(key, opts)?;
+            Ok(())
+        }
         ("engine", "texture") => {
             let _ = asset_manager.reload::<TextureAsset>(key, opts)?;
             Ok(())
@@ -9632,6 +10139,26 @@ fn validate_level_manifest_lines(
         }
     }
 
+    for placement in &manifest.prefabs {
+        let result = resolve_prefab(&placement.prefab, |key| {
+            let location = resolver.resolve(key)?;
+            let ResolvedPath::File(path) = &location.path else {
+                return Err("prefab must resolve to a file".to_string());
+            };
+            let text = std::fs::read_to_string(path).map_err(|err| err.to_string())?;
+            PrefabDef::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+        });
+        if let Err(err) = result {
+            lines.push(format!(
+                "{}:{} [prefab]: {}",
+                manifest_path.display(),
+                placement.line,
+                err
+            ));
+            errors += 1;
+        }
+    }
+
     errors
 }
 
@@ -9829,169 +10356,148 @@ fn register_collision_debug_cvars(
 }
 
 fn register_movement_cvars(registry: &mut CvarRegistry) -> Result<MovementCvars, String> {
-    let air_max_speed = registry.register(
-        CvarDef::new(
-            "arena_air_max_speed",
-            CvarValue::Float(TEST_MAP_MAX_AIR_SPEED),
-            "Arena air max speed.",
-        )
-        .with_bounds(CvarBounds::Float {
-            min: Some(0.0),
-            max: None,
-        }),
-    )?;
-    let air_accel = registry.register(
-        CvarDef::new(
-            "arena_air_accel",
-            CvarValue::Float(TEST_MAP_AIR_ACCEL),
-            "Arena air acceleration.",
-        )
-        .with_bounds(CvarBounds::Float {
-            min: Some(0.0),
-            max: None,
-        }),
-    )?;
-    let air_resistance = registry.register(
-        CvarDef::new(
-            "arena_air_resistance",
-            CvarValue::Float(TEST_MAP_AIR_RESISTANCE),
-            "Arena air resistance (speed-scaled).",
-        )
-        .with_bounds(CvarBounds::Float {
-            min: Some(0.0),
-            max: Some(10.0),
-        }),
-    )?;
-    let air_resistance_speed_scale = registry.register(
-        CvarDef::new(
-            "arena_air_resistance_speed_scale",
-            CvarValue::Float(TEST_MAP_MAX_AIR_SPEED * 16.0),
-            "Arena air resistance reaches full strength at this speed.",
-        )
-        .with_bounds(CvarBounds::Float {
-            min: Some(0.01),
-            max: None,
-        }),
-    )?;
-    let golden_target_deg = registry.register(
+    let motors = MotorRegistry::builtin();
+    let mut motor_params = Vec::new();
+    for kind in motors.kinds() {
+        let Some(motor) = motors.build(kind) else {
+            continue;
+        };
+        for param in motor.params() {
+            let value = motor.param(param.name).unwrap_or_default();
+            let name = motor_param_cvar_name(kind, param.name);
+            let def = if param.is_flag() {
+                CvarDef::new(name, CvarValue::Bool(value > 0.5), param.help)
+            } else {
+                CvarDef::new(name, CvarValue::Float(value), param.help).with_bounds(
+                    CvarBounds::Float {
+                        min: param.min,
+                        max: param.max,
+                    },
+                )
+            };
+            motor_params.push(MotorParamCvar {
+                motor: kind,
+                param: param.name,
+                flag: param.is_flag(),
+                id: registry.register(def.with_flags(CvarFlags::SAVEGAME))?,
+            });
+        }
+    }
+    let camera_defaults = CameraModeTuning::default();
+    let cam_mode = registry.register(
         CvarDef::new(
-            "arena_golden_target_deg",
-            CvarValue::Float(TEST_MAP_GOLDEN_ANGLE_TARGET.to_degrees()),
-            "Golden angle target (degrees, view-forward relative).",
+            "cam_mode",
+            CvarValue::Int(1),
+            "Test map camera mode (1=first, 2=third, 3=spectator, 4=chase).",
         )
-        .with_bounds(CvarBounds::Float {
-            min: Some(0.0),
-            max: Some(180.0),
+        .with_bounds(CvarBounds::Int {
+            min: Some(1),
+            max: Some(4),
         }),
     )?;
-    let golden_gain_min = registry.register(
+    let cam_boom_length = registry.register(
         CvarDef::new(
-            "arena_golden_gain_min",
-            CvarValue::Float(TEST_MAP_GOLDEN_ANGLE_GAIN_MIN),
-            "Golden angle minimum gain.",
+            "cam_boom_length",
+            CvarValue::Float(camera_defaults.boom_length),
+            "Third-person camera distance behind the eye.",
         )
         .with_bounds(CvarBounds::Float {
-            min: Some(0.0),
-            max: None,
+            min: Some(0.5),
+            max: Some(20.0),
         }),
     )?;
-    let golden_gain_peak = registry.register(
+    let cam_chase_distance = registry.register(
         CvarDef::new(
-            "arena_golden_gain_peak",
-            CvarValue::Float(TEST_MAP_GOLDEN_ANGLE_GAIN_PEAK),
-            "Golden angle peak gain.",
+            "cam_chase_distance",
+            CvarValue::Float(camera_defaults.chase_distance),
+            "Chase camera distance behind its target.",
         )
         .with_bounds(CvarBounds::Float {
-            min: Some(0.0),
-            max: None,
+            min: Some(0.5),
+            max: Some(20.0),
         }),
     )?;
-    let golden_bonus_scale = registry.register(
+    let cam_spectator_speed = registry.register(
         CvarDef::new(
-            "arena_golden_bonus_scale",
-            CvarValue::Float(0.25),
-            "Golden angle bonus scale for uncapped speed growth.",
+            "cam_spectator_speed",
+            CvarValue::Float(camera_defaults.spectator_speed),
+            "Spectator camera fly speed (m/s).",
         )
         .with_bounds(CvarBounds::Float {
-            min: Some(0.0),
-            max: None,
+            min: Some(0.1),
+            max: Some(100.0),
         }),
     )?;
-    let golden_blend_start = registry.register(
+    let cam_blend_time = registry.register(
         CvarDef::new(
-            "arena_golden_blend_start",
-            CvarValue::Float(TEST_MAP_GOLDEN_ANGLE_BLEND_START),
-            "Golden angle blend start speed.",
+            "cam_blend_time",
+            CvarValue::Float(camera_defaults.blend_time),
+            "Seconds to blend between camera modes (0 cuts).",
         )
         .with_bounds(CvarBounds::Float {
             min: Some(0.0),
-            max: None,
+            max: Some(5.0),
         }),
     )?;
-    let golden_blend_end = registry.register(
+    let effects_defaults = CameraEffectsTuning::default();
+    let cam_bob = registry.register(
         CvarDef::new(
-            "arena_golden_blend_end",
-            CvarValue::Float(TEST_MAP_GOLDEN_ANGLE_BLEND_END),
-            "Golden angle blend end speed.",
+            "cam_bob",
+            CvarValue::Float(effects_defaults.bob),
+            "View bob amplitude at walking speed (m, 0 disables).",
         )
         .with_bounds(CvarBounds::Float {
             min: Some(0.0),
-            max: None,
+            max: Some(0.5),
         }),
     )?;
-    let corridor_shaping_strength = registry.register(
+    let cam_dip = registry.register(
         CvarDef::new(
-            "arena_cs_strength_deg",
-            CvarValue::Float(TEST_MAP_CORRIDOR_SHAPING_STRENGTH_DEG),
-            "Corridor shaping strength (degrees/sec).",
+            "cam_dip",
+            CvarValue::Float(effects_defaults.dip),
+            "Landing dip per m/s of fall speed (m, 0 disables).",
         )
         .with_bounds(CvarBounds::Float {
             min: Some(0.0),
-            max: None,
+            max: Some(0.1),
         }),
     )?;
-    let corridor_shaping_min_speed = registry.register(
+    let cam_roll = registry.register(
         CvarDef::new(
-            "arena_cs_min_speed",
-            CvarValue::Float(TEST_MAP_CORRIDOR_SHAPING_MIN_SPEED),
-            "Corridor shaping minimum speed.",
+            "cam_roll",
+            CvarValue::Float(effects_defaults.roll.to_degrees()),
+            "View roll while strafing at ground speed (degrees, 0 disables).",
         )
         .with_bounds(CvarBounds::Float {
             min: Some(0.0),
-            max: None,
+            max: Some(15.0),
         }),
     )?;
-    let corridor_shaping_max_angle = registry.register(
+    let cam_fov_kick = registry.register(
         CvarDef::new(
-            "arena_cs_max_angle_deg",
-            CvarValue::Float(TEST_MAP_CORRIDOR_SHAPING_MAX_ANGLE_DEG),
-            "Corridor shaping max angle per tick (degrees).",
+            "cam_fov_kick",
+            CvarValue::Float(effects_defaults.fov_kick.to_degrees()),
+            "Extra FOV at high speed (degrees, 0 disables).",
         )
         .with_bounds(CvarBounds::Float {
             min: Some(0.0),
-            max: Some(180.0),
-        }),
-    )?;
-    let corridor_shaping_min_alignment = registry.register(
-        CvarDef::new(
-            "arena_cs_min_alignment",
-            CvarValue::Float(TEST_MAP_CORRIDOR_SHAPING_MIN_ALIGNMENT),
-            "Corridor shaping minimum alignment (dot).",
-        )
-        .with_bounds(CvarBounds::Float {
-            min: Some(-1.0),
-            max: Some(1.0),
+            max: Some(40.0),
         }),
     )?;
+    let motor_numbers: Vec<String> = motors
+        .kinds()
+        .enumerate()
+        .map(|(index, kind)| format!("{}={}", index + 1, kind.label()))
+        .collect();
     let dev_motor = registry.register(
         CvarDef::new(
             "dev_motor",
             CvarValue::Int(1),
-            "Test map motor selection (1=arena, 2=rpg).",
+            format!("Test map motor selection ({}).", motor_numbers.join(", ")),
         )
         .with_bounds(CvarBounds::Int {
             min: Some(1),
-            max: Some(2),
+            max: Some(motors.len() as i32),
         })
         .with_flags(CvarFlags::NO_PERSIST),
     )?;
@@ -10020,20 +10526,16 @@ fn register_movement_cvars(registry: &mut CvarRegistry) -> Result<MovementCvars,
         .with_flags(CvarFlags::NO_PERSIST),
     )?;
     Ok(MovementCvars {
-        air_max_speed,
-        air_accel,
-        air_resistance,
-        air_resistance_speed_scale,
-        golden_target_deg,
-        golden_gain_min,
-        golden_gain_peak,
-        golden_bonus_scale,
-        golden_blend_start,
-        golden_blend_end,
-        corridor_shaping_strength,
-        corridor_shaping_min_speed,
-        corridor_shaping_max_angle,
-        corridor_shaping_min_alignment,
+        motor_params,
+        cam_mode,
+        cam_boom_length,
+        cam_chase_distance,
+        cam_spectator_speed,
+        cam_blend_time,
+        cam_bob,
+        cam_dip,
+        cam_roll,
+        cam_fov_kick,
         dev_motor,
         dev_fixed_dt,
         dev_substeps,
@@ -10046,54 +10548,56 @@ fn apply_movement_cvars(
     runtime: &mut TestMapRuntime,
     camera: &mut CameraState,
 ) {
-    let config = runtime.controller.motor_mut().arena_config_mut();
-    if let Some(value) = cvar_float(cvars, ids.air_max_speed) {
-        config.max_speed_air = value.max(0.0);
-    }
-    if let Some(value) = cvar_float(cvars, ids.air_accel) {
-        config.air_accel = value.max(0.0);
-    }
-    if let Some(value) = cvar_float(cvars, ids.air_resistance) {
-        config.air_resistance = value.max(0.0);
-    }
-    if let Some(value) = cvar_float(cvars, ids.air_resistance_speed_scale) {
-        config.air_resistance_speed_scale = value.max(0.01);
-    }
-    if let Some(value) = cvar_float(cvars, ids.golden_target_deg) {
-        config.golden_angle_target = value.max(0.0).to_radians();
-    }
-    if let Some(value) = cvar_float(cvars, ids.golden_gain_min) {
-        config.golden_angle_gain_min = value.max(0.0);
+    let motors = runtime.controller.motor_mut();
+    for entry in &ids.motor_params {
+        let value = if entry.flag {
+            cvar_bool(cvars, entry.id).map(|flag| if flag { 1.0 } else { 0.0 })
+        } else {
+            cvar_float(cvars, entry.id)
+        };
+        if let (Some(value), Some(motor)) = (value, motors.motor_mut(entry.motor)) {
+            let _ = motor.set_param(entry.param, value);
+        }
     }
-    if let Some(value) = cvar_float(cvars, ids.golden_gain_peak) {
-        config.golden_angle_gain_peak = value.max(0.0);
+    let view = runtime.controller.camera_mut();
+    let tuning = view.tuning_mut();
+    if let Some(value) = cvar_float(cvars, ids.cam_boom_length) {
+        tuning.boom_length = value.max(0.0);
     }
-    if let Some(value) = cvar_float(cvars, ids.golden_bonus_scale) {
-        config.golden_angle_bonus_scale = value.max(0.0);
+    if let Some(value) = cvar_float(cvars, ids.cam_chase_distance) {
+        tuning.chase_distance = value.max(0.0);
     }
-    if let Some(value) = cvar_float(cvars, ids.golden_blend_start) {
-        config.golden_angle_blend_speed_start = value.max(0.0);
+    if let Some(value) = cvar_float(cvars, ids.cam_spectator_speed) {
+        tuning.spectator_speed = value.max(0.0);
     }
-    if let Some(value) = cvar_float(cvars, ids.golden_blend_end) {
-        config.golden_angle_blend_speed_end = value.max(0.0);
+    if let Some(value) = cvar_float(cvars, ids.cam_blend_time) {
+        tuning.blend_time = value.max(0.0);
     }
-    if let Some(value) = cvar_float(cvars, ids.corridor_shaping_strength) {
-        config.corridor_shaping_strength = value.max(0.0).to_radians();
+    let effects = view.effects_tuning_mut();
+    if let Some(value) = cvar_float(cvars, ids.cam_bob) {
+        effects.bob = value.max(0.0);
     }
-    if let Some(value) = cvar_float(cvars, ids.corridor_shaping_min_speed) {
-        config.corridor_shaping_min_speed = value.max(0.0);
+    if let Some(value) = cvar_float(cvars, ids.cam_dip) {
+        effects.dip = value.max(0.0);
     }
-    if let Some(value) = cvar_float(cvars, ids.corridor_shaping_max_angle) {
-        config.corridor_shaping_max_angle_per_tick = value.max(0.0).to_radians();
+    if let Some(value) = cvar_float(cvars, ids.cam_roll) {
+        effects.roll = value.max(0.0).to_radians();
     }
-    if let Some(value) = cvar_float(cvars, ids.corridor_shaping_min_alignment) {
-        config.corridor_shaping_min_alignment = value.clamp(-1.0, 1.0);
+    if let Some(value) = cvar_float(cvars, ids.cam_fov_kick) {
+        effects.fov_kick = value.max(0.0).to_radians();
     }
+    view.set_mode(CameraMode::from_cvar(
+        cvar_int(cvars, ids.cam_mode).unwrap_or(1),
+    ));
     let motor_value = cvar_int(cvars, ids.dev_motor).unwrap_or(1);
-    let motor_kind = MotorKind::from_cvar(motor_value);
+    let motor_kind = runtime.controller.motor().kind_from_cvar(motor_value);
     switch_test_map_motor(runtime, camera, motor_kind);
 }
 
+fn motor_param_cvar_name(motor: MotorKind, param: &str) -> String {
+    format!("{}_{}", motor.label(), param)
+}
+
 fn cvar_bool(cvars: &CvarRegistry, id: CvarId) -> Option<bool> {
     match cvars.get(id)?.value {
         engine_core::control_plane::CvarValue::Bool(value) => Some(value),
@@ -10282,12 +10786,22 @@ fn load_scene(
                     "quake mounts not configured for map load",
                 ));
             }
-            let (mesh, bounds, scene_collision, spawn) = load_bsp_scene(asset_manager, &name)?;
+            let sidecar = load_bsp_sidecar(asset_manager, &name);
+            let scale = sidecar
+                .as_ref()
+                .map_or(QUAKE_MAP_TO_WORLD_SCALE, |sidecar| {
+                    sidecar.map_to_world_scale
+                });
+            let bsp = load_bsp_scene(asset_manager, &name, scale)?;
             Ok(LoadedScene {
-                mesh,
-                bounds,
-                collision: Some(scene_collision),
-                spawn,
+                mesh: bsp.mesh,
+                bounds: bsp.bounds,
+                collision: Some(bsp.collision),
+                submodel_meshes: bsp.submodel_meshes,
+                entities: bsp.entities,
+                sidecar,
+                placements: load_level_placements(asset_manager, &name),
+                bsp: Some(bsp.bytes),
                 kind: SceneKind::Bsp,
                 test_map: None,
             })
@@ -10298,7 +10812,11 @@ fn load_scene(
                 mesh,
                 bounds,
                 collision: None,
-                spawn: None,
+                submodel_meshes: Vec::new(),
+                entities: Vec::new(),
+                sidecar: None,
+                placements: Vec::new(),
+                bsp: None,
                 kind: SceneKind::TestMap,
                 test_map: Some(test_map),
             })
@@ -10314,9 +10832,23 @@ fn load_lmp_image(asset_manager: &AssetManager, asset: &str) -> Result<ImageData
 
     let asset_name = normalize_asset_name(asset);
     let image_bytes = load_quake_raw_asset(asset_manager, &asset_name, AssetBudgetTag::Boot)?;
-    let image = lmp::parse_lmp_image(&image_bytes)
-        .map_err(|err| ExitError::new(EXIT_IMAGE, format!("image parse failed: {}", err)))?;
-    let rgba = image.to_rgba8(&palette);
+    let (image, rgba) = match asset_name.split_once('#') {
+        // A lump inside a WAD, e.g. gfx.wad#conchars; HUD art is keyed on index 255.
+        Some((_, lump)) => {
+            let image = wad::parse_lump_image(lump, &image_bytes).map_err(|err| {
+                ExitError::new(EXIT_IMAGE, format!("image parse failed: {}", err))
+            })?;
+            let rgba = image.to_rgba8_transparent(&palette, wad::TRANSPARENT_INDEX);
+            (image, rgba)
+        }
+        None => {
+            let image = lmp::parse_lmp_image(&image_bytes).map_err(|err| {
+                ExitError::new(EXIT_IMAGE, format!("image parse failed: {}", err))
+            })?;
+            let rgba = image.to_rgba8(&palette);
+            (image, rgba)
+        }
+    };
     let image = ImageData::new(image.width, image.height, rgba)
         .map_err(|err| ExitError::new(EXIT_IMAGE, format!("image data failed: {}", err)))?;
 
@@ -10363,6 +10895,8 @@ fn load_test_map_scene(
         collision_world_key.canonical(),
         collision_world.chunks.len()
     );
+    let layers = load_collision_layers(asset_manager, reload);
+    let surfaces = load_surface_table(asset_manager, reload);
     Ok((
         mesh,
         bounds,
@@ -10371,10 +10905,172 @@ fn load_test_map_scene(
             map,
             collision_world_key,
             collision_world,
+            layers,
+            surfaces,
         },
     ))
 }
 
+/// Loads the shipped collision layer table, falling back to the built-in one.
+fn load_collision_layers(asset_manager: &AssetManager, reload: bool) -> CollisionLayerTable {
+    load_config_table(
+        asset_manager,
+        COLLISION_LAYERS_CONFIG,
+        "collision layers",
+        CollisionLayerTable::parse_toml,
+        reload,
+    )
+}
+
+/// Loads the shipped surface material table, falling back to the built-in one.
+fn load_surface_table(asset_manager: &AssetManager, reload: bool) -> SurfaceTable {
+    load_config_table(
+        asset_manager,
+        SURFACES_CONFIG,
+        "surface table",
+        SurfaceTable::parse_toml,
+        reload,
+    )
+}
+
+fn load_config_table<T: Default>(
+    asset_manager: &AssetManager,
+    path: &str,
+    label: &str,
+    parse: fn(&str) -> Result<T, String>,
+    reload: bool,
+) -> T {
+    let Ok(key) = AssetKey::from_parts("engine", "config", path) else {
+        return T::default();
+    };
+    let opts = RequestOpts {
+        priority: AssetPriority::High,
+        budget_tag: AssetBudgetTag::Boot,
+    };
+    let handle = if reload {
+        match asset_manager.reload::<ConfigAsset>(key.clone(), opts) {
+            Ok(handle) => handle,
+            Err(err) => {
+                eprintln!("{} reload failed: {}", label, err);
+                return T::default();
+            }
+        }
+    } else {
+        asset_manager.request::<ConfigAsset>(key.clone(), opts)
+    };
+    let parsed = asset_manager
+        .await_ready(&handle, Duration::from_secs(2))
+        .map_err(|err| err.to_string())
+        .and_then(|asset| parse(&asset.text));
+    match parsed {
+        Ok(table) => table,
+        Err(err) => {
+            eprintln!(
+                "{} load failed ({}): {}; using built-in table",
+                label,
+                key.canonical(),
+                err
+            );
+            T::default()
+        }
+    }
+}
+
+/// Query groups for a named layer mask; every layer when `mask` is `None`.
+fn query_groups(
+    layers: &CollisionLayerTable,
+    mask: Option<&str>,
+) -> Result<InteractionGroups, String> {
+    match mask {
+        None => Ok(InteractionGroups::all()),
+        Some(name) if layers.masks.contains_key(name) => {
+            Ok(layer_mask_groups(layers.named_mask(name)))
+        }
+        Some(name) => match layers.bit(name) {
+            Some(bit) => Ok(layer_mask_groups(bit)),
+            None => Err(format!("unknown collision mask or layer: {}", name)),
+        },
+    }
+}
+
+fn hit_source(collider: ColliderHandle, source: Option<&ColliderSource>) -> HitSource {
+    match source {
+        Some(ColliderSource::Solid { id, tags }) => HitSource {
+            kind: "solid".to_string(),
+            id: id.clone(),
+            tags: tags.clone(),
+        },
+        Some(ColliderSource::Chunk { chunk_id }) => HitSource {
+            kind: "chunk".to_string(),
+            id: chunk_id.clone(),
+            tags: Vec::new(),
+        },
+        Some(ColliderSource::Prop { id }) => HitSource {
+            kind: "prop".to_string(),
+            id: id.clone(),
+            tags: Vec::new(),
+        },
+        None => HitSource {
+            kind: "collider".to_string(),
+            id: collider.into_raw_parts().0.to_string(),
+            tags: Vec::new(),
+        },
+    }
+}
+
+fn format_hit_source(source: &HitSource) -> String {
+    if source.tags.is_empty() {
+        format!("{} {}", source.kind, source.id)
+    } else {
+        format!("{} {} [{}]", source.kind, source.id, source.tags.join(","))
+    }
+}
+
+impl SceneQuery for TestMapRuntime {
+    fn trace_ray(
+        &self,
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+        mask: Option<&str>,
+        all: bool,
+    ) -> Vec<TraceHit> {
+        let Ok(groups) = query_groups(&self.layers, mask) else {
+            return Vec::new();
+        };
+        let origin = Point::from(origin);
+        let direction = Vector::from(direction);
+        let hits = if all {
+            self.world
+                .raycast_all(origin, direction, max_distance, groups)
+        } else {
+            self.world
+                .raycast(origin, direction, max_distance, groups)
+                .into_iter()
+                .collect()
+        };
+        hits.into_iter()
+            .map(|hit| TraceHit {
+                distance: hit.toi,
+                point: hit.point.coords.into(),
+                normal: hit.normal.into(),
+                source: hit_source(hit.collider, hit.source.as_ref()),
+            })
+            .collect()
+    }
+
+    fn overlap_box(&self, min: [f32; 3], max: [f32; 3], mask: Option<&str>) -> Vec<HitSource> {
+        let Ok(groups) = query_groups(&self.layers, mask) else {
+            return Vec::new();
+        };
+        self.world
+            .overlap_aabb(Point::from(min), Point::from(max), groups)
+            .into_iter()
+            .map(|overlap| hit_source(overlap.collider, overlap.source.as_ref()))
+            .collect()
+    }
+}
+
 fn fetch_test_map_asset(
     asset_manager: &AssetManager,
     key: &AssetKey,
@@ -10461,10 +11157,20 @@ fn fetch_collision_world_asset(
         })
 }
 
+struct BspScene {
+    mesh: MeshData,
+    bounds: Bounds,
+    collision: SceneCollision,
+    submodel_meshes: Vec<SubmodelMesh>,
+    entities: Vec<MapEntity>,
+    bytes: Arc<Vec<u8>>,
+}
+
 fn load_bsp_scene(
     asset_manager: &AssetManager,
     map: &str,
-) -> Result<(MeshData, Bounds, SceneCollision, Option<SpawnPoint>), ExitError> {
+    scale: f32,
+) -> Result<BspScene, ExitError> {
     let map_name = normalize_map_asset(map);
     let bsp_bytes = load_quake_raw_asset(asset_manager, &map_name, AssetBudgetTag::Boot)?;
     let bsp = bsp::parse_bsp(&bsp_bytes)
@@ -10477,35 +11183,340 @@ fn load_bsp_scene(
         bsp.faces.len()
     );
 
-    let spawn = bsp::parse_spawn(&bsp_bytes, &bsp.header)
-        .map_err(|err| ExitError::new(EXIT_BSP, format!("bsp spawn parse failed: {}", err)))?;
+    let entities: Vec<_> = bsp::parse_entities(&bsp_bytes, &bsp.header)
+        .map_err(|err| ExitError::new(EXIT_BSP, format!("bsp entity parse failed: {}", err)))?
+        .iter()
+        .map(|entity| map_entity_from_bsp(entity, scale))
+        .collect();
 
-    let (mesh, bounds, collision) = build_scene_mesh(&bsp)?;
-    Ok((mesh, bounds, collision, spawn))
+    let (mesh, bounds, collision, submodel_meshes) = build_scene_mesh(&bsp, &entities)?;
+    Ok(BspScene {
+        mesh,
+        bounds,
+        collision,
+        submodel_meshes,
+        entities,
+        bytes: bsp_bytes,
+    })
 }
 
-// NOTE: Test maps are a graybox-only renderer path. We build simple per-triangle
-// vertex colors from normals to keep this visualization deterministic and
-// dependency-free. This is intentionally minimal because it is expected to be
-// replaced by a proper render asset pipeline later, so keep changes local and
-// well-documented if you refactor this path.
-fn build_test_map_mesh(
-    map: &TestMap,
-    solids: &[ResolvedSolid],
-) -> Result<(MeshData, Bounds), ExitError> {
-    let scale = map.map_to_world_scale.unwrap_or(1.0);
-    if !scale.is_finite() || scale <= 0.0 {
-        return Err(ExitError::new(
-            EXIT_SCENE,
-            "test map scale must be finite and > 0",
-        ));
-    }
-
-    let mut vertices = Vec::new();
+// Sidecars live next to the shipped content as
+// `map_sidecars/quake1/bsp/<map>.toml`, the path `tools` writes by default.
+// A missing sidecar is normal; a broken one is reported and ignored.
+/// Placements from the `engine:level/<map>` manifest; none when the map has
+/// no manifest.
+fn load_level_placements(asset_manager: &AssetManager, map: &str) -> Vec<PrefabPlacement> {
+    let map_name = normalize_map_asset(map);
+    let Some(stem) = map_name
+        .strip_prefix("maps/")
+        .and_then(|name| name.strip_suffix(".bsp"))
+    else {
+        return Vec::new();
+    };
+    let Ok(key) = AssetKey::from_parts("engine", "level", stem) else {
+        return Vec::new();
+    };
+    let Ok(entry) = resolve_level_manifest_path(asset_manager.path_policy(), &key) else {
+        return Vec::new();
+    };
+    match load_level_manifest(&entry.path) {
+        Ok(manifest) => manifest.prefabs,
+        Err(err) => {
+            eprintln!("level manifest {}: {}", key.canonical(), err);
+            Vec::new()
+        }
+    }
+}
+
+fn load_bsp_sidecar(asset_manager: &AssetManager, map: &str) -> Option<MapSidecar> {
+    let map_name = normalize_map_asset(map);
+    let stem = map_name
+        .strip_prefix("maps/")
+        .and_then(|name| name.strip_suffix(".bsp"))?;
+    let map_id = format!("quake1:bsp/{}", stem);
+    let path = asset_manager
+        .path_policy()
+        .content_root()
+        .join("map_sidecars")
+        .join("quake1")
+        .join("bsp")
+        .join(stem)
+        .with_extension("toml");
+    let text = std::fs::read_to_string(&path).ok()?;
+    let sidecar = match MapSidecar::parse_toml(&text) {
+        Ok(sidecar) => sidecar,
+        Err(err) => {
+            eprintln!("map sidecar {} parse failed: {}", path.display(), err);
+            return None;
+        }
+    };
+    let validation = sidecar.validate();
+    if !validation.is_ok() {
+        eprintln!(
+            "map sidecar {} invalid: {}",
+            path.display(),
+            validation.errors.join("; ")
+        );
+        return None;
+    }
+    if sidecar.map_id != map_id {
+        eprintln!(
+            "map sidecar {} is for '{}', expected '{}'",
+            path.display(),
+            sidecar.map_id,
+            map_id
+        );
+        return None;
+    }
+    Some(sidecar)
+}
+
+// Map entities are world meters, like every other game-side position; only
+// the scene camera stays in Quake units. Negative angles are Quake's up/down
+// mover directions, not facings.
+fn map_entity_from_bsp(entity: &BspEntity, scale: f32) -> MapEntity {
+    MapEntity {
+        classname: entity.classname.clone(),
+        origin: entity
+            .origin
+            .map(|origin| [origin[0] * scale, origin[2] * scale, -origin[1] * scale])
+            .unwrap_or_default(),
+        yaw_deg: entity.yaw().filter(|yaw| *yaw >= 0.0).map(|yaw| 90.0 - yaw),
+        brush_model: entity.brush_model,
+        targetname: entity.targetname.clone(),
+        target: entity.target.clone(),
+        keys: entity.keys.clone(),
+    }
+}
+
+/// Game side of a BSP map. The session and its physics world are in world
+/// meters; the scene camera moves in Quake units and crosses over at `scale`.
+struct MapSession {
+    session: GameSession,
+    physics: PhysicsWorld,
+    player: Entity,
+    /// Meters per Quake unit.
+    scale: f32,
+    /// Brush models drawn where their movers are.
+    models: Vec<SubmodelMesh>,
+    accum: f32,
+    trigger_events: Vec<TriggerEvent>,
+    liquid_damage_events: Vec<LiquidDamageEvent>,
+}
+
+/// Loads the game side of a BSP map; also returns where the player
+/// entered it.
+#[allow(clippy::too_many_arguments)]
+fn build_map_session(
+    asset_manager: &AssetManager,
+    map: &str,
+    entities: &[MapEntity],
+    sidecar: Option<&MapSidecar>,
+    placements: &[PrefabPlacement],
+    bsp: &[u8],
+    bounds: &Bounds,
+    models: Vec<SubmodelMesh>,
+) -> (MapSession, Option<GameSpawnPoint>) {
+    let scale = sidecar.map_or(QUAKE_MAP_TO_WORLD_SCALE, |sidecar| {
+        sidecar.map_to_world_scale
+    });
+    let rules = SandboxRules {
+        kill_height: bounds.min.y * scale - TEST_MAP_KILL_DEPTH,
+    };
+    let source = QuakeLevelSource {
+        map,
+        entities,
+        sidecar,
+        bsp,
+        scale,
+        placements,
+    };
+    let level = QuakeLevel::load(rules, &source, |key| load_prefab(asset_manager, key));
+    for warning in &level.warnings {
+        eprintln!("{}", warning);
+    }
+    println!("spawned {} map movers", level.movers.len());
+    println!("added {} map liquid volumes", level.liquids);
+    println!(
+        "spawned {} map entities ({} without prefab)",
+        level.entities.spawned.len(),
+        level.entities.unmapped.values().sum::<usize>()
+    );
+    println!("placed {} level prefabs", level.placed.len());
+    let mut session = level.session;
+    session.set_fixed_dt(MAP_SESSION_DT);
+    let map = MapSession {
+        session,
+        physics: level.physics,
+        player: level.player,
+        scale,
+        models,
+        accum: 0.0,
+        trigger_events: Vec::new(),
+        liquid_damage_events: Vec::new(),
+    };
+    (map, level.spawn)
+}
+
+/// Runs the map session's fixed ticks for `dt` of frame time, mirroring the
+/// camera into the player entity first. Respawns move the camera, and so do
+/// the movers the camera rides or is pushed by.
+fn update_map_session(
+    map: &mut MapSession,
+    camera: &mut CameraState,
+    mut collision: Option<&mut SceneCollision>,
+    dt: f32,
+) {
+    map.accum = (map.accum + dt).min(MAP_SESSION_MAX_ACCUM);
+    while map.accum >= MAP_SESSION_DT {
+        map.accum -= MAP_SESSION_DT;
+        let origin = camera.collision_origin().scale(map.scale);
+        map.session.set_player_position(
+            map.player,
+            ecs::Vec3 {
+                x: origin.x,
+                y: origin.y,
+                z: origin.z,
+            },
+        );
+        map.physics.step(MAP_SESSION_DT);
+        let feet = origin.y - (QUAKE_PLAYER_HALF_HEIGHT - QUAKE_PLAYER_CENTER_OFFSET) * map.scale;
+        let height = QUAKE_PLAYER_HALF_HEIGHT * 2.0 * map.scale;
+        let liquid = map
+            .physics
+            .liquid_depth(Point::new(origin.x, feet, origin.z), height)
+            .map(|contact| (contact.kind, (contact.depth / height).clamp(0.0, 1.0)));
+        map.session.set_player_liquid(map.player, liquid);
+        camera.water_level = liquid.map_or(0.0, |(_, level)| level);
+        let hull = Cuboid::new(Vector::new(
+            QUAKE_PLAYER_HALF_WIDTH * map.scale,
+            QUAKE_PLAYER_HALF_HEIGHT * map.scale,
+            QUAKE_PLAYER_HALF_WIDTH * map.scale,
+        ));
+        let center = Isometry::translation(
+            origin.x,
+            origin.y + QUAKE_PLAYER_CENTER_OFFSET * map.scale,
+            origin.z,
+        );
+        map.session
+            .sense_triggers(&map.physics, map.player, &hull, &center);
+        let physics_events = map.physics.drain_events();
+        map.session.apply_physics_events(&physics_events);
+        for spawned in map.session.tick() {
+            if spawned.entity == map.player {
+                place_camera_at_map_spawn(map, camera, collision.as_deref(), &spawned.point);
+            }
+        }
+        map.session.apply_mover_targets(&mut map.physics);
+        if let Some(collision) = collision.as_deref_mut() {
+            move_map_submodels(map, camera, collision);
+        }
+        let trigger_events = map.session.drain_trigger_events();
+        map.trigger_events.extend(trigger_events);
+        let liquid_damage = map.session.drain_liquid_damage_events();
+        map.liquid_damage_events.extend(liquid_damage);
+    }
+}
+
+/// Moves brush model clip hulls to their movers' offsets, carrying the
+/// camera along.
+fn move_map_submodels(
+    map: &mut MapSession,
+    camera: &mut CameraState,
+    collision: &mut SceneCollision,
+) {
+    for (model, offset) in map.session.brush_model_offsets() {
+        let offset = Vec3::from(offset).scale(1.0 / map.scale);
+        let origin = camera.collision_origin();
+        let moved = collision.move_submodel(model, offset, origin, camera.on_ground);
+        camera.position = camera.camera_from_origin(moved);
+    }
+}
+
+/// Uploads the vertices of every brush model whose mover moved since the
+/// last draw.
+fn draw_map_submodels(map: &mut MapSession, renderer: &mut render_wgpu::Renderer) {
+    let offsets = map.session.brush_model_offsets();
+    for mesh in &mut map.models {
+        let offset = offsets
+            .iter()
+            .find(|(model, _)| *model == mesh.model)
+            .map_or(Vec3::zero(), |(_, offset)| {
+                Vec3::from(*offset).scale(1.0 / map.scale)
+            });
+        if offset == mesh.drawn_offset {
+            continue;
+        }
+        let vertices: Vec<_> = mesh
+            .vertices
+            .iter()
+            .map(|vertex| MeshVertex {
+                position: Vec3::from(vertex.position).add(offset).to_array(),
+                color: vertex.color,
+            })
+            .collect();
+        match renderer.update_scene_vertices(mesh.first_vertex, &vertices) {
+            Ok(()) => mesh.drawn_offset = offset,
+            Err(err) => eprintln!("brush model {} upload failed: {}", mesh.model, err),
+        }
+    }
+}
+
+fn place_camera_at_map_spawn(
+    map: &MapSession,
+    camera: &mut CameraState,
+    collision: Option<&SceneCollision>,
+    spawn: &GameSpawnPoint,
+) {
+    let origin = Vec3::from(spawn.origin).scale(1.0 / map.scale);
+    camera.position = camera.camera_from_origin(origin);
+    if let Some(yaw_deg) = spawn.yaw_deg {
+        camera.yaw = yaw_deg.to_radians();
+        camera.pitch = 0.0;
+    }
+    camera.velocity = Vec3::zero();
+    camera.vertical_velocity = 0.0;
+    camera.on_ground = false;
+    if let Some(collision) = collision {
+        camera.snap_to_floor(collision);
+    }
+}
+
+fn load_prefab(asset_manager: &AssetManager, key: &AssetKey) -> Result<PrefabDef, String> {
+    let opts = RequestOpts {
+        priority: AssetPriority::High,
+        budget_tag: AssetBudgetTag::Boot,
+    };
+    resolve_prefab(key, |key| {
+        let handle = asset_manager.request::<PrefabAsset>(key.clone(), opts);
+        asset_manager
+            .await_ready(&handle, Duration::from_secs(2))
+            .map(|asset| asset.prefab.clone())
+    })
+}
+
+// NOTE: Test maps are a graybox-only renderer path. We build simple per-triangle
+// vertex colors from normals to keep this visualization deterministic and
+// dependency-free. This is intentionally minimal because it is expected to be
+// replaced by a proper render asset pipeline later, so keep changes local and
+// well-documented if you refactor this path.
+fn build_test_map_mesh(
+    map: &TestMap,
+    solids: &[ResolvedSolid],
+) -> Result<(MeshData, Bounds), ExitError> {
+    let scale = map.map_to_world_scale.unwrap_or(1.0);
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err(ExitError::new(
+            EXIT_SCENE,
+            "test map scale must be finite and > 0",
+        ));
+    }
+
+    let mut vertices = Vec::new();
     let mut indices = Vec::new();
     let mut bounds = Bounds::empty();
 
-    for solid in solids {
+    for solid in solids.iter().filter(|solid| !solid.is_trigger()) {
         let size = [
             solid.size[0] * scale,
             solid.size[1] * scale,
@@ -10549,41 +11560,6 @@ fn build_test_map_mesh(
     Ok((mesh, bounds))
 }
 
-fn test_map_motor_config() -> ArenaMotorConfig {
-    ArenaMotorConfig {
-        max_speed_ground: TEST_MAP_MOVE_SPEED,
-        max_speed_air: TEST_MAP_MAX_AIR_SPEED,
-        ground_accel: TEST_MAP_ACCEL,
-        air_accel: TEST_MAP_AIR_ACCEL,
-        friction: TEST_MAP_FRICTION,
-        stop_speed: TEST_MAP_STOP_SPEED,
-        gravity: TEST_MAP_GRAVITY,
-        jump_speed: TEST_MAP_JUMP_SPEED,
-        air_resistance: TEST_MAP_AIR_RESISTANCE,
-        air_resistance_speed_scale: TEST_MAP_MAX_AIR_SPEED * 16.0,
-        golden_angle_target: TEST_MAP_GOLDEN_ANGLE_TARGET,
-        golden_angle_gain_min: TEST_MAP_GOLDEN_ANGLE_GAIN_MIN,
-        golden_angle_gain_peak: TEST_MAP_GOLDEN_ANGLE_GAIN_PEAK,
-        golden_angle_bonus_scale: 0.25,
-        golden_angle_blend_speed_start: TEST_MAP_GOLDEN_ANGLE_BLEND_START,
-        golden_angle_blend_speed_end: TEST_MAP_GOLDEN_ANGLE_BLEND_END,
-        corridor_shaping_strength: TEST_MAP_CORRIDOR_SHAPING_STRENGTH_DEG.to_radians(),
-        corridor_shaping_min_speed: TEST_MAP_CORRIDOR_SHAPING_MIN_SPEED,
-        corridor_shaping_max_angle_per_tick: TEST_MAP_CORRIDOR_SHAPING_MAX_ANGLE_DEG.to_radians(),
-        corridor_shaping_min_alignment: TEST_MAP_CORRIDOR_SHAPING_MIN_ALIGNMENT,
-        jump_buffer_enabled: true,
-        jump_buffer_window: TEST_MAP_JUMP_BUFFER_WINDOW,
-        frictionless_jump_mode: FrictionlessJumpMode::Soft,
-        frictionless_jump_grace: TEST_MAP_BHOP_GRACE,
-        frictionless_jump_friction_scale: TEST_MAP_BHOP_FRICTION_SCALE,
-        frictionless_jump_friction_scale_best_angle: TEST_MAP_BHOP_FRICTION_SCALE_BEST_ANGLE,
-    }
-}
-
-fn test_map_rpg_motor_config() -> RpgMotorConfig {
-    RpgMotorConfig::default()
-}
-
 #[derive(Clone, Copy, Debug)]
 struct CameraMotorTuning {
     speed: f32,
@@ -10593,23 +11569,17 @@ struct CameraMotorTuning {
     jump_speed: f32,
 }
 
-fn camera_tuning_from_arena(config: ArenaMotorConfig) -> CameraMotorTuning {
-    CameraMotorTuning {
-        speed: config.max_speed_ground,
-        accel: config.ground_accel,
-        friction: config.friction,
-        gravity: config.gravity,
-        jump_speed: config.jump_speed,
-    }
-}
-
-fn camera_tuning_from_rpg(config: RpgMotorConfig) -> CameraMotorTuning {
+/// Noclip camera tuning from the motor's shared params; motors without one
+/// fall back to the test map arena values.
+fn camera_tuning_from_motor(motor: &dyn Motor) -> CameraMotorTuning {
+    let fallback = test_map_arena_config();
+    let param = |name, default| motor.param(name).unwrap_or(default);
     CameraMotorTuning {
-        speed: config.max_speed_ground,
-        accel: config.ground_accel,
-        friction: config.friction,
-        gravity: config.gravity,
-        jump_speed: config.jump_speed,
+        speed: param("ground_max_speed", fallback.max_speed_ground),
+        accel: param("ground_accel", fallback.ground_accel),
+        friction: param("friction", fallback.friction),
+        gravity: param("gravity", fallback.gravity),
+        jump_speed: param("jump_speed", fallback.jump_speed),
     }
 }
 
@@ -10621,16 +11591,14 @@ fn apply_test_map_camera_tuning(camera: &mut CameraState, tuning: CameraMotorTun
     camera.jump_speed = tuning.jump_speed;
 }
 
-fn snap_test_map_runtime_to_ground(runtime: &mut TestMapRuntime, bounds: &Bounds) {
-    let extent = bounds.extent();
-    let drop = (extent.y + extent.x.max(extent.z)).max(1.0) + runtime.capsule_offset + 2.0;
-    let desired = Vector::new(0.0, -drop, 0.0);
-    let result = runtime.controller.collision_mut().move_character(
+fn drop_test_map_runtime(runtime: &mut TestMapRuntime, drop: f32) {
+    let dt = runtime.session.fixed_dt();
+    let result = drop_to_ground(
+        runtime.controller.collision_mut(),
         &runtime.world,
         runtime.position,
-        desired,
-        runtime.grounded,
-        1.0 / 60.0,
+        drop,
+        dt,
     );
     runtime.position = result.position;
     runtime.prev_position = runtime.position;
@@ -10671,106 +11639,69 @@ fn collision_interest_bounds(position: Vector<Real>, radius: f32) -> CollisionAa
     }
 }
 
-fn build_test_map_collision_runtime(
-    world: &mut PhysicsWorld,
-    data: &TestMapSceneData,
-) -> Result<CollisionWorldRuntime, ExitError> {
-    let colliders = build_test_map_colliders(&data.map).map_err(|err| {
+// NOTE: Test map runtime routes through the shared controller module
+// (input -> motor -> collision -> camera) so gameplay and tests stay aligned.
+fn build_test_map_runtime(data: &TestMapSceneData) -> Result<TestMapRuntime, ExitError> {
+    let source = CourseSource {
+        name: data.key.canonical(),
+        map: &data.map,
+        collision_world: &data.collision_world,
+        layers: &data.layers,
+        surfaces: &data.surfaces,
+    };
+    let course = Course::load(course_rules(&data.collision_world), &source).map_err(|err| {
         ExitError::new(
             EXIT_SCENE,
-            format!("test map collider build failed: {}", err),
+            format!("{} ({})", err, data.collision_world_key.canonical()),
         )
     })?;
-    let mut collider_by_id = HashMap::new();
-    for collider in colliders.colliders {
-        collider_by_id.insert(collider.id.clone(), collider.collider);
-    }
-
-    let selection = CollisionChunkSelection::Bounds(data.collision_world.root_bounds);
-    let selected_chunks = select_collision_chunks(&data.collision_world, selection);
-    if selected_chunks.is_empty() {
-        return Err(ExitError::new(
-            EXIT_SCENE,
-            format!(
-                "collision world has no selectable chunks ({})",
-                data.collision_world_key.canonical()
-            ),
-        ));
-    }
-    let mut collider_handles = Vec::new();
-    let mut triangle_count = 0u64;
-    for chunk_index in &selected_chunks {
-        let chunk = match data.collision_world.chunks.get(*chunk_index as usize) {
-            Some(chunk) => chunk,
-            None => continue,
-        };
-        let payload_id = chunk
-            .payload_ref
-            .strip_prefix("inline:test_map/")
-            .unwrap_or(&chunk.chunk_id);
-        let collider = match collider_by_id.remove(payload_id) {
-            Some(collider) => collider,
-            None => {
-                eprintln!(
-                    "collision world chunk missing collider: {} (payload {})",
-                    chunk.chunk_id, chunk.payload_ref
-                );
-                continue;
-            }
-        };
-        collider_handles.push(world.insert_static_collider(collider));
-        triangle_count = triangle_count.saturating_add(chunk.triangle_count as u64);
+    for warning in &course.warnings {
+        eprintln!("{}", warning);
     }
-    if collider_handles.is_empty() {
-        return Err(ExitError::new(
-            EXIT_SCENE,
-            format!(
-                "collision world produced zero colliders ({})",
-                data.collision_world_key.canonical()
-            ),
-        ));
-    }
-
-    Ok(CollisionWorldRuntime {
-        world: data.collision_world.clone(),
-        loaded_chunks: selected_chunks,
-        collider_handles,
-        triangle_count,
-    })
-}
-
-// NOTE: Test map runtime routes through the shared controller module
-// (input -> motor -> collision -> camera) so gameplay and tests stay aligned.
-fn build_test_map_runtime(
-    data: &TestMapSceneData,
-    bounds: &Bounds,
-) -> Result<TestMapRuntime, ExitError> {
-    let arena_config = test_map_motor_config();
-    let rpg_config = test_map_rpg_motor_config();
-    let mut world = PhysicsWorld::new(Vector::new(0.0, -arena_config.gravity, 0.0));
-    let collision_world = build_test_map_collision_runtime(&mut world, data)?;
-    world.step(1.0 / 60.0);
-
-    let profile = CollisionProfile::arena_default();
-    let capsule_offset = profile.capsule_height * 0.5 + profile.capsule_radius;
-    let center = bounds.center();
-    let position = Isometry::translation(center.x, bounds.max.y + capsule_offset + 1.0, center.z);
-    let motor = DualMotor::new(arena_config, rpg_config);
-    let camera = PlayerCamera::new(TEST_MAP_EYE_HEIGHT);
-    let controller = PlayerController::new(DirectInputAdapter, motor, profile, camera, position);
+    let Course {
+        world,
+        colliders,
+        session,
+        player,
+        controller,
+        ..
+    } = course;
+    let state = controller.state();
+    let position = state.position;
+    let grounded = state.grounded;
+    let ground_normal = state.ground_normal;
+    let capsule_offset = capsule_offset(&controller.collision().profile());
     let runtime = TestMapRuntime {
         key: data.key.clone(),
         world,
-        collision_world,
+        collision_world: CollisionWorldRuntime {
+            world: data.collision_world.clone(),
+            physics: colliders,
+        },
         controller,
         position,
         prev_position: position,
         velocity: Vec3::zero(),
         prev_velocity: Vec3::zero(),
-        grounded: false,
-        ground_normal: None,
+        grounded,
+        ground_normal,
         capsule_offset,
         kcc_query_ms: 0.0,
+        session,
+        player,
+        layers: data.layers.clone(),
+        trigger_events: Vec::new(),
+        liquid_damage_events: Vec::new(),
+        surfaces: data.surfaces.clone(),
+        footsteps: Vec::new(),
+        footstep_sounds: HashMap::new(),
+        footstep_count: 0,
+        chase_entity: None,
+        prev_eye: position.translation.vector,
+        eye: position.translation.vector,
+        prev_feel: CameraFeel::default(),
+        feel: CameraFeel::default(),
+        movement_record: None,
     };
     Ok(runtime)
 }
@@ -10803,21 +11734,21 @@ fn switch_test_map_motor(
     if runtime.controller.motor().kind() == motor_kind {
         return;
     }
-    runtime.controller.motor_mut().set_kind(motor_kind);
-    let (profile, tuning) = match motor_kind {
-        MotorKind::Arena => (
-            CollisionProfile::arena_default(),
-            camera_tuning_from_arena(runtime.controller.motor().arena_config()),
-        ),
-        MotorKind::Rpg => (
-            CollisionProfile::rpg_default(),
-            camera_tuning_from_rpg(runtime.controller.motor().rpg_config()),
-        ),
+    if let Err(err) = runtime.controller.motor_mut().set_kind(motor_kind) {
+        eprintln!("motor switch failed: {}", err);
+        return;
+    }
+    let mut profile = if motor_kind == MotorKind::RPG {
+        CollisionProfile::rpg_default()
+    } else {
+        CollisionProfile::arena_default()
     };
-    let origin_y = runtime.position.translation.y - runtime.capsule_offset;
+    let tuning = camera_tuning_from_motor(runtime.controller.motor());
+    profile.layer_mask = runtime.controller.collision().profile().layer_mask;
+    let origin_y = runtime.position.translation.y - runtime.controller.collision().foot_offset();
     runtime.controller.collision_mut().set_profile(profile);
     runtime.capsule_offset = profile.capsule_height * 0.5 + profile.capsule_radius;
-    runtime.position.translation.y = origin_y + runtime.capsule_offset;
+    runtime.position.translation.y = origin_y + runtime.controller.collision().foot_offset();
     runtime.prev_position = runtime.position;
     let state = runtime.controller.state_mut();
     state.position = runtime.position;
@@ -10827,6 +11758,218 @@ fn switch_test_map_motor(
     apply_test_map_camera_tuning(camera, tuning);
 }
 
+fn capture_save_game(
+    runtime: &mut TestMapRuntime,
+    camera: &CameraState,
+    cvars: &CvarRegistry,
+    script: Option<&ScriptRuntime>,
+) -> SaveGame {
+    let registry = game_save_registry();
+    let mut save = SaveGame::new(&registry);
+    save.level = Some(runtime.key.canonical().to_string());
+    save.world = runtime.session.capture_world(&registry);
+    let motor = runtime.controller.motor();
+    save.player = Some(PlayerSave {
+        motor: motor.kind().label().to_string(),
+        kinematics: runtime.controller.state().clone(),
+        yaw: camera.yaw,
+        pitch: camera.pitch,
+        arena_timers: motor
+            .get::<ArenaMotor>()
+            .map(ArenaMotor::timers)
+            .unwrap_or_default(),
+        rpg_smoothed_axis: motor
+            .get::<RpgMotor>()
+            .map_or([0.0, 0.0], RpgMotor::smoothed_axis),
+    });
+    capture_save_host_state(&mut save, cvars, script);
+    save
+}
+
+/// Saves a BSP map: its game session, and the scene camera as the player.
+/// The camera moves in Quake units; the save holds it in world meters like
+/// every other player.
+fn capture_map_save_game(
+    map: &mut MapSession,
+    camera: &CameraState,
+    cvars: &CvarRegistry,
+    script: Option<&ScriptRuntime>,
+) -> SaveGame {
+    let registry = game_save_registry();
+    let mut save = SaveGame::new(&registry);
+    save.level = map.session.current_map().map(str::to_string);
+    save.world = map.session.capture_world(&registry);
+    let origin = camera.collision_origin().scale(map.scale);
+    let mut kinematics = PlayerKinematics::new(Isometry::translation(origin.x, origin.y, origin.z));
+    kinematics.velocity = Vector::new(
+        camera.velocity.x,
+        camera.vertical_velocity,
+        camera.velocity.z,
+    ) * map.scale;
+    kinematics.grounded = camera.on_ground;
+    save.player = Some(PlayerSave {
+        motor: MAP_CAMERA_MOTOR.to_string(),
+        kinematics,
+        yaw: camera.yaw,
+        pitch: camera.pitch,
+        arena_timers: ArenaMotorTimers::default(),
+        rpg_smoothed_axis: [0.0, 0.0],
+    });
+    capture_save_host_state(&mut save, cvars, script);
+    save
+}
+
+/// Script entities and `SAVEGAME` cvars, shared by every kind of level.
+fn capture_save_host_state(
+    save: &mut SaveGame,
+    cvars: &CvarRegistry,
+    script: Option<&ScriptRuntime>,
+) {
+    if let Some(script) = script {
+        save.script = script.host.borrow().save_state();
+    }
+    let mut entries: Vec<_> = cvars
+        .list()
+        .into_iter()
+        .filter(|entry| entry.def.flags.contains(CvarFlags::SAVEGAME))
+        .collect();
+    entries.sort_by(|a, b| a.def.name.cmp(&b.def.name));
+    save.cvars = entries
+        .into_iter()
+        .map(|entry| (entry.def.name.clone(), entry.value.display()))
+        .collect();
+}
+
+fn apply_save_cvars(save: &SaveGame, cvars: &mut CvarRegistry) -> Result<(), String> {
+    for (name, value) in &save.cvars {
+        cvars
+            .set_from_str(name, value)
+            .map_err(|err| format!("save cvar {}: {}", name, err))?;
+    }
+    Ok(())
+}
+
+/// Applies a save onto a BSP map freshly re-entered from the same level.
+/// Brush models move to their restored offsets before the camera is placed,
+/// so riding a mover does not carry the camera off its saved spot.
+fn apply_map_save_game(
+    save: &SaveGame,
+    map: &mut MapSession,
+    camera: &mut CameraState,
+    collision: Option<&mut SceneCollision>,
+    cvars: &mut CvarRegistry,
+    script: Option<&ScriptRuntime>,
+) -> Result<(), String> {
+    apply_save_cvars(save, cvars)?;
+    map.session
+        .restore_world(&mut map.physics, &game_save_registry(), &save.world)
+        .map_err(|err| format!("save world: {}", err))?;
+    map.accum = 0.0;
+    if let Some(collision) = collision {
+        move_map_submodels(map, camera, collision);
+    }
+    if let Some(player) = &save.player {
+        if player.motor != MAP_CAMERA_MOTOR {
+            return Err(format!(
+                "save player uses motor {}, not the map camera",
+                player.motor
+            ));
+        }
+        let kinematics = &player.kinematics;
+        let origin = kinematics.position.translation.vector / map.scale;
+        let velocity = kinematics.velocity / map.scale;
+        camera.position = camera.camera_from_origin(Vec3::new(origin.x, origin.y, origin.z));
+        camera.velocity = Vec3::new(velocity.x, 0.0, velocity.z);
+        camera.vertical_velocity = velocity.y;
+        camera.on_ground = kinematics.grounded;
+        camera.yaw = player.yaw;
+        camera.pitch = player.pitch;
+    }
+    camera.water_level = map
+        .session
+        .world()
+        .get::<InLiquid>(map.player)
+        .map_or(0.0, |liquid| liquid.level);
+    if let Some(script) = script {
+        script.host.borrow_mut().restore_state(&save.script);
+    }
+    Ok(())
+}
+
+// Restores cvars first so the motor switch below sees the saved profile, then
+// overwrites the motor timers that the switch resets.
+fn apply_save_game(
+    save: &SaveGame,
+    runtime: &mut TestMapRuntime,
+    camera: &mut CameraState,
+    cvars: &mut CvarRegistry,
+    script: Option<&ScriptRuntime>,
+) -> Result<(), String> {
+    apply_save_cvars(save, cvars)?;
+    if let Some(player) = &save.player {
+        let registry = MotorRegistry::builtin();
+        let kind = registry.parse(&player.motor)?;
+        let number = registry.cvar_value(kind).unwrap_or(1);
+        cvars.set_from_str("dev_motor", &number.to_string())?;
+        switch_test_map_motor(runtime, camera, kind);
+        let kinematics = &player.kinematics;
+        runtime.position = kinematics.position;
+        runtime.prev_position = runtime.position;
+        runtime.velocity = Vec3::new(
+            kinematics.velocity.x,
+            kinematics.velocity.y,
+            kinematics.velocity.z,
+        );
+        runtime.prev_velocity = runtime.velocity;
+        runtime.grounded = kinematics.grounded;
+        runtime.ground_normal = kinematics.ground_normal;
+        *runtime.controller.state_mut() = kinematics.clone();
+        // A crouched save reloads crouched; the controller stands up once
+        // there is headroom, so a low ceiling never ends up inside the head.
+        let collision = runtime.controller.collision_mut();
+        if kinematics.crouched {
+            collision.crouch();
+        } else {
+            collision.stand();
+        }
+        let eye_drop = (collision.profile().capsule_height - collision.capsule_height()) * 0.5;
+        runtime
+            .controller
+            .camera_mut()
+            .ease_eye_drop(eye_drop, 0.0, 0.0);
+        runtime
+            .controller
+            .camera_mut()
+            .set_look(player.yaw, player.pitch);
+        let motor = runtime.controller.motor_mut();
+        if let Some(arena) = motor.get_mut::<ArenaMotor>() {
+            arena.set_timers(player.arena_timers);
+        }
+        if let Some(rpg) = motor.get_mut::<RpgMotor>() {
+            rpg.set_smoothed_axis(player.rpg_smoothed_axis);
+        }
+        camera.yaw = player.yaw;
+        camera.pitch = player.pitch;
+        let origin_y = runtime.position.translation.y - runtime.capsule_offset;
+        camera.position = Vec3::new(
+            runtime.position.translation.x,
+            origin_y + camera.eye_height - eye_drop,
+            runtime.position.translation.z,
+        );
+        camera.velocity = runtime.velocity;
+        camera.vertical_velocity = runtime.velocity.y;
+        camera.on_ground = runtime.grounded;
+    }
+    runtime
+        .session
+        .restore_world(&mut runtime.world, &game_save_registry(), &save.world)
+        .map_err(|err| format!("save world: {}", err))?;
+    if let Some(script) = script {
+        script.host.borrow_mut().restore_state(&save.script);
+    }
+    Ok(())
+}
+
 fn sync_test_map_runtime_to_camera(runtime: &mut TestMapRuntime, camera: &CameraState) {
     let origin = Vec3::new(
         camera.position.x,
@@ -10835,6 +11978,10 @@ fn sync_test_map_runtime_to_camera(runtime: &mut TestMapRuntime, camera: &Camera
     );
     runtime.position = Isometry::translation(origin.x, origin.y + runtime.capsule_offset, origin.z);
     runtime.prev_position = runtime.position;
+    runtime.eye = Vector::new(camera.position.x, camera.position.y, camera.position.z);
+    runtime.prev_eye = runtime.eye;
+    runtime.feel = CameraFeel::default();
+    runtime.prev_feel = runtime.feel;
     runtime.velocity = Vec3::zero();
     runtime.prev_velocity = Vec3::zero();
     runtime.grounded = false;
@@ -10844,11 +11991,19 @@ fn sync_test_map_runtime_to_camera(runtime: &mut TestMapRuntime, camera: &Camera
         .camera_mut()
         .set_look(camera.yaw, camera.pitch);
     runtime.controller.motor_mut().reset_states();
+    runtime.controller.collision_mut().clear_ground();
+    runtime.controller.collision_mut().stand();
+    runtime.controller.camera_mut().reset_eye_drop();
     let state = runtime.controller.state_mut();
     state.position = runtime.position;
     state.velocity = Vector::zeros();
     state.grounded = false;
     state.ground_normal = None;
+    state.crouched = false;
+    state.immersion = None;
+    state.ladder = None;
+    state.ledge = None;
+    state.ground_surface = None;
     runtime.kcc_query_ms = 0.0;
 }
 
@@ -10859,6 +12014,7 @@ fn update_test_map_runtime(
     dt: f32,
 ) {
     runtime.world.step(dt);
+    runtime.session.sync_props(&runtime.world);
     runtime.prev_position = runtime.position;
     runtime.prev_velocity = runtime.velocity;
     let move_x = bool_to_axis(input.right, input.left);
@@ -10867,36 +12023,51 @@ fn update_test_map_runtime(
         move_x,
         move_y,
         jump: input.jump_active(),
+        crouch: input.down,
         look_delta: [0.0, 0.0],
     };
     runtime
         .controller
         .camera_mut()
         .set_look(camera.yaw, camera.pitch);
+    let chase_target = runtime
+        .chase_entity
+        .and_then(|entity| runtime.session.player_position(entity))
+        .map(|position| Vector::new(position.x, position.y, position.z));
+    runtime
+        .controller
+        .camera_mut()
+        .set_chase_target(chase_target);
     let kcc_start = Instant::now();
-    let frame = runtime.controller.tick(&runtime.world, raw_input, dt);
+    let mut frame = runtime.controller.tick(&runtime.world, raw_input, dt);
+    runtime.prev_eye = runtime.eye;
+    runtime.eye = frame.camera.eye;
+    runtime.prev_feel = runtime.feel;
+    runtime.feel = frame.camera.feel;
+    runtime
+        .controller
+        .collision()
+        .push_dynamic_bodies(&mut runtime.world, dt);
     runtime.kcc_query_ms = update_kcc_query_ms(runtime.kcc_query_ms, kcc_start.elapsed());
     runtime.position = frame.kinematics.position;
     runtime.grounded = frame.kinematics.grounded;
     runtime.ground_normal = frame.kinematics.ground_normal;
+    runtime.footsteps.extend(frame.footstep.take());
 
-    let mut next_velocity = frame.kinematics.velocity;
-    if frame.collision.hit_ceiling && next_velocity.y > 0.0 {
-        next_velocity.y = 0.0;
-    }
-    if frame.kinematics.grounded && next_velocity.y < 0.0 {
-        let allow_downhill = frame
-            .kinematics
-            .ground_normal
-            .map(|normal| normal.y < 0.99)
-            .unwrap_or(false);
-        if !allow_downhill {
-            next_velocity.y = 0.0;
-        }
-    }
+    let next_velocity = settle_velocity(&frame);
+    frame.kinematics.velocity = next_velocity;
     runtime.velocity = Vec3::new(next_velocity.x, next_velocity.y, next_velocity.z);
     let state = runtime.controller.state_mut();
     state.velocity = next_velocity;
+    if let Some((_, recorder)) = runtime.movement_record.as_mut() {
+        recorder.record(
+            runtime.controller.motor(),
+            raw_input,
+            frame.camera.yaw,
+            dt,
+            &frame,
+        );
+    }
 
     camera.position = Vec3::new(frame.camera.eye.x, frame.camera.eye.y, frame.camera.eye.z);
     camera.yaw = frame.camera.yaw;
@@ -10904,6 +12075,123 @@ fn update_test_map_runtime(
     camera.velocity = Vec3::new(runtime.velocity.x, runtime.velocity.y, runtime.velocity.z);
     camera.vertical_velocity = runtime.velocity.y;
     camera.on_ground = runtime.grounded;
+
+    let origin = runtime.position.translation;
+    runtime.session.set_player_position(
+        runtime.player,
+        ecs::Vec3 {
+            x: origin.x,
+            y: origin.y - runtime.controller.collision().foot_offset(),
+            z: origin.z,
+        },
+    );
+    if frame.collision.crushed {
+        runtime.session.request_respawn(runtime.player);
+    }
+    let liquid = frame
+        .kinematics
+        .immersion
+        .map(|immersion| (immersion.kind, immersion.level));
+    runtime.session.set_player_liquid(runtime.player, liquid);
+    runtime.session.sense_triggers(
+        &runtime.world,
+        runtime.player,
+        runtime.controller.collision().capsule(),
+        &runtime.position,
+    );
+    let physics_events = runtime.world.drain_events();
+    runtime.session.apply_physics_events(&physics_events);
+    runtime.session.set_fixed_dt(dt);
+    for spawned in runtime.session.tick() {
+        if spawned.entity == runtime.player {
+            respawn_test_map_player(runtime, camera, &spawned.point);
+        }
+    }
+    runtime.session.apply_mover_targets(&mut runtime.world);
+    let trigger_events = runtime.session.drain_trigger_events();
+    runtime.trigger_events.extend(trigger_events);
+    let liquid_damage = runtime.session.drain_liquid_damage_events();
+    runtime.liquid_damage_events.extend(liquid_damage);
+}
+
+/// Forwards session events to the script hooks, naming entities the way
+/// scripts see them.
+fn dispatch_game_events(
+    engine: &mut ScriptEngine,
+    session: &GameSession,
+    trigger_events: &[TriggerEvent],
+    liquid_damage: &[LiquidDamageEvent],
+) -> Result<(), ScriptError> {
+    for event in trigger_events {
+        let other = session.script_name(event.other);
+        engine.on_trigger(&event.name, &other, event.phase.as_str())?;
+    }
+    for event in liquid_damage {
+        let entity = session.script_name(event.entity);
+        engine.on_liquid_damage(event.kind.as_str(), event.amount, &entity)?;
+    }
+    Ok(())
+}
+
+/// Plays each footstep's sound, cycling through the set its surface carries
+/// (the default surface's when the ground has none). Surfaces without sounds, as
+/// in the stock Quake 1 content, stay silent; sounds that fail to load are
+/// reported once and then skipped.
+fn play_footsteps(
+    runtime: &mut TestMapRuntime,
+    audio: &AudioEngine,
+    asset_manager: &AssetManager,
+    footsteps: &[Footstep],
+) {
+    for footstep in footsteps {
+        let sounds: &[String] = match &footstep.surface {
+            Some(surface) => &surface.footsteps,
+            None => runtime
+                .surfaces
+                .spec(&runtime.surfaces.default_surface)
+                .map_or(&[], |spec| spec.footsteps.as_slice()),
+        };
+        if sounds.is_empty() {
+            continue;
+        }
+        let asset = &sounds[runtime.footstep_count % sounds.len()];
+        runtime.footstep_count = runtime.footstep_count.wrapping_add(1);
+        let sound = runtime
+            .footstep_sounds
+            .entry(asset.clone())
+            .or_insert_with(|| {
+                load_wav_sfx(asset_manager, asset, AssetBudgetTag::Streaming)
+                    .map_err(|err| eprintln!("footstep sound {} failed: {}", asset, err.message))
+                    .ok()
+            });
+        if let Some(data) = sound {
+            if let Err(err) = audio.play_wav(data.clone()) {
+                eprintln!("footstep play failed: {}", err);
+            }
+        }
+    }
+}
+
+fn respawn_test_map_player(
+    runtime: &mut TestMapRuntime,
+    camera: &mut CameraState,
+    spawn: &GameSpawnPoint,
+) {
+    camera.position = Vec3::new(
+        spawn.origin[0],
+        spawn.origin[1] + camera.eye_height,
+        spawn.origin[2],
+    );
+    if let Some(yaw_deg) = spawn.yaw_deg {
+        camera.yaw = yaw_deg.to_radians();
+        camera.pitch = 0.0;
+    }
+    sync_test_map_runtime_to_camera(runtime, camera);
+    drop_test_map_runtime(runtime, TEST_MAP_SPAWN_DROP);
+    camera.position.y = runtime.position.translation.y - runtime.capsule_offset + camera.eye_height;
+    camera.velocity = Vec3::zero();
+    camera.vertical_velocity = 0.0;
+    camera.on_ground = runtime.grounded;
 }
 
 fn update_kcc_query_ms(previous: f32, elapsed: Duration) -> f32 {
@@ -10922,8 +12210,20 @@ fn apply_test_map_interpolation(runtime: &TestMapRuntime, camera: &mut CameraSta
     let interp_x = prev.x + (curr.x - prev.x) * alpha;
     let interp_y = prev.y + (curr.y - prev.y) * alpha;
     let interp_z = prev.z + (curr.z - prev.z) * alpha;
-    let origin_y = interp_y - runtime.capsule_offset;
-    camera.position = Vec3::new(interp_x, origin_y + camera.eye_height, interp_z);
+    if runtime.controller.camera().mode() == CameraMode::FirstPerson {
+        let origin_y = interp_y - runtime.capsule_offset;
+        let eye_height = camera.eye_height - runtime.controller.camera().eye_drop();
+        camera.position = Vec3::new(interp_x, origin_y + eye_height, interp_z);
+    } else {
+        let eye = runtime.prev_eye + (runtime.eye - runtime.prev_eye) * alpha;
+        camera.position = Vec3::new(eye.x, eye.y, eye.z);
+    }
+    let feel = runtime.prev_feel.lerp(&runtime.feel, alpha);
+    camera.position = camera
+        .position
+        .add(Vec3::new(feel.offset.x, feel.offset.y, feel.offset.z));
+    camera.roll = feel.roll;
+    camera.fov_offset = feel.fov_offset;
     let prev_vel = runtime.prev_velocity;
     let curr_vel = runtime.velocity;
     let vel = Vec3::new(
@@ -11186,7 +12486,19 @@ fn push_triangle(
     Ok(())
 }
 
-fn build_scene_mesh(bsp: &Bsp) -> Result<(MeshData, Bounds, SceneCollision), ExitError> {
+/// Render vertices of an inline brush model a mover drives, at rest.
+struct SubmodelMesh {
+    model: u32,
+    first_vertex: usize,
+    vertices: Vec<MeshVertex>,
+    /// Offset last uploaded, in Quake units on render axes.
+    drawn_offset: Vec3,
+}
+
+fn build_scene_mesh(
+    bsp: &Bsp,
+    entities: &[MapEntity],
+) -> Result<(MeshData, Bounds, SceneCollision, Vec<SubmodelMesh>), ExitError> {
     let face_range = bsp.world_face_range().unwrap_or(0..bsp.faces.len());
 
     let mut vertices = Vec::new();
@@ -11195,99 +12507,13 @@ fn build_scene_mesh(bsp: &Bsp) -> Result<(MeshData, Bounds, SceneCollision), Exi
     let mut bounds = Bounds::empty();
 
     for face_index in face_range {
-        let face = match bsp.faces.get(face_index) {
-            Some(face) => face,
-            None => {
-                return Err(ExitError::new(
-                    EXIT_BSP,
-                    format!("face index out of bounds: {}", face_index),
-                ));
-            }
-        };
-
-        let num_edges = face.num_edges as usize;
-        if num_edges < 3 {
-            continue;
-        }
-        let first_edge = usize::try_from(face.first_edge).map_err(|_| {
-            ExitError::new(
-                EXIT_BSP,
-                format!("face has negative first_edge: {}", face.first_edge),
-            )
-        })?;
-        let end = first_edge
-            .checked_add(num_edges)
-            .ok_or_else(|| ExitError::new(EXIT_BSP, "face edge range overflow"))?;
-        if end > bsp.surfedges.len() {
-            return Err(ExitError::new(
-                EXIT_BSP,
-                format!(
-                    "face edge range out of bounds: {}..{} (surfedges {})",
-                    first_edge,
-                    end,
-                    bsp.surfedges.len()
-                ),
-            ));
-        }
-
-        let mut polygon = Vec::with_capacity(num_edges);
-        for &surfedge in &bsp.surfedges[first_edge..end] {
-            let edge_index = if surfedge < 0 { -surfedge } else { surfedge } as usize;
-            let reversed = surfedge < 0;
-            let edge = match bsp.edges.get(edge_index) {
-                Some(edge) => *edge,
-                None => {
-                    return Err(ExitError::new(
-                        EXIT_BSP,
-                        format!("edge index out of bounds: {}", edge_index),
-                    ));
-                }
-            };
-            let vertex_index = if reversed { edge[1] } else { edge[0] } as usize;
-            let vertex = match bsp.vertices.get(vertex_index) {
-                Some(vertex) => *vertex,
-                None => {
-                    return Err(ExitError::new(
-                        EXIT_BSP,
-                        format!("vertex index out of bounds: {}", vertex_index),
-                    ));
-                }
-            };
-            polygon.push(vertex);
-        }
-
-        if polygon.len() < 3 {
-            continue;
-        }
-
-        let v0 = quake_to_render(polygon[0]);
-        for i in 1..polygon.len() - 1 {
-            let v1 = quake_to_render(polygon[i]);
-            let v2 = quake_to_render(polygon[i + 1]);
-            let normal = v1.sub(v0).cross(v2.sub(v0)).normalize_or_zero();
+        for [v0, v1, v2] in face_triangles(bsp, face_index)? {
+            let normal = push_scene_triangle(&mut vertices, &mut indices, [v0, v1, v2])?;
             if let Some(triangle) = Triangle::from_normal(v0, v1, v2, normal) {
                 if triangle.normal.y >= FLOOR_NORMAL_MIN {
                     floors.push(triangle);
                 }
             }
-            let color = normal.abs().scale(0.8).add(Vec3::new(0.2, 0.2, 0.2));
-
-            let base = u32::try_from(vertices.len())
-                .map_err(|_| ExitError::new(EXIT_BSP, "vertex count overflow building mesh"))?;
-            vertices.push(MeshVertex {
-                position: v0.to_array(),
-                color: color.to_array(),
-            });
-            vertices.push(MeshVertex {
-                position: v1.to_array(),
-                color: color.to_array(),
-            });
-            vertices.push(MeshVertex {
-                position: v2.to_array(),
-                color: color.to_array(),
-            });
-            indices.extend_from_slice(&[base, base + 1, base + 2]);
-
             bounds.include(v0);
             bounds.include(v1);
             bounds.include(v2);
@@ -11301,6 +12527,47 @@ fn build_scene_mesh(bsp: &Bsp) -> Result<(MeshData, Bounds, SceneCollision), Exi
         ));
     }
 
+    // Brush entities draw and block where the BSP put them until a mover
+    // shifts them; triggers are invisible and only sensed by the session.
+    let mut submodels = Vec::new();
+    let mut submodel_meshes = Vec::new();
+    for entity in entities {
+        let Some(model) = entity.brush_model.filter(|model| *model > 0) else {
+            continue;
+        };
+        if entity.classname.starts_with("trigger_")
+            || submodel_meshes
+                .iter()
+                .any(|mesh: &SubmodelMesh| mesh.model == model)
+        {
+            continue;
+        }
+        let Some(face_range) = bsp.model_face_range(model as usize) else {
+            continue;
+        };
+        let first_vertex = vertices.len();
+        for face_index in face_range {
+            for triangle in face_triangles(bsp, face_index)? {
+                push_scene_triangle(&mut vertices, &mut indices, triangle)?;
+            }
+        }
+        submodel_meshes.push(SubmodelMesh {
+            model,
+            first_vertex,
+            vertices: vertices[first_vertex..].to_vec(),
+            drawn_offset: Vec3::zero(),
+        });
+        if entity.classname != "func_illusionary" && !bsp.clipnodes.is_empty() {
+            if let Some(bsp_model) = bsp.models.get(model as usize) {
+                submodels.push(SubmodelHull {
+                    model,
+                    headnode: bsp_model.headnode[1],
+                    offset: Vec3::zero(),
+                });
+            }
+        }
+    }
+
     let mesh = MeshData::new(vertices, indices)
         .map_err(|err| ExitError::new(EXIT_BSP, format!("mesh build failed: {}", err)))?;
     let planes = bsp
@@ -11335,10 +12602,112 @@ fn build_scene_mesh(bsp: &Bsp) -> Result<(MeshData, Bounds, SceneCollision), Exi
             planes,
             clipnodes,
             headnode,
+            submodels,
         },
+        submodel_meshes,
     ))
 }
 
+/// Fan-triangulates BSP face `face_index` into render-axis triangles.
+fn face_triangles(bsp: &Bsp, face_index: usize) -> Result<Vec<[Vec3; 3]>, ExitError> {
+    let face = match bsp.faces.get(face_index) {
+        Some(face) => face,
+        None => {
+            return Err(ExitError::new(
+                EXIT_BSP,
+                format!("face index out of bounds: {}", face_index),
+            ));
+        }
+    };
+
+    let num_edges = face.num_edges as usize;
+    if num_edges < 3 {
+        return Ok(Vec::new());
+    }
+    let first_edge = usize::try_from(face.first_edge).map_err(|_| {
+        ExitError::new(
+            EXIT_BSP,
+            format!("face has negative first_edge: {}", face.first_edge),
+        )
+    })?;
+    let end = first_edge
+        .checked_add(num_edges)
+        .ok_or_else(|| ExitError::new(EXIT_BSP, "face edge range overflow"))?;
+    if end > bsp.surfedges.len() {
+        return Err(ExitError::new(
+            EXIT_BSP,
+            format!(
+                "face edge range out of bounds: {}..{} (surfedges {})",
+                first_edge,
+                end,
+                bsp.surfedges.len()
+            ),
+        ));
+    }
+
+    let mut polygon = Vec::with_capacity(num_edges);
+    for &surfedge in &bsp.surfedges[first_edge..end] {
+        let edge_index = if surfedge < 0 { -surfedge } else { surfedge } as usize;
+        let reversed = surfedge < 0;
+        let edge = match bsp.edges.get(edge_index) {
+            Some(edge) => *edge,
+            None => {
+                return Err(ExitError::new(
+                    EXIT_BSP,
+                    format!("edge index out of bounds: {}", edge_index),
+                ));
+            }
+        };
+        let vertex_index = if reversed { edge[1] } else { edge[0] } as usize;
+        let vertex = match bsp.vertices.get(vertex_index) {
+            Some(vertex) => *vertex,
+            None => {
+                return Err(ExitError::new(
+                    EXIT_BSP,
+                    format!("vertex index out of bounds: {}", vertex_index),
+                ));
+            }
+        };
+        polygon.push(vertex);
+    }
+
+    if polygon.len() < 3 {
+        return Ok(Vec::new());
+    }
+
+    let v0 = quake_to_render(polygon[0]);
+    Ok((1..polygon.len() - 1)
+        .map(|i| {
+            [
+                v0,
+                quake_to_render(polygon[i]),
+                quake_to_render(polygon[i + 1]),
+            ]
+        })
+        .collect())
+}
+
+/// Appends a flat-shaded triangle and returns its normal.
+fn push_scene_triangle(
+    vertices: &mut Vec<MeshVertex>,
+    indices: &mut Vec<u32>,
+    [v0, v1, v2]: [Vec3; 3],
+) -> Result<Vec3, ExitError> {
+    let normal = v1.sub(v0).cross(v2.sub(v0)).normalize_or_zero();
+    let color = normal.abs().scale(0.8).add(Vec3::new(0.2, 0.2, 0.2));
+
+    let base = u32::try_from(vertices.len())
+        .map_err(|_| ExitError::new(EXIT_BSP, "vertex count overflow building mesh"))?;
+    for position in [v0, v1, v2] {
+        vertices.push(MeshVertex {
+            position: position.to_array(),
+            color: color.to_array(),
+        });
+    }
+    indices.extend_from_slice(&[base, base + 1, base + 2]);
+    Ok(normal)
+}
+
 fn normalize_asset_name(name: &str) -> String {
     let normalized = name.replace('\\', "/");
     normalized
@@ -11558,9 +12927,10 @@ fn handle_non_video_key_input(
     window: &Window,
     settings: &mut Settings,
     settings_flags: &mut SettingsChangeFlags,
-    test_map_reload_requests: &mut VecDeque<AssetKey>,
+    test_map_reload_requests: &mut VecDeque<TestMapReloadRequest>,
     active_test_map: Option<AssetKey>,
     test_map_runtime: Option<&mut TestMapRuntime>,
+    map_session: Option<&mut MapSession>,
     camera: &mut CameraState,
     input_trace_record: &mut Option<InputTraceRecorder>,
     input_trace_playback: &mut Option<InputTracePlayback>,
@@ -11674,6 +13044,7 @@ fn handle_non_video_key_input(
                                 test_map_reload_requests,
                                 active_test_map.clone(),
                                 test_map_runtime,
+                                map_session,
                                 Some(camera),
                                 input_trace_record,
                                 input_trace_playback,
@@ -1,6 +1,11 @@
 //! Rapier integration entrypoints and shared world setup.
 #![forbid(unsafe_code)]
 
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use rapier3d::control::{CharacterCollision, KinematicCharacterController};
+use rapier3d::parry::query::ShapeCastOptions;
 use rapier3d::prelude::*;
 
 #[derive(Clone, Copy, Debug, Default)]
@@ -20,6 +25,8 @@ pub struct DebugLine {
 #[derive(Default)]
 pub struct PhysicsDebugLines {
     pub lines: Vec<DebugLine>,
+    /// Colliders attached to dynamic bodies, drawn in their own color.
+    dynamic_colliders: HashSet<ColliderHandle>,
 }
 
 impl PhysicsDebugLines {
@@ -41,6 +48,11 @@ impl rapier3d::pipeline::DebugRenderBackend for PhysicsDebugLines {
         _color: [f32; 4],
     ) {
         let color = match object {
+            rapier3d::pipeline::DebugRenderObject::Collider(handle, _)
+                if self.dynamic_colliders.contains(&handle) =>
+            {
+                [0.95, 0.55, 0.15, 1.0]
+            }
             rapier3d::pipeline::DebugRenderObject::Collider(..)
             | rapier3d::pipeline::DebugRenderObject::ColliderAabb(..) => [0.2, 0.8, 0.9, 1.0],
             rapier3d::pipeline::DebugRenderObject::RigidBody(..) => [0.3, 0.7, 0.3, 1.0],
@@ -52,6 +64,267 @@ impl rapier3d::pipeline::DebugRenderBackend for PhysicsDebugLines {
     }
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub enum DynamicBodyShape {
+    Box {
+        half_extents: [f32; 3],
+    },
+    Sphere {
+        radius: f32,
+    },
+    /// Points in body space.
+    ConvexHull {
+        points: Vec<[f32; 3]>,
+    },
+}
+
+/// A free rigid body such as a crate or barrel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DynamicBodyDesc {
+    pub shape: DynamicBodyShape,
+    pub position: Isometry<Real>,
+    /// Kilograms.
+    pub mass: Real,
+    pub friction: Real,
+    pub restitution: Real,
+    /// Continuous collision detection, for small or fast bodies.
+    pub ccd: bool,
+}
+
+impl DynamicBodyDesc {
+    pub fn new(shape: DynamicBodyShape, position: Isometry<Real>, mass: Real) -> Self {
+        Self {
+            shape,
+            position,
+            mass,
+            friction: 0.5,
+            restitution: 0.0,
+            ccd: false,
+        }
+    }
+
+    fn collider(&self) -> Option<Collider> {
+        let builder = match &self.shape {
+            DynamicBodyShape::Box { half_extents } => {
+                ColliderBuilder::cuboid(half_extents[0], half_extents[1], half_extents[2])
+            }
+            DynamicBodyShape::Sphere { radius } => ColliderBuilder::ball(*radius),
+            DynamicBodyShape::ConvexHull { points } => {
+                let points: Vec<Point<Real>> = points
+                    .iter()
+                    .map(|point| Point::new(point[0], point[1], point[2]))
+                    .collect();
+                ColliderBuilder::convex_hull(&points)?
+            }
+        };
+        Some(
+            builder
+                .mass(self.mass)
+                .friction(self.friction)
+                .restitution(self.restitution)
+                .build(),
+        )
+    }
+}
+
+/// Interaction groups from raw layer bits (see `collision_world::layers`).
+pub fn layer_groups(memberships: u32, filter: u32) -> InteractionGroups {
+    InteractionGroups::new(
+        Group::from_bits_truncate(memberships),
+        Group::from_bits_truncate(filter),
+    )
+}
+
+/// Query groups that hit colliders on any layer in `mask`.
+pub fn layer_mask_groups(mask: u32) -> InteractionGroups {
+    layer_groups(u32::MAX, mask)
+}
+
+/// Content a collider was built from, reported with query hits.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ColliderSource {
+    /// A test map solid (trigger and mover solids included).
+    Solid { id: String, tags: Vec<String> },
+    /// A cooked collision world chunk.
+    Chunk { chunk_id: String },
+    /// A dynamic prop.
+    Prop { id: String },
+}
+
+impl ColliderSource {
+    pub fn id(&self) -> &str {
+        match self {
+            ColliderSource::Solid { id, .. } | ColliderSource::Prop { id } => id,
+            ColliderSource::Chunk { chunk_id } => chunk_id,
+        }
+    }
+
+    pub fn tags(&self) -> &[String] {
+        match self {
+            ColliderSource::Solid { tags, .. } => tags,
+            _ => &[],
+        }
+    }
+}
+
+/// A solid hit by a ray or shape cast.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SceneHit {
+    pub collider: ColliderHandle,
+    /// Distance along the ray, or fraction of the cast velocity.
+    pub toi: Real,
+    pub point: Point<Real>,
+    pub normal: Vector<Real>,
+    pub source: Option<ColliderSource>,
+}
+
+/// A collider overlapping an `overlap_*` query volume.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SceneOverlap {
+    pub collider: ColliderHandle,
+    pub source: Option<ColliderSource>,
+}
+
+/// Liquid a volume is filled with; content tags `water`, `slime` and `lava`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LiquidKind {
+    Water,
+    Slime,
+    Lava,
+}
+
+impl LiquidKind {
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "water" => Some(LiquidKind::Water),
+            "slime" => Some(LiquidKind::Slime),
+            "lava" => Some(LiquidKind::Lava),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LiquidKind::Water => "water",
+            LiquidKind::Slime => "slime",
+            LiquidKind::Lava => "lava",
+        }
+    }
+}
+
+/// The deepest liquid volume a vertical span reaches into.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LiquidContact {
+    pub kind: LiquidKind,
+    pub collider: ColliderHandle,
+    /// How far the liquid surface sits above the span's base, clamped to the
+    /// span height.
+    pub depth: Real,
+}
+
+/// Ground tuning of the surface material a collider is made of.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SurfaceMaterial {
+    /// Index in the host's surface table, for names.
+    pub id: u16,
+    /// Multiplier on ground friction.
+    pub friction: Real,
+    /// Multiplier on ground acceleration.
+    pub acceleration: Real,
+    /// Ice-like: slow movement keeps sliding instead of stopping.
+    pub slide: bool,
+    /// Footstep sound assets, played in turn; empty when the content has none.
+    pub footsteps: Arc<[String]>,
+}
+
+/// A climbable collider found by `climbable_contact`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClimbContact {
+    pub collider: ColliderHandle,
+    /// Horizontal unit normal pointing from the surface toward the probe.
+    pub normal: Vector<Real>,
+    /// Distance the probe travelled before touching the surface.
+    pub distance: Real,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PhysicsEventKind {
+    /// Two solid colliders started or stopped touching.
+    Contact,
+    /// A sensor started or stopped overlapping another collider.
+    Intersection,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PhysicsEvent {
+    pub kind: PhysicsEventKind,
+    pub started: bool,
+    pub collider1: ColliderHandle,
+    pub collider2: ColliderHandle,
+}
+
+impl PhysicsEvent {
+    fn from_collision(event: CollisionEvent) -> Self {
+        let kind = if event.sensor() {
+            PhysicsEventKind::Intersection
+        } else {
+            PhysicsEventKind::Contact
+        };
+        Self {
+            kind,
+            started: event.started(),
+            collider1: event.collider1(),
+            collider2: event.collider2(),
+        }
+    }
+
+    /// The handle paired with `collider`, if this event involves it.
+    pub fn other(&self, collider: ColliderHandle) -> Option<ColliderHandle> {
+        if self.collider1 == collider {
+            Some(self.collider2)
+        } else if self.collider2 == collider {
+            Some(self.collider1)
+        } else {
+            None
+        }
+    }
+}
+
+/// Collects events raised during `PhysicsPipeline::step` until drained.
+#[derive(Default)]
+struct EventQueue {
+    events: Mutex<Vec<PhysicsEvent>>,
+}
+
+impl EventQueue {
+    fn drain(&mut self) -> Vec<PhysicsEvent> {
+        std::mem::take(self.events.get_mut().unwrap_or_else(|err| err.into_inner()))
+    }
+}
+
+impl EventHandler for EventQueue {
+    fn handle_collision_event(
+        &self,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        event: CollisionEvent,
+        _contact_pair: Option<&ContactPair>,
+    ) {
+        let mut events = self.events.lock().unwrap_or_else(|err| err.into_inner());
+        events.push(PhysicsEvent::from_collision(event));
+    }
+
+    fn handle_contact_force_event(
+        &self,
+        _dt: Real,
+        _bodies: &RigidBodySet,
+        _colliders: &ColliderSet,
+        _contact_pair: &ContactPair,
+        _total_force_magnitude: Real,
+    ) {
+    }
+}
+
 pub struct PhysicsWorld {
     pub gravity: Vector<Real>,
     integration_parameters: IntegrationParameters,
@@ -66,6 +339,13 @@ pub struct PhysicsWorld {
     ccd_solver: CCDSolver,
     query_pipeline: QueryPipeline,
     debug_pipeline: rapier3d::pipeline::DebugRenderPipeline,
+    events: EventQueue,
+    /// How far each kinematic body moved during the last `step`.
+    kinematic_motion: HashMap<RigidBodyHandle, Isometry<Real>>,
+    sources: HashMap<ColliderHandle, ColliderSource>,
+    liquids: HashMap<ColliderHandle, LiquidKind>,
+    climbables: HashSet<ColliderHandle>,
+    surfaces: HashMap<ColliderHandle, SurfaceMaterial>,
 }
 
 impl PhysicsWorld {
@@ -84,6 +364,12 @@ impl PhysicsWorld {
             ccd_solver: CCDSolver::new(),
             query_pipeline: QueryPipeline::new(),
             debug_pipeline: rapier3d::pipeline::DebugRenderPipeline::default(),
+            events: EventQueue::default(),
+            kinematic_motion: HashMap::new(),
+            sources: HashMap::new(),
+            liquids: HashMap::new(),
+            climbables: HashSet::new(),
+            surfaces: HashMap::new(),
         }
     }
 
@@ -99,10 +385,18 @@ impl PhysicsWorld {
         &self.query_pipeline
     }
 
+    /// Advances the simulation by `dt`. Events the caller did not drain after
+    /// the previous step are dropped, so the queue never outgrows one step.
     pub fn step(&mut self, dt: Real) {
+        self.events.drain();
         self.integration_parameters.dt = dt;
+        let before: Vec<(RigidBodyHandle, Isometry<Real>)> = self
+            .bodies
+            .iter()
+            .filter(|(_, body)| body.is_kinematic())
+            .map(|(handle, body)| (handle, *body.position()))
+            .collect();
         let physics_hooks = ();
-        let event_handler = ();
         self.pipeline.step(
             &self.gravity,
             &self.integration_parameters,
@@ -116,15 +410,470 @@ impl PhysicsWorld {
             &mut self.ccd_solver,
             Some(&mut self.query_pipeline),
             &physics_hooks,
-            &event_handler,
+            &self.events,
         );
         self.query_pipeline.update(&self.colliders);
+        self.kinematic_motion.clear();
+        for (handle, previous) in before {
+            if let Some(body) = self.bodies.get(handle) {
+                self.kinematic_motion
+                    .insert(handle, body.position() * previous.inverse());
+            }
+        }
     }
 
     pub fn insert_static_collider(&mut self, collider: Collider) -> ColliderHandle {
         self.colliders.insert(collider)
     }
 
+    /// Attaches `collider` to a new kinematic position-based body placed at
+    /// the collider's current position; drive it with `set_kinematic_position`.
+    pub fn insert_kinematic_collider(
+        &mut self,
+        mut collider: Collider,
+    ) -> (RigidBodyHandle, ColliderHandle) {
+        let position = *collider.position();
+        collider.set_position(Isometry::identity());
+        let body = self.bodies.insert(
+            RigidBodyBuilder::kinematic_position_based()
+                .position(position)
+                .build(),
+        );
+        let handle = self
+            .colliders
+            .insert_with_parent(collider, body, &mut self.bodies);
+        (body, handle)
+    }
+
+    /// Moves a kinematic body to `position` over the next `step`.
+    pub fn set_kinematic_position(&mut self, body: RigidBodyHandle, position: Isometry<Real>) {
+        if let Some(body) = self.bodies.get_mut(body) {
+            body.set_next_kinematic_position(position);
+        }
+    }
+
+    /// How the kinematic body owning `collider` moved during the last step,
+    /// as a world-space transform; `None` for static or unknown colliders.
+    pub fn kinematic_displacement(&self, collider: ColliderHandle) -> Option<Isometry<Real>> {
+        let parent = self.colliders.get(collider)?.parent()?;
+        self.kinematic_motion.get(&parent).copied()
+    }
+
+    /// Adds a dynamic body; `None` if a convex hull cannot be built from the
+    /// given points.
+    pub fn insert_dynamic_body(
+        &mut self,
+        desc: &DynamicBodyDesc,
+    ) -> Option<(RigidBodyHandle, ColliderHandle)> {
+        let collider = desc.collider()?;
+        let body = self.bodies.insert(
+            RigidBodyBuilder::dynamic()
+                .position(desc.position)
+                .ccd_enabled(desc.ccd)
+                .build(),
+        );
+        let handle = self
+            .colliders
+            .insert_with_parent(collider, body, &mut self.bodies);
+        Some((body, handle))
+    }
+
+    /// Teleports a body to `position` moving at the given velocities, e.g.
+    /// when loading a save. Kinematic bodies also hold that pose over the
+    /// next `step`.
+    pub fn set_body_state(
+        &mut self,
+        body: RigidBodyHandle,
+        position: Isometry<Real>,
+        linvel: Vector<Real>,
+        angvel: Vector<Real>,
+    ) {
+        if let Some(body) = self.bodies.get_mut(body) {
+            body.set_position(position, true);
+            if body.is_kinematic() {
+                body.set_next_kinematic_position(position);
+            } else {
+                body.set_linvel(linvel, true);
+                body.set_angvel(angvel, true);
+            }
+        }
+    }
+
+    pub fn remove_body(&mut self, body: RigidBodyHandle) {
+        if let Some(removed) = self.bodies.get(body) {
+            for collider in removed.colliders() {
+                self.sources.remove(collider);
+                self.liquids.remove(collider);
+                self.climbables.remove(collider);
+                self.surfaces.remove(collider);
+            }
+        }
+        self.bodies.remove(
+            body,
+            &mut self.island_manager,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        );
+    }
+
+    /// Pushes dynamic bodies the character ran into during its last move.
+    /// Bodies heavier than `max_push_mass` are left alone and keep blocking
+    /// the character.
+    pub fn apply_character_impulses(
+        &mut self,
+        controller: &KinematicCharacterController,
+        dt: Real,
+        shape: &dyn Shape,
+        character_mass: Real,
+        max_push_mass: Real,
+        collisions: &[CharacterCollision],
+    ) {
+        let pushable: HashSet<ColliderHandle> = collisions
+            .iter()
+            .filter(|collision| {
+                self.colliders
+                    .get(collision.handle)
+                    .and_then(|collider| collider.parent())
+                    .and_then(|parent| self.bodies.get(parent))
+                    .is_some_and(|body| body.is_dynamic() && body.mass() <= max_push_mass)
+            })
+            .map(|collision| collision.handle)
+            .collect();
+        if pushable.is_empty() {
+            return;
+        }
+        let predicate = |handle: ColliderHandle, _: &Collider| pushable.contains(&handle);
+        controller.solve_character_collision_impulses(
+            dt,
+            &mut self.bodies,
+            &self.colliders,
+            &self.query_pipeline,
+            shape,
+            character_mass,
+            collisions,
+            QueryFilter::default()
+                .exclude_sensors()
+                .predicate(&predicate),
+        );
+    }
+
+    /// Inserts `collider` as a sensor that reports intersection events with
+    /// every other collider, static ones included.
+    pub fn insert_sensor_collider(&mut self, mut collider: Collider) -> ColliderHandle {
+        collider.set_sensor(true);
+        collider.set_active_events(ActiveEvents::COLLISION_EVENTS);
+        collider.set_active_collision_types(ActiveCollisionTypes::all());
+        self.colliders.insert(collider)
+    }
+
+    /// Inserts `collider` as a sensor filled with `kind`. Liquids still raise
+    /// intersection events; `liquid_depth` and `liquid_at` find them.
+    pub fn insert_liquid_collider(
+        &mut self,
+        collider: Collider,
+        kind: LiquidKind,
+    ) -> ColliderHandle {
+        let handle = self.insert_sensor_collider(collider);
+        self.liquids.insert(handle, kind);
+        handle
+    }
+
+    pub fn liquid_kind(&self, collider: ColliderHandle) -> Option<LiquidKind> {
+        self.liquids.get(&collider).copied()
+    }
+
+    /// Liquid containing `point`, if any.
+    pub fn liquid_at(&self, point: Point<Real>) -> Option<(ColliderHandle, LiquidKind)> {
+        let mut found = None;
+        let predicate = |handle: ColliderHandle, _: &Collider| self.liquids.contains_key(&handle);
+        self.query_pipeline.intersections_with_point(
+            &self.bodies,
+            &self.colliders,
+            &point,
+            QueryFilter::default().predicate(&predicate),
+            |handle| {
+                found = self.liquid_kind(handle).map(|kind| (handle, kind));
+                false
+            },
+        );
+        found
+    }
+
+    /// How deep the vertical span from `base` up to `base + height` sits in
+    /// liquid, found by casting down from the top of the span. A span whose
+    /// top is inside a liquid reports the full `height`.
+    pub fn liquid_depth(&self, base: Point<Real>, height: Real) -> Option<LiquidContact> {
+        if height <= 0.0 {
+            return None;
+        }
+        let ray = Ray::new(base + Vector::y() * height, -Vector::y());
+        let predicate = |handle: ColliderHandle, _: &Collider| self.liquids.contains_key(&handle);
+        let mut deepest: Option<LiquidContact> = None;
+        self.query_pipeline.intersections_with_ray(
+            &self.bodies,
+            &self.colliders,
+            &ray,
+            height,
+            true,
+            QueryFilter::default().predicate(&predicate),
+            |collider, hit| {
+                let depth = (height - hit.time_of_impact).clamp(0.0, height);
+                let deeper = deepest.is_none_or(|contact| depth > contact.depth);
+                if let (true, Some(kind)) = (deeper, self.liquid_kind(collider)) {
+                    deepest = Some(LiquidContact {
+                        kind,
+                        collider,
+                        depth,
+                    });
+                }
+                true
+            },
+        );
+        deepest
+    }
+
+    /// Marks `collider` as a surface characters can climb: a solid ladder or
+    /// a sensor volume enclosing one.
+    pub fn set_climbable(&mut self, collider: ColliderHandle, climbable: bool) {
+        if climbable {
+            self.climbables.insert(collider);
+        } else {
+            self.climbables.remove(&collider);
+        }
+    }
+
+    pub fn is_climbable(&self, collider: ColliderHandle) -> bool {
+        self.climbables.contains(&collider)
+    }
+
+    /// Records the surface material of `collider`; `None` clears it.
+    pub fn set_surface(&mut self, collider: ColliderHandle, surface: Option<SurfaceMaterial>) {
+        match surface {
+            Some(surface) => {
+                self.surfaces.insert(collider, surface);
+            }
+            None => {
+                self.surfaces.remove(&collider);
+            }
+        }
+    }
+
+    pub fn surface(&self, collider: ColliderHandle) -> Option<SurfaceMaterial> {
+        self.surfaces.get(&collider).cloned()
+    }
+
+    /// Nearest climbable collider `shape` touches moving from `position`
+    /// along `direction` (normalized) within `reach`. Solid faces that are
+    /// not roughly vertical are ignored; sensor volumes have no face, so
+    /// they report a normal facing back along `direction`.
+    pub fn climbable_contact(
+        &self,
+        shape: &dyn Shape,
+        position: &Isometry<Real>,
+        direction: Vector<Real>,
+        reach: Real,
+    ) -> Option<ClimbContact> {
+        let predicate = |handle: ColliderHandle, _: &Collider| self.climbables.contains(&handle);
+        let (collider, hit) = self.query_pipeline.cast_shape(
+            &self.bodies,
+            &self.colliders,
+            position,
+            &direction,
+            shape,
+            ShapeCastOptions {
+                max_time_of_impact: reach,
+                target_distance: 0.0,
+                stop_at_penetration: true,
+                compute_impact_geometry_on_penetration: true,
+            },
+            QueryFilter::default().predicate(&predicate),
+        )?;
+        let sensor = self
+            .colliders
+            .get(collider)
+            .is_some_and(|collider| collider.is_sensor());
+        let facing = if sensor { -direction } else { *hit.normal1 };
+        let normal = Vector::new(facing.x, 0.0, facing.z);
+        if normal.norm() < 0.7 {
+            return None;
+        }
+        Some(ClimbContact {
+            collider,
+            normal: normal.normalize(),
+            distance: hit.time_of_impact,
+        })
+    }
+
+    /// Contact and intersection events raised by the last `step`.
+    pub fn drain_events(&mut self) -> Vec<PhysicsEvent> {
+        self.events.drain()
+    }
+
+    /// Sensors overlapping `shape` at `position`. Kinematic characters have no
+    /// collider in the world, so they poll this instead of relying on events.
+    pub fn sensors_intersecting(
+        &self,
+        shape: &dyn Shape,
+        position: &Isometry<Real>,
+    ) -> Vec<ColliderHandle> {
+        let mut sensors = Vec::new();
+        self.query_pipeline.intersections_with_shape(
+            &self.bodies,
+            &self.colliders,
+            position,
+            shape,
+            QueryFilter::default().exclude_solids(),
+            |handle| {
+                sensors.push(handle);
+                true
+            },
+        );
+        sensors
+    }
+
+    /// Records the content `collider` came from, for query hits.
+    pub fn set_collider_source(&mut self, collider: ColliderHandle, source: ColliderSource) {
+        self.sources.insert(collider, source);
+    }
+
+    pub fn collider_source(&self, collider: ColliderHandle) -> Option<&ColliderSource> {
+        self.sources.get(&collider)
+    }
+
+    /// Closest solid collider along `direction` (normalized) within
+    /// `max_toi`, ignoring sensors and colliders outside `groups`.
+    pub fn raycast(
+        &self,
+        origin: Point<Real>,
+        direction: Vector<Real>,
+        max_toi: Real,
+        groups: InteractionGroups,
+    ) -> Option<SceneHit> {
+        let ray = Ray::new(origin, direction);
+        let (collider, hit) = self.query_pipeline.cast_ray_and_get_normal(
+            &self.bodies,
+            &self.colliders,
+            &ray,
+            max_toi,
+            true,
+            QueryFilter::default().exclude_sensors().groups(groups),
+        )?;
+        Some(SceneHit {
+            collider,
+            toi: hit.time_of_impact,
+            point: ray.point_at(hit.time_of_impact),
+            normal: hit.normal,
+            source: self.sources.get(&collider).cloned(),
+        })
+    }
+
+    /// Every solid collider along the ray, nearest first.
+    pub fn raycast_all(
+        &self,
+        origin: Point<Real>,
+        direction: Vector<Real>,
+        max_toi: Real,
+        groups: InteractionGroups,
+    ) -> Vec<SceneHit> {
+        let ray = Ray::new(origin, direction);
+        let mut hits = Vec::new();
+        self.query_pipeline.intersections_with_ray(
+            &self.bodies,
+            &self.colliders,
+            &ray,
+            max_toi,
+            true,
+            QueryFilter::default().exclude_sensors().groups(groups),
+            |collider, hit| {
+                hits.push(SceneHit {
+                    collider,
+                    toi: hit.time_of_impact,
+                    point: ray.point_at(hit.time_of_impact),
+                    normal: hit.normal,
+                    source: self.sources.get(&collider).cloned(),
+                });
+                true
+            },
+        );
+        hits.sort_by(|a, b| a.toi.total_cmp(&b.toi));
+        hits
+    }
+
+    /// First solid collider `shape` touches moving from `position` along
+    /// `velocity` for up to `max_toi`, with the same filtering as `raycast`.
+    pub fn shapecast(
+        &self,
+        shape: &dyn Shape,
+        position: &Isometry<Real>,
+        velocity: Vector<Real>,
+        max_toi: Real,
+        groups: InteractionGroups,
+    ) -> Option<SceneHit> {
+        let (collider, hit) = self.query_pipeline.cast_shape(
+            &self.bodies,
+            &self.colliders,
+            position,
+            &velocity,
+            shape,
+            ShapeCastOptions {
+                max_time_of_impact: max_toi,
+                target_distance: 0.0,
+                stop_at_penetration: true,
+                compute_impact_geometry_on_penetration: true,
+            },
+            QueryFilter::default().exclude_sensors().groups(groups),
+        )?;
+        Some(SceneHit {
+            collider,
+            toi: hit.time_of_impact,
+            point: hit.witness1,
+            normal: *hit.normal1,
+            source: self.sources.get(&collider).cloned(),
+        })
+    }
+
+    /// Colliders, sensors included, whose shapes overlap the box `min..max`.
+    pub fn overlap_aabb(
+        &self,
+        min: Point<Real>,
+        max: Point<Real>,
+        groups: InteractionGroups,
+    ) -> Vec<SceneOverlap> {
+        let half = (max - min) * 0.5;
+        let cuboid = Cuboid::new(half.map(|value| value.max(0.0)));
+        let center = Isometry::from(nalgebra::center(&min, &max));
+        self.overlap_shape(&cuboid, &center, groups)
+    }
+
+    /// Colliders, sensors included, overlapping `shape` at `position`, in
+    /// handle order.
+    pub fn overlap_shape(
+        &self,
+        shape: &dyn Shape,
+        position: &Isometry<Real>,
+        groups: InteractionGroups,
+    ) -> Vec<SceneOverlap> {
+        let mut overlaps = Vec::new();
+        self.query_pipeline.intersections_with_shape(
+            &self.bodies,
+            &self.colliders,
+            position,
+            shape,
+            QueryFilter::default().groups(groups),
+            |collider| {
+                overlaps.push(SceneOverlap {
+                    collider,
+                    source: self.sources.get(&collider).cloned(),
+                });
+                true
+            },
+        );
+        overlaps.sort_by_key(|overlap| overlap.collider.into_raw_parts());
+        overlaps
+    }
+
     pub fn debug_lines(&mut self, config: DebugDrawConfig) -> PhysicsDebugLines {
         let mut lines = PhysicsDebugLines::default();
         let mut mode = rapier3d::pipeline::DebugRenderMode::empty();
@@ -138,6 +887,17 @@ impl PhysicsWorld {
             mode |= rapier3d::pipeline::DebugRenderMode::CONTACTS;
         }
         if !mode.is_empty() {
+            lines.dynamic_colliders = self
+                .colliders
+                .iter()
+                .filter(|(_, collider)| {
+                    collider
+                        .parent()
+                        .and_then(|parent| self.bodies.get(parent))
+                        .is_some_and(|body| body.is_dynamic())
+                })
+                .map(|(handle, _)| handle)
+                .collect();
             self.debug_pipeline.mode = mode;
             self.debug_pipeline.render(
                 &mut lines,
@@ -164,6 +924,223 @@ mod tests {
         world.insert_static_collider(floor);
     }
 
+    #[test]
+    fn sensor_reports_intersection_events() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        let sensor = world.insert_sensor_collider(
+            ColliderBuilder::cuboid(1.0, 1.0, 1.0)
+                .translation(vector![0.0, 0.5, 0.0])
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+        let events = world.drain_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind, PhysicsEventKind::Intersection);
+        assert!(events[0].started);
+        assert!(events[0].other(sensor).is_some());
+        assert!(world.drain_events().is_empty());
+
+        let capsule = Capsule::new_y(0.9, 0.4);
+        let inside = Isometry::translation(0.0, 1.0, 0.0);
+        let outside = Isometry::translation(4.0, 1.0, 0.0);
+        assert_eq!(world.sensors_intersecting(&capsule, &inside), vec![sensor]);
+        assert!(world.sensors_intersecting(&capsule, &outside).is_empty());
+
+        // An undrained event is dropped by the next step.
+        world.insert_sensor_collider(
+            ColliderBuilder::cuboid(1.0, 1.0, 1.0)
+                .translation(vector![3.0, 0.5, 0.0])
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+        world.step(1.0 / 60.0);
+        assert!(world.drain_events().is_empty());
+    }
+
+    #[test]
+    fn kinematic_body_reports_step_displacement() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        let (body, collider) = world.insert_kinematic_collider(
+            ColliderBuilder::cuboid(1.0, 0.1, 1.0)
+                .translation(vector![0.0, 1.0, 0.0])
+                .build(),
+        );
+        world.set_kinematic_position(body, Isometry::translation(0.0, 1.5, 0.0));
+        world.step(1.0 / 60.0);
+        let moved = world.kinematic_displacement(collider).expect("kinematic");
+        assert!((moved.translation.y - 0.5).abs() < 1.0e-5);
+        assert!((world.colliders().get(collider).unwrap().translation().y - 1.5).abs() < 1.0e-5);
+        world.step(1.0 / 60.0);
+        let idle = world.kinematic_displacement(collider).expect("kinematic");
+        assert!(idle.translation.vector.norm() < 1.0e-5);
+    }
+
+    #[test]
+    fn dynamic_bodies_settle_deterministically() {
+        let run = || {
+            let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+            build_floor(&mut world);
+            let mut crate_desc = DynamicBodyDesc::new(
+                DynamicBodyShape::Box {
+                    half_extents: [0.25, 0.25, 0.25],
+                },
+                Isometry::translation(0.0, 2.0, 0.0),
+                10.0,
+            );
+            crate_desc.ccd = true;
+            let (crate_body, _) = world.insert_dynamic_body(&crate_desc).expect("box");
+            let mut ball_desc = DynamicBodyDesc::new(
+                DynamicBodyShape::Sphere { radius: 0.2 },
+                Isometry::translation(1.0, 1.0, 0.0),
+                2.0,
+            );
+            ball_desc.restitution = 0.5;
+            let (ball, _) = world.insert_dynamic_body(&ball_desc).expect("sphere");
+            for _ in 0..180 {
+                world.step(1.0 / 60.0);
+            }
+            let crate_pos = *world.bodies().get(crate_body).unwrap().position();
+            let ball_pos = *world.bodies().get(ball).unwrap().position();
+            (crate_pos, ball_pos, world)
+        };
+        let (crate_a, ball_a, mut world) = run();
+        let (crate_b, ball_b, _) = run();
+        assert_eq!(crate_a, crate_b);
+        assert_eq!(ball_a, ball_b);
+        assert!((crate_a.translation.y - 0.25).abs() < 0.05);
+
+        let lines = world.debug_lines(DebugDrawConfig {
+            draw_colliders: true,
+            ..DebugDrawConfig::default()
+        });
+        assert!(lines
+            .lines
+            .iter()
+            .any(|line| line.color == [0.95, 0.55, 0.15, 1.0]));
+
+        let wedge = DynamicBodyDesc::new(
+            DynamicBodyShape::ConvexHull {
+                points: vec![[0.0; 3], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]],
+            },
+            Isometry::translation(-2.0, 1.0, 0.0),
+            4.0,
+        );
+        let (wedge, _) = world.insert_dynamic_body(&wedge).expect("hull");
+        assert!((world.bodies().get(wedge).unwrap().mass() - 4.0).abs() < 1.0e-3);
+    }
+
+    #[test]
+    fn layered_queries_skip_masked_colliders() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        world.insert_static_collider(
+            ColliderBuilder::cuboid(0.1, 2.0, 2.0)
+                .translation(vector![2.0, 2.0, 0.0])
+                .collision_groups(layer_groups(0b10, u32::MAX))
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+
+        let origin = Point::new(0.0, 1.0, 0.0);
+        let world_only = layer_mask_groups(0b01);
+        assert!(world
+            .raycast(origin, Vector::x(), 10.0, world_only)
+            .is_none());
+        let hit = world
+            .raycast(origin, Vector::x(), 10.0, layer_mask_groups(0b11))
+            .expect("clip wall");
+        assert!((hit.toi - 1.9).abs() < 1.0e-4);
+        assert!((hit.normal - -Vector::x()).norm() < 1.0e-4);
+        assert!((hit.point.x - 1.9).abs() < 1.0e-4);
+
+        let ball = Ball::new(0.25);
+        let hit = world
+            .shapecast(
+                &ball,
+                &Isometry::translation(0.0, 1.0, 0.0),
+                vector![0.0, -1.0, 0.0],
+                5.0,
+                world_only,
+            )
+            .expect("floor");
+        assert!((hit.toi - 0.75).abs() < 1.0e-3);
+        assert!((hit.normal - Vector::y()).norm() < 1.0e-3);
+        assert!(hit.point.y.abs() < 1.0e-3);
+    }
+
+    #[test]
+    fn scene_queries_report_collider_sources() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        let floor = world.insert_static_collider(
+            ColliderBuilder::cuboid(5.0, 0.1, 5.0)
+                .translation(vector![0.0, -0.1, 0.0])
+                .build(),
+        );
+        world.set_collider_source(
+            floor,
+            ColliderSource::Chunk {
+                chunk_id: "chunk_0".to_string(),
+            },
+        );
+        let wall = world.insert_static_collider(
+            ColliderBuilder::cuboid(0.1, 2.0, 2.0)
+                .translation(vector![2.0, 2.0, 0.0])
+                .build(),
+        );
+        world.set_collider_source(
+            wall,
+            ColliderSource::Solid {
+                id: "wall".to_string(),
+                tags: vec!["wall".to_string()],
+            },
+        );
+        let sensor = world.insert_sensor_collider(
+            ColliderBuilder::cuboid(0.5, 0.5, 0.5)
+                .translation(vector![0.0, 1.0, 0.0])
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+
+        let direction = vector![1.0, -1.0, 0.0].normalize();
+        let hits = world.raycast_all(
+            Point::new(0.0, 1.0, 0.0),
+            direction,
+            10.0,
+            InteractionGroups::all(),
+        );
+        let ids: Vec<_> = hits
+            .iter()
+            .map(|hit| hit.source.as_ref().map(ColliderSource::id))
+            .collect();
+        assert_eq!(ids, vec![Some("chunk_0")]);
+        let hits = world.raycast_all(
+            Point::new(-1.0, 1.0, 0.0),
+            Vector::x(),
+            10.0,
+            InteractionGroups::all(),
+        );
+        assert_eq!(hits.len(), 1, "sensors are not traced");
+        assert_eq!(hits[0].source.as_ref().unwrap().tags(), ["wall"]);
+
+        let overlaps = world.overlap_aabb(
+            Point::new(-0.2, 0.4, -0.2),
+            Point::new(0.2, 0.6, 0.2),
+            InteractionGroups::all(),
+        );
+        assert_eq!(overlaps.len(), 1);
+        assert_eq!(overlaps[0].collider, sensor);
+        assert!(overlaps[0].source.is_none());
+        let overlaps = world.overlap_shape(
+            &Ball::new(0.5),
+            &Isometry::translation(1.8, 0.1, 0.0),
+            InteractionGroups::all(),
+        );
+        let mut colliders: Vec<_> = overlaps.iter().map(|overlap| overlap.collider).collect();
+        colliders.sort_by_key(|handle| handle.into_raw_parts());
+        assert_eq!(colliders, vec![floor, wall]);
+    }
+
     #[test]
     fn kcc_detects_ground_contact() {
         let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
@@ -234,6 +1211,85 @@ mod tests {
         assert!(position.translation.x > 2.0);
     }
 
+    #[test]
+    fn liquid_depth_measures_immersion() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        let pool = world.insert_liquid_collider(
+            ColliderBuilder::cuboid(2.0, 0.5, 2.0)
+                .translation(vector![0.0, 0.5, 0.0])
+                .build(),
+            LiquidKind::Slime,
+        );
+        world.step(1.0 / 60.0);
+
+        let wading = world
+            .liquid_depth(Point::new(0.0, 0.0, 0.0), 1.8)
+            .expect("wading");
+        assert_eq!(wading.kind, LiquidKind::Slime);
+        assert_eq!(wading.collider, pool);
+        assert!((wading.depth - 1.0).abs() < 1.0e-4);
+        let under = world
+            .liquid_depth(Point::new(0.0, 0.0, 0.0), 0.8)
+            .expect("submerged");
+        assert!((under.depth - 0.8).abs() < 1.0e-4);
+        assert!(world.liquid_depth(Point::new(3.0, 0.0, 0.0), 1.8).is_none());
+
+        assert_eq!(
+            world.liquid_at(Point::new(0.0, 0.9, 0.0)),
+            Some((pool, LiquidKind::Slime))
+        );
+        assert!(world.liquid_at(Point::new(0.0, 1.1, 0.0)).is_none());
+        assert_eq!(LiquidKind::from_tag("lava"), Some(LiquidKind::Lava));
+    }
+
+    #[test]
+    fn climbable_contact_finds_ladder_faces_and_volumes() {
+        let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+        build_floor(&mut world);
+        let ladder = world.insert_static_collider(
+            ColliderBuilder::cuboid(0.1, 2.0, 0.5)
+                .translation(vector![1.0, 2.0, 0.0])
+                .build(),
+        );
+        world.set_climbable(ladder, true);
+        let zone = world.insert_sensor_collider(
+            ColliderBuilder::cuboid(0.5, 2.0, 0.5)
+                .translation(vector![-3.0, 2.0, 0.0])
+                .build(),
+        );
+        world.set_climbable(zone, true);
+        world.step(1.0 / 60.0);
+
+        let ball = Ball::new(0.4);
+        let facing_ladder = Isometry::translation(0.4, 1.0, 0.0);
+        let contact = world
+            .climbable_contact(&ball, &facing_ladder, Vector::x(), 0.25)
+            .expect("ladder");
+        assert_eq!(contact.collider, ladder);
+        assert!((contact.normal - -Vector::x()).norm() < 1.0e-4);
+        assert!((contact.distance - 0.1).abs() < 1.0e-3);
+        assert!(world
+            .climbable_contact(&ball, &facing_ladder, -Vector::x(), 0.25)
+            .is_none());
+        let above = Isometry::translation(1.0, 4.5, 0.0);
+        assert!(world
+            .climbable_contact(&ball, &above, -Vector::y(), 0.25)
+            .is_none());
+
+        let inside = Isometry::translation(-3.0, 1.0, 0.0);
+        let contact = world
+            .climbable_contact(&ball, &inside, Vector::z(), 0.25)
+            .expect("volume");
+        assert_eq!(contact.collider, zone);
+        assert_eq!(contact.normal, -Vector::z());
+
+        world.set_climbable(ladder, false);
+        assert!(world
+            .climbable_contact(&ball, &facing_ladder, Vector::x(), 0.25)
+            .is_none());
+    }
+
     #[test]
     fn kcc_respects_slope_limit() {
         let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
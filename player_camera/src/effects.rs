@@ -0,0 +1,160 @@
+//! Procedural view effects layered on top of the first-person eye.
+//!
+//! Every effect is integrated from per-tick motion with the tick's `dt`, so
+//! the same inputs always produce the same offsets and captures replay
+//! exactly.
+
+use rapier3d::math::Vector;
+use rapier3d::prelude::Real;
+
+/// Ground distance covered by one step of the bob cycle.
+const BOB_STEP_LENGTH: Real = 1.5;
+/// How quickly the bob fades in on the ground and out in the air, per second.
+const BOB_FADE_RATE: Real = 6.0;
+/// Landings slower than this do not dip the view.
+const DIP_MIN_SPEED: Real = 2.0;
+const DIP_MAX: Real = 0.3;
+/// Spring stiffness pulling the dip back to rest; critically damped.
+const DIP_STIFFNESS: Real = 120.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraEffectsTuning {
+    /// Head bob amplitude in meters at `bob_speed` (0 = off).
+    pub bob: Real,
+    /// Ground speed giving the full bob.
+    pub bob_speed: Real,
+    /// Landing dip in meters per m/s of fall speed (0 = off).
+    pub dip: Real,
+    /// Roll in radians at `roll_speed` of strafe velocity (0 = off).
+    pub roll: Real,
+    pub roll_speed: Real,
+    /// Extra vertical FOV in radians at `fov_kick_speed_end` (0 = off).
+    pub fov_kick: Real,
+    /// Horizontal speed where the FOV starts widening.
+    pub fov_kick_speed_start: Real,
+    pub fov_kick_speed_end: Real,
+}
+
+impl Default for CameraEffectsTuning {
+    fn default() -> Self {
+        Self {
+            bob: 0.04,
+            bob_speed: 4.0,
+            dip: 0.015,
+            roll: 2.0_f32.to_radians(),
+            roll_speed: 4.0,
+            fov_kick: 10.0_f32.to_radians(),
+            fov_kick_speed_start: 4.0,
+            fov_kick_speed_end: 8.0,
+        }
+    }
+}
+
+/// What the effects react to this tick.
+#[derive(Clone, Copy, Debug)]
+pub struct CameraMotion {
+    pub velocity: Vector<Real>,
+    pub yaw: Real,
+    pub grounded: bool,
+    /// Downward speed of a landing this tick, 0 otherwise.
+    pub landing_speed: Real,
+}
+
+/// Effect output for the renderer to apply on top of the raw pose.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraFeel {
+    /// World-space eye offset from bob and landing dip.
+    pub offset: Vector<Real>,
+    /// View roll in radians; positive leans right, into a rightward strafe.
+    pub roll: Real,
+    /// Added to the vertical field of view, in radians.
+    pub fov_offset: Real,
+}
+
+impl Default for CameraFeel {
+    fn default() -> Self {
+        Self {
+            offset: Vector::zeros(),
+            roll: 0.0,
+            fov_offset: 0.0,
+        }
+    }
+}
+
+impl CameraFeel {
+    /// Linear blend, for interpolating between fixed ticks.
+    pub fn lerp(&self, other: &CameraFeel, t: Real) -> CameraFeel {
+        CameraFeel {
+            offset: self.offset + (other.offset - self.offset) * t,
+            roll: self.roll + (other.roll - self.roll) * t,
+            fov_offset: self.fov_offset + (other.fov_offset - self.fov_offset) * t,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CameraEffects {
+    bob_phase: Real,
+    bob_weight: Real,
+    dip: Real,
+    dip_velocity: Real,
+}
+
+impl CameraEffects {
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn update(
+        &mut self,
+        tuning: &CameraEffectsTuning,
+        motion: CameraMotion,
+        dt: Real,
+    ) -> CameraFeel {
+        let dt = dt.max(0.0);
+        let horizontal = Vector::new(motion.velocity.x, 0.0, motion.velocity.z);
+        let speed = horizontal.norm();
+        let right = Vector::new(motion.yaw.cos(), 0.0, motion.yaw.sin());
+
+        let fade_target = if motion.grounded { 1.0 } else { 0.0 };
+        let fade_step = BOB_FADE_RATE * dt;
+        self.bob_weight += (fade_target - self.bob_weight).clamp(-fade_step, fade_step);
+        if motion.grounded {
+            self.bob_phase = (self.bob_phase + std::f32::consts::PI * speed * dt / BOB_STEP_LENGTH)
+                % std::f32::consts::TAU;
+        }
+        let amplitude =
+            tuning.bob * self.bob_weight * (speed / tuning.bob_speed.max(1.0e-3)).min(1.0);
+        let (sin_phase, _) = self.bob_phase.sin_cos();
+        let bob =
+            Vector::y() * amplitude * (sin_phase.abs() - 0.5) + right * amplitude * 0.5 * sin_phase;
+
+        let impact = motion.landing_speed - DIP_MIN_SPEED;
+        let omega = DIP_STIFFNESS.sqrt();
+        if impact > 0.0 {
+            // A critically damped spring kicked with v0 peaks at v0 / (omega * e).
+            self.dip_velocity -= impact * tuning.dip * omega * std::f32::consts::E;
+        }
+        let spring = -DIP_STIFFNESS * self.dip - 2.0 * omega * self.dip_velocity;
+        self.dip_velocity += spring * dt;
+        self.dip = (self.dip + self.dip_velocity * dt).clamp(-DIP_MAX, 0.0);
+        let at_floor = self.dip <= -DIP_MAX && self.dip_velocity < 0.0;
+        let at_rest = self.dip >= 0.0 && self.dip_velocity > 0.0;
+        if at_floor || at_rest {
+            self.dip_velocity = 0.0;
+        }
+
+        let strafe = horizontal.dot(&right);
+        let roll = tuning.roll * (strafe / tuning.roll_speed.max(1.0e-3)).clamp(-1.0, 1.0);
+
+        let kick_range = (tuning.fov_kick_speed_end - tuning.fov_kick_speed_start).max(1.0e-3);
+        let kick = ((speed - tuning.fov_kick_speed_start) / kick_range).clamp(0.0, 1.0);
+        let fov_offset = tuning.fov_kick * kick * kick * (3.0 - 2.0 * kick);
+
+        CameraFeel {
+            offset: bob + Vector::y() * self.dip,
+            roll,
+            fov_offset,
+        }
+    }
+}
@@ -1,33 +1,169 @@
 //! Camera derivation from player pose.
 #![forbid(unsafe_code)]
 
-use rapier3d::math::Vector;
-use rapier3d::prelude::Real;
+pub mod effects;
+
+pub use effects::{CameraEffects, CameraEffectsTuning, CameraFeel, CameraMotion};
+use physics_rapier::PhysicsWorld;
+use rapier3d::math::{Isometry, Vector};
+use rapier3d::prelude::{Ball, InteractionGroups, Real};
 
 const PITCH_LIMIT: Real = 1.54;
+/// Gap kept between a clipped boom and the surface it hit.
+const BOOM_SURFACE_GAP: Real = 0.05;
+
+/// Where the view sits relative to the player.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CameraMode {
+    /// At the player's eye.
+    #[default]
+    FirstPerson,
+    /// On a boom orbiting behind the player's eye.
+    ThirdPerson,
+    /// Free-flying noclip view; the player body stands still.
+    Spectator,
+    /// Following a host-supplied target from behind.
+    Chase,
+}
+
+impl CameraMode {
+    /// Maps the `cam_mode` cvar value (1 first person, 2 third person,
+    /// 3 spectator, 4 chase).
+    pub fn from_cvar(value: i32) -> Self {
+        match value {
+            2 => CameraMode::ThirdPerson,
+            3 => CameraMode::Spectator,
+            4 => CameraMode::Chase,
+            _ => CameraMode::FirstPerson,
+        }
+    }
+
+    pub fn cvar_value(self) -> &'static str {
+        match self {
+            CameraMode::FirstPerson => "1",
+            CameraMode::ThirdPerson => "2",
+            CameraMode::Spectator => "3",
+            CameraMode::Chase => "4",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CameraMode::FirstPerson => "first",
+            CameraMode::ThirdPerson => "third",
+            CameraMode::Spectator => "spectator",
+            CameraMode::Chase => "chase",
+        }
+    }
+
+    pub fn parse(input: &str) -> Result<Self, String> {
+        match input {
+            "first" | "1" => Ok(CameraMode::FirstPerson),
+            "third" | "2" => Ok(CameraMode::ThirdPerson),
+            "spectator" | "3" => Ok(CameraMode::Spectator),
+            "chase" | "4" => Ok(CameraMode::Chase),
+            _ => Err("expected first, third, spectator or chase".to_string()),
+        }
+    }
+
+    /// The view is not attached to the player, so movement input should not
+    /// drive the body.
+    pub fn is_detached(self) -> bool {
+        matches!(self, CameraMode::Spectator | CameraMode::Chase)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraModeTuning {
+    /// Third-person distance behind the eye.
+    pub boom_length: Real,
+    /// Radius of the sphere cast along booms to keep the view out of walls.
+    pub boom_radius: Real,
+    /// Spectator fly speed in m/s.
+    pub spectator_speed: Real,
+    /// Chase distance behind the target.
+    pub chase_distance: Real,
+    /// Chase pivot height above the target.
+    pub chase_height: Real,
+    /// How quickly the chase view catches up with the target, per second.
+    pub chase_follow_rate: Real,
+    /// Seconds taken to blend between modes (0 = cut).
+    pub blend_time: Real,
+}
+
+impl Default for CameraModeTuning {
+    fn default() -> Self {
+        Self {
+            boom_length: 3.0,
+            boom_radius: 0.2,
+            spectator_speed: 8.0,
+            chase_distance: 4.0,
+            chase_height: 1.5,
+            chase_follow_rate: 8.0,
+            blend_time: 0.3,
+        }
+    }
+}
 
 #[derive(Clone, Copy, Debug)]
 pub struct CameraPose {
     pub eye: Vector<Real>,
     pub yaw: Real,
     pub pitch: Real,
+    /// The eye is inside a liquid volume; audio and render can muffle or tint.
+    pub underwater: bool,
+    pub mode: CameraMode,
+    /// View effects to apply on top of `eye`; zero outside first person.
+    pub feel: CameraFeel,
+}
+
+/// A mode switch easing the eye from where it was.
+#[derive(Clone, Copy, Debug)]
+struct ModeBlend {
+    from: Vector<Real>,
+    elapsed: Real,
 }
 
 #[derive(Clone, Copy, Debug)]
 pub struct PlayerCamera {
     eye_height: Real,
+    /// How far the eye currently sits below `eye_height`, e.g. while crouched.
+    eye_drop: Real,
     yaw: Real,
     pitch: Real,
     eye: Vector<Real>,
+    underwater: bool,
+    mode: CameraMode,
+    tuning: CameraModeTuning,
+    blend: Option<ModeBlend>,
+    /// Spectator position, flown by `fly`.
+    free_eye: Vector<Real>,
+    chase_target: Option<Vector<Real>>,
+    /// Smoothed chase view position.
+    chase_eye: Vector<Real>,
+    effects: CameraEffects,
+    effects_tuning: CameraEffectsTuning,
+    feel: CameraFeel,
 }
 
 impl PlayerCamera {
     pub fn new(eye_height: Real) -> Self {
         Self {
             eye_height,
+            eye_drop: 0.0,
             yaw: 0.0,
             pitch: 0.0,
             eye: Vector::zeros(),
+            underwater: false,
+            mode: CameraMode::FirstPerson,
+            tuning: CameraModeTuning::default(),
+            blend: None,
+            free_eye: Vector::zeros(),
+            chase_target: None,
+            chase_eye: Vector::zeros(),
+            effects: CameraEffects::default(),
+            effects_tuning: CameraEffectsTuning::default(),
+            feel: CameraFeel::default(),
         }
     }
 
@@ -49,8 +185,143 @@ impl PlayerCamera {
         self.pitch = (self.pitch + delta[1]).clamp(-PITCH_LIMIT, PITCH_LIMIT);
     }
 
+    pub fn eye_drop(&self) -> Real {
+        self.eye_drop
+    }
+
+    /// Moves the eye drop toward `target` at `speed` m/s (0 = instant).
+    pub fn ease_eye_drop(&mut self, target: Real, speed: Real, dt: Real) {
+        if speed <= 0.0 {
+            self.eye_drop = target;
+            return;
+        }
+        let max_delta = speed * dt.max(0.0);
+        self.eye_drop += (target - self.eye_drop).clamp(-max_delta, max_delta);
+    }
+
+    pub fn reset_eye_drop(&mut self) {
+        self.eye_drop = 0.0;
+    }
+
+    pub fn is_underwater(&self) -> bool {
+        self.underwater
+    }
+
+    /// Set by the owner after checking the eye position against liquids.
+    pub fn set_underwater(&mut self, underwater: bool) {
+        self.underwater = underwater;
+    }
+
+    pub fn mode(&self) -> CameraMode {
+        self.mode
+    }
+
+    /// Switches mode, blending from the current eye over `blend_time`.
+    /// Spectator and chase views start where the eye is now.
+    pub fn set_mode(&mut self, mode: CameraMode) {
+        if mode == self.mode {
+            return;
+        }
+        self.mode = mode;
+        self.free_eye = self.eye;
+        self.chase_eye = self.eye;
+        self.blend = (self.tuning.blend_time > 0.0).then_some(ModeBlend {
+            from: self.eye,
+            elapsed: 0.0,
+        });
+    }
+
+    pub fn tuning(&self) -> CameraModeTuning {
+        self.tuning
+    }
+
+    pub fn tuning_mut(&mut self) -> &mut CameraModeTuning {
+        &mut self.tuning
+    }
+
+    /// Position the chase view follows; without one it stays at the eye.
+    pub fn set_chase_target(&mut self, target: Option<Vector<Real>>) {
+        self.chase_target = target;
+    }
+
+    pub fn effects_tuning(&self) -> CameraEffectsTuning {
+        self.effects_tuning
+    }
+
+    pub fn effects_tuning_mut(&mut self) -> &mut CameraEffectsTuning {
+        &mut self.effects_tuning
+    }
+
+    /// Advances bob, landing dip, strafe roll and FOV kick by one tick.
+    /// Only the first-person view gets them; other modes reset to rest.
+    pub fn apply_effects(&mut self, motion: CameraMotion, dt: Real) -> CameraFeel {
+        self.feel = if self.mode == CameraMode::FirstPerson {
+            self.effects.update(&self.effects_tuning, motion, dt)
+        } else {
+            self.effects.reset();
+            CameraFeel::default()
+        };
+        self.feel
+    }
+
+    /// Moves the spectator view along the look direction by `move_axis`
+    /// (strafe, forward) and straight up by `move_up`.
+    pub fn fly(&mut self, move_axis: [Real; 2], move_up: Real, dt: Real) {
+        if self.mode != CameraMode::Spectator {
+            return;
+        }
+        let right = Vector::new(self.yaw.cos(), 0.0, self.yaw.sin());
+        let wish = right * move_axis[0] + self.forward() * move_axis[1] + Vector::y() * move_up;
+        let wish = if wish.norm_squared() > 1.0 {
+            wish.normalize()
+        } else {
+            wish
+        };
+        self.free_eye += wish * self.tuning.spectator_speed * dt.max(0.0);
+    }
+
+    /// First-person eye for a player at `origin`, ignoring the mode.
     pub fn update_from_origin(&mut self, origin: Vector<Real>) -> CameraPose {
-        self.eye = origin + Vector::new(0.0, self.eye_height, 0.0);
+        self.eye = self.head(origin);
+        self.pose()
+    }
+
+    /// Places the view for the current mode around a player at `origin`.
+    /// Booms are shortened where they would pass through solids in `world`.
+    pub fn update(&mut self, world: &PhysicsWorld, origin: Vector<Real>, dt: Real) -> CameraPose {
+        let dt = dt.max(0.0);
+        let head = self.head(origin);
+        let target = match self.mode {
+            CameraMode::FirstPerson => head,
+            CameraMode::ThirdPerson => {
+                self.boom(world, head, -self.forward() * self.tuning.boom_length)
+            }
+            CameraMode::Spectator => self.free_eye,
+            CameraMode::Chase => match self.chase_target {
+                Some(target) => {
+                    let pivot = target + Vector::y() * self.tuning.chase_height;
+                    let behind = -self.forward() * self.tuning.chase_distance;
+                    let desired = self.boom(world, pivot, behind);
+                    let follow = 1.0 - (-self.tuning.chase_follow_rate.max(0.0) * dt).exp();
+                    self.chase_eye += (desired - self.chase_eye) * follow;
+                    self.chase_eye
+                }
+                None => head,
+            },
+        };
+        self.eye = match self.blend.as_mut() {
+            Some(blend) => {
+                blend.elapsed += dt;
+                let t = (blend.elapsed / self.tuning.blend_time.max(1.0e-4)).min(1.0);
+                let eased = t * t * (3.0 - 2.0 * t);
+                let eye = blend.from + (target - blend.from) * eased;
+                if t >= 1.0 {
+                    self.blend = None;
+                }
+                eye
+            }
+            None => target,
+        };
         self.pose()
     }
 
@@ -59,6 +330,46 @@ impl PlayerCamera {
             eye: self.eye,
             yaw: self.yaw,
             pitch: self.pitch,
+            underwater: self.underwater,
+            mode: self.mode,
+            feel: self.feel,
+        }
+    }
+
+    fn head(&self, origin: Vector<Real>) -> Vector<Real> {
+        origin + Vector::new(0.0, self.eye_height - self.eye_drop, 0.0)
+    }
+
+    /// Unit look direction including pitch.
+    fn forward(&self) -> Vector<Real> {
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        Vector::new(
+            self.yaw.sin() * cos_pitch,
+            sin_pitch,
+            -self.yaw.cos() * cos_pitch,
+        )
+    }
+
+    /// `pivot + offset`, pulled in to just short of the first solid a sphere
+    /// cast along the offset hits.
+    fn boom(
+        &self,
+        world: &PhysicsWorld,
+        pivot: Vector<Real>,
+        offset: Vector<Real>,
+    ) -> Vector<Real> {
+        let length = offset.norm();
+        if length <= 1.0e-4 {
+            return pivot;
         }
+        let direction = offset / length;
+        let ball = Ball::new(self.tuning.boom_radius.max(0.01));
+        let start = Isometry::translation(pivot.x, pivot.y, pivot.z);
+        let reach =
+            match world.shapecast(&ball, &start, direction, length, InteractionGroups::all()) {
+                Some(hit) => (hit.toi - BOOM_SURFACE_GAP).max(0.0),
+                None => length,
+            };
+        pivot + direction * reach
     }
 }
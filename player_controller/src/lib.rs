@@ -1,17 +1,26 @@
 //! Player controller composition (input + motor + collision + camera).
 #![forbid(unsafe_code)]
 
-use character_collision::{CharacterCollision, CollisionMoveResult, CollisionProfile};
-use physics_rapier::PhysicsWorld;
-use player_camera::{CameraPose, PlayerCamera};
-use rapier3d::math::{Isometry, Vector};
+pub mod params;
+
+use std::any::Any;
+
+use character_collision::{
+    CharacterCollision, CollisionMoveResult, CollisionProfile, CrouchUpdate, Ledge, LedgeQuery,
+};
+use physics_rapier::{ClimbContact, LiquidKind, PhysicsWorld, SurfaceMaterial};
+use player_camera::{CameraMode, CameraMotion, CameraPose, PlayerCamera};
+use rapier3d::math::{Isometry, Point, Vector};
 use rapier3d::prelude::Real;
 
+pub use params::{find_param, MotorParam, ParamUnit};
+
 #[derive(Clone, Copy, Debug, Default)]
 pub struct RawInput {
     pub move_x: Real,
     pub move_y: Real,
     pub jump: bool,
+    pub crouch: bool,
     pub look_delta: [Real; 2],
 }
 
@@ -19,9 +28,23 @@ pub struct RawInput {
 pub struct InputIntent {
     pub move_axis: [Real; 2],
     pub jump: bool,
+    pub crouch: bool,
+    /// Vertical swim input in -1..=1; jump swims up, crouch swims down.
+    pub move_up: Real,
     pub look_delta: [Real; 2],
 }
 
+/// How far ahead of the capsule a climbable surface can be grabbed.
+const LADDER_REACH: Real = 0.25;
+/// Minimum cosine between view and surface for grabbing a ladder.
+const LADDER_GRAB_FACING: Real = 0.5;
+/// Seconds after jumping off a ladder before it can be grabbed again.
+const LADDER_REGRAB_DELAY: Real = 0.3;
+/// Ground distance covered between footsteps, in meters.
+const FOOTSTEP_STRIDE: Real = 1.5;
+/// Slowest fall that still lands with a footstep.
+const FOOTSTEP_LANDING_SPEED: Real = 2.0;
+
 pub trait InputAdapter {
     fn intent(&mut self, raw: RawInput) -> InputIntent;
 }
@@ -46,17 +69,46 @@ impl InputAdapter for DirectInputAdapter {
         InputIntent {
             move_axis,
             jump: raw.jump,
+            crouch: raw.crouch,
+            move_up: bool_axis(raw.jump) - bool_axis(raw.crouch),
             look_delta: raw.look_delta,
         }
     }
 }
 
+fn bool_axis(value: bool) -> Real {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// How far the player's capsule is submerged in a liquid volume.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Immersion {
+    pub kind: LiquidKind,
+    /// Liquid depth above the feet, in meters.
+    pub depth: Real,
+    /// `depth` over the capsule height: 0 dry, 1 fully submerged.
+    pub level: Real,
+}
+
 #[derive(Clone, Debug)]
 pub struct PlayerKinematics {
     pub position: Isometry<Real>,
     pub velocity: Vector<Real>,
     pub grounded: bool,
     pub ground_normal: Option<Vector<Real>>,
+    /// The collision capsule is below standing height.
+    pub crouched: bool,
+    pub immersion: Option<Immersion>,
+    /// Climbable surface the player is holding on to.
+    pub ladder: Option<ClimbContact>,
+    /// Ledge ahead the motor may mantle, probed while moving forward.
+    pub ledge: Option<Ledge>,
+    /// Material of the ground collider, when grounded on one that has one.
+    pub ground_surface: Option<SurfaceMaterial>,
 }
 
 impl PlayerKinematics {
@@ -66,14 +118,25 @@ impl PlayerKinematics {
             velocity: Vector::zeros(),
             grounded: false,
             ground_normal: None,
+            crouched: false,
+            immersion: None,
+            ladder: None,
+            ledge: None,
+            ground_surface: None,
         }
     }
+
+    pub fn immersion_level(&self) -> Real {
+        self.immersion.map_or(0.0, |immersion| immersion.level)
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
 pub struct MotorContext {
     pub dt: Real,
     pub yaw: Real,
+    pub pitch: Real,
+    pub camera_mode: CameraMode,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -82,15 +145,42 @@ pub struct MotorOutput {
     pub next_velocity: Vector<Real>,
 }
 
-pub trait Motor {
+/// Turns input intent into velocity. `Any` lets hosts that hold motors as
+/// trait objects reach a concrete motor's own state.
+pub trait Motor: Any {
     fn step(
         &mut self,
         input: &InputIntent,
         state: &PlayerKinematics,
         ctx: MotorContext,
     ) -> MotorOutput;
+
+    /// Ledges this motor can mantle; `None` skips ledge detection.
+    fn ledge_query(&self) -> Option<LedgeQuery> {
+        None
+    }
+
+    /// Tuning parameters `param` and `set_param` accept.
+    fn params(&self) -> &'static [MotorParam] {
+        &[]
+    }
+
+    fn param(&self, _name: &str) -> Option<Real> {
+        None
+    }
+
+    /// Sets a parameter, clamped to its range.
+    fn set_param(&mut self, name: &str, _value: Real) -> Result<(), String> {
+        Err(format!("unknown motor param: {}", name))
+    }
+
+    /// Clears timers and smoothing carried between steps.
+    fn reset_state(&mut self) {}
 }
 
+const SIMPLE_MOTOR_PARAMS: &[MotorParam] =
+    &[MotorParam::new("move_speed", ParamUnit::MetersPerSecond, "Walk speed.").at_least(0.0)];
+
 #[derive(Clone, Copy, Debug)]
 pub struct SimpleMotor {
     pub move_speed: Real,
@@ -125,13 +215,37 @@ impl Motor for SimpleMotor {
             next_velocity,
         }
     }
+
+    fn params(&self) -> &'static [MotorParam] {
+        SIMPLE_MOTOR_PARAMS
+    }
+
+    fn param(&self, name: &str) -> Option<Real> {
+        (name == "move_speed").then_some(self.move_speed)
+    }
+
+    fn set_param(&mut self, name: &str, value: Real) -> Result<(), String> {
+        self.move_speed = find_param(SIMPLE_MOTOR_PARAMS, name)?.clamp(value);
+        Ok(())
+    }
+}
+
+/// A step the player took this tick, for hosts to play a sound.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Footstep {
+    pub position: Vector<Real>,
+    pub surface: Option<SurfaceMaterial>,
+    /// Touching down from a fall rather than walking.
+    pub landing: bool,
 }
 
 #[derive(Clone, Debug)]
 pub struct PlayerFrame {
     pub kinematics: PlayerKinematics,
+    pub crouch: CrouchUpdate,
     pub collision: CollisionMoveResult,
     pub camera: CameraPose,
+    pub footstep: Option<Footstep>,
 }
 
 pub struct PlayerController<A: InputAdapter, M: Motor> {
@@ -140,6 +254,9 @@ pub struct PlayerController<A: InputAdapter, M: Motor> {
     collision: CharacterCollision,
     camera: PlayerCamera,
     state: PlayerKinematics,
+    ladder_cooldown: Real,
+    /// Ground distance walked since the last footstep.
+    stride: Real,
 }
 
 impl<A: InputAdapter, M: Motor> PlayerController<A, M> {
@@ -156,6 +273,8 @@ impl<A: InputAdapter, M: Motor> PlayerController<A, M> {
             collision: CharacterCollision::new(profile),
             camera,
             state: PlayerKinematics::new(position),
+            ladder_cooldown: 0.0,
+            stride: 0.0,
         }
     }
 
@@ -191,17 +310,93 @@ impl<A: InputAdapter, M: Motor> PlayerController<A, M> {
         &mut self.camera
     }
 
+    /// Liquid the capsule at `position` stands in, measured from its feet.
+    pub fn immersion(&self, world: &PhysicsWorld, position: Isometry<Real>) -> Option<Immersion> {
+        let foot_offset = self.collision.foot_offset();
+        let height = foot_offset * 2.0;
+        let feet = position.translation.vector - Vector::y() * foot_offset;
+        let contact = world.liquid_depth(Point::from(feet), height)?;
+        Some(Immersion {
+            kind: contact.kind,
+            depth: contact.depth,
+            level: (contact.depth / height.max(1.0e-3)).clamp(0.0, 1.0),
+        })
+    }
+
+    /// Climbable surface held this tick. Grabbing takes forward input while
+    /// facing the surface; the player lets go when it is out of reach, after
+    /// jumping off, or when standing without pressing forward.
+    fn ladder_contact(&self, world: &PhysicsWorld, intent: &InputIntent) -> Option<ClimbContact> {
+        if self.ladder_cooldown > 0.0 {
+            return None;
+        }
+        let pressing = intent.move_axis[1] > 0.0;
+        let yaw = self.camera.yaw();
+        let forward = Vector::new(yaw.sin(), 0.0, -yaw.cos());
+        let direction = match self.state.ladder {
+            Some(_) if self.state.grounded && !pressing => return None,
+            Some(held) => -held.normal,
+            None if pressing => forward,
+            None => return None,
+        };
+        let contact = world.climbable_contact(
+            self.collision.capsule(),
+            &self.state.position,
+            direction,
+            LADDER_REACH,
+        )?;
+        let facing = forward.dot(&-contact.normal) >= LADDER_GRAB_FACING;
+        (self.state.ladder.is_some() || facing).then_some(contact)
+    }
+
     pub fn tick(&mut self, world: &PhysicsWorld, raw: RawInput, dt: Real) -> PlayerFrame {
-        let intent = self.input.intent(raw);
+        let mut intent = self.input.intent(raw);
         self.camera.apply_look_delta(intent.look_delta);
+        // Detached views take the movement input; the body stands idle.
+        if self.camera.mode().is_detached() {
+            self.camera.fly(intent.move_axis, intent.move_up, dt);
+            intent = InputIntent {
+                look_delta: intent.look_delta,
+                ..Default::default()
+            };
+        }
+        // Crouch swims down while floating instead of shrinking the capsule.
+        let floating = self.state.immersion.is_some() && !self.state.grounded;
+        let crouch = self.collision.update_crouch(
+            world,
+            self.state.position,
+            intent.crouch && !floating,
+            self.state.grounded,
+            dt,
+        );
+        self.state.position = crouch.position;
+        self.state.crouched = crouch.crouched;
+        self.state.immersion = self.immersion(world, self.state.position);
+        self.ladder_cooldown = (self.ladder_cooldown - dt).max(0.0);
+        self.state.ladder = self.ladder_contact(world, &intent);
+        self.state.ledge = match self.motor.ledge_query() {
+            Some(query) if self.state.ladder.is_none() && intent.move_axis[1] > 0.0 => {
+                let yaw = self.camera.yaw();
+                let forward = Vector::new(yaw.sin(), 0.0, -yaw.cos());
+                self.collision
+                    .detect_ledge(world, self.state.position, forward, query)
+            }
+            _ => None,
+        };
         let motor_output = self.motor.step(
             &intent,
             &self.state,
             MotorContext {
                 dt,
                 yaw: self.camera.yaw(),
+                pitch: self.camera.pitch(),
+                camera_mode: self.camera.mode(),
             },
         );
+        if self.state.ladder.is_some() && intent.jump {
+            self.state.ladder = None;
+            self.ladder_cooldown = LADDER_REGRAB_DELAY;
+        }
         let allow_step = self.state.grounded && !intent.jump;
         let collision = self.collision.move_character(
             world,
@@ -222,17 +417,63 @@ impl<A: InputAdapter, M: Motor> PlayerController<A, M> {
                 }
             }
         }
+        let landing_speed = if collision.grounded && !self.state.grounded {
+            (-self.state.velocity.y).max(0.0)
+        } else {
+            0.0
+        };
+        let footstep = self.footstep(&collision);
         self.state.velocity = next_velocity;
         self.state.grounded = collision.grounded;
         self.state.ground_normal = collision.ground_normal;
-        let camera = self
+        self.state.ground_surface = collision.ground_surface.clone();
+        let profile = self.collision.profile();
+        let eye_drop = (profile.capsule_height - self.collision.capsule_height()) * 0.5;
+        self.camera
+            .ease_eye_drop(eye_drop, profile.crouch_transition_speed, dt);
+        self.camera.apply_effects(
+            CameraMotion {
+                velocity: next_velocity,
+                yaw: self.camera.yaw(),
+                grounded: collision.grounded,
+                landing_speed,
+            },
+            dt,
+        );
+        let mut camera = self
             .camera
-            .update_from_origin(self.state.position.translation.vector);
+            .update(world, self.state.position.translation.vector, dt);
+        camera.underwater = world.liquid_at(Point::from(camera.eye)).is_some();
+        self.camera.set_underwater(camera.underwater);
         PlayerFrame {
             kinematics: self.state.clone(),
+            crouch,
             collision,
             camera,
+            footstep,
+        }
+    }
+
+    /// Counts ground distance after a move and reports a footstep every
+    /// stride, or on landing from a fall. Swimming and climbing are silent.
+    fn footstep(&mut self, collision: &CollisionMoveResult) -> Option<Footstep> {
+        let swimming = self.state.immersion_level() >= 0.5;
+        if !collision.grounded || self.state.ladder.is_some() || swimming {
+            self.stride = 0.0;
+            return None;
+        }
+        let landing = !self.state.grounded && self.state.velocity.y <= -FOOTSTEP_LANDING_SPEED;
+        let walked = collision.translation;
+        self.stride += Vector::new(walked.x, 0.0, walked.z).norm();
+        if !landing && self.stride < FOOTSTEP_STRIDE {
+            return None;
         }
+        self.stride = 0.0;
+        Some(Footstep {
+            position: collision.position.translation.vector,
+            surface: collision.ground_surface.clone(),
+            landing,
+        })
     }
 }
 
@@ -291,4 +532,283 @@ mod tests {
         assert!(controller.state().position.translation.x > 1.5);
         assert!(controller.state().position.translation.y > 0.9);
     }
+
+    #[test]
+    fn controller_reports_immersion_and_underwater_eye() {
+        let mut world = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        build_scene(&mut world);
+        world.insert_liquid_collider(
+            ColliderBuilder::cuboid(2.0, 0.6, 2.0)
+                .translation(Vector::new(-4.0, 0.6, 4.0))
+                .build(),
+            LiquidKind::Water,
+        );
+        world.step(1.0 / 60.0);
+
+        let profile = CollisionProfile::arena_default();
+        let camera = PlayerCamera::new(0.8);
+        let foot_offset = profile.capsule_height * 0.5 + profile.capsule_radius;
+        let start = Isometry::translation(-4.0, foot_offset, 4.0);
+        let mut controller = PlayerController::new(
+            DirectInputAdapter,
+            SimpleMotor::default(),
+            profile,
+            camera,
+            start,
+        );
+        let immersion = controller.immersion(&world, start).expect("wading");
+        assert_eq!(immersion.kind, LiquidKind::Water);
+        assert!((immersion.depth - 1.2).abs() < 0.05);
+        assert!((immersion.level - 1.2 / (foot_offset * 2.0)).abs() < 0.05);
+
+        let frame = controller.tick(&world, RawInput::default(), 1.0 / 60.0);
+        assert!(frame.kinematics.immersion.is_some());
+        assert!(!frame.camera.underwater);
+        let sunk = Isometry::translation(-4.0, 0.0, 4.0);
+        controller.state_mut().position = sunk;
+        let frame = controller.tick(&world, RawInput::default(), 1.0 / 60.0);
+        assert!(frame.camera.underwater);
+        assert!(controller.camera().is_underwater());
+    }
+
+    #[test]
+    fn controller_grabs_climbable_surface_and_jumps_off() {
+        let mut world = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        build_scene(&mut world);
+        let ladder = world.insert_static_collider(
+            ColliderBuilder::cuboid(0.5, 2.0, 0.1)
+                .translation(Vector::new(-4.0, 2.0, -0.6))
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+
+        let profile = CollisionProfile::arena_default();
+        let foot_offset = profile.capsule_height * 0.5 + profile.capsule_radius;
+        let start = Isometry::translation(-4.0, foot_offset, 0.0);
+        let mut controller = PlayerController::new(
+            DirectInputAdapter,
+            SimpleMotor::default(),
+            profile,
+            PlayerCamera::new(0.8),
+            start,
+        );
+        let forward = RawInput {
+            move_y: 1.0,
+            ..Default::default()
+        };
+        let frame = controller.tick(&world, forward, 1.0 / 60.0);
+        assert!(frame.kinematics.ladder.is_none());
+
+        world.set_climbable(ladder, true);
+        let frame = controller.tick(&world, forward, 1.0 / 60.0);
+        let contact = frame.kinematics.ladder.expect("grabbed");
+        assert_eq!(contact.collider, ladder);
+        assert!((contact.normal - Vector::z()).norm() < 1.0e-3);
+        // Standing at the foot without pressing forward steps off.
+        assert!(controller
+            .tick(&world, RawInput::default(), 1.0 / 60.0)
+            .kinematics
+            .ladder
+            .is_none());
+        assert!(controller
+            .tick(&world, forward, 1.0 / 60.0)
+            .kinematics
+            .ladder
+            .is_some());
+
+        let jump = RawInput {
+            move_y: 1.0,
+            jump: true,
+            ..Default::default()
+        };
+        assert!(controller
+            .tick(&world, jump, 1.0 / 60.0)
+            .kinematics
+            .ladder
+            .is_none());
+        assert!(controller
+            .tick(&world, forward, 1.0 / 60.0)
+            .kinematics
+            .ladder
+            .is_none());
+    }
+
+    #[test]
+    fn controller_steps_on_surface_materials() {
+        let mut world = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        let floor = world.insert_static_collider(
+            ColliderBuilder::cuboid(8.0, 0.1, 8.0)
+                .translation(Vector::new(0.0, -0.1, 0.0))
+                .build(),
+        );
+        let ice = SurfaceMaterial {
+            id: 2,
+            friction: 0.1,
+            acceleration: 0.25,
+            slide: true,
+            footsteps: ["sound/step/ice1.wav".to_string()].into(),
+        };
+        world.set_surface(floor, Some(ice.clone()));
+        world.step(1.0 / 60.0);
+
+        let profile = CollisionProfile::arena_default();
+        let foot_offset = profile.capsule_height * 0.5 + profile.capsule_radius;
+        let start = Isometry::translation(4.0, foot_offset, 0.0);
+        let mut controller = PlayerController::new(
+            DirectInputAdapter,
+            SimpleMotor::default(),
+            profile,
+            PlayerCamera::new(0.8),
+            start,
+        );
+        let left = RawInput {
+            move_x: -1.0,
+            ..Default::default()
+        };
+        // 100 ticks at 4 m/s covers four strides, but not five.
+        let mut steps = Vec::new();
+        for _ in 0..100 {
+            let frame = controller.tick(&world, left, 1.0 / 60.0);
+            steps.extend(frame.footstep);
+        }
+        assert_eq!(controller.state().ground_surface.as_ref(), Some(&ice));
+        assert_eq!(steps.len(), 4);
+        assert!(steps
+            .iter()
+            .all(|step| step.surface.as_ref() == Some(&ice) && !step.landing));
+        assert_eq!(
+            &*steps[0].surface.as_ref().expect("surface").footsteps,
+            ["sound/step/ice1.wav"]
+        );
+        assert!(controller
+            .tick(&world, RawInput::default(), 1.0 / 60.0)
+            .footstep
+            .is_none());
+
+        let state = controller.state_mut();
+        state.grounded = false;
+        state.velocity = Vector::new(0.0, -5.0, 0.0);
+        let landed = controller
+            .tick(&world, RawInput::default(), 1.0 / 60.0)
+            .footstep
+            .expect("landing");
+        assert!(landed.landing);
+    }
+
+    #[test]
+    fn camera_modes_boom_detach_and_blend() {
+        let mut world = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        build_scene(&mut world);
+        // Wall 1.5 m behind a player at the origin facing -Z.
+        world.insert_static_collider(
+            ColliderBuilder::cuboid(3.0, 3.0, 0.1)
+                .translation(Vector::new(-4.0, 3.0, 5.6))
+                .build(),
+        );
+        world.step(1.0 / 60.0);
+
+        let profile = CollisionProfile::arena_default();
+        let foot_offset = profile.capsule_height * 0.5 + profile.capsule_radius;
+        let mut camera = PlayerCamera::new(0.8);
+        camera.tuning_mut().blend_time = 0.0;
+        let mut controller = PlayerController::new(
+            DirectInputAdapter,
+            SimpleMotor::default(),
+            profile,
+            camera,
+            Isometry::translation(-4.0, foot_offset, 0.0),
+        );
+        let idle = RawInput::default();
+        let head = controller.tick(&world, idle, 1.0 / 60.0).camera.eye;
+
+        controller.camera_mut().set_mode(CameraMode::ThirdPerson);
+        let boom = controller.tick(&world, idle, 1.0 / 60.0).camera.eye;
+        assert!((boom.z - head.z - 3.0).abs() < 1.0e-3);
+        controller.state_mut().position = Isometry::translation(-4.0, foot_offset, 4.0);
+        let clipped = controller.tick(&world, idle, 1.0 / 60.0).camera.eye;
+        assert!(clipped.z < 5.5 - 0.2 && clipped.z > 4.0);
+
+        controller.state_mut().position = Isometry::translation(-4.0, foot_offset, 0.0);
+        controller.tick(&world, idle, 1.0 / 60.0);
+        controller.camera_mut().set_mode(CameraMode::Spectator);
+        let forward = RawInput {
+            move_y: 1.0,
+            ..Default::default()
+        };
+        let body = controller.state().position.translation.vector;
+        let mut frame = controller.tick(&world, forward, 1.0 / 60.0);
+        for _ in 0..29 {
+            frame = controller.tick(&world, forward, 1.0 / 60.0);
+        }
+        let travelled = boom - frame.camera.eye;
+        assert!((travelled.z - 4.0).abs() < 0.05);
+        assert!((frame.kinematics.position.translation.vector - body).norm() < 1.0e-3);
+
+        controller.camera_mut().tuning_mut().blend_time = 0.3;
+        let from = frame.camera.eye;
+        controller.camera_mut().set_mode(CameraMode::FirstPerson);
+        let eased = controller.tick(&world, idle, 1.0 / 60.0).camera.eye;
+        assert!((eased - from).norm() > 0.0 && (eased - head).norm() > 0.9);
+        for _ in 0..20 {
+            controller.tick(&world, idle, 1.0 / 60.0);
+        }
+        assert!((controller.camera().pose().eye - head).norm() < 1.0e-3);
+    }
+
+    #[test]
+    fn camera_feel_bobs_rolls_and_dips_deterministically() {
+        let mut world = PhysicsWorld::new(Vector::new(0.0, -9.81, 0.0));
+        build_scene(&mut world);
+        world.step(1.0 / 60.0);
+
+        let profile = CollisionProfile::arena_default();
+        let foot_offset = profile.capsule_height * 0.5 + profile.capsule_radius;
+        let spawn = Isometry::translation(-4.0, foot_offset, 4.0);
+        let run = |strafe: f32| {
+            let mut controller = PlayerController::new(
+                DirectInputAdapter,
+                SimpleMotor::default(),
+                profile,
+                PlayerCamera::new(0.8),
+                spawn,
+            );
+            let input = RawInput {
+                move_x: strafe,
+                ..Default::default()
+            };
+            (0..40)
+                .map(|_| controller.tick(&world, input, 1.0 / 60.0).camera.feel)
+                .collect::<Vec<_>>()
+        };
+        let right = run(1.0);
+        assert_eq!(right, run(1.0));
+        let last = right.last().unwrap();
+        assert!(last.roll > 0.0 && run(-1.0).last().unwrap().roll < 0.0);
+        assert!(right.iter().any(|feel| feel.offset.norm() > 0.005));
+        assert_eq!(last.fov_offset, 0.0);
+
+        let mut controller = PlayerController::new(
+            DirectInputAdapter,
+            SimpleMotor::default(),
+            profile,
+            PlayerCamera::new(0.8),
+            spawn,
+        );
+        controller.tick(&world, RawInput::default(), 1.0 / 60.0);
+        controller.state_mut().grounded = false;
+        controller.state_mut().velocity.y = -8.0;
+        let dips = (0..30)
+            .map(|_| {
+                controller
+                    .tick(&world, RawInput::default(), 1.0 / 60.0)
+                    .camera
+                    .feel
+                    .offset
+                    .y
+            })
+            .collect::<Vec<_>>();
+        let deepest = dips.iter().cloned().fold(0.0, f32::min);
+        assert!(deepest < -0.05);
+        assert!(dips.last().unwrap().abs() < 0.01);
+    }
 }
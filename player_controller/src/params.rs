@@ -0,0 +1,97 @@
+//! Reflective tuning parameters a motor exposes to hosts.
+//!
+//! Hosts list a motor's [`MotorParam`]s to build console variables, tune
+//! commands and profile loaders without knowing the motor's config type.
+//! Values are plain numbers in the parameter's [`ParamUnit`]; flags are 0 or 1.
+
+use rapier3d::prelude::Real;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParamUnit {
+    Meters,
+    MetersPerSecond,
+    MetersPerSecondSquared,
+    /// Rates applied per second, e.g. Quake-style acceleration and friction.
+    PerSecond,
+    Degrees,
+    DegreesPerSecond,
+    Seconds,
+    /// Unitless multiplier or dot product.
+    Scale,
+    /// On/off switch stored as 0 or 1.
+    Flag,
+}
+
+impl ParamUnit {
+    pub fn label(self) -> &'static str {
+        match self {
+            ParamUnit::Meters => "m",
+            ParamUnit::MetersPerSecond => "m/s",
+            ParamUnit::MetersPerSecondSquared => "m/s^2",
+            ParamUnit::PerSecond => "1/s",
+            ParamUnit::Degrees => "deg",
+            ParamUnit::DegreesPerSecond => "deg/s",
+            ParamUnit::Seconds => "s",
+            ParamUnit::Scale => "x",
+            ParamUnit::Flag => "0/1",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MotorParam {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub unit: ParamUnit,
+    pub min: Option<Real>,
+    pub max: Option<Real>,
+}
+
+impl MotorParam {
+    /// A parameter with no lower or upper limit.
+    pub const fn new(name: &'static str, unit: ParamUnit, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            unit,
+            min: None,
+            max: None,
+        }
+    }
+
+    pub const fn flag(name: &'static str, help: &'static str) -> Self {
+        Self::new(name, ParamUnit::Flag, help).range(0.0, 1.0)
+    }
+
+    pub const fn at_least(mut self, min: Real) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    pub const fn range(mut self, min: Real, max: Real) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    pub fn is_flag(&self) -> bool {
+        self.unit == ParamUnit::Flag
+    }
+
+    /// `value` limited to the parameter's range; flags snap to 0 or 1.
+    pub fn clamp(&self, value: Real) -> Real {
+        if self.is_flag() {
+            return if value >= 0.5 { 1.0 } else { 0.0 };
+        }
+        let value = self.min.map_or(value, |min| value.max(min));
+        self.max.map_or(value, |max| value.min(max))
+    }
+}
+
+/// Finds `name` among `params`.
+pub fn find_param<'a>(params: &'a [MotorParam], name: &str) -> Result<&'a MotorParam, String> {
+    params
+        .iter()
+        .find(|param| param.name == name)
+        .ok_or_else(|| format!("unknown motor param: {}", name))
+}
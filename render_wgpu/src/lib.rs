@@ -754,6 +754,7 @@ impl<'a> YuvImageView<'a> {
 pub enum SceneError {
     EmptyMesh,
     IndexOutOfBounds { index: u32, vertex_count: u32 },
+    VertexRangeOutOfBounds { end: usize, vertex_count: u32 },
     SizeOverflow,
 }
 
@@ -769,6 +770,11 @@ impl fmt::Display for SceneError {
                 "mesh index out of bounds: {} (vertex count {})",
                 index, vertex_count
             ),
+            SceneError::VertexRangeOutOfBounds { end, vertex_count } => write!(
+                f,
+                "mesh vertex range out of bounds: ..{} (vertex count {})",
+                end, vertex_count
+            ),
             SceneError::SizeOverflow => write!(f, "mesh size overflow"),
         }
     }
@@ -776,6 +782,9 @@ impl fmt::Display for SceneError {
 
 impl std::error::Error for SceneError {}
 
+/// Bytes per [`MeshVertex`] in the scene vertex buffer.
+const MESH_VERTEX_SIZE: usize = 24;
+
 #[derive(Debug, Clone)]
 pub struct MeshVertex {
     pub position: [f32; 3],
@@ -1177,6 +1186,19 @@ impl<'window> Renderer<'window> {
         }
     }
 
+    /// Overwrites scene vertices from `first` on, for geometry that moves
+    /// (doors, lifts). The index buffer is unchanged. No-op without a scene.
+    pub fn update_scene_vertices(
+        &mut self,
+        first: usize,
+        vertices: &[MeshVertex],
+    ) -> Result<(), SceneError> {
+        match &self.scene {
+            Some(scene) => scene.update_vertices(&self.queue, first, vertices),
+            None => Ok(()),
+        }
+    }
+
     async fn new_async(window: &'window winit::window::Window) -> Result<Self, RenderInitError> {
         let size = window.inner_size();
         let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
@@ -1752,6 +1774,7 @@ impl YuvQuad {
 struct SceneRenderer {
     pipeline: wgpu::RenderPipeline,
     vertex_buffer: wgpu::Buffer,
+    vertex_count: u32,
     index_buffer: wgpu::Buffer,
     index_count: u32,
     camera_buffer: wgpu::Buffer,
@@ -1813,7 +1836,7 @@ impl SceneRenderer {
                 module: &shader,
                 entry_point: "vs_main",
                 buffers: &[wgpu::VertexBufferLayout {
-                    array_stride: 24,
+                    array_stride: MESH_VERTEX_SIZE as wgpu::BufferAddress,
                     step_mode: wgpu::VertexStepMode::Vertex,
                     attributes: &[
                         wgpu::VertexAttribute {
@@ -1859,8 +1882,10 @@ impl SceneRenderer {
         let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("pallet.scene.vertex_buffer"),
             contents: &vertex_bytes,
-            usage: wgpu::BufferUsages::VERTEX,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
         });
+        let vertex_count =
+            u32::try_from(mesh.vertices.len()).map_err(|_| SceneError::SizeOverflow)?;
 
         let index_bytes = mesh_index_bytes(&mesh.indices)?;
         let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -1876,6 +1901,7 @@ impl SceneRenderer {
         Ok(Self {
             pipeline,
             vertex_buffer,
+            vertex_count,
             index_buffer,
             index_count,
             camera_buffer,
@@ -1898,6 +1924,27 @@ impl SceneRenderer {
         queue.write_buffer(&self.camera_buffer, 0, &bytes);
     }
 
+    fn update_vertices(
+        &self,
+        queue: &wgpu::Queue,
+        first: usize,
+        vertices: &[MeshVertex],
+    ) -> Result<(), SceneError> {
+        let end = first
+            .checked_add(vertices.len())
+            .ok_or(SceneError::SizeOverflow)?;
+        if end > self.vertex_count as usize {
+            return Err(SceneError::VertexRangeOutOfBounds {
+                end,
+                vertex_count: self.vertex_count,
+            });
+        }
+        let bytes = mesh_vertex_bytes(vertices)?;
+        let offset = (first * MESH_VERTEX_SIZE) as wgpu::BufferAddress;
+        queue.write_buffer(&self.vertex_buffer, offset, &bytes);
+        Ok(())
+    }
+
     fn resize(&mut self, device: &wgpu::Device, config: &wgpu::SurfaceConfiguration) {
         let (depth_texture, depth_view) = create_depth_texture(device, config);
         self.depth_texture = depth_texture;
@@ -2082,7 +2129,7 @@ fn quad_index_bytes() -> Vec<u8> {
 }
 
 fn mesh_vertex_bytes(vertices: &[MeshVertex]) -> Result<Vec<u8>, SceneError> {
-    let mut bytes = Vec::with_capacity(vertices.len().saturating_mul(24));
+    let mut bytes = Vec::with_capacity(vertices.len().saturating_mul(MESH_VERTEX_SIZE));
     for vertex in vertices {
         for value in vertex.position {
             bytes.extend_from_slice(&value.to_le_bytes());
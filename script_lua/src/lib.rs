@@ -7,7 +7,10 @@ use std::fs;
 use std::path::Path;
 use std::rc::Rc;
 
-use mlua::{Function, HookTriggers, IntoLuaMulti, Lua, RegistryKey, Table, Value, VmState};
+use mlua::{
+    FromLuaMulti, Function, HookTriggers, IntoLuaMulti, Lua, MultiValue, RegistryKey, Table, Value,
+    VmState,
+};
 
 const DEFAULT_INSTRUCTION_LIMIT: u64 = 200_000;
 const DEFAULT_HOOK_STEP: u64 = 1_000;
@@ -33,6 +36,40 @@ pub struct SpawnRequest {
     pub yaw: f32,
 }
 
+/// What a query hit was built from: `kind` is "solid", "chunk", "prop" or
+/// "collider" when the host has no record of it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HitSource {
+    pub kind: String,
+    pub id: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceHit {
+    pub distance: f32,
+    pub point: [f32; 3],
+    pub normal: [f32; 3],
+    pub source: HitSource,
+}
+
+/// Physics queries a host exposes to scripts for the duration of
+/// [`ScriptEngine::with_scene`]. `mask` names a collision layer mask; `None`
+/// hits every layer.
+pub trait SceneQuery {
+    /// Hits along the ray, nearest first; only the nearest unless `all`.
+    fn trace_ray(
+        &self,
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+        mask: Option<&str>,
+        all: bool,
+    ) -> Vec<TraceHit>;
+
+    fn overlap_box(&self, min: [f32; 3], max: [f32; 3], mask: Option<&str>) -> Vec<HitSource>;
+}
+
 pub struct HostCallbacks {
     pub spawn_entity: Box<dyn FnMut(SpawnRequest) -> u32>,
     pub play_sound: Box<dyn FnMut(String) -> Result<(), String>>,
@@ -82,6 +119,8 @@ struct ScriptHooks {
     on_tick: Option<Rc<RegistryKey>>,
     on_key: Option<Rc<RegistryKey>>,
     on_spawn: Option<Rc<RegistryKey>>,
+    on_trigger: Option<Rc<RegistryKey>>,
+    on_liquid_damage: Option<Rc<RegistryKey>>,
 }
 
 impl ScriptEngine {
@@ -127,6 +166,89 @@ impl ScriptEngine {
         self.call_hook("on_spawn", (id, position[0], position[1], position[2], yaw))
     }
 
+    /// `other` is the entity in the trigger as the host names it, e.g.
+    /// "player:0"; `phase` is "enter", "stay" or "exit".
+    pub fn on_trigger(&mut self, name: &str, other: &str, phase: &str) -> Result<(), ScriptError> {
+        self.call_hook("on_trigger", (name, other, phase))
+    }
+
+    /// `kind` is "slime" or "lava"; the rules decide `amount` and how often.
+    pub fn on_liquid_damage(
+        &mut self,
+        kind: &str,
+        amount: f32,
+        entity: &str,
+    ) -> Result<(), ScriptError> {
+        self.call_hook("on_liquid_damage", (kind, amount, entity))
+    }
+
+    /// Runs `func` with `trace_ray`, `trace_ray_all` and `overlap_box` bound
+    /// to `scene`; outside of it those globals are nil.
+    pub fn with_scene<T>(
+        &mut self,
+        scene: &dyn SceneQuery,
+        func: impl FnOnce(&mut Self) -> Result<T, ScriptError>,
+    ) -> Result<T, ScriptError> {
+        let lua = self.lua.clone();
+        let mut result = None;
+        lua.scope(|scope| {
+            let globals = lua.globals();
+            globals.set(
+                "trace_ray",
+                scope.create_function(|lua, args: TraceArgs| {
+                    let hits = args.run(scene, false);
+                    match hits.first() {
+                        Some(hit) => Ok(Value::Table(trace_hit_table(lua, hit)?)),
+                        None => Ok(Value::Nil),
+                    }
+                })?,
+            )?;
+            globals.set(
+                "trace_ray_all",
+                scope.create_function(|lua, args: TraceArgs| {
+                    let hits = args.run(scene, true);
+                    let table = lua.create_table()?;
+                    for hit in &hits {
+                        table.push(trace_hit_table(lua, hit)?)?;
+                    }
+                    Ok(table)
+                })?,
+            )?;
+            globals.set(
+                "overlap_box",
+                scope.create_function(
+                    |lua,
+                     (min_x, min_y, min_z, max_x, max_y, max_z, mask): (
+                        f32,
+                        f32,
+                        f32,
+                        f32,
+                        f32,
+                        f32,
+                        Option<String>,
+                    )| {
+                        let sources = scene.overlap_box(
+                            [min_x, min_y, min_z],
+                            [max_x, max_y, max_z],
+                            mask.as_deref(),
+                        );
+                        let table = lua.create_table()?;
+                        for source in &sources {
+                            table.push(hit_source_table(lua, source)?)?;
+                        }
+                        Ok(table)
+                    },
+                )?,
+            )?;
+            result = Some(func(self));
+            for name in ["trace_ray", "trace_ray_all", "overlap_box"] {
+                globals.set(name, Value::Nil)?;
+            }
+            Ok(())
+        })?;
+        result.expect("scene scope ran")
+    }
+
     pub fn run_command(&mut self, name: &str, args: &[String]) -> Result<bool, ScriptError> {
         let key = { self.commands.borrow().get(name).cloned() };
         if let Some(key) = key {
@@ -147,6 +269,8 @@ impl ScriptEngine {
         hooks.on_tick = capture_hook(&self.lua, &globals, "on_tick")?;
         hooks.on_key = capture_hook(&self.lua, &globals, "on_key")?;
         hooks.on_spawn = capture_hook(&self.lua, &globals, "on_spawn")?;
+        hooks.on_trigger = capture_hook(&self.lua, &globals, "on_trigger")?;
+        hooks.on_liquid_damage = capture_hook(&self.lua, &globals, "on_liquid_damage")?;
         Ok(())
     }
 
@@ -160,6 +284,8 @@ impl ScriptEngine {
                 "on_tick" => hooks.on_tick.as_ref().map(Rc::clone),
                 "on_key" => hooks.on_key.as_ref().map(Rc::clone),
                 "on_spawn" => hooks.on_spawn.as_ref().map(Rc::clone),
+                "on_trigger" => hooks.on_trigger.as_ref().map(Rc::clone),
+                "on_liquid_damage" => hooks.on_liquid_damage.as_ref().map(Rc::clone),
                 _ => None,
             }
         };
@@ -263,6 +389,67 @@ fn register_globals(
     Ok(())
 }
 
+/// `(ox, oy, oz, dx, dy, dz, max_distance [, mask])`
+struct TraceArgs {
+    origin: [f32; 3],
+    direction: [f32; 3],
+    max_distance: f32,
+    mask: Option<String>,
+}
+
+impl FromLuaMulti for TraceArgs {
+    fn from_lua_multi(values: MultiValue, lua: &Lua) -> mlua::Result<Self> {
+        let (ox, oy, oz, dx, dy, dz, max_distance, mask) =
+            <(f32, f32, f32, f32, f32, f32, f32, Option<String>)>::from_lua_multi(values, lua)?;
+        Ok(Self {
+            origin: [ox, oy, oz],
+            direction: [dx, dy, dz],
+            max_distance,
+            mask,
+        })
+    }
+}
+
+impl TraceArgs {
+    fn run(&self, scene: &dyn SceneQuery, all: bool) -> Vec<TraceHit> {
+        let [x, y, z] = self.direction;
+        let length = (x * x + y * y + z * z).sqrt();
+        if length <= f32::EPSILON || !self.max_distance.is_finite() || self.max_distance <= 0.0 {
+            return Vec::new();
+        }
+        scene.trace_ray(
+            self.origin,
+            [x / length, y / length, z / length],
+            self.max_distance,
+            self.mask.as_deref(),
+            all,
+        )
+    }
+}
+
+fn trace_hit_table(lua: &Lua, hit: &TraceHit) -> mlua::Result<Table> {
+    let table = hit_source_table(lua, &hit.source)?;
+    table.set("distance", hit.distance)?;
+    table.set("x", hit.point[0])?;
+    table.set("y", hit.point[1])?;
+    table.set("z", hit.point[2])?;
+    table.set("nx", hit.normal[0])?;
+    table.set("ny", hit.normal[1])?;
+    table.set("nz", hit.normal[2])?;
+    Ok(table)
+}
+
+fn hit_source_table(lua: &Lua, source: &HitSource) -> mlua::Result<Table> {
+    let table = lua.create_table()?;
+    table.set("kind", source.kind.as_str())?;
+    table.set("id", source.id.as_str())?;
+    table.set(
+        "tags",
+        lua.create_sequence_from(source.tags.iter().cloned())?,
+    )?;
+    Ok(table)
+}
+
 fn sandbox_globals(lua: &Lua) -> Result<(), ScriptError> {
     let globals = lua.globals();
     globals.set("dofile", Value::Nil)?;
@@ -287,3 +474,234 @@ fn capture_hook(
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use physics_rapier::{ColliderSource, PhysicsWorld};
+    use rapier3d::prelude::*;
+
+    /// A floor slab with its top at y = 0 and a crate prop centered at
+    /// y = 2, straight above the origin.
+    struct TestScene {
+        world: PhysicsWorld,
+    }
+
+    impl TestScene {
+        fn new() -> Self {
+            let mut world = PhysicsWorld::new(vector![0.0, -9.81, 0.0]);
+            let floor = world.insert_static_collider(
+                ColliderBuilder::cuboid(5.0, 0.1, 5.0)
+                    .translation(vector![0.0, -0.1, 0.0])
+                    .build(),
+            );
+            world.set_collider_source(
+                floor,
+                ColliderSource::Solid {
+                    id: "floor".to_string(),
+                    tags: vec!["stone".to_string()],
+                },
+            );
+            let crate_box = world.insert_static_collider(
+                ColliderBuilder::cuboid(0.5, 0.5, 0.5)
+                    .translation(vector![0.0, 2.0, 0.0])
+                    .build(),
+            );
+            world.set_collider_source(
+                crate_box,
+                ColliderSource::Prop {
+                    id: "crate".to_string(),
+                },
+            );
+            world.step(1.0 / 60.0);
+            Self { world }
+        }
+
+        fn groups(mask: Option<&str>) -> InteractionGroups {
+            match mask {
+                Some("none") => InteractionGroups::none(),
+                _ => InteractionGroups::all(),
+            }
+        }
+    }
+
+    fn test_source(source: Option<&ColliderSource>) -> HitSource {
+        match source {
+            Some(ColliderSource::Solid { id, tags }) => HitSource {
+                kind: "solid".to_string(),
+                id: id.clone(),
+                tags: tags.clone(),
+            },
+            Some(ColliderSource::Prop { id }) => HitSource {
+                kind: "prop".to_string(),
+                id: id.clone(),
+                tags: Vec::new(),
+            },
+            _ => HitSource {
+                kind: "collider".to_string(),
+                ..HitSource::default()
+            },
+        }
+    }
+
+    impl SceneQuery for TestScene {
+        fn trace_ray(
+            &self,
+            origin: [f32; 3],
+            direction: [f32; 3],
+            max_distance: f32,
+            mask: Option<&str>,
+            all: bool,
+        ) -> Vec<TraceHit> {
+            let origin = Point::from(origin);
+            let direction = Vector::from(direction);
+            let groups = Self::groups(mask);
+            let hits = if all {
+                self.world
+                    .raycast_all(origin, direction, max_distance, groups)
+            } else {
+                self.world
+                    .raycast(origin, direction, max_distance, groups)
+                    .into_iter()
+                    .collect()
+            };
+            hits.into_iter()
+                .map(|hit| TraceHit {
+                    distance: hit.toi,
+                    point: hit.point.coords.into(),
+                    normal: hit.normal.into(),
+                    source: test_source(hit.source.as_ref()),
+                })
+                .collect()
+        }
+
+        fn overlap_box(&self, min: [f32; 3], max: [f32; 3], mask: Option<&str>) -> Vec<HitSource> {
+            self.world
+                .overlap_aabb(Point::from(min), Point::from(max), Self::groups(mask))
+                .into_iter()
+                .map(|overlap| test_source(overlap.source.as_ref()))
+                .collect()
+        }
+    }
+
+    fn run_with_scene(engine: &mut ScriptEngine, scene: &TestScene, command: &str) {
+        engine
+            .with_scene(scene, |engine| engine.run_command(command, &[]))
+            .expect("command");
+    }
+
+    fn engine_with_log() -> (ScriptEngine, Rc<RefCell<Vec<String>>>) {
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        let log_lines = Rc::clone(&lines);
+        let callbacks = HostCallbacks {
+            spawn_entity: Box::new(|_| 0),
+            play_sound: Box::new(|_| Ok(())),
+            log: Box::new(move |line| log_lines.borrow_mut().push(line)),
+            run_command: Box::new(|_| Ok(())),
+        };
+        let engine = ScriptEngine::new(ScriptConfig::default(), callbacks).expect("engine");
+        (engine, lines)
+    }
+
+    #[test]
+    fn on_trigger_receives_name_other_and_phase() {
+        let (mut engine, lines) = engine_with_log();
+        engine
+            .load_script(
+                r#"
+function on_trigger(name, other, phase)
+  log(name .. " " .. other .. " " .. phase)
+end
+"#,
+            )
+            .expect("load");
+        engine
+            .on_trigger("exit_pad", "player:0", "enter")
+            .expect("enter");
+        engine
+            .on_trigger("exit_pad", "player:0", "exit")
+            .expect("exit");
+        assert_eq!(
+            *lines.borrow(),
+            vec!["exit_pad player:0 enter", "exit_pad player:0 exit"]
+        );
+    }
+
+    #[test]
+    fn trace_ray_reports_the_nearest_hit_and_its_source() {
+        let (mut engine, lines) = engine_with_log();
+        engine
+            .load_script(
+                r#"
+register_command("probe", function()
+  local hit = trace_ray(0, 5, 0, 0, -2, 0, 10)
+  log(string.format("%s %s %.2f %.2f %.2f", hit.kind, hit.id, hit.distance, hit.y, hit.ny))
+  local floor = trace_ray(3, 5, 0, 0, -1, 0, 10)
+  log(string.format("%s %s %s %.2f", floor.kind, floor.id, floor.tags[1], floor.distance))
+  log(tostring(trace_ray(0, 5, 0, 0, -1, 0, 1)))
+  log(tostring(trace_ray(0, 5, 0, 0, -1, 0, 10, "none")))
+end)
+"#,
+            )
+            .expect("load");
+        let scene = TestScene::new();
+        run_with_scene(&mut engine, &scene, "probe");
+        assert_eq!(
+            *lines.borrow(),
+            vec![
+                "prop crate 2.50 2.50 1.00",
+                "solid floor stone 5.00",
+                "nil",
+                "nil"
+            ]
+        );
+    }
+
+    #[test]
+    fn trace_ray_all_lists_every_hit_nearest_first() {
+        let (mut engine, lines) = engine_with_log();
+        engine
+            .load_script(
+                r#"
+register_command("probe", function()
+  local hits = trace_ray_all(0, 5, 0, 0, -1, 0, 10)
+  log(tostring(#hits))
+  for _, hit in ipairs(hits) do
+    log(string.format("%s %.2f", hit.id, hit.distance))
+  end
+end)
+"#,
+            )
+            .expect("load");
+        let scene = TestScene::new();
+        run_with_scene(&mut engine, &scene, "probe");
+        assert_eq!(*lines.borrow(), vec!["2", "crate 2.50", "floor 5.00"]);
+    }
+
+    #[test]
+    fn overlap_box_returns_sources_and_scene_globals_are_scoped() {
+        let (mut engine, lines) = engine_with_log();
+        engine
+            .load_script(
+                r#"
+register_command("probe", function()
+  local found = {}
+  for _, source in ipairs(overlap_box(-1, -0.5, -1, 1, 0.5, 1)) do
+    found[#found + 1] = source.kind .. " " .. source.id
+  end
+  log(table.concat(found, ","))
+  log(tostring(#overlap_box(-1, -0.5, -1, 1, 3, 1)))
+  log(tostring(#overlap_box(3, 4, 3, 4, 5, 4)))
+end)
+register_command("outside", function()
+  log(tostring(trace_ray) .. " " .. tostring(overlap_box))
+end)
+"#,
+            )
+            .expect("load");
+        let scene = TestScene::new();
+        run_with_scene(&mut engine, &scene, "probe");
+        engine.run_command("outside", &[]).expect("outside");
+        assert_eq!(*lines.borrow(), vec!["solid floor", "2", "0", "nil nil"]);
+    }
+}
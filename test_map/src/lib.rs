@@ -19,6 +19,10 @@ pub struct TestMap {
     pub solids: Vec<SolidSpec>,
     #[serde(default)]
     pub generators: Vec<GeneratorSpec>,
+    #[serde(default)]
+    pub movers: Vec<MoverSpec>,
+    #[serde(default)]
+    pub props: Vec<PropSpec>,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize)]
@@ -63,6 +67,68 @@ pub enum SolidKind {
     Cylinder,
 }
 
+/// Moves an authored solid along `path`, a list of offsets (map units) from
+/// the solid's `pos`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct MoverSpec {
+    pub id: String,
+    pub kind: MoverKind,
+    pub solid: String,
+    pub path: Vec<[f32; 3]>,
+    /// Map units per second.
+    pub speed: f32,
+    /// Seconds to pause at each stop (doors: only while open).
+    #[serde(default = "default_mover_wait")]
+    pub wait: f32,
+    /// Trigger solid whose enter event activates the mover (required for doors).
+    #[serde(default)]
+    pub trigger: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MoverKind {
+    /// Opens to the end of the path when triggered, waits, then closes.
+    Door,
+    /// Runs back and forth along the path.
+    Elevator,
+    /// Follows the path and wraps to the start.
+    Platform,
+}
+
+/// A dynamic rigid body spawned at map load. Sizes and points are in map
+/// units; `points` are relative to `pos`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct PropSpec {
+    pub id: String,
+    pub kind: PropKind,
+    pub pos: [f32; 3],
+    #[serde(default)]
+    pub size: Option<[f32; 3]>,
+    #[serde(default)]
+    pub radius: Option<f32>,
+    #[serde(default)]
+    pub points: Vec<[f32; 3]>,
+    #[serde(default)]
+    pub yaw_deg: Option<f32>,
+    /// Kilograms.
+    pub mass: f32,
+    #[serde(default = "default_prop_friction")]
+    pub friction: f32,
+    #[serde(default)]
+    pub restitution: f32,
+    #[serde(default)]
+    pub ccd: bool,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PropKind {
+    Box,
+    Sphere,
+    ConvexHull,
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum GeneratorSpec {
@@ -113,6 +179,42 @@ pub enum GeneratorSpec {
         #[serde(default)]
         tags: Vec<String>,
     },
+    /// Side-by-side tunnels, one per entry in `clearances` (floor to ceiling),
+    /// for checking where a crouched capsule fits and a standing one does not.
+    Crawlspaces {
+        id: String,
+        pos: [f32; 3],
+        #[serde(default)]
+        yaw_deg: f32,
+        length: f32,
+        width: f32,
+        clearances: Vec<f32>,
+        #[serde(default = "default_wall_thickness")]
+        wall_thickness: f32,
+        #[serde(default)]
+        gap: f32,
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    /// Climbable faces of varying height, one per entry in `heights`, each
+    /// on the front of a block the player can climb onto. Approach along
+    /// local +X.
+    Ladders {
+        id: String,
+        pos: [f32; 3],
+        #[serde(default)]
+        yaw_deg: f32,
+        width: f32,
+        heights: Vec<f32>,
+        #[serde(default = "default_ladder_depth")]
+        depth: f32,
+        #[serde(default = "default_ladder_landing")]
+        landing: f32,
+        #[serde(default)]
+        gap: f32,
+        #[serde(default)]
+        tags: Vec<String>,
+    },
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -129,6 +231,15 @@ pub struct StairsVariant {
     pub width: Option<f32>,
 }
 
+/// Solids tagged `trigger` are invisible sensor volumes rather than geometry.
+pub const TRIGGER_TAG: &str = "trigger";
+
+/// Solids tagged with a liquid are swimmable sensor volumes, like triggers.
+pub const LIQUID_TAGS: [&str; 3] = ["water", "slime", "lava"];
+
+/// Solids tagged `climbable` are ladders; on a trigger the whole volume is.
+pub const CLIMBABLE_TAG: &str = "climbable";
+
 #[derive(Clone, Debug)]
 pub struct ResolvedSolid {
     pub id: String,
@@ -140,6 +251,27 @@ pub struct ResolvedSolid {
     pub tags: Vec<String>,
 }
 
+impl ResolvedSolid {
+    pub fn is_trigger(&self) -> bool {
+        self.tags.iter().any(|tag| tag == TRIGGER_TAG)
+    }
+
+    pub fn is_climbable(&self) -> bool {
+        self.tags.iter().any(|tag| tag == CLIMBABLE_TAG)
+    }
+
+    /// The first liquid tag on this solid, if it is a liquid volume.
+    pub fn liquid_tag(&self) -> Option<&str> {
+        liquid_tag(&self.tags)
+    }
+}
+
+pub fn liquid_tag(tags: &[String]) -> Option<&str> {
+    tags.iter()
+        .map(String::as_str)
+        .find(|tag| LIQUID_TAGS.contains(tag))
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct TestMapValidation {
     pub errors: Vec<String>,
@@ -192,6 +324,21 @@ impl TestMap {
                     .errors
                     .push("solid id must not be empty".to_string());
             }
+            if liquid_tag(&solid.tags).is_some() && solid.tags.iter().any(|tag| tag == TRIGGER_TAG)
+            {
+                validation.errors.push(format!(
+                    "solid '{}' cannot be both a trigger and a liquid",
+                    solid.id
+                ));
+            }
+            if liquid_tag(&solid.tags).is_some()
+                && solid.tags.iter().any(|tag| tag == CLIMBABLE_TAG)
+            {
+                validation.errors.push(format!(
+                    "solid '{}' cannot be both climbable and a liquid",
+                    solid.id
+                ));
+            }
             if !vector_is_finite(solid.pos) {
                 validation
                     .errors
@@ -222,9 +369,85 @@ impl TestMap {
         for generator in &self.generators {
             validate_generator(generator, &mut validation);
         }
+        for mover in &self.movers {
+            self.validate_mover(mover, &mut validation);
+        }
+        for prop in &self.props {
+            validate_prop(prop, &mut validation);
+        }
         validation
     }
 
+    fn validate_mover(&self, mover: &MoverSpec, validation: &mut TestMapValidation) {
+        let solid = |id: &str| self.solids.iter().find(|solid| solid.id == id);
+        match solid(&mover.solid) {
+            Some(solid) if solid.tags.iter().any(|tag| tag == TRIGGER_TAG) => validation
+                .errors
+                .push(format!("mover '{}' cannot move a trigger solid", mover.id)),
+            Some(solid) if liquid_tag(&solid.tags).is_some() => validation
+                .errors
+                .push(format!("mover '{}' cannot move a liquid solid", mover.id)),
+            Some(_) => {}
+            None => validation.errors.push(format!(
+                "mover '{}' references unknown solid '{}'",
+                mover.id, mover.solid
+            )),
+        }
+        if mover.path.len() < 2 || mover.path.iter().any(|point| !vector_is_finite(*point)) {
+            validation.errors.push(format!(
+                "mover '{}' path needs at least two finite points",
+                mover.id
+            ));
+        } else {
+            // Platforms wrap from the last point back to the first.
+            let wraps = mover.kind == MoverKind::Platform;
+            let segments = mover
+                .path
+                .windows(2)
+                .map(|pair| (pair[0], pair[1]))
+                .chain(wraps.then(|| (mover.path[mover.path.len() - 1], mover.path[0])));
+            for (index, (from, to)) in segments.enumerate() {
+                if from == to {
+                    validation.errors.push(format!(
+                        "mover '{}' path segment {} has zero length",
+                        mover.id, index
+                    ));
+                }
+            }
+        }
+        if !mover.speed.is_finite() || mover.speed <= 0.0 {
+            validation
+                .errors
+                .push(format!("mover '{}' speed must be > 0", mover.id));
+        }
+        if !mover.wait.is_finite() || mover.wait < 0.0 {
+            validation
+                .errors
+                .push(format!("mover '{}' wait must be >= 0", mover.id));
+        }
+        match mover.trigger.as_deref() {
+            Some(trigger) => {
+                let tagged = solid(trigger)
+                    .is_some_and(|solid| solid.tags.iter().any(|tag| tag == TRIGGER_TAG));
+                if !tagged {
+                    validation.errors.push(format!(
+                        "mover '{}' trigger '{}' is not a trigger solid",
+                        mover.id, trigger
+                    ));
+                }
+            }
+            None if mover.kind == MoverKind::Door => validation
+                .errors
+                .push(format!("door '{}' requires a trigger", mover.id)),
+            None => {}
+        }
+    }
+
+    /// Whether `solid_id` is moved by a mover rather than static geometry.
+    pub fn is_mover_solid(&self, solid_id: &str) -> bool {
+        self.movers.iter().any(|mover| mover.solid == solid_id)
+    }
+
     pub fn expanded_solids(&self) -> Result<Vec<ResolvedSolid>, String> {
         let validation = self.validate();
         if !validation.is_ok() {
@@ -356,6 +579,48 @@ impl GeneratorSpec {
                 gap: *gap,
                 tags,
             }),
+            GeneratorSpec::Crawlspaces {
+                id,
+                pos,
+                yaw_deg,
+                length,
+                width,
+                clearances,
+                wall_thickness,
+                gap,
+                tags,
+            } => build_crawlspaces(CrawlspacesParams {
+                base_id: id,
+                pos: *pos,
+                yaw_deg: *yaw_deg,
+                length: *length,
+                width: *width,
+                clearances,
+                wall_thickness: *wall_thickness,
+                gap: *gap,
+                tags,
+            }),
+            GeneratorSpec::Ladders {
+                id,
+                pos,
+                yaw_deg,
+                width,
+                heights,
+                depth,
+                landing,
+                gap,
+                tags,
+            } => build_ladders(LaddersParams {
+                base_id: id,
+                pos: *pos,
+                yaw_deg: *yaw_deg,
+                width: *width,
+                heights,
+                depth: *depth,
+                landing: *landing,
+                gap: *gap,
+                tags,
+            }),
         }
     }
 }
@@ -457,6 +722,64 @@ fn validate_generator(generator: &GeneratorSpec, validation: &mut TestMapValidat
                     .push(format!("corridors '{}' margins must be >= 0", id));
             }
         }
+        GeneratorSpec::Crawlspaces {
+            id,
+            length,
+            width,
+            clearances,
+            wall_thickness,
+            ..
+        } => {
+            if id.trim().is_empty() {
+                validation
+                    .errors
+                    .push("crawlspaces generator id must not be empty".to_string());
+            }
+            if *length <= 0.0 || *width <= 0.0 || *wall_thickness <= 0.0 {
+                validation
+                    .errors
+                    .push(format!("crawlspaces '{}' dimensions must be > 0", id));
+            }
+            if clearances.is_empty() {
+                validation
+                    .errors
+                    .push(format!("crawlspaces '{}' clearances must not be empty", id));
+            }
+            if clearances.iter().any(|clearance| *clearance <= 0.0) {
+                validation
+                    .errors
+                    .push(format!("crawlspaces '{}' clearances must be > 0", id));
+            }
+        }
+        GeneratorSpec::Ladders {
+            id,
+            width,
+            heights,
+            depth,
+            landing,
+            ..
+        } => {
+            if id.trim().is_empty() {
+                validation
+                    .errors
+                    .push("ladders generator id must not be empty".to_string());
+            }
+            if *width <= 0.0 || *depth <= 0.0 || *landing <= 0.0 {
+                validation
+                    .errors
+                    .push(format!("ladders '{}' dimensions must be > 0", id));
+            }
+            if heights.is_empty() {
+                validation
+                    .errors
+                    .push(format!("ladders '{}' heights must not be empty", id));
+            }
+            if heights.iter().any(|height| *height <= 0.0) {
+                validation
+                    .errors
+                    .push(format!("ladders '{}' heights must be > 0", id));
+            }
+        }
     }
 }
 
@@ -694,6 +1017,125 @@ fn build_corridors(params: CorridorsParams<'_>) -> Result<Vec<ResolvedSolid>, St
     Ok(solids)
 }
 
+struct CrawlspacesParams<'a> {
+    base_id: &'a str,
+    pos: [f32; 3],
+    yaw_deg: f32,
+    length: f32,
+    width: f32,
+    clearances: &'a [f32],
+    wall_thickness: f32,
+    gap: f32,
+    tags: &'a [String],
+}
+
+/// Each crawlspace runs along local +X from `pos`; `pos.y` is the floor top.
+fn build_crawlspaces(params: CrawlspacesParams<'_>) -> Result<Vec<ResolvedSolid>, String> {
+    let mut solids = Vec::new();
+    let yaw = params.yaw_deg.to_radians();
+    let outer_width = params.width + params.wall_thickness * 2.0;
+    for (index, clearance) in params.clearances.iter().enumerate() {
+        let crawl_id = format!("{}/crawl_{:02}", params.base_id, index + 1);
+        let offset = (outer_width + params.gap) * index as f32;
+        let solid = |name: &str, local: [f32; 3], size: [f32; 3]| {
+            let rotated = rotate_y(
+                [params.length * 0.5 + local[0], local[1], offset + local[2]],
+                yaw,
+            );
+            ResolvedSolid {
+                id: format!("{}/{}", crawl_id, name),
+                kind: SolidKind::Box,
+                pos: [
+                    params.pos[0] + rotated[0],
+                    params.pos[1] + rotated[1],
+                    params.pos[2] + rotated[2],
+                ],
+                size,
+                yaw_deg: Some(params.yaw_deg),
+                rot_euler_deg: None,
+                tags: params.tags.to_vec(),
+            }
+        };
+        let wall_offset = params.width * 0.5 + params.wall_thickness * 0.5;
+        solids.extend([
+            solid(
+                "floor",
+                [0.0, -params.wall_thickness * 0.5, 0.0],
+                [params.length, params.wall_thickness, outer_width],
+            ),
+            solid(
+                "ceiling",
+                [0.0, clearance + params.wall_thickness * 0.5, 0.0],
+                [params.length, params.wall_thickness, outer_width],
+            ),
+            solid(
+                "wall_left",
+                [0.0, clearance * 0.5, -wall_offset],
+                [params.length, *clearance, params.wall_thickness],
+            ),
+            solid(
+                "wall_right",
+                [0.0, clearance * 0.5, wall_offset],
+                [params.length, *clearance, params.wall_thickness],
+            ),
+        ]);
+    }
+    Ok(solids)
+}
+
+struct LaddersParams<'a> {
+    base_id: &'a str,
+    pos: [f32; 3],
+    yaw_deg: f32,
+    width: f32,
+    heights: &'a [f32],
+    depth: f32,
+    landing: f32,
+    gap: f32,
+    tags: &'a [String],
+}
+
+/// Each ladder's climbable face starts at `pos` along local +X, backed by a
+/// landing block of the same height; `pos.y` is the floor top.
+fn build_ladders(params: LaddersParams<'_>) -> Result<Vec<ResolvedSolid>, String> {
+    let mut solids = Vec::new();
+    let yaw = params.yaw_deg.to_radians();
+    for (index, height) in params.heights.iter().enumerate() {
+        let ladder_id = format!("{}/ladder_{:02}", params.base_id, index + 1);
+        let offset = (params.width + params.gap) * index as f32;
+        let solid = |name: &str, local_x: f32, length: f32, tags: Vec<String>| {
+            let rotated = rotate_y([local_x + length * 0.5, height * 0.5, offset], yaw);
+            ResolvedSolid {
+                id: format!("{}/{}", ladder_id, name),
+                kind: SolidKind::Box,
+                pos: [
+                    params.pos[0] + rotated[0],
+                    params.pos[1] + rotated[1],
+                    params.pos[2] + rotated[2],
+                ],
+                size: [length, *height, params.width],
+                yaw_deg: Some(params.yaw_deg),
+                rot_euler_deg: None,
+                tags,
+            }
+        };
+        let mut climbable = params.tags.to_vec();
+        if !climbable.iter().any(|tag| tag == CLIMBABLE_TAG) {
+            climbable.push(CLIMBABLE_TAG.to_string());
+        }
+        solids.extend([
+            solid("rungs", 0.0, params.depth, climbable),
+            solid(
+                "landing",
+                params.depth,
+                params.landing,
+                params.tags.to_vec(),
+            ),
+        ]);
+    }
+    Ok(solids)
+}
+
 fn rotate_y(value: [f32; 3], yaw: f32) -> [f32; 3] {
     let (sin, cos) = yaw.sin_cos();
     [
@@ -703,6 +1145,56 @@ fn rotate_y(value: [f32; 3], yaw: f32) -> [f32; 3] {
     ]
 }
 
+fn validate_prop(prop: &PropSpec, validation: &mut TestMapValidation) {
+    if prop.id.trim().is_empty() {
+        validation
+            .errors
+            .push("prop id must not be empty".to_string());
+    }
+    if !vector_is_finite(prop.pos) {
+        validation
+            .errors
+            .push(format!("prop '{}' has invalid pos", prop.id));
+    }
+    let shape_ok = match prop.kind {
+        PropKind::Box => prop
+            .size
+            .is_some_and(|size| vector_is_finite(size) && size.iter().all(|value| *value > 0.0)),
+        PropKind::Sphere => prop
+            .radius
+            .is_some_and(|radius| radius.is_finite() && radius > 0.0),
+        PropKind::ConvexHull => {
+            prop.points.len() >= 4 && prop.points.iter().all(|point| vector_is_finite(*point))
+        }
+    };
+    if !shape_ok {
+        let needs = match prop.kind {
+            PropKind::Box => "a positive size",
+            PropKind::Sphere => "a positive radius",
+            PropKind::ConvexHull => "at least four finite points",
+        };
+        validation
+            .errors
+            .push(format!("prop '{}' needs {}", prop.id, needs));
+    }
+    if !prop.mass.is_finite() || prop.mass <= 0.0 {
+        validation
+            .errors
+            .push(format!("prop '{}' mass must be > 0", prop.id));
+    }
+    if !prop.friction.is_finite() || prop.friction < 0.0 {
+        validation
+            .errors
+            .push(format!("prop '{}' friction must be >= 0", prop.id));
+    }
+    if !(0.0..=1.0).contains(&prop.restitution) {
+        validation.errors.push(format!(
+            "prop '{}' restitution must be between 0 and 1",
+            prop.id
+        ));
+    }
+}
+
 fn vector_is_finite(value: [f32; 3]) -> bool {
     value.iter().all(|component| component.is_finite())
 }
@@ -711,6 +1203,22 @@ fn default_wall_thickness() -> f32 {
     0.2
 }
 
+fn default_ladder_depth() -> f32 {
+    0.2
+}
+
+fn default_ladder_landing() -> f32 {
+    2.0
+}
+
+fn default_mover_wait() -> f32 {
+    1.0
+}
+
+fn default_prop_friction() -> f32 {
+    0.5
+}
+
 fn default_stairs_variant_gap() -> f32 {
     1.0
 }
@@ -737,4 +1245,174 @@ size = [10.0, 1.0, 10.0]
         assert_eq!(solids.len(), 1);
         assert_eq!(solids[0].id, "floor");
     }
+
+    #[test]
+    fn movers_validate_solid_and_trigger_references() {
+        let text = r#"
+version = 1
+name = "movers"
+
+[[solids]]
+id = "gate"
+kind = "box"
+pos = [0.0, 1.5, 0.0]
+size = [0.2, 3.0, 4.0]
+
+[[solids]]
+id = "gate_zone"
+kind = "box"
+pos = [2.0, 1.5, 0.0]
+size = [2.0, 3.0, 4.0]
+tags = ["trigger"]
+
+[[movers]]
+id = "gate_door"
+kind = "door"
+solid = "gate"
+path = [[0.0, 0.0, 0.0], [0.0, 3.0, 0.0]]
+speed = 2.0
+trigger = "gate_zone"
+
+[[movers]]
+id = "bad_lift"
+kind = "elevator"
+solid = "missing"
+path = [[0.0, 0.0, 0.0]]
+speed = 1.0
+trigger = "gate"
+
+[[movers]]
+id = "stuck_platform"
+kind = "platform"
+solid = "gate"
+path = [[0.0, 0.0, 0.0], [0.0, 0.0, 0.0], [0.0, 2.0, 0.0]]
+speed = 1.0
+wait = 0.0
+"#;
+        let mut map = TestMap::parse_toml(text).expect("parse");
+        let validation = map.validate();
+        assert_eq!(validation.errors.len(), 4, "{:?}", validation.errors);
+        assert!(validation
+            .errors
+            .iter()
+            .any(|error| error.contains("'stuck_platform' path segment 0 has zero length")));
+        map.movers.truncate(1);
+        assert!(map.validate().is_ok());
+        assert!(map.is_mover_solid("gate"));
+        assert_eq!(map.movers[0].wait, 1.0);
+    }
+
+    #[test]
+    fn crawlspaces_expand_to_low_ceiling_tunnels() {
+        let text = r#"
+version = 1
+name = "crawl"
+
+[[generators]]
+id = "crawl"
+kind = "crawlspaces"
+pos = [0.0, 0.0, 0.0]
+length = 6.0
+width = 1.2
+clearances = [1.2, 1.8]
+gap = 1.0
+"#;
+        let map = TestMap::parse_toml(text).expect("parse");
+        assert!(map.validate().is_ok());
+        let solids = map.expanded_solids().expect("expand");
+        assert_eq!(solids.len(), 8);
+        let ceiling = solids
+            .iter()
+            .find(|solid| solid.id == "crawl/crawl_02/ceiling")
+            .expect("ceiling");
+        assert!((ceiling.pos[1] - ceiling.size[1] * 0.5 - 1.8).abs() < 1.0e-5);
+        assert!((ceiling.pos[0] - 3.0).abs() < 1.0e-5);
+        assert!((ceiling.pos[2] - 2.6).abs() < 1.0e-5);
+
+        let bad = text.replace("[1.2, 1.8]", "[]");
+        let map = TestMap::parse_toml(&bad).expect("parse");
+        assert!(!map.validate().is_ok());
+    }
+
+    #[test]
+    fn ladders_expand_to_climbable_faces_on_landings() {
+        let text = r#"
+version = 1
+name = "ladders"
+
+[[generators]]
+id = "climb"
+kind = "ladders"
+pos = [0.0, 0.0, 0.0]
+width = 1.0
+heights = [2.0, 4.0]
+gap = 1.0
+"#;
+        let map = TestMap::parse_toml(text).expect("parse");
+        assert!(map.validate().is_ok());
+        let solids = map.expanded_solids().expect("expand");
+        assert_eq!(solids.len(), 4);
+        let rungs = solids
+            .iter()
+            .find(|solid| solid.id == "climb/ladder_02/rungs")
+            .expect("rungs");
+        assert!(rungs.is_climbable());
+        assert_eq!(rungs.size, [0.2, 4.0, 1.0]);
+        assert!((rungs.pos[0] - 0.1).abs() < 1.0e-5);
+        assert!((rungs.pos[1] - 2.0).abs() < 1.0e-5);
+        assert!((rungs.pos[2] - 2.0).abs() < 1.0e-5);
+        let landing = solids
+            .iter()
+            .find(|solid| solid.id == "climb/ladder_02/landing")
+            .expect("landing");
+        assert!(!landing.is_climbable());
+        assert!((landing.pos[0] - 1.2).abs() < 1.0e-5);
+
+        let bad = text.replace("[2.0, 4.0]", "[2.0, -1.0]");
+        let map = TestMap::parse_toml(&bad).expect("parse");
+        assert!(!map.validate().is_ok());
+    }
+
+    #[test]
+    fn props_validate_shape_and_mass() {
+        let text = r#"
+version = 1
+name = "props"
+
+[[solids]]
+id = "floor"
+kind = "box"
+pos = [0.0, -0.1, 0.0]
+size = [10.0, 0.2, 10.0]
+
+[[props]]
+id = "crate"
+kind = "box"
+pos = [1.0, 0.5, 0.0]
+size = [1.0, 1.0, 1.0]
+mass = 20.0
+ccd = true
+
+[[props]]
+id = "pebble"
+kind = "sphere"
+pos = [2.0, 0.5, 0.0]
+mass = 0.0
+restitution = 1.5
+
+[[props]]
+id = "shard"
+kind = "convex_hull"
+pos = [3.0, 0.5, 0.0]
+points = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0]]
+mass = 1.0
+"#;
+        let mut map = TestMap::parse_toml(text).expect("parse");
+        let validation = map.validate();
+        assert_eq!(validation.errors.len(), 4, "{:?}", validation.errors);
+        map.props.truncate(1);
+        assert!(map.validate().is_ok());
+        assert_eq!(map.props[0].friction, 0.5);
+        assert!(map.props[0].ccd);
+    }
 }
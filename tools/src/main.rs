@@ -6,7 +6,9 @@ use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use clap::{ArgAction, Parser, Subcommand, ValueEnum};
-use collision_world::{CollisionWorld, CollisionWorldValidationConfig};
+use collision_world::{
+    CollisionLayerTable, CollisionWorld, CollisionWorldValidationConfig, SurfaceTable,
+};
 use compat_quake::pak::{self, PakFile};
 use engine_core::asset_id::AssetKey;
 use engine_core::asset_manager::{
@@ -27,9 +29,15 @@ use engine_core::mount_manifest::{load_mount_manifest, MountManifestEntry};
 use engine_core::path_policy::{ConfigKind, PathOverrides, PathPolicy};
 use engine_core::quake_index::QuakeIndex;
 use engine_core::vfs::{MountKind, Vfs};
+use engine_game::movement_telemetry::{MovementRecorder, MovementRecording};
+use engine_game::movement_trace::{
+    first_divergence, parse_input_trace, Divergence, GoldenTrajectory, InputTraceFrame,
+    TrajectorySample, TrajectoryTolerance,
+};
+use engine_game::{Course, MotorKind};
 use map_cook::{
     build_bsp_collision_world, build_test_map_collision_world, BspCookConfig, BspKind, MapSidecar,
-    Quadtree2dConfig,
+    Quadtree2dConfig, DEFAULT_PATCH_LEVEL,
 };
 use test_map::TestMap;
 
@@ -39,6 +47,8 @@ const EXIT_QUAKE_DIR: i32 = 10;
 const EXIT_PAK: i32 = 11;
 const EXIT_BSP: i32 = 12;
 const QUAKE_VROOT: &str = "raw/quake";
+const MOVEMENT_FIXTURE_DIR: &str = "content/fixtures/movement";
+const MOVEMENT_TICK_RATE: f32 = 60.0;
 
 #[derive(Parser)]
 #[command(name = "tools", version, about = "Pallet tools CLI")]
@@ -57,6 +67,7 @@ enum Commands {
     Content(ContentArgs),
     TestMap(TestMapArgs),
     CollisionWorld(CollisionWorldArgs),
+    Movement(MovementArgs),
     Quake(QuakeArgs),
 }
 
@@ -152,6 +163,54 @@ struct CollisionWorldArgs {
     command: CollisionWorldCommand,
 }
 
+#[derive(Parser)]
+struct MovementArgs {
+    #[command(subcommand)]
+    command: MovementCommand,
+}
+
+#[derive(Subcommand)]
+enum MovementCommand {
+    /// Replays an input trace on a test map at a fixed tick.
+    Run(MovementRunArgs),
+    /// Replays every golden trajectory in a directory.
+    Check {
+        #[arg(long, value_name = "PATH", default_value = MOVEMENT_FIXTURE_DIR)]
+        dir: PathBuf,
+    },
+    /// Prints per-jump and overall stats for a movement telemetry CSV.
+    Summarize {
+        #[arg(value_name = "PATH")]
+        telemetry: PathBuf,
+    },
+}
+
+#[derive(Parser)]
+struct MovementRunArgs {
+    /// Test map asset key, e.g. engine:test_map/stairs_and_steps.toml.
+    #[arg(long, value_name = "KEY")]
+    test_map: String,
+    #[arg(long, value_name = "PATH")]
+    trace: PathBuf,
+    #[arg(long, value_name = "arena|rpg", default_value = "arena")]
+    motor: String,
+    /// Golden trajectory to compare against.
+    #[arg(long, value_name = "PATH")]
+    golden: Option<PathBuf>,
+    /// Write the run to `--golden` instead of comparing.
+    #[arg(long, requires = "golden")]
+    bless: bool,
+    #[arg(long, value_name = "HZ", default_value_t = MOVEMENT_TICK_RATE)]
+    tick_rate: f32,
+    #[arg(long, value_name = "METERS")]
+    position_tolerance: Option<f32>,
+    #[arg(long, value_name = "M/S")]
+    velocity_tolerance: Option<f32>,
+    /// Also write per-tick movement telemetry as CSV.
+    #[arg(long, value_name = "PATH")]
+    telemetry: Option<PathBuf>,
+}
+
 #[derive(Parser)]
 struct CollisionWorldCookBspArgs {
     #[arg(long, value_name = "MAP_ID")]
@@ -170,6 +229,9 @@ struct CollisionWorldCookBspArgs {
     min_leaf_size_xy: Option<f32>,
     #[arg(long)]
     max_depth: Option<u32>,
+    /// Steps per Bezier segment for Quake 3 curved patches.
+    #[arg(long)]
+    patch_level: Option<u32>,
 }
 
 #[derive(Subcommand)]
@@ -226,6 +288,9 @@ enum CollisionWorldCommand {
         test_map: PathBuf,
         #[arg(long, value_name = "PATH")]
         out: PathBuf,
+        /// Collision layer table; the built-in table when omitted.
+        #[arg(long, value_name = "PATH")]
+        layers: Option<PathBuf>,
     },
     CookBsp(CollisionWorldCookBspArgs),
 }
@@ -380,6 +445,7 @@ fn main() {
         Commands::Content(args) => run_content(args),
         Commands::TestMap(args) => run_test_map(args),
         Commands::CollisionWorld(args) => run_collision_world(args),
+        Commands::Movement(args) => run_movement(args),
         Commands::Quake(args) => run_quake(args),
     };
     std::process::exit(exit_code);
@@ -642,11 +708,290 @@ fn run_collision_world(args: CollisionWorldArgs) -> i32 {
             max_tris_per_chunk,
             max_chunks,
         } => collision_world_validate(&path, max_tris_per_chunk, max_chunks),
-        CollisionWorldCommand::Cook { test_map, out } => collision_world_cook(&test_map, &out),
+        CollisionWorldCommand::Cook {
+            test_map,
+            out,
+            layers,
+        } => collision_world_cook(&test_map, &out, layers.as_deref()),
         CollisionWorldCommand::CookBsp(args) => collision_world_cook_bsp(&args),
     }
 }
 
+fn run_movement(args: MovementArgs) -> i32 {
+    match args.command {
+        MovementCommand::Run(args) => movement_run(&args),
+        MovementCommand::Check { dir } => movement_check(&dir),
+        MovementCommand::Summarize { telemetry } => movement_summarize(&telemetry),
+    }
+}
+
+fn movement_summarize(path: &Path) -> i32 {
+    let recording = match std::fs::read_to_string(path)
+        .map_err(|err| format!("telemetry read failed ({}): {}", path.display(), err))
+        .and_then(|text| MovementRecording::parse_csv(&text))
+    {
+        Ok(recording) => recording,
+        Err(err) => {
+            eprintln!("{}", err);
+            return EXIT_USAGE;
+        }
+    };
+    println!("{}", recording.summary());
+    EXIT_SUCCESS
+}
+
+fn movement_run(args: &MovementRunArgs) -> i32 {
+    let motor = match MotorKind::parse(&args.motor) {
+        Ok(motor) => motor,
+        Err(err) => {
+            eprintln!("invalid motor: {}", err);
+            return EXIT_USAGE;
+        }
+    };
+    if !args.tick_rate.is_finite() || args.tick_rate <= 0.0 {
+        eprintln!("tick rate must be positive");
+        return EXIT_USAGE;
+    }
+    let frames = match load_movement_trace(&args.trace) {
+        Ok(frames) => frames,
+        Err(err) => {
+            eprintln!("{}", err);
+            return EXIT_USAGE;
+        }
+    };
+    let path_policy = PathPolicy::from_overrides(PathOverrides::default());
+    let (samples, recording) = match run_movement_course(
+        &path_policy,
+        &args.test_map,
+        motor,
+        &frames,
+        1.0 / args.tick_rate,
+    ) {
+        Ok(run) => run,
+        Err(err) => {
+            eprintln!("movement run failed: {}", err);
+            return EXIT_USAGE;
+        }
+    };
+    if let Some(last) = samples.last() {
+        println!(
+            "movement run: ticks={} end=({:.3}, {:.3}, {:.3}) grounded={}",
+            samples.len(),
+            last.position[0],
+            last.position[1],
+            last.position[2],
+            last.grounded
+        );
+    }
+    if let Some(telemetry_path) = args.telemetry.as_deref() {
+        if let Err(err) = std::fs::write(telemetry_path, recording.to_csv()) {
+            eprintln!(
+                "telemetry write failed ({}): {}",
+                telemetry_path.display(),
+                err
+            );
+            return EXIT_USAGE;
+        }
+        println!("telemetry: wrote {}", telemetry_path.display());
+    }
+    let Some(golden_path) = args.golden.as_deref() else {
+        return EXIT_SUCCESS;
+    };
+    if args.bless {
+        let trace = args
+            .trace
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let golden = GoldenTrajectory {
+            test_map: args.test_map.clone(),
+            motor,
+            trace,
+            samples,
+        };
+        if let Err(err) = std::fs::write(golden_path, golden.serialize()) {
+            eprintln!("golden write failed ({}): {}", golden_path.display(), err);
+            return EXIT_USAGE;
+        }
+        println!("golden written: {}", golden_path.display());
+        return EXIT_SUCCESS;
+    }
+    let golden = match read_golden_trajectory(golden_path) {
+        Ok(golden) => golden,
+        Err(err) => {
+            eprintln!("{}", err);
+            return EXIT_USAGE;
+        }
+    };
+    let defaults = TrajectoryTolerance::default();
+    let tolerance = TrajectoryTolerance {
+        position: args.position_tolerance.unwrap_or(defaults.position),
+        velocity: args.velocity_tolerance.unwrap_or(defaults.velocity),
+    };
+    match first_divergence(&samples, &golden.samples, tolerance) {
+        Some(divergence) => {
+            eprintln!("diverged from {} at {}", golden_path.display(), divergence);
+            1
+        }
+        None => {
+            println!("matches golden: {}", golden_path.display());
+            EXIT_SUCCESS
+        }
+    }
+}
+
+fn movement_check(dir: &Path) -> i32 {
+    let path_policy = PathPolicy::from_overrides(PathOverrides::default());
+    let goldens = match movement_goldens(dir) {
+        Ok(goldens) => goldens,
+        Err(err) => {
+            eprintln!("{}", err);
+            return EXIT_USAGE;
+        }
+    };
+    let mut failures = 0;
+    for path in &goldens {
+        match check_movement_golden(&path_policy, path, TrajectoryTolerance::default()) {
+            Ok(None) => println!("ok: {}", path.display()),
+            Ok(Some(divergence)) => {
+                failures += 1;
+                eprintln!("diverged: {} at {}", path.display(), divergence);
+            }
+            Err(err) => {
+                failures += 1;
+                eprintln!("failed: {}: {}", path.display(), err);
+            }
+        }
+    }
+    println!(
+        "movement check: goldens={} failed={}",
+        goldens.len(),
+        failures
+    );
+    if failures == 0 {
+        EXIT_SUCCESS
+    } else {
+        1
+    }
+}
+
+/// Golden trajectory files in `dir`, sorted by name.
+fn movement_goldens(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(dir)
+        .map_err(|err| format!("golden dir read failed ({}): {}", dir.display(), err))?;
+    let mut goldens: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "golden"))
+        .collect();
+    goldens.sort();
+    Ok(goldens)
+}
+
+/// Replays the run a golden records; its trace sits next to it.
+fn check_movement_golden(
+    path_policy: &PathPolicy,
+    golden_path: &Path,
+    tolerance: TrajectoryTolerance,
+) -> Result<Option<Divergence>, String> {
+    let golden = read_golden_trajectory(golden_path)?;
+    let trace_path = golden_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(&golden.trace);
+    let frames = load_movement_trace(&trace_path)?;
+    let (samples, _) = run_movement_course(
+        path_policy,
+        &golden.test_map,
+        golden.motor,
+        &frames,
+        1.0 / MOVEMENT_TICK_RATE,
+    )?;
+    Ok(first_divergence(&samples, &golden.samples, tolerance))
+}
+
+fn read_golden_trajectory(path: &Path) -> Result<GoldenTrajectory, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("golden read failed ({}): {}", path.display(), err))?;
+    GoldenTrajectory::parse(&text).map_err(|err| format!("{}: {}", path.display(), err))
+}
+
+fn load_movement_trace(path: &Path) -> Result<Vec<InputTraceFrame>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|err| format!("trace read failed ({}): {}", path.display(), err))?;
+    let frames = parse_input_trace(&text).map_err(|err| format!("{}: {}", path.display(), err))?;
+    if frames.is_empty() {
+        return Err(format!("trace contains no frames: {}", path.display()));
+    }
+    Ok(frames)
+}
+
+/// Builds the course for `test_map` from its cooked collision world and
+/// drives it with one trace frame per fixed tick of `dt`, returning the
+/// trajectory and its telemetry. The recorded frame times are ignored so
+/// wall-clock captures replay deterministically.
+fn run_movement_course(
+    path_policy: &PathPolicy,
+    test_map: &str,
+    motor: MotorKind,
+    frames: &[InputTraceFrame],
+    dt: f32,
+) -> Result<(Vec<TrajectorySample>, MovementRecording), String> {
+    let key = AssetKey::parse(test_map).map_err(|err| err.to_string())?;
+    if key.namespace() != "engine" || key.kind() != "test_map" {
+        return Err(format!("expected engine:test_map key, got {}", test_map));
+    }
+    let resolver = AssetResolver::new(path_policy, None);
+    let map = TestMap::parse_toml(&read_engine_text(&resolver, &key)?)?;
+    let world_key = AssetKey::from_parts(
+        "engine",
+        "collision_world",
+        &format!("test_maps/{}", key.path()),
+    )
+    .map_err(|err| err.to_string())?;
+    let collision_world = CollisionWorld::parse_toml(&read_engine_text(&resolver, &world_key)?)?;
+    let layers = read_engine_config(
+        &resolver,
+        "physics/collision_layers.toml",
+        CollisionLayerTable::parse_toml,
+    )?;
+    let surfaces =
+        read_engine_config(&resolver, "physics/surfaces.toml", SurfaceTable::parse_toml)?;
+    let mut course = Course::build(&map, &collision_world, &layers, &surfaces, motor)?;
+    let mut recorder = MovementRecorder::new(motor);
+    let samples = frames
+        .iter()
+        .map(|frame| {
+            let raw = frame.raw_input();
+            let tick = course.step(raw, frame.yaw, frame.pitch, dt);
+            recorder.record(course.controller.motor(), raw, frame.yaw, dt, &tick);
+            TrajectorySample::from_frame(&tick)
+        })
+        .collect();
+    Ok((samples, recorder.finish()))
+}
+
+fn read_engine_text(resolver: &AssetResolver, key: &AssetKey) -> Result<String, String> {
+    let location = resolver.resolve(key)?;
+    match &location.path {
+        ResolvedPath::File(path) => std::fs::read_to_string(path)
+            .map_err(|err| format!("read failed ({}): {}", path.display(), err)),
+        _ => Err(format!("{} is not a loose file", key.canonical())),
+    }
+}
+
+/// Engine config table at `path`; the built-in table when none ships.
+fn read_engine_config<T: Default>(
+    resolver: &AssetResolver,
+    path: &str,
+    parse: fn(&str) -> Result<T, String>,
+) -> Result<T, String> {
+    let key = AssetKey::from_parts("engine", "config", path).map_err(|err| err.to_string())?;
+    if resolver.resolve(&key).is_err() {
+        return Ok(T::default());
+    }
+    parse(&read_engine_text(resolver, &key)?).map_err(|err| format!("{}: {}", path, err))
+}
+
 fn test_map_validate(path: &Path) -> i32 {
     if !path.is_file() {
         eprintln!("test map not found: {}", path.display());
@@ -741,7 +1086,7 @@ fn collision_world_validate(
     EXIT_SUCCESS
 }
 
-fn collision_world_cook(test_map_path: &Path, out_path: &Path) -> i32 {
+fn collision_world_cook(test_map_path: &Path, out_path: &Path, layers_path: Option<&Path>) -> i32 {
     if !test_map_path.is_file() {
         eprintln!("test map not found: {}", test_map_path.display());
         return EXIT_USAGE;
@@ -760,7 +1105,22 @@ fn collision_world_cook(test_map_path: &Path, out_path: &Path) -> i32 {
             return EXIT_USAGE;
         }
     };
-    let world = match build_test_map_collision_world(&map) {
+    let layers = match layers_path {
+        Some(path) => {
+            let parsed = std::fs::read_to_string(path)
+                .map_err(|err| err.to_string())
+                .and_then(|text| CollisionLayerTable::parse_toml(&text));
+            match parsed {
+                Ok(layers) => layers,
+                Err(err) => {
+                    eprintln!("collision layers load failed ({}): {}", path.display(), err);
+                    return EXIT_USAGE;
+                }
+            }
+        }
+        None => CollisionLayerTable::default(),
+    };
+    let world = match build_test_map_collision_world(&map, &layers) {
         Ok(world) => world,
         Err(err) => {
             eprintln!("collision world build failed: {}", err);
@@ -865,6 +1225,7 @@ fn collision_world_cook_bsp(args: &CollisionWorldCookBspArgs) -> i32 {
         map_to_world_scale: scale,
         space_origin,
         quadtree,
+        patch_level: args.patch_level.unwrap_or(DEFAULT_PATCH_LEVEL),
     };
     let world = match build_bsp_collision_world(kind, &bsp_bytes, &config) {
         Ok(world) => world,
@@ -3453,12 +3814,15 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
-    fn fixture_root() -> PathBuf {
-        let repo_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    fn repo_root() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
             .parent()
             .expect("repo root")
-            .to_path_buf();
-        repo_root.join("content").join("fixtures").join("golden")
+            .to_path_buf()
+    }
+
+    fn fixture_root() -> PathBuf {
+        repo_root().join("content").join("fixtures").join("golden")
     }
 
     fn fixture_policy() -> PathPolicy {
@@ -3536,6 +3900,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn movement_goldens_cover_every_test_map() {
+        let repo_root = repo_root();
+        let path_policy = PathPolicy::from_overrides(PathOverrides {
+            content_root: Some(repo_root.join("content")),
+            dev_override_root: None,
+            user_config_root: None,
+        });
+        let golden_dir = repo_root.join(MOVEMENT_FIXTURE_DIR);
+        let goldens = movement_goldens(&golden_dir).expect("goldens");
+        let mut courses = std::fs::read_dir(repo_root.join("content").join("test_maps"))
+            .expect("test maps")
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect::<Vec<_>>();
+        courses.sort();
+        assert!(!courses.is_empty());
+        for course in &courses {
+            let stem = course.file_stem().expect("stem").to_string_lossy();
+            for motor in ["arena", "rpg"] {
+                let golden = golden_dir.join(format!("{}_{}.golden", stem, motor));
+                assert!(goldens.contains(&golden), "missing {}", golden.display());
+            }
+        }
+        for golden in &goldens {
+            let divergence =
+                check_movement_golden(&path_policy, golden, TrajectoryTolerance::default())
+                    .expect("movement run");
+            assert!(
+                divergence.is_none(),
+                "{} diverged at {}",
+                golden.display(),
+                divergence.expect("divergence")
+            );
+        }
+    }
+
     #[test]
     fn build_inputs_are_deterministic() {
         let path_policy = fixture_policy();